@@ -1,31 +1,201 @@
+use std::collections::HashMap;
+
+use reqwest::header::HeaderMap;
+
+use crate::services::chain_networks::{self, NetworkProfile};
+use crate::services::sources::MergePolicy;
+use crate::services::stream_headers;
+use crate::services::token_gate;
+
+/// Where playlist and EPG state is persisted between restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Nothing is persisted.
+    Memory,
+    /// A JSON file at `STORAGE_PATH`.
+    Json,
+    /// A SQLite database at `STORAGE_PATH` (requires the `sql` feature).
+    Sqlite,
+    /// A Postgres database at `STORAGE_DATABASE_URL` (requires the `sql` feature).
+    Postgres,
+}
+
+/// How channel liveness is probed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeMode {
+    /// A HEAD request, or a ranged GET when HEAD is refused.
+    Basic,
+    /// HLS channels are probed down to a segment: the playlist must parse,
+    /// and one variant and one of its segments must be fetchable. Other
+    /// channels are probed as in [`ProbeMode::Basic`].
+    Hls,
+}
+
+/// Where on-chain playlists are looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainPlaylistSource {
+    /// The Subscan API at `SUBSCAN_API_URL`.
+    Subscan,
+    /// Recent blocks read from the node at `CHAIN_RPC_URL`.
+    Rpc,
+}
+
 /// Application configuration loaded from environment variables.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// TCP port the HTTP server listens on.
     pub port: u16,
-    /// URL of the remote M3U playlist source.
+    /// Comma-separated remote M3U playlist sources; `prefix|url` entries
+    /// prefix that source's groups.
     pub m3u_source_url: String,
+    /// Which source wins when several carry the same stream URL.
+    pub m3u_merge_policy: MergePolicy,
+    /// Interval in minutes between reloads of the M3U sources; 0 disables them.
+    pub m3u_refresh_interval_mins: u64,
     /// Timeout in seconds for individual channel probe requests.
     pub probe_timeout_secs: u64,
     /// Interval in minutes between background liveness checks.
     pub probe_interval_mins: u64,
+    /// Interval in seconds between checks of channels pinned to high-frequency probing.
+    pub probe_pinned_interval_secs: u64,
+    /// How deep channel liveness probes go.
+    pub probe_mode: ProbeMode,
+    /// Most channel probes in flight at once.
+    pub probe_concurrency: usize,
+    /// Most channel probes in flight at once against a single host.
+    pub probe_host_concurrency: usize,
+    /// Longest random delay in milliseconds before a probe starts.
+    pub probe_jitter_ms: u64,
+    /// Retries of a probe that failed without a definite answer.
+    pub probe_retries: u32,
+    /// Failed probes in a row before a live channel is marked dead.
+    pub probe_failure_threshold: u32,
+    /// Failed probes in a row before a channel is quarantined; 0 disables it.
+    pub probe_quarantine_after: u32,
+    /// Headers sent with every probe; a channel's own user agent and
+    /// referrer take precedence.
+    pub probe_headers: HeaderMap,
+    /// Whether live HLS channels get a segment-level health score after each probe.
+    pub hls_health_enabled: bool,
+    /// `ffprobe` executable used to read stream metadata.
+    #[cfg_attr(not(feature = "ffprobe"), allow(dead_code))]
+    pub ffprobe_path: String,
+    /// Proxy that every outbound HTTP request is sent through, when set.
+    pub http_proxy_url: Option<String>,
+    /// Most redirects an outbound HTTP request follows; 0 follows none.
+    pub http_max_redirects: usize,
     /// Base URL for the Subscan API used for on-chain playlist lookups.
     pub subscan_api_url: String,
+    /// Key sent to Subscan as `X-API-Key`, for higher rate limits.
+    pub subscan_api_key: Option<String>,
+    /// HTTP JSON-RPC endpoint of a node, used to read token gates and,
+    /// with [`ChainPlaylistSource::Rpc`], on-chain playlists.
+    pub chain_rpc_url: Option<String>,
+    /// Where on-chain playlists are looked up.
+    pub chain_playlist_source: ChainPlaylistSource,
+    /// How many recent blocks an RPC playlist lookup scans.
+    pub chain_scan_blocks: u32,
+    /// Address whose newly finalized playlist remarks replace the default
+    /// playlist, and whose registry publications reload the M3U sources.
+    pub chain_watch_address: Option<String>,
+    /// WebSocket JSON-RPC endpoint the watcher subscribes to; derived from
+    /// `CHAIN_RPC_URL` when unset.
+    pub chain_ws_url: Option<String>,
+    /// Further chains on-chain playlists can be looked up on by name.
+    pub chain_networks: Vec<NetworkProfile>,
+    /// IPFS gateway that playlists published as a CID are fetched from.
+    pub ipfs_gateway_url: String,
+    /// Seconds an on-chain playlist lookup is reused for the same network
+    /// and address; 0 disables the cache.
+    pub chain_cache_secs: u64,
+    /// Groups of the default playlist gated by the token-gate pallet, as
+    /// group name to resource ID.
+    pub token_gated_groups: HashMap<String, u32>,
     /// Whether EPG fetching from iptv-org is enabled.
     pub epg_enabled: bool,
+    /// Public base URL of the backend, used for links in exported playlists;
+    /// derived from the request's `Host` header when unset.
+    pub public_url: Option<String>,
+    /// Login players use for the Xtream Codes emulation; the emulation is
+    /// off unless both the username and password are set.
+    pub xtream_username: Option<String>,
+    /// Password for [`Config::xtream_username`].
+    pub xtream_password: Option<String>,
+    /// Key required for requests that change state (anything but `GET`,
+    /// `HEAD` and `OPTIONS`); such requests are open when unset.
+    pub api_key: Option<String>,
+    /// Whether anyone may create an account through `/api/auth/register`;
+    /// otherwise only the operator creates them.
+    pub allow_registration: bool,
+    /// Hours a user session lasts after signing in.
+    pub session_ttl_hours: u64,
+    /// JSON file the EPG channel alias table is persisted to; in-memory only when unset.
+    pub epg_aliases_path: Option<String>,
+    /// Extra seconds a background task may stay silent beyond its own
+    /// schedule before the watchdog reports it as stalled.
+    pub watchdog_stall_secs: u64,
+    /// Whether the watchdog restarts stalled background tasks.
+    pub watchdog_restart: bool,
+    /// Backend that playlists, liveness results, pins, and EPG aliases are saved to.
+    pub storage_backend: StorageBackend,
+    /// JSON or SQLite file for the `json` and `sqlite` backends.
+    pub storage_path: Option<String>,
+    /// Database URL for the `postgres` backend.
+    #[cfg_attr(not(feature = "sql"), allow(dead_code))]
+    pub storage_database_url: Option<String>,
+    /// Seconds to wait after a change before saving state, so bursts are saved once.
+    pub storage_debounce_secs: u64,
 }
 
 impl Config {
     /// Build a [`Config`] from environment variables, falling back to sensible defaults.
     ///
-    /// | Variable              | Default                             |
-    /// |-----------------------|-------------------------------------|
-    /// | `BACKEND_PORT`        | `3001`                              |
-    /// | `M3U_SOURCE_URL`      | (empty string)                      |
-    /// | `PROBE_TIMEOUT_SECS`  | `5`                                 |
-    /// | `PROBE_INTERVAL_MINS` | `10`                                |
-    /// | `SUBSCAN_API_URL`     | `https://paseo.api.subscan.io`      |
-    /// | `EPG_ENABLED`         | `true`                              |
+    /// | Variable                     | Default                             |
+    /// |------------------------------|-------------------------------------|
+    /// | `BACKEND_PORT`               | `3001`                              |
+    /// | `M3U_SOURCE_URL`             | (empty string)                      |
+    /// | `M3U_MERGE_POLICY`           | `first`                             |
+    /// | `M3U_REFRESH_INTERVAL_MINS`  | `360`                               |
+    /// | `PROBE_TIMEOUT_SECS`         | `5`                                 |
+    /// | `PROBE_INTERVAL_MINS`        | `10`                                |
+    /// | `PROBE_PINNED_INTERVAL_SECS` | `60`                                |
+    /// | `PROBE_MODE`                 | `basic`                             |
+    /// | `PROBE_CONCURRENCY`          | `20`                                |
+    /// | `PROBE_HOST_CONCURRENCY`     | `4`                                 |
+    /// | `PROBE_JITTER_MS`            | `500`                               |
+    /// | `PROBE_RETRIES`              | `2`                                 |
+    /// | `PROBE_FAILURE_THRESHOLD`    | `2`                                 |
+    /// | `PROBE_QUARANTINE_AFTER`     | `144`                               |
+    /// | `PROBE_HEADERS`              | (empty string)                      |
+    /// | `HLS_HEALTH_ENABLED`         | `false`                             |
+    /// | `FFPROBE_PATH`               | `ffprobe`                           |
+    /// | `HTTP_PROXY_URL`             | (unset)                             |
+    /// | `HTTP_MAX_REDIRECTS`         | `10`                                |
+    /// | `SUBSCAN_API_URL`            | `https://paseo.api.subscan.io`      |
+    /// | `SUBSCAN_API_KEY`            | (unset)                             |
+    /// | `CHAIN_RPC_URL`              | (unset)                             |
+    /// | `CHAIN_PLAYLIST_SOURCE`      | `subscan`                           |
+    /// | `CHAIN_SCAN_BLOCKS`          | `600`                               |
+    /// | `CHAIN_WATCH_ADDRESS`        | (unset)                             |
+    /// | `CHAIN_WS_URL`               | `CHAIN_RPC_URL` as `ws://`/`wss://` |
+    /// | `CHAIN_NETWORKS`             | (empty string)                      |
+    /// | `CHAIN_CACHE_SECS`           | `60`                                |
+    /// | `IPFS_GATEWAY_URL`           | `https://ipfs.io`                   |
+    /// | `TOKEN_GATED_GROUPS`         | (empty string)                      |
+    /// | `EPG_ENABLED`                | `true`                              |
+    /// | `PUBLIC_URL`                 | (unset)                             |
+    /// | `XTREAM_USERNAME`            | (unset)                             |
+    /// | `XTREAM_PASSWORD`            | (unset)                             |
+    /// | `API_KEY`                    | (unset)                             |
+    /// | `ALLOW_REGISTRATION`         | `false`                             |
+    /// | `SESSION_TTL_HOURS`          | `720`                               |
+    /// | `EPG_ALIASES_PATH`           | (unset)                             |
+    /// | `WATCHDOG_STALL_SECS`        | `600`                               |
+    /// | `WATCHDOG_RESTART`           | `false`                             |
+    /// | `STORAGE_BACKEND`            | `json` if `STORAGE_PATH` is set, else `memory` |
+    /// | `STORAGE_PATH`               | (unset)                             |
+    /// | `STORAGE_DATABASE_URL`       | (unset)                             |
+    /// | `STORAGE_DEBOUNCE_SECS`      | `5`                                 |
     pub fn from_env() -> Self {
         let port = std::env::var("BACKEND_PORT")
             .ok()
@@ -34,6 +204,14 @@ impl Config {
 
         let m3u_source_url = std::env::var("M3U_SOURCE_URL").unwrap_or_default();
 
+        let m3u_merge_policy =
+            MergePolicy::from_param(std::env::var("M3U_MERGE_POLICY").ok().as_deref());
+
+        let m3u_refresh_interval_mins = std::env::var("M3U_REFRESH_INTERVAL_MINS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(360);
+
         let probe_timeout_secs = std::env::var("PROBE_TIMEOUT_SECS")
             .ok()
             .and_then(|v| v.parse::<u64>().ok())
@@ -44,20 +222,229 @@ impl Config {
             .and_then(|v| v.parse::<u64>().ok())
             .unwrap_or(10);
 
+        let probe_pinned_interval_secs = std::env::var("PROBE_PINNED_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(60);
+
+        let probe_mode = match std::env::var("PROBE_MODE").ok().as_deref() {
+            Some("hls") => ProbeMode::Hls,
+            _ => ProbeMode::Basic,
+        };
+
+        let probe_concurrency = std::env::var("PROBE_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(20);
+
+        let probe_host_concurrency = std::env::var("PROBE_HOST_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(4);
+
+        let probe_jitter_ms = std::env::var("PROBE_JITTER_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(500);
+
+        let probe_retries = std::env::var("PROBE_RETRIES")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(2);
+
+        let probe_failure_threshold = std::env::var("PROBE_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(2);
+
+        let probe_quarantine_after = std::env::var("PROBE_QUARANTINE_AFTER")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(144);
+
+        let probe_headers =
+            stream_headers::parse_headers(&std::env::var("PROBE_HEADERS").unwrap_or_default());
+
+        let hls_health_enabled = std::env::var("HLS_HEALTH_ENABLED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let ffprobe_path = std::env::var("FFPROBE_PATH").unwrap_or_else(|_| "ffprobe".to_string());
+
+        let http_proxy_url = std::env::var("HTTP_PROXY_URL")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let http_max_redirects = std::env::var("HTTP_MAX_REDIRECTS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(10);
+
         let subscan_api_url = std::env::var("SUBSCAN_API_URL")
             .unwrap_or_else(|_| "https://paseo.api.subscan.io".to_string());
 
+        let subscan_api_key = std::env::var("SUBSCAN_API_KEY")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let chain_rpc_url = std::env::var("CHAIN_RPC_URL")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let chain_playlist_source = match std::env::var("CHAIN_PLAYLIST_SOURCE").ok().as_deref() {
+            Some("rpc") => ChainPlaylistSource::Rpc,
+            _ => ChainPlaylistSource::Subscan,
+        };
+
+        let chain_scan_blocks = std::env::var("CHAIN_SCAN_BLOCKS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(600);
+
+        let chain_watch_address = std::env::var("CHAIN_WATCH_ADDRESS")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let chain_ws_url = std::env::var("CHAIN_WS_URL")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .or_else(|| {
+                let rpc = chain_rpc_url.as_deref()?;
+                rpc.strip_prefix("http")
+                    .filter(|rest| rest.starts_with("://") || rest.starts_with("s://"))
+                    .map(|rest| format!("ws{rest}"))
+            });
+
+        let chain_networks =
+            chain_networks::parse_networks(&std::env::var("CHAIN_NETWORKS").unwrap_or_default());
+
+        let ipfs_gateway_url = std::env::var("IPFS_GATEWAY_URL")
+            .ok()
+            .map(|v| v.trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "https://ipfs.io".to_string());
+
+        let chain_cache_secs = std::env::var("CHAIN_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(60);
+
+        let token_gated_groups = token_gate::parse_gated_groups(
+            &std::env::var("TOKEN_GATED_GROUPS").unwrap_or_default(),
+        );
+
         let epg_enabled = std::env::var("EPG_ENABLED")
             .map(|v| v != "false" && v != "0")
             .unwrap_or(true);
 
+        let public_url = std::env::var("PUBLIC_URL")
+            .ok()
+            .map(|v| v.trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty());
+
+        let xtream_username = std::env::var("XTREAM_USERNAME")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let xtream_password = std::env::var("XTREAM_PASSWORD")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let api_key = std::env::var("API_KEY").ok().filter(|v| !v.is_empty());
+
+        let allow_registration = std::env::var("ALLOW_REGISTRATION")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let session_ttl_hours = std::env::var("SESSION_TTL_HOURS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(720);
+
+        let epg_aliases_path = std::env::var("EPG_ALIASES_PATH")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let watchdog_stall_secs = std::env::var("WATCHDOG_STALL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(600);
+
+        let watchdog_restart = std::env::var("WATCHDOG_RESTART")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let storage_path = std::env::var("STORAGE_PATH").ok().filter(|v| !v.is_empty());
+
+        let storage_backend = match std::env::var("STORAGE_BACKEND").ok().as_deref() {
+            Some("memory") => StorageBackend::Memory,
+            Some("json") => StorageBackend::Json,
+            Some("sqlite") => StorageBackend::Sqlite,
+            Some("postgres") => StorageBackend::Postgres,
+            _ if storage_path.is_some() => StorageBackend::Json,
+            _ => StorageBackend::Memory,
+        };
+
+        let storage_database_url = std::env::var("STORAGE_DATABASE_URL")
+            .ok()
+            .filter(|v| !v.is_empty());
+
+        let storage_debounce_secs = std::env::var("STORAGE_DEBOUNCE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5);
+
         Self {
             port,
             m3u_source_url,
+            m3u_merge_policy,
+            m3u_refresh_interval_mins,
             probe_timeout_secs,
             probe_interval_mins,
+            probe_pinned_interval_secs,
+            probe_mode,
+            probe_concurrency,
+            probe_host_concurrency,
+            probe_jitter_ms,
+            probe_retries,
+            probe_failure_threshold,
+            probe_quarantine_after,
+            probe_headers,
+            hls_health_enabled,
+            ffprobe_path,
+            http_proxy_url,
+            http_max_redirects,
             subscan_api_url,
+            subscan_api_key,
+            chain_rpc_url,
+            chain_playlist_source,
+            chain_scan_blocks,
+            chain_watch_address,
+            chain_ws_url,
+            chain_networks,
+            ipfs_gateway_url,
+            chain_cache_secs,
+            token_gated_groups,
             epg_enabled,
+            public_url,
+            xtream_username,
+            xtream_password,
+            api_key,
+            allow_registration,
+            session_ttl_hours,
+            epg_aliases_path,
+            watchdog_stall_secs,
+            watchdog_restart,
+            storage_backend,
+            storage_path,
+            storage_database_url,
+            storage_debounce_secs,
         }
     }
 }
@@ -3,17 +3,36 @@ mod models;
 mod routes;
 mod services;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::{routing::{get, post}, Router};
+use axum::{middleware, routing::{get, patch, post, put}, Router};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
-use config::Config;
+use config::{ChainPlaylistSource, Config};
 use models::{AppState, EpgCache, Playlist};
 use services::channel_checker;
+use services::epg_aliases;
+use services::http_client;
+use services::accounts::Accounts;
+use services::chain_networks::{ChainNetwork, ChainNetworks};
+use services::chain_rpc::ChainRpc;
+use services::chain_watcher;
+use services::events::EventBus;
+use services::import_jobs::ImportJobs;
+use services::ipfs::IpfsGateway;
 use services::iptv_org::IptvOrgIndex;
+use services::probe_schedule::ProbeSchedule;
+use services::search_index::SearchIndex;
+use services::signature;
+use services::sources::{self, SourceConfig, SourceSet};
+use services::storage;
+use services::subscan::Subscan;
+use services::token_gate::TokenGate;
+use services::uptime::UptimeHistory;
+use services::watchdog::{self, RestartFn, Watchdog};
 
 /// Entry point for the IPTV backend service.
 ///
@@ -39,39 +58,216 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         channels: Vec::new(),
         last_checked: None,
         source: cfg.m3u_source_url.clone(),
+        epg_urls: Vec::new(),
     };
 
-    let epg_cache = EpgCache::new();
+    let probe_interval = Duration::from_secs(cfg.probe_interval_mins * 60);
+    let probe_timeout = Duration::from_secs(cfg.probe_timeout_secs);
+    let probe_schedule = ProbeSchedule::new(
+        probe_interval,
+        Duration::from_secs(cfg.probe_pinned_interval_secs),
+    );
+
+    let mut epg_cache = EpgCache::new();
+    if let Some(ref path) = cfg.epg_aliases_path {
+        match epg_aliases::load(std::path::Path::new(path)) {
+            Ok(aliases) => {
+                info!("Loaded {} EPG aliases from {path}", aliases.len());
+                epg_cache.aliases = aliases;
+            }
+            Err(e) => tracing::warn!("Failed to load EPG aliases from {path}: {e}"),
+        }
+    }
+
+    let source_set = SourceSet {
+        config: SourceConfig {
+            sources: sources::parse_source_list(&cfg.m3u_source_url),
+            policy: cfg.m3u_merge_policy,
+        },
+        ..Default::default()
+    };
+
+    let http = http_client::shared_client(&cfg)?;
+    let chain_rpc = cfg
+        .chain_rpc_url
+        .clone()
+        .map(|url| ChainRpc::new(http.clone(), url));
+    let default_network = ChainNetwork {
+        subscan_api_url: (cfg.chain_playlist_source == ChainPlaylistSource::Subscan)
+            .then(|| cfg.subscan_api_url.clone()),
+        rpc: chain_rpc.clone(),
+        ss58_prefix: None,
+    };
 
     let state = Arc::new(AppState {
         playlist: tokio::sync::RwLock::new(playlist),
+        playlists: tokio::sync::RwLock::new(HashMap::new()),
+        sources: tokio::sync::RwLock::new(source_set),
+        refresh_sources: tokio::sync::Notify::new(),
         config: cfg.clone(),
+        http: http.clone(),
         check_now: tokio::sync::Notify::new(),
+        probe_schedule: tokio::sync::RwLock::new(probe_schedule),
+        schedule_changed: tokio::sync::Notify::new(),
+        uptime: tokio::sync::RwLock::new(UptimeHistory::default()),
+        events: EventBus::default(),
+        state_changed: tokio::sync::Notify::new(),
         epg_cache: tokio::sync::RwLock::new(epg_cache),
         iptv_org_index: tokio::sync::RwLock::new(IptvOrgIndex::new()),
+        search_index: tokio::sync::RwLock::new(SearchIndex::new()),
+        import_jobs: tokio::sync::RwLock::new(ImportJobs::new()),
+        accounts: tokio::sync::RwLock::new(Accounts::default()),
+        token_gate: TokenGate::new(chain_rpc.clone(), cfg.token_gated_groups.clone()),
+        chain_networks: ChainNetworks::new(
+            &http,
+            default_network,
+            &cfg.chain_networks,
+            Duration::from_secs(cfg.chain_cache_secs),
+        ),
+        subscan: Subscan::new(http.clone(), cfg.subscan_api_key.clone()),
+        ipfs: IpfsGateway::new(http, cfg.ipfs_gateway_url.clone()),
+        chain_rpc,
+        watchdog: Watchdog::new(),
     });
 
     // Spawn the background channel liveness checker *before* loading the
     // playlist so it is ready to receive the notify signal.
-    channel_checker::start_background_checker(
+    let stall_grace = Duration::from_secs(cfg.watchdog_stall_secs);
+
+    state
+        .watchdog
+        .register(watchdog::CHANNEL_CHECKER, probe_interval + stall_grace);
+    let checker = channel_checker::start_background_checker(
         Arc::clone(&state),
-        Duration::from_secs(cfg.probe_interval_mins * 60),
-        Duration::from_secs(cfg.probe_timeout_secs),
+        probe_interval,
+        probe_timeout,
+    );
+    state
+        .watchdog
+        .attach(watchdog::CHANNEL_CHECKER, checker.abort_handle());
+
+    let mut restarts: HashMap<&'static str, RestartFn> = HashMap::new();
+    restarts.insert(
+        watchdog::CHANNEL_CHECKER,
+        Box::new(move |state| {
+            channel_checker::start_background_checker(state, probe_interval, probe_timeout)
+        }),
     );
 
-    // If a source URL is configured, fetch and parse the M3U on startup,
-    // then trigger an immediate liveness check.
-    if !cfg.m3u_source_url.is_empty() {
-        if let Err(e) = fetch_and_load_playlist(&cfg.m3u_source_url, &state).await {
-            tracing::error!("Failed to load initial playlist: {e}");
-        } else {
-            state.check_now.notify_one();
+    // Reload the M3U sources on a schedule, and on request. Only a scheduled
+    // refresher has a heartbeat deadline to watch.
+    let refresh_interval = (cfg.m3u_refresh_interval_mins > 0)
+        .then(|| Duration::from_secs(cfg.m3u_refresh_interval_mins * 60));
+    let refresher = sources::start_source_refresher(Arc::clone(&state), refresh_interval);
+    if let Some(interval) = refresh_interval {
+        state
+            .watchdog
+            .register(watchdog::SOURCE_REFRESHER, interval + stall_grace);
+        state
+            .watchdog
+            .attach(watchdog::SOURCE_REFRESHER, refresher.abort_handle());
+        restarts.insert(
+            watchdog::SOURCE_REFRESHER,
+            Box::new(move |state| sources::start_source_refresher(state, refresh_interval)),
+        );
+    }
+    // Restore saved state, then keep saving it as it changes.
+    let mut restored = false;
+    let storage = storage::open(&cfg).await.unwrap_or_else(|e| {
+        tracing::error!(
+            "Failed to open {:?} storage, keeping state in memory: {e}",
+            cfg.storage_backend
+        );
+        None
+    });
+    if let Some(storage) = storage {
+        match storage.load().await {
+            Ok(Some(snapshot)) => {
+                info!(
+                    "Restored {} channels and {} named playlists from {:?} storage",
+                    snapshot.playlist.channels.len(),
+                    snapshot.playlists.len(),
+                    cfg.storage_backend
+                );
+                restored = !snapshot.playlist.channels.is_empty();
+                storage::restore(&state, snapshot).await;
+            }
+            Ok(None) => info!("No saved state yet, starting fresh"),
+            Err(e) => tracing::warn!("Failed to load saved state: {e}"),
+        }
+        storage::start_persister(
+            Arc::clone(&state),
+            storage,
+            Duration::from_secs(cfg.storage_debounce_secs),
+        );
+    }
+
+    // Re-check a restored playlist right away. Otherwise, if sources are
+    // configured, fetch and merge them on startup, then trigger an
+    // immediate liveness check.
+    if restored {
+        state.check_now.notify_one();
+    } else if !state.sources.read().await.config.sources.is_empty() {
+        match sources::load_sources(&state).await {
+            Ok(diff) => {
+                info!("Loaded {} channels from M3U sources", diff.added);
+                state.check_now.notify_one();
+            }
+            Err(e) => tracing::error!("Failed to load initial playlist: {e}"),
         }
     }
 
+    // Follow the chain for new playlist publications.
+    if let (Some(address), Some(ws_url), Some(rpc)) = (
+        cfg.chain_watch_address.clone(),
+        cfg.chain_ws_url.clone(),
+        state.chain_rpc.clone(),
+    ) {
+        match signature::decode_ss58(&address) {
+            Ok(signer) => {
+                state.watchdog.register(
+                    watchdog::CHAIN_WATCHER,
+                    chain_watcher::MAX_SILENCE + stall_grace,
+                );
+                let watcher = chain_watcher::start_chain_watcher(
+                    Arc::clone(&state),
+                    rpc.clone(),
+                    ws_url.clone(),
+                    signer,
+                );
+                state
+                    .watchdog
+                    .attach(watchdog::CHAIN_WATCHER, watcher.abort_handle());
+                restarts.insert(
+                    watchdog::CHAIN_WATCHER,
+                    Box::new(move |state| {
+                        chain_watcher::start_chain_watcher(
+                            state,
+                            rpc.clone(),
+                            ws_url.clone(),
+                            signer,
+                        )
+                    }),
+                );
+            }
+            Err(_) => {
+                tracing::warn!("Not watching the chain: invalid CHAIN_WATCH_ADDRESS {address}")
+            }
+        }
+    }
+    watchdog::start_watchdog(
+        Arc::clone(&state),
+        Duration::from_secs(30),
+        cfg.watchdog_restart,
+        restarts,
+    );
+
     if cfg.epg_enabled {
         info!("EPG enabled (on-demand via iptv-org API)");
     }
+    if cfg.api_key.is_none() {
+        tracing::warn!("API_KEY is unset: anyone who can reach the server can change the playlist");
+    }
 
     // CORS: allow all origins during development.
     let cors = CorsLayer::new()
@@ -81,12 +277,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let app = Router::new()
         .route("/api/health", get(routes::health::health))
+        .route("/readyz", get(routes::health::readyz))
+        .route("/metrics", get(routes::metrics::metrics))
         .route("/api/playlist", get(routes::playlist::get_playlist).put(routes::playlist::update_playlist))
+        .route("/api/channels", get(routes::playlist::get_channels))
+        .route("/api/channels/:id/probe", get(routes::checker::get_channel_probe))
+        .route("/api/channels/:id/check", post(routes::checker::check_channel))
+        .route("/api/channels/:id/uptime", get(routes::checker::get_channel_uptime))
         .route("/api/playlist/m3u", get(routes::playlist::get_playlist_m3u))
+        .route("/api/playlist/export", get(routes::playlist::export_playlist))
+        .route("/api/playlist/groups", get(routes::playlist::get_groups))
+        .route("/api/playlist/reorder", post(routes::playlist::reorder_playlist))
+        .route("/api/playlist/dedupe", post(routes::channels::dedupe_playlist))
         .route("/api/playlist/upload", post(routes::playlist::upload_playlist))
+        .route("/api/playlist/import", post(routes::playlist::import_playlist))
+        .route("/api/import/xtream", post(routes::playlist::import_xtream))
+        .route("/player_api.php", get(routes::xtream::player_api))
+        .route("/get.php", get(routes::xtream::get_php))
+        .route("/xmltv.php", get(routes::xtream::xmltv_php))
+        .route("/live/:username/:password/:stream", get(routes::xtream::play_live))
+        .route("/api/playlist/channels", post(routes::channels::create_channel))
+        .route("/api/playlist/channels/:id", patch(routes::channels::update_channel).delete(routes::channels::delete_channel))
+        .route("/api/playlists", get(routes::playlist::list_playlists))
+        .route("/api/playlists/:slug", get(routes::playlist::get_named_playlist).post(routes::playlist::put_named_playlist).delete(routes::playlist::delete_named_playlist))
+        .route("/api/playlists/:slug/m3u", get(routes::playlist::get_named_playlist_m3u))
+        .route("/api/sources", get(routes::sources::get_sources).put(routes::sources::put_sources))
+        .route("/api/sources/refresh", post(routes::sources::refresh_sources))
+        .route("/api/jobs/:id", get(routes::jobs::get_job))
+        .route("/api/auth/register", post(routes::users::register))
+        .route("/api/auth/login", post(routes::users::login))
+        .route("/api/auth/logout", post(routes::users::logout))
+        .route("/api/auth/challenge", post(routes::users::challenge))
+        .route("/api/auth/signature", post(routes::users::signature_login))
+        .route("/api/me", get(routes::users::get_me).patch(routes::users::update_me))
+        .route("/api/me/address", put(routes::users::link_address).delete(routes::users::unlink_address))
+        .route("/api/me/favorites/:channel_id", put(routes::users::add_favorite).delete(routes::users::remove_favorite))
+        .route("/api/me/history", get(routes::users::get_history).post(routes::users::record_watch))
+        .route("/api/users", get(routes::users::list_users).post(routes::users::create_user))
+        .route("/api/users/:username", patch(routes::users::update_user).delete(routes::users::delete_user))
         .route("/api/chain/playlist", get(routes::chain::get_chain_playlist))
+        .route("/api/chain/registry/:address", get(routes::chain::get_registry_playlists))
+        .route("/api/events", get(routes::events::stream_events))
+        .route("/api/checks/run", post(routes::checker::run_checks))
+        .route("/api/checker/schedule", get(routes::checker::get_schedule).patch(routes::checker::update_schedule))
+        .route("/api/epg/prefetch", post(routes::epg::prefetch_epg))
+        .route("/api/epg/aliases", get(routes::epg::list_aliases))
+        .route("/api/epg/aliases/:channel_id", put(routes::epg::set_alias).delete(routes::epg::delete_alias))
+        .route("/api/epg/xmltv", get(routes::epg::get_xmltv))
+        .route("/api/epg/search", get(routes::epg::search_programmes))
         .route("/api/epg/:channel_id", get(routes::epg::get_schedule))
         .route("/api/epg/:channel_id/now", get(routes::epg::get_now_next))
+        .layer(middleware::from_fn_with_state(Arc::clone(&state), routes::auth::require_api_key))
         .layer(cors)
         .with_state(state);
 
@@ -96,18 +337,3 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
-
-/// Fetch an M3U playlist from `url` and load its channels into the shared state.
-async fn fetch_and_load_playlist(
-    url: &str,
-    state: &Arc<AppState>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let body = reqwest::get(url).await?.text().await?;
-    let channels = services::m3u_parser::parse_m3u(&body);
-    info!("Parsed {} channels from {}", channels.len(), url);
-
-    let mut playlist = state.playlist.write().await;
-    playlist.channels = channels;
-
-    Ok(())
-}
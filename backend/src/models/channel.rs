@@ -1,7 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Represents a single IPTV channel from an M3U playlist.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Channel {
     /// Unique identifier derived from the stream URL hash.
     pub id: String,
@@ -18,4 +19,199 @@ pub struct Channel {
     /// Optional EPG identifier used to match against XMLTV programme data.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tvg_id: Option<String>,
+    /// Alternate quality streams for the same channel, best quality first.
+    ///
+    /// Empty unless the variant-grouping pass merged several entries
+    /// (e.g. "Channel HD" and "Channel SD") into this canonical channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<ChannelVariant>,
+    /// HLS segment-level health score (0–100) from the last deep probe.
+    ///
+    /// Only set for HLS channels when `HLS_HEALTH_ENABLED` is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_score: Option<u8>,
+    /// Variant attributes of the HLS stream, from the last probe.
+    ///
+    /// Only set when `PROBE_MODE=hls` validated a master playlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_info: Option<StreamInfo>,
+    /// Stream metadata gathered by the checker.
+    #[serde(default, skip_serializing_if = "ChannelHealth::is_empty")]
+    pub health: ChannelHealth,
+    /// Manual sort position set by `POST /api/playlist/reorder`.
+    ///
+    /// M3U exports list channels by position; channels without one follow
+    /// in playlist order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+    /// Extended M3U attributes, exported as they were imported.
+    #[serde(flatten)]
+    pub attributes: M3uAttributes,
+}
+
+/// Extended M3U attributes understood by players like TiviMate and Kodi.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct M3uAttributes {
+    /// Channel number (`tvg-chno`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvg_chno: Option<String>,
+    /// EPG time shift in hours (`tvg-shift`), e.g. `"-2"` or `"+1.5"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvg_shift: Option<String>,
+    /// Catch-up mode (`catchup`), e.g. `"default"`, `"append"` or `"shift"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catchup: Option<String>,
+    /// Days of catch-up the provider keeps (`catchup-days`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catchup_days: Option<u32>,
+    /// URL template for catch-up streams (`catchup-source`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catchup_source: Option<String>,
+    /// Whether the stream is a radio station (`radio="true"`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub radio: bool,
+    /// User agent the stream must be requested with
+    /// (`#EXTVLCOPT:http-user-agent=`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Referrer the stream must be requested with
+    /// (`#EXTVLCOPT:http-referrer=`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referrer: Option<String>,
+}
+
+/// Attributes of the HLS variant a deep probe played, from its
+/// `#EXT-X-STREAM-INF` tag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StreamInfo {
+    /// Peak bitrate in bits per second (`BANDWIDTH`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth: Option<u64>,
+    /// Frame size, e.g. `"1920x1080"` (`RESOLUTION`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+}
+
+/// What the checker has learned about a channel's stream beyond liveness.
+///
+/// Liveness alone cannot tell a slow stream from a healthy one, or a
+/// stream that just dropped from one that has been gone for days.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChannelHealth {
+    /// Summary of the last probe; unset until the channel is probed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<HealthStatus>,
+    /// HTTP status code that decided the last probe, when a response arrived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_status_code: Option<u16>,
+    /// How long the last probe took, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Probes failed in a row; 0 while the channel is up.
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// When the channel was last probed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<DateTime<Utc>>,
+    /// When a probe last succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_ok: Option<DateTime<Utc>>,
+    /// When the current run of failed probes started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub down_since: Option<DateTime<Utc>>,
+    /// Why the last probe was refused, when the refusal says the stream is
+    /// up but out of reach from here; unset while up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<AccessIssue>,
+    /// Codecs, resolution and frame rate read by `ffprobe`.
+    ///
+    /// Only set when the backend is built with the `ffprobe` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<MediaInfo>,
+}
+
+impl ChannelHealth {
+    /// Whether nothing is known yet.
+    pub fn is_empty(&self) -> bool {
+        self.last_checked.is_none() && self.media.is_none()
+    }
+}
+
+/// A refusal that points at where or by whom a stream is watched, rather
+/// than at the stream being gone.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessIssue {
+    /// Refused with `403` or `451`: typically only served to some
+    /// countries, so a VPN rather than a new URL is the fix.
+    GeoBlocked,
+    /// Refused with `401` or `402`, or redirected to a login page: the
+    /// provider wants credentials or a subscription.
+    AuthRequired,
+}
+
+/// Summary of a channel's last probe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The stream answered promptly.
+    Up,
+    /// The stream answered, but took over half the probe timeout, or
+    /// failed fewer times than it takes to be marked dead.
+    Degraded,
+    /// The stream did not answer.
+    Down,
+    /// The stream has failed for so long that it is no longer probed,
+    /// until released through `PATCH /api/checker/schedule`.
+    Quarantined,
+}
+
+/// Stream metadata read by `ffprobe`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaInfo {
+    /// Codec of the video stream, e.g. `"h264"`.
+    pub video_codec: Option<String>,
+    /// Codec of the first audio stream, e.g. `"aac"`.
+    pub audio_codec: Option<String>,
+    /// Frame width in pixels.
+    pub width: Option<u32>,
+    /// Frame height in pixels.
+    pub height: Option<u32>,
+    /// Average frames per second.
+    pub fps: Option<f64>,
+    /// When the stream was probed.
+    pub probed_at: DateTime<Utc>,
+}
+
+/// A single quality variant of a channel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChannelVariant {
+    /// Normalised quality label (e.g. `"uhd"`, `"fhd"`, `"hd"`, `"sd"`).
+    pub quality: String,
+    /// Stream URL for this quality.
+    pub stream_url: String,
+}
+
+#[cfg(test)]
+impl Channel {
+    /// A test channel streaming from `http://example.com/{id}.m3u8`.
+    pub fn sample(id: &str, name: &str, group: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            group: group.to_string(),
+            stream_url: format!("http://example.com/{id}.m3u8"),
+            ..Default::default()
+        }
+    }
+
+    /// A test channel as the M3U parser reads it: its id is the hash of
+    /// `stream_url`.
+    pub fn parsed(name: &str, group: &str, stream_url: &str) -> Self {
+        Self {
+            id: crate::services::m3u_parser::hash_url(stream_url),
+            stream_url: stream_url.to_string(),
+            ..Self::sample("", name, group)
+        }
+    }
 }
@@ -4,6 +4,8 @@ use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::services::fetch::Validators;
+
 /// A single programme entry from an XMLTV EPG feed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpgProgram {
@@ -50,6 +52,43 @@ pub struct EpgNowNext {
     pub next: Option<EpgProgram>,
 }
 
+/// Compact "now playing" summary used to enrich channel listings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EpgNowSummary {
+    /// Title of the currently airing programme, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub now: Option<String>,
+    /// Title of the next programme, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// How far through the current programme we are, 0–100.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u8>,
+}
+
+/// Mapping from a playlist channel ID to the XMLTV channel carrying its guide.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EpgAlias {
+    /// The XMLTV channel ID whose schedule serves the playlist channel.
+    pub xmltv_id: String,
+    /// Whether the alias was set by an operator; manual aliases are never
+    /// overwritten by automatic matching.
+    #[serde(default)]
+    pub manual: bool,
+}
+
+/// What the last download of a guide yielded, kept with the validators it
+/// was served with so a `304 Not Modified` can be answered from the cache.
+#[derive(Debug, Clone, Default)]
+pub struct GuideRecord {
+    /// `ETag` and `Last-Modified` of the guide response.
+    pub validators: Validators,
+    /// XMLTV channel IDs the guide carried schedules for.
+    pub channel_ids: Vec<String>,
+    /// Lowercase display name → XMLTV channel ID, as parsed from the guide.
+    pub display_names: HashMap<String, String>,
+}
+
 /// How long cached EPG data stays fresh before a re-fetch (15 minutes).
 const EPG_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
 
@@ -58,8 +97,18 @@ const EPG_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
 pub struct EpgCache {
     /// Map from XMLTV channel ID to its schedule.
     pub schedules: HashMap<String, EpgSchedule>,
+    /// Map from playlist channel ID to the XMLTV channel it resolved to.
+    /// Kept across fetches so matching is only done once per channel.
+    pub aliases: HashMap<String, EpgAlias>,
     /// When the cache was last written to.
     pub last_updated: Option<Instant>,
+    /// When each playlist `url-tvg` guide was last fetched, by URL.
+    pub guides: HashMap<String, Instant>,
+    /// Lowercase display name → XMLTV channel ID across fetched playlist
+    /// guides, for matching channels by name.
+    pub guide_names: HashMap<String, String>,
+    /// Guides served with an `ETag` or `Last-Modified`, by URL.
+    pub guide_records: HashMap<String, GuideRecord>,
 }
 
 impl EpgCache {
@@ -67,7 +116,11 @@ impl EpgCache {
     pub fn new() -> Self {
         Self {
             schedules: HashMap::new(),
+            aliases: HashMap::new(),
             last_updated: None,
+            guides: HashMap::new(),
+            guide_names: HashMap::new(),
+            guide_records: HashMap::new(),
         }
     }
 
@@ -79,12 +132,58 @@ impl EpgCache {
         }
     }
 
+    /// Whether the playlist guide at `url` was fetched within the cache TTL.
+    pub fn guide_is_fresh(&self, url: &str) -> bool {
+        self.guides
+            .get(url)
+            .is_some_and(|ts| ts.elapsed() <= EPG_CACHE_TTL)
+    }
+
     /// Look up today's schedule for a channel, only if the cache is fresh.
+    ///
+    /// `channel_id` may be an XMLTV ID or a playlist channel ID with an alias.
     pub fn get_schedule(&self, channel_id: &str) -> Option<&EpgSchedule> {
         if self.is_stale() {
             return None;
         }
-        self.schedules.get(channel_id)
+        self.lookup(channel_id)
+    }
+
+    /// Find a schedule by alias first, then by XMLTV ID, ignoring freshness.
+    pub fn lookup(&self, channel_id: &str) -> Option<&EpgSchedule> {
+        self.aliases
+            .get(channel_id)
+            .and_then(|alias| self.schedules.get(&alias.xmltv_id))
+            .or_else(|| self.schedules.get(channel_id))
+    }
+
+    /// The XMLTV ID `channel_id` is aliased to, if any.
+    pub fn alias_for(&self, channel_id: &str) -> Option<&str> {
+        self.aliases.get(channel_id).map(|a| a.xmltv_id.as_str())
+    }
+
+    /// Record that `channel_id` is served by `xmltv_id`.
+    ///
+    /// Self-aliases are ignored, and an automatic alias never replaces a manual
+    /// one. Returns whether the alias table changed.
+    pub fn set_alias(&mut self, channel_id: &str, xmltv_id: &str, manual: bool) -> bool {
+        if channel_id == xmltv_id {
+            return false;
+        }
+        if let Some(existing) = self.aliases.get(channel_id) {
+            let unchanged = existing.xmltv_id == xmltv_id && existing.manual == manual;
+            if unchanged || (existing.manual && !manual) {
+                return false;
+            }
+        }
+        self.aliases.insert(
+            channel_id.to_string(),
+            EpgAlias {
+                xmltv_id: xmltv_id.to_string(),
+                manual,
+            },
+        );
+        true
     }
 
     /// Find the current and next programme for a channel based on `now`.
@@ -92,7 +191,7 @@ impl EpgCache {
         if self.is_stale() {
             return None;
         }
-        let schedule = self.schedules.get(channel_id)?;
+        let schedule = self.lookup(channel_id)?;
         let mut current = None;
         let mut next = None;
 
@@ -116,6 +215,32 @@ impl EpgCache {
             next,
         })
     }
+
+    /// Build a "now playing" summary from the first key with cached data.
+    ///
+    /// Only consults the cache; never triggers a fetch. Returns `None` when
+    /// none of `keys` has a fresh schedule or nothing is airing or upcoming.
+    pub fn get_now_summary(&self, keys: &[&str], now: DateTime<Utc>) -> Option<EpgNowSummary> {
+        let now_next = keys.iter().find_map(|key| self.get_now_next(key, now))?;
+        if now_next.now.is_none() && now_next.next.is_none() {
+            return None;
+        }
+
+        let progress = now_next.now.as_ref().and_then(|prog| {
+            let total = (prog.end - prog.start).num_seconds();
+            if total <= 0 {
+                return None;
+            }
+            let elapsed = (now - prog.start).num_seconds().clamp(0, total);
+            u8::try_from(elapsed * 100 / total).ok()
+        });
+
+        Some(EpgNowSummary {
+            now: now_next.now.map(|p| p.title),
+            next: now_next.next.map(|p| p.title),
+            progress,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +308,64 @@ mod tests {
         assert_eq!(result.next.as_ref().unwrap().title, "Afternoon News");
     }
 
+    #[test]
+    fn get_now_summary_uses_first_matching_key() {
+        let mut cache = EpgCache::new();
+        cache.last_updated = Some(Instant::now());
+        cache.schedules.insert(
+            "CNN.us".to_string(),
+            EpgSchedule {
+                channel_id: "CNN.us".to_string(),
+                programs: vec![
+                    make_program("CNN.us", 10, 12, "Morning News"),
+                    make_program("CNN.us", 12, 13, "Noon Report"),
+                ],
+            },
+        );
+
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 10, 30, 0).unwrap();
+        let summary = cache.get_now_summary(&["missing", "CNN.us"], now).unwrap();
+        assert_eq!(summary.now.as_deref(), Some("Morning News"));
+        assert_eq!(summary.next.as_deref(), Some("Noon Report"));
+        assert_eq!(summary.progress, Some(25));
+
+        assert!(cache.get_now_summary(&["missing"], now).is_none());
+    }
+
+    #[test]
+    fn alias_resolves_to_xmltv_schedule() {
+        let mut cache = EpgCache::new();
+        cache.last_updated = Some(Instant::now());
+        cache.schedules.insert(
+            "CNN.us".to_string(),
+            EpgSchedule {
+                channel_id: "CNN.us".to_string(),
+                programs: vec![make_program("CNN.us", 10, 11, "Morning News")],
+            },
+        );
+
+        assert!(cache.get_schedule("cnn-hd").is_none());
+        assert!(cache.set_alias("cnn-hd", "CNN.us", false));
+        assert_eq!(cache.get_schedule("cnn-hd").unwrap().channel_id, "CNN.us");
+
+        // Self-aliases and repeats are no-ops.
+        assert!(!cache.set_alias("CNN.us", "CNN.us", false));
+        assert!(!cache.set_alias("cnn-hd", "CNN.us", false));
+    }
+
+    #[test]
+    fn manual_alias_is_not_overwritten() {
+        let mut cache = EpgCache::new();
+        assert!(cache.set_alias("ch1", "Manual.fr", true));
+        assert!(!cache.set_alias("ch1", "Auto.fr", false));
+        assert_eq!(cache.alias_for("ch1"), Some("Manual.fr"));
+
+        // A manual correction replaces an automatic alias.
+        assert!(cache.set_alias("ch2", "Auto.fr", false));
+        assert!(cache.set_alias("ch2", "Fixed.fr", true));
+        assert_eq!(cache.alias_for("ch2"), Some("Fixed.fr"));
+    }
+
     #[test]
     fn get_now_next_unknown_channel() {
         let cache = EpgCache::new();
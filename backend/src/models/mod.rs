@@ -2,26 +2,81 @@ pub mod channel;
 pub mod epg;
 pub mod playlist;
 
+use std::collections::HashMap;
+
 use tokio::sync::{Notify, RwLock};
 
-pub use channel::Channel;
-pub use epg::EpgCache;
+pub use channel::{
+    AccessIssue, Channel, ChannelHealth, ChannelVariant, HealthStatus, MediaInfo, StreamInfo,
+};
+pub use epg::{EpgAlias, EpgCache};
 pub use playlist::Playlist;
 
 use crate::config::Config;
+use crate::services::accounts::Accounts;
+use crate::services::chain_networks::ChainNetworks;
+use crate::services::chain_rpc::ChainRpc;
+use crate::services::events::EventBus;
+use crate::services::import_jobs::ImportJobs;
+use crate::services::ipfs::IpfsGateway;
 use crate::services::iptv_org::IptvOrgIndex;
+use crate::services::probe_schedule::ProbeSchedule;
+use crate::services::search_index::SearchIndex;
+use crate::services::sources::SourceSet;
+use crate::services::subscan::Subscan;
+use crate::services::token_gate::TokenGate;
+use crate::services::uptime::UptimeHistory;
+use crate::services::watchdog::Watchdog;
 
 /// Shared application state holding the current playlist data and configuration.
 #[derive(Debug)]
 pub struct AppState {
     /// The current playlist protected by an async read-write lock.
     pub playlist: RwLock<Playlist>,
+    /// Named, curated playlists keyed by slug, served alongside the default one.
+    pub playlists: RwLock<HashMap<String, Playlist>>,
+    /// M3U sources the default playlist is built from, and their last load.
+    pub sources: RwLock<SourceSet>,
+    /// Wakes the source refresher to reload the M3U sources now.
+    pub refresh_sources: Notify,
     /// Application configuration (Subscan URL, etc.).
     pub config: Config,
+    /// HTTP client shared by outbound requests (see
+    /// [`shared_client`](crate::services::http_client::shared_client)).
+    pub http: reqwest::Client,
     /// Signals the background checker to run immediately.
     pub check_now: Notify,
+    /// Per-channel probe schedule driving the background checker.
+    pub probe_schedule: RwLock<ProbeSchedule>,
+    /// Wakes the background checker to re-read the probe schedule.
+    pub schedule_changed: Notify,
+    /// Hourly liveness history of each channel.
+    pub uptime: RwLock<UptimeHistory>,
+    /// Liveness flips, finished check cycles and playlist replacements,
+    /// streamed to clients by `GET /api/events`.
+    pub events: EventBus,
+    /// Tells the persister that playlists, pins, or EPG aliases changed and should be saved.
+    pub state_changed: Notify,
     /// Cached EPG data, refreshed on-demand per channel.
     pub epg_cache: RwLock<EpgCache>,
     /// Cached iptv-org channel/guide index, refreshed lazily.
     pub iptv_org_index: RwLock<IptvOrgIndex>,
+    /// Full-text index over channels and cached EPG programmes.
+    pub search_index: RwLock<SearchIndex>,
+    /// Background playlist imports and their progress.
+    pub import_jobs: RwLock<ImportJobs>,
+    /// User accounts and their sessions.
+    pub accounts: RwLock<Accounts>,
+    /// JSON-RPC client of the node at `CHAIN_RPC_URL`, when set.
+    pub chain_rpc: Option<ChainRpc>,
+    /// Networks on-chain playlists are looked up on, and recent lookups.
+    pub chain_networks: ChainNetworks,
+    /// Subscan API client shared by every network's lookups.
+    pub subscan: Subscan,
+    /// Gateway that playlists published as an IPFS CID are fetched from.
+    pub ipfs: IpfsGateway,
+    /// Token-gated groups and cached access decisions for chain-linked users.
+    pub token_gate: TokenGate,
+    /// Heartbeat tracker for long-running background tasks.
+    pub watchdog: Watchdog,
 }
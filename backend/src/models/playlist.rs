@@ -12,4 +12,8 @@ pub struct Playlist {
     pub last_checked: Option<String>,
     /// The original M3U source URL.
     pub source: String,
+    /// XMLTV guides named by the source M3U's `url-tvg` header, consulted
+    /// before iptv-org for this playlist's channels.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub epg_urls: Vec<String>,
 }
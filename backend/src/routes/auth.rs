@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::models::AppState;
+
+/// Header carrying the API key.
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Query parameter carrying the API key, for clients that cannot set headers.
+#[derive(Debug, Deserialize)]
+struct ApiKeyQuery {
+    api_key: Option<String>,
+}
+
+/// Whether `path` is `prefix` or below it.
+fn is_under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether `path` is account administration, which is closed without an
+/// API key.
+fn is_account_admin(path: &str) -> bool {
+    is_under(path, "/api/users")
+}
+
+/// Whether a request to `path` with `method` needs the API key.
+///
+/// Account administration always does. Sign-in and the `/api/me`
+/// endpoints never do, since they act for the signed-in user. Everything
+/// else does unless it only reads.
+fn needs_api_key(method: &Method, path: &str) -> bool {
+    if is_account_admin(path) {
+        return true;
+    }
+    if is_under(path, "/api/auth") || is_under(path, "/api/me") {
+        return false;
+    }
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// The API key a request carries: the `X-API-Key` header, an
+/// `Authorization: Bearer` token, or the `api_key` query parameter, in
+/// that order.
+fn provided_key(headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    let header_value = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    header_value(API_KEY_HEADER)
+        .or_else(|| header_value(header::AUTHORIZATION.as_str())?.strip_prefix("Bearer "))
+        .map(|key| key.trim().to_string())
+        .or_else(|| Query::<ApiKeyQuery>::try_from_uri(uri).ok()?.0.api_key)
+        .filter(|key| !key.is_empty())
+}
+
+/// Compare two keys or passwords in time independent of where they first
+/// differ.
+pub fn keys_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Whether a request carries the configured API key. Always `false` when
+/// no key is configured.
+pub fn has_api_key(state: &AppState, headers: &HeaderMap, uri: &Uri) -> bool {
+    state.config.api_key.as_deref().is_some_and(|expected| {
+        provided_key(headers, uri).is_some_and(|key| keys_match(&key, expected))
+    })
+}
+
+/// Middleware requiring [`Config::api_key`](crate::config::Config::api_key)
+/// on every request that is not a `GET`, `HEAD` or `OPTIONS`, and on
+/// account administration under `/api/users`.
+///
+/// Other read-only requests and user session endpoints always pass, and
+/// so does everything but account administration when no key is
+/// configured; account administration then gets `403 Forbidden`.
+/// Otherwise a missing or wrong key gets `401 Unauthorized`.
+pub async fn require_api_key(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(ref expected) = state.config.api_key else {
+        if is_account_admin(request.uri().path()) {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "Account administration requires API_KEY"})),
+            )
+                .into_response();
+        }
+        return next.run(request).await;
+    };
+    if !needs_api_key(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match provided_key(request.headers(), request.uri()) {
+        Some(key) if keys_match(&key, expected) => next.run(request).await,
+        Some(_) => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid API key"})),
+        )
+            .into_response(),
+        None => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "API key required"})),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn reads_key_from_header_bearer_or_query() {
+        let uri: Uri = "/api/playlist?api_key=from-query".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(provided_key(&headers, &uri).as_deref(), Some("from-query"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer from-bearer"),
+        );
+        assert_eq!(provided_key(&headers, &uri).as_deref(), Some("from-bearer"));
+
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("from-header"));
+        assert_eq!(provided_key(&headers, &uri).as_deref(), Some("from-header"));
+
+        let bare: Uri = "/api/playlist".parse().unwrap();
+        assert!(provided_key(&HeaderMap::new(), &bare).is_none());
+    }
+
+    #[test]
+    fn compares_keys() {
+        assert!(keys_match("secret", "secret"));
+        assert!(!keys_match("secreT", "secret"));
+        assert!(!keys_match("secret-longer", "secret"));
+    }
+
+    #[test]
+    fn guards_writes_and_account_admin() {
+        assert!(!needs_api_key(&Method::GET, "/api/playlist"));
+        assert!(needs_api_key(&Method::DELETE, "/api/playlist/channels/a"));
+        assert!(needs_api_key(&Method::GET, "/api/users"));
+        assert!(needs_api_key(&Method::PATCH, "/api/users/alice"));
+        assert!(!needs_api_key(&Method::POST, "/api/auth/login"));
+        assert!(!needs_api_key(&Method::PUT, "/api/me/favorites/a"));
+        assert!(needs_api_key(&Method::POST, "/api/meta"));
+    }
+
+    #[test]
+    fn recognises_account_admin() {
+        assert!(is_account_admin("/api/users"));
+        assert!(is_account_admin("/api/users/alice"));
+        assert!(!is_account_admin("/api/usersx"));
+        assert!(!is_account_admin("/api/me"));
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
@@ -10,21 +10,41 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 use tracing::warn;
 
-use crate::models::{AppState, Channel};
+use crate::models::AppState;
+use crate::services::chain_networks::{ChainNetwork, DEFAULT_NETWORK};
+use crate::services::chain_playlist::{signed_remark, ChainRemark};
+use crate::services::chain_rpc::{blake2_256, decode_hex, ChainRpc, RpcError};
+use crate::services::subscan::{Subscan, SubscanError};
+use crate::services::{playlist_registry, signature};
+
+/// Number of blocks fetched per batch of RPC calls when scanning.
+const SCAN_BATCH: u32 = 50;
 
 /// Query parameters for the on-chain playlist lookup endpoint.
 #[derive(Debug, Deserialize)]
 pub struct ChainPlaylistQuery {
     /// Substrate address whose `system.remark_with_event` extrinsics to scan.
     pub address: Option<String>,
+    /// Name of the network to look on; the default network when absent.
+    pub network: Option<String>,
 }
 
 /// Look up a playlist published on-chain via `system.remark_with_event`.
 ///
-/// Queries the Subscan API for recent extrinsics from the given `address`,
-/// finds the newest remark prefixed with `IPTV:`, decodes it, and returns
-/// the playlist as JSON.  Returns `{"found": false}` when no matching
-/// remark is found or when the Subscan request fails.
+/// Finds the newest remark prefixed with `IPTV:` from the given `address`
+/// on `network`, decodes it, and returns the playlist as JSON. Remarks of
+/// the form `IPTV:ipfs://<cid>` are fetched from the IPFS gateway at
+/// `IPFS_GATEWAY_URL` and checked against their CID. The default
+/// network asks the Subscan API for the address's recent extrinsics with
+/// `CHAIN_PLAYLIST_SOURCE=subscan`, and reads the last `CHAIN_SCAN_BLOCKS`
+/// blocks from the node at `CHAIN_RPC_URL` with `rpc`; networks from
+/// `CHAIN_NETWORKS` use Subscan when they have a Subscan URL and their node
+/// otherwise. Responses are reused for `CHAIN_CACHE_SECS` per network and
+/// address.
+///
+/// Returns `{"found": false}` when no matching remark is found or when the
+/// lookup fails, and `400 Bad Request` for an unknown network or an address
+/// of another network.
 pub async fn get_chain_playlist(
     State(state): State<Arc<AppState>>,
     Query(params): Query<ChainPlaylistQuery>,
@@ -35,40 +55,127 @@ pub async fn get_chain_playlist(
             Json(json!({"error": "Missing required query parameter: address"})),
         )
     })?;
+    let name = params.network.as_deref().unwrap_or(DEFAULT_NETWORK);
+    let network = state.chain_networks.get(name).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Unknown network: {name}")})),
+        )
+    })?;
+    if let Some(prefix) = network.ss58_prefix {
+        if signature::ss58_prefix(&address).ok() != Some(prefix) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("Not an address on {name}")})),
+            ));
+        }
+    }
 
-    match fetch_chain_playlist(&state.config.subscan_api_url, &address).await {
-        Ok(result) => Ok(Json(result)),
+    if let Some(cached) = state.chain_networks.cached(name, &address) {
+        return Ok(Json(cached));
+    }
+    match lookup_chain_playlist(&state, network, &address).await {
+        Ok(result) => {
+            state.chain_networks.store(name, &address, result.clone());
+            Ok(Json(result))
+        }
         Err(e) => {
-            warn!("Subscan lookup failed for {address}: {e}");
+            warn!("On-chain playlist lookup failed for {address} on {name}: {e}");
             Ok(Json(json!({"found": false})))
         }
     }
 }
 
-/// Internal helper that calls the Subscan API and parses the response.
+/// Lists the playlists an address registered in the playlist-registry
+/// pallet, oldest first, read from the node at `CHAIN_RPC_URL`.
+///
+/// Each playlist carries its metadata, the channels listed on-chain and
+/// its published content versions. Returns `400 Bad Request` for an
+/// invalid address, `503 Service Unavailable` without `CHAIN_RPC_URL`, and
+/// `502 Bad Gateway` when the node cannot be read.
+///
+/// # Route
+///
+/// `GET /api/chain/registry/:address`
+pub async fn get_registry_playlists(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let owner = signature::decode_ss58(&address).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+    let rpc = state.chain_rpc.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": RpcError::NotConfigured.to_string()})),
+        )
+    })?;
+
+    match playlist_registry::playlists_of(rpc, &owner).await {
+        Ok(playlists) => Ok(Json(json!({"address": address, "playlists": playlists}))),
+        Err(e) => {
+            warn!("Registry lookup failed for {address}: {e}");
+            Err((
+                StatusCode::BAD_GATEWAY,
+                Json(json!({"error": format!("Registry lookup failed: {e}")})),
+            ))
+        }
+    }
+}
+
+/// A playlist remark found on-chain, with where it was found.
+struct FoundRemark {
+    remark: ChainRemark,
+    block_number: Option<u64>,
+    extrinsic_hash: String,
+}
+
+/// Look up the newest playlist published by `address` on `network` and
+/// build the response, fetching the playlist from IPFS when the remark
+/// names a CID.
+async fn lookup_chain_playlist(
+    state: &AppState,
+    network: &ChainNetwork,
+    address: &str,
+) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+    let found = match &network.subscan_api_url {
+        Some(subscan_api_url) => {
+            fetch_chain_playlist(&state.subscan, subscan_api_url, address).await?
+        }
+        None => {
+            let depth = state.config.chain_scan_blocks;
+            scan_chain_playlist(network.rpc.as_ref(), address, depth).await?
+        }
+    };
+    let Some(found) = found else {
+        return Ok(json!({"found": false}));
+    };
+    let playlist = found.remark.resolve(&state.ipfs).await?;
+    Ok(json!({
+        "found": true,
+        "playlist": {
+            "name": playlist.name,
+            "channels": playlist.channels,
+            "last_checked": null,
+            "source": "on-chain"
+        },
+        "cid": playlist.cid,
+        "block_number": found.block_number,
+        "extrinsic_hash": found.extrinsic_hash
+    }))
+}
+
+/// Internal helper that calls the Subscan API at `subscan_api_url` and
+/// finds the newest playlist remark in the response.
 async fn fetch_chain_playlist(
+    subscan: &Subscan,
     subscan_api_url: &str,
     address: &str,
-) -> Result<Value, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let url = format!("{subscan_api_url}/api/v2/scan/extrinsics");
-
-    let body = json!({
-        "module": "system",
-        "call": "remark_with_event",
-        "address": address,
-        "page": 0,
-        "row": 25
-    });
-
-    let resp = client
-        .post(&url)
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?
-        .json::<Value>()
-        .await?;
+) -> Result<Option<FoundRemark>, SubscanError> {
+    let resp = subscan.remark_extrinsics(subscan_api_url, address).await?;
 
     let extrinsics = resp
         .get("data")
@@ -77,7 +184,7 @@ async fn fetch_chain_playlist(
 
     let extrinsics = match extrinsics {
         Some(arr) => arr,
-        None => return Ok(json!({"found": false})),
+        None => return Ok(None),
     };
 
     // Walk extrinsics newest-first looking for an IPTV: remark.
@@ -115,39 +222,75 @@ async fn fetch_chain_playlist(
                 None => continue,
             };
 
-            if !decoded.starts_with("IPTV:") {
-                continue;
-            }
+            let remark = match ChainRemark::parse(&decoded) {
+                Some(r) => r,
+                None => continue,
+            };
 
-            let json_payload = &decoded["IPTV:".len()..];
+            return Ok(Some(FoundRemark {
+                remark,
+                block_number: block_num,
+                extrinsic_hash: extrinsic_hash.to_string(),
+            }));
+        }
+    }
 
-            let playlist_data: Value = match serde_json::from_str(json_payload) {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
+    Ok(None)
+}
 
-            let channels = parse_chain_channels(&playlist_data);
-            // On-chain compact format uses "n" for name
-            let playlist_name = playlist_data
-                .get("n")
-                .and_then(|n| n.as_str())
-                .unwrap_or("On-Chain Playlist");
+/// Look up the newest playlist remark signed by `address` in the last
+/// `depth` blocks of the node behind `rpc`, newest block first.
+async fn scan_chain_playlist(
+    rpc: Option<&ChainRpc>,
+    address: &str,
+    depth: u32,
+) -> Result<Option<FoundRemark>, RpcError> {
+    let rpc = rpc.ok_or(RpcError::NotConfigured)?;
+    let Ok(signer) = signature::decode_ss58(address) else {
+        return Ok(None);
+    };
 
-            return Ok(json!({
-                "found": true,
-                "playlist": {
-                    "name": playlist_name,
-                    "channels": channels,
-                    "last_checked": null,
-                    "source": "on-chain"
-                },
-                "block_number": block_num,
-                "extrinsic_hash": extrinsic_hash
-            }));
+    let best = rpc.best_block().await?;
+    let oldest = best.saturating_sub(depth.saturating_sub(1));
+    let mut end = best.saturating_add(1);
+    while end > oldest {
+        let start = end.saturating_sub(SCAN_BATCH).max(oldest);
+        let numbers: Vec<u32> = (start..end).rev().collect();
+        let hash_calls: Vec<_> = numbers
+            .iter()
+            .map(|n| ("chain_getBlockHash", json!([n])))
+            .collect();
+        let block_calls: Vec<_> = rpc
+            .batch(&hash_calls)
+            .await?
+            .into_iter()
+            .map(|hash| ("chain_getBlock", json!([hash])))
+            .collect();
+        let blocks = rpc.batch(&block_calls).await?;
+
+        for (number, block) in numbers.iter().zip(&blocks) {
+            let extrinsics = block
+                .pointer("/block/extrinsics")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            for extrinsic in extrinsics.iter().rev().filter_map(Value::as_str) {
+                let bytes = decode_hex(extrinsic)?;
+                let Some(remark) = signed_remark(&bytes, &signer).and_then(ChainRemark::parse)
+                else {
+                    continue;
+                };
+                return Ok(Some(FoundRemark {
+                    remark,
+                    block_number: Some(u64::from(*number)),
+                    extrinsic_hash: format!("0x{}", hex::encode(blake2_256(&bytes))),
+                }));
+            }
         }
+        end = start;
     }
 
-    Ok(json!({"found": false}))
+    Ok(None)
 }
 
 /// Decode a hex string (with or without `0x` prefix) into a UTF-8 string.
@@ -160,54 +303,6 @@ fn decode_hex_to_string(hex: &str) -> Option<String> {
     String::from_utf8(bytes).ok()
 }
 
-/// Build a list of [`Channel`] structs from the on-chain compact JSON payload.
-///
-/// The compact format uses short keys: `c` for the channel array, and within
-/// each channel: `n` (name), `s` (stream_url), `g` (group), `l` (logo_url).
-fn parse_chain_channels(data: &Value) -> Vec<Channel> {
-    let channels = match data.get("c").and_then(|c| c.as_array()) {
-        Some(arr) => arr,
-        None => return Vec::new(),
-    };
-
-    channels
-        .iter()
-        .enumerate()
-        .filter_map(|(i, ch)| {
-            let name = ch.get("n").and_then(|n| n.as_str())?;
-            let stream_url = ch.get("s").and_then(|u| u.as_str())?;
-
-            let group = ch
-                .get("g")
-                .and_then(|g| g.as_str())
-                .unwrap_or("On-Chain");
-
-            let logo_url = ch.get("l").and_then(|l| l.as_str());
-
-            let id = format!("chain-{i}-{}", hash_url(stream_url));
-
-            Some(Channel {
-                id,
-                name: name.to_string(),
-                group: group.to_string(),
-                logo_url: logo_url.map(String::from),
-                stream_url: stream_url.to_string(),
-                is_live: true,
-                tvg_id: None,
-            })
-        })
-        .collect()
-}
-
-/// Simple hash of a URL to generate a stable channel ID component.
-fn hash_url(url: &str) -> u64 {
-    let mut hash: u64 = 5381;
-    for byte in url.bytes() {
-        hash = hash.wrapping_mul(33).wrapping_add(u64::from(byte));
-    }
-    hash
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,79 +325,4 @@ mod tests {
         let hex = "0xfffe";
         assert_eq!(decode_hex_to_string(hex), None);
     }
-
-    #[test]
-    fn test_parse_chain_channels_valid() {
-        let data = json!({
-            "v": 1,
-            "n": "My Playlist",
-            "c": [
-                {
-                    "n": "Channel One",
-                    "s": "http://example.com/stream1.m3u8",
-                    "g": "News",
-                    "l": "http://example.com/logo1.png"
-                },
-                {
-                    "n": "Channel Two",
-                    "s": "http://example.com/stream2.m3u8",
-                    "g": "Sports",
-                    "l": null
-                }
-            ]
-        });
-
-        let channels = parse_chain_channels(&data);
-        assert_eq!(channels.len(), 2);
-
-        assert_eq!(channels[0].name, "Channel One");
-        assert_eq!(channels[0].stream_url, "http://example.com/stream1.m3u8");
-        assert_eq!(channels[0].group, "News");
-        assert_eq!(
-            channels[0].logo_url.as_deref(),
-            Some("http://example.com/logo1.png")
-        );
-
-        assert_eq!(channels[1].name, "Channel Two");
-        assert_eq!(channels[1].stream_url, "http://example.com/stream2.m3u8");
-        assert_eq!(channels[1].group, "Sports");
-        assert!(channels[1].logo_url.is_none());
-    }
-
-    #[test]
-    fn test_parse_chain_channels_missing_channels_key() {
-        let data = json!({"n": "Empty"});
-        let channels = parse_chain_channels(&data);
-        assert!(channels.is_empty());
-    }
-
-    #[test]
-    fn test_parse_chain_channels_skips_invalid() {
-        let data = json!({
-            "c": [
-                {"n": "Valid", "s": "http://example.com/ok.m3u8"},
-                {"n": "No URL"},
-                {"s": "http://example.com/no-name.m3u8"}
-            ]
-        });
-
-        let channels = parse_chain_channels(&data);
-        // Only the first has both name and stream_url
-        assert_eq!(channels.len(), 1);
-        assert_eq!(channels[0].name, "Valid");
-    }
-
-    #[test]
-    fn test_hash_url_deterministic() {
-        let url = "http://example.com/stream.m3u8";
-        assert_eq!(hash_url(url), hash_url(url));
-    }
-
-    #[test]
-    fn test_hash_url_different_for_different_urls() {
-        assert_ne!(
-            hash_url("http://example.com/a.m3u8"),
-            hash_url("http://example.com/b.m3u8")
-        );
-    }
 }
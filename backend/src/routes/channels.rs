@@ -0,0 +1,408 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::models::{AppState, Channel, ChannelHealth, Playlist};
+use crate::services::dedupe::{self, DedupePolicy};
+use crate::services::m3u_parser;
+
+/// Request body for [`create_channel`].
+#[derive(Debug, Deserialize)]
+pub struct NewChannel {
+    /// Display name of the channel.
+    pub name: String,
+    /// The HLS/MPEG-TS stream URL; the channel ID is derived from it.
+    pub stream_url: String,
+    /// Group or category; empty when omitted.
+    #[serde(default)]
+    pub group: String,
+    /// Optional URL to the channel logo image.
+    pub logo_url: Option<String>,
+    /// Optional EPG identifier.
+    pub tvg_id: Option<String>,
+}
+
+/// Request body for [`update_channel`].
+///
+/// Omitted fields are left unchanged. An empty `logo_url`, `tvg_id`,
+/// `user_agent` or `referrer` clears it.
+#[derive(Debug, Default, Deserialize)]
+pub struct ChannelUpdate {
+    /// New display name.
+    pub name: Option<String>,
+    /// New group or category.
+    pub group: Option<String>,
+    /// New logo URL, or `""` to remove the logo.
+    pub logo_url: Option<String>,
+    /// New stream URL. The channel keeps its ID and is re-probed.
+    pub stream_url: Option<String>,
+    /// New EPG identifier, or `""` to remove it.
+    pub tvg_id: Option<String>,
+    /// User agent to request the stream with, or `""` to remove it.
+    pub user_agent: Option<String>,
+    /// Referrer to request the stream with, or `""` to remove it.
+    pub referrer: Option<String>,
+}
+
+fn bad_request(message: &str) -> (StatusCode, Json<Value>) {
+    (StatusCode::BAD_REQUEST, Json(json!({"error": message})))
+}
+
+fn not_found(id: &str) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Channel not found", "channel_id": id})),
+    )
+}
+
+fn duplicate_stream(existing_id: &str) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": "A channel with this stream URL already exists",
+            "channel_id": existing_id
+        })),
+    )
+}
+
+/// `None` for an empty string, so optional fields can be cleared.
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Apply `update` to `channel`, returning whether it needs probing again:
+/// its stream URL, or the headers it is requested with, changed.
+///
+/// A new stream URL resets the channel's liveness and health score until
+/// it is probed again.
+fn apply_update(channel: &mut Channel, update: ChannelUpdate) -> bool {
+    let mut headers_changed = false;
+    let attributes = &mut channel.attributes;
+    for (field, value) in [
+        (&mut attributes.user_agent, update.user_agent),
+        (&mut attributes.referrer, update.referrer),
+    ] {
+        if let Some(value) = value.map(non_empty).filter(|value| value != field) {
+            *field = value;
+            headers_changed = true;
+        }
+    }
+    if let Some(name) = update.name {
+        channel.name = name.trim().to_string();
+    }
+    if let Some(group) = update.group {
+        channel.group = group.trim().to_string();
+    }
+    if let Some(logo_url) = update.logo_url {
+        channel.logo_url = non_empty(logo_url);
+    }
+    if let Some(tvg_id) = update.tvg_id {
+        channel.tvg_id = non_empty(tvg_id);
+    }
+    match update.stream_url {
+        Some(url) if url.trim() != channel.stream_url => {
+            channel.stream_url = url.trim().to_string();
+            channel.is_live = false;
+            channel.health_score = None;
+            channel.stream_info = None;
+            channel.health = ChannelHealth::default();
+            true
+        }
+        _ => headers_changed,
+    }
+}
+
+/// Re-index and re-schedule the playlist after a channel edit, then wake
+/// the checker and the persister.
+async fn channels_changed(state: &AppState, playlist: &Playlist) {
+    state
+        .search_index
+        .write()
+        .await
+        .sync_channels(&playlist.channels);
+    state
+        .probe_schedule
+        .write()
+        .await
+        .sync(&playlist.channels, Utc::now());
+    state.schedule_changed.notify_one();
+    state.state_changed.notify_one();
+}
+
+/// Adds a single channel to the playlist.
+///
+/// Responds `201 Created` with the new channel, which is probed on the
+/// checker's next pass. Returns `400 Bad Request` when `name` or
+/// `stream_url` is empty, and `409 Conflict` when the stream URL is
+/// already in the playlist.
+///
+/// # Route
+///
+/// `POST /api/playlist/channels`
+pub async fn create_channel(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<NewChannel>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let name = body.name.trim();
+    let stream_url = body.stream_url.trim();
+    if name.is_empty() || stream_url.is_empty() {
+        return Err(bad_request("name and stream_url are required"));
+    }
+
+    let channel = Channel {
+        id: m3u_parser::hash_url(stream_url),
+        name: name.to_string(),
+        group: body.group.trim().to_string(),
+        logo_url: body.logo_url.and_then(non_empty),
+        stream_url: stream_url.to_string(),
+        is_live: false,
+        tvg_id: body.tvg_id.and_then(non_empty),
+        variants: Vec::new(),
+        health_score: None,
+        stream_info: None,
+        health: Default::default(),
+        position: None,
+        attributes: Default::default(),
+    };
+
+    let mut playlist = state.playlist.write().await;
+    if let Some(existing) = playlist
+        .channels
+        .iter()
+        .find(|c| c.id == channel.id || c.stream_url == channel.stream_url)
+    {
+        return Err(duplicate_stream(&existing.id));
+    }
+    playlist.channels.push(channel.clone());
+    channels_changed(&state, &playlist).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::to_value(channel).unwrap_or_default()),
+    ))
+}
+
+/// Edits a channel in place, e.g. to fix its logo URL or rename it.
+///
+/// Responds with the updated channel. A changed stream URL, user agent or
+/// referrer is probed on the checker's next pass. Returns `404 Not Found` for an unknown ID and
+/// `409 Conflict` if the new stream URL belongs to another channel.
+///
+/// # Route
+///
+/// `PATCH /api/playlist/channels/:id`
+pub async fn update_channel(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(update): Json<ChannelUpdate>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if update.name.as_deref().is_some_and(|n| n.trim().is_empty())
+        || update
+            .stream_url
+            .as_deref()
+            .is_some_and(|u| u.trim().is_empty())
+    {
+        return Err(bad_request("name and stream_url cannot be empty"));
+    }
+
+    let mut playlist = state.playlist.write().await;
+    if let Some(url) = update.stream_url.as_deref() {
+        if let Some(other) = playlist
+            .channels
+            .iter()
+            .find(|c| c.id != id && c.stream_url == url.trim())
+        {
+            return Err(duplicate_stream(&other.id));
+        }
+    }
+
+    let channel = playlist
+        .channels
+        .iter_mut()
+        .find(|c| c.id == id)
+        .ok_or_else(|| not_found(&id))?;
+    let reprobe = apply_update(channel, update);
+    let value = serde_json::to_value(&*channel).unwrap_or_default();
+
+    if reprobe {
+        state.probe_schedule.write().await.mark_due(&id, Utc::now());
+    }
+    // Keep the edit when the channel's source is refreshed.
+    state.sources.write().await.tracking.edited.insert(id);
+    channels_changed(&state, &playlist).await;
+
+    Ok(Json(value))
+}
+
+/// Removes a channel from the playlist.
+///
+/// Responds `204 No Content`, or `404 Not Found` for an unknown ID.
+///
+/// # Route
+///
+/// `DELETE /api/playlist/channels/:id`
+pub async fn delete_channel(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let mut playlist = state.playlist.write().await;
+    let before = playlist.channels.len();
+    playlist.channels.retain(|c| c.id != id);
+    if playlist.channels.len() == before {
+        return Err(not_found(&id));
+    }
+    channels_changed(&state, &playlist).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Request body for [`dedupe_playlist`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DedupeRequest {
+    /// Which duplicate to keep; prefers live, then https, streams by default.
+    #[serde(flatten)]
+    pub policy: DedupePolicy,
+    /// Report what would be removed without changing the playlist.
+    pub dry_run: bool,
+}
+
+/// Removes duplicate channels from the playlist and reports what was removed.
+///
+/// Channels sharing a stream URL, or a normalised name within a group, are
+/// merged into the one preferred by the request's policy (see
+/// [`dedupe::dedupe`]).
+///
+/// # Route
+///
+/// `POST /api/playlist/dedupe`
+pub async fn dedupe_playlist(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<DedupeRequest>,
+) -> Json<Value> {
+    let mut playlist = state.playlist.write().await;
+    let (kept, removed) = dedupe::dedupe(playlist.channels.clone(), body.policy);
+
+    if !body.dry_run && !removed.is_empty() {
+        playlist.channels = kept;
+        channels_changed(&state, &playlist).await;
+    }
+
+    Json(json!({
+        "dry_run": body.dry_run,
+        "removed_count": removed.len(),
+        "removed": removed,
+        "channels_count": if body.dry_run {
+            playlist.channels.len() - removed.len()
+        } else {
+            playlist.channels.len()
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A live channel with a logo, guide id and health score.
+    fn live_channel() -> Channel {
+        Channel {
+            logo_url: Some("http://example.com/cnn.png".to_string()),
+            is_live: true,
+            tvg_id: Some("CNN.us".to_string()),
+            health_score: Some(90),
+            ..Channel::sample("abc", "CNN", "News")
+        }
+    }
+
+    #[test]
+    fn update_changes_only_given_fields() {
+        let mut ch = live_channel();
+        let changed = apply_update(
+            &mut ch,
+            ChannelUpdate {
+                logo_url: Some("http://example.com/new.png".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert!(!changed);
+        assert_eq!(ch.logo_url.as_deref(), Some("http://example.com/new.png"));
+        assert_eq!(ch.name, "CNN");
+        assert!(ch.is_live);
+    }
+
+    #[test]
+    fn update_clears_optional_fields_with_empty_strings() {
+        let mut ch = live_channel();
+        apply_update(
+            &mut ch,
+            ChannelUpdate {
+                logo_url: Some(String::new()),
+                tvg_id: Some("  ".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(ch.logo_url, None);
+        assert_eq!(ch.tvg_id, None);
+    }
+
+    #[test]
+    fn new_request_headers_need_a_probe() {
+        let mut ch = live_channel();
+        let user_agent = || ChannelUpdate {
+            user_agent: Some("VLC/3.0.20".to_string()),
+            ..Default::default()
+        };
+        assert!(apply_update(&mut ch, user_agent()));
+        assert_eq!(ch.attributes.user_agent.as_deref(), Some("VLC/3.0.20"));
+        assert!(ch.is_live);
+
+        // The same user agent again is not a change.
+        assert!(!apply_update(&mut ch, user_agent()));
+        assert!(apply_update(
+            &mut ch,
+            ChannelUpdate {
+                user_agent: Some(String::new()),
+                ..Default::default()
+            },
+        ));
+        assert_eq!(ch.attributes.user_agent, None);
+    }
+
+    #[test]
+    fn new_stream_url_resets_liveness() {
+        let mut ch = live_channel();
+        let changed = apply_update(
+            &mut ch,
+            ChannelUpdate {
+                stream_url: Some("http://example.com/cnn-backup.m3u8".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert!(changed);
+        assert_eq!(ch.id, "abc");
+        assert!(!ch.is_live);
+        assert_eq!(ch.health_score, None);
+
+        // The same URL again is not a change.
+        let url = ch.stream_url.clone();
+        assert!(!apply_update(
+            &mut ch,
+            ChannelUpdate {
+                stream_url: Some(url),
+                ..Default::default()
+            },
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::models::{AppState, HealthStatus, MediaInfo};
+use crate::services::channel_checker::{self, ProbeOptions, ProbeTarget};
+#[cfg(feature = "ffprobe")]
+use crate::services::stream_probe;
+
+/// Returns the background checker's per-channel schedule.
+///
+/// Channels are listed soonest-due first, each with its next check time,
+/// interval class (`normal` or `pinned`), consecutive failure count, and
+/// whether it is quarantined (never checked until released).
+///
+/// # Route
+///
+/// `GET /api/checker/schedule`
+pub async fn get_schedule(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let playlist = state.playlist.read().await;
+    let schedule = state.probe_schedule.read().await;
+
+    let mut channels: Vec<Value> = playlist
+        .channels
+        .iter()
+        .filter_map(|ch| {
+            let entry = schedule.get(&ch.id)?;
+            Some(json!({
+                "channel_id": ch.id,
+                "name": ch.name,
+                "interval_class": schedule.class_of(&ch.id),
+                "next_check": entry.next_check,
+                "last_checked": entry.last_checked,
+                "consecutive_failures": entry.consecutive_failures,
+                "quarantined": schedule.is_quarantined(&ch.id),
+                "is_live": ch.is_live
+            }))
+        })
+        .collect();
+    channels.sort_by(|a, b| a["next_check"].as_str().cmp(&b["next_check"].as_str()));
+
+    Json(json!({
+        "interval_secs": schedule.normal_interval().as_secs(),
+        "pinned_interval_secs": schedule.pinned_interval().as_secs(),
+        "channels": channels
+    }))
+}
+
+/// Request body for [`update_schedule`].
+#[derive(Debug, Deserialize)]
+pub struct ScheduleUpdate {
+    /// Channel IDs to pin to high-frequency checking.
+    #[serde(default)]
+    pub pin: Vec<String>,
+    /// Channel IDs to return to the normal interval.
+    #[serde(default)]
+    pub unpin: Vec<String>,
+    /// Quarantined channel IDs to check again straight away.
+    #[serde(default)]
+    pub release: Vec<String>,
+}
+
+/// Pins channels to, or unpins them from, high-frequency checking, and
+/// releases channels from quarantine.
+///
+/// A released channel forgets its failures and is checked at once; it is
+/// quarantined again only after as many failed checks as the first time.
+/// Unknown channel IDs are rejected with `404 Not Found` and nothing is changed.
+///
+/// # Route
+///
+/// `PATCH /api/checker/schedule`
+pub async fn update_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(update): Json<ScheduleUpdate>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let now = Utc::now();
+    {
+        let mut playlist = state.playlist.write().await;
+        let unknown: Vec<&String> = update
+            .pin
+            .iter()
+            .chain(&update.unpin)
+            .chain(&update.release)
+            .filter(|id| !playlist.channels.iter().any(|c| &c.id == *id))
+            .collect();
+        if !unknown.is_empty() {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Unknown channel IDs", "channel_ids": unknown})),
+            ));
+        }
+
+        let mut schedule = state.probe_schedule.write().await;
+        for channel in &mut playlist.channels {
+            if update.release.contains(&channel.id)
+                && channel.health.status == Some(HealthStatus::Quarantined)
+            {
+                channel.health.status = None;
+                channel.health.consecutive_failures = 0;
+                schedule.set_quarantined(&channel.id, false, now);
+            }
+        }
+        schedule.sync(&playlist.channels, now);
+        for id in &update.unpin {
+            schedule.set_pinned(id, false, now);
+        }
+        for id in &update.pin {
+            schedule.set_pinned(id, true, now);
+        }
+    }
+
+    // Let the checker pick up pulled-forward checks without waiting out its sleep.
+    state.schedule_changed.notify_one();
+    state.state_changed.notify_one();
+
+    Ok(Json(json!({
+        "pinned": update.pin,
+        "unpinned": update.unpin,
+        "released": update.release
+    })))
+}
+
+/// Starts a check of every channel now, without waiting for the schedule.
+///
+/// Responds `202 Accepted` at once; a check cycle already running is
+/// canceled and started over (see
+/// [`start_background_checker`](channel_checker::start_background_checker)).
+/// Quarantined channels are left out.
+///
+/// # Route
+///
+/// `POST /api/checks/run`
+pub async fn run_checks(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    state.check_now.notify_one();
+    (StatusCode::ACCEPTED, Json(json!({"status": "accepted"})))
+}
+
+/// Probes one channel now and returns the result.
+///
+/// The probe is the checker's own (see [`channel_checker::check_streams`]),
+/// with its retries but without the start delay, and is recorded on the
+/// channel as a scheduled one would be, unless the channel's stream URL
+/// changed meanwhile. Returns `404 Not Found` for an unknown ID.
+///
+/// # Route
+///
+/// `POST /api/channels/:id/check`
+pub async fn check_channel(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let target = {
+        let playlist = state.playlist.read().await;
+        let channel = playlist
+            .channels
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Channel not found", "channel_id": id})),
+                )
+            })?;
+        ProbeTarget::of(channel, &state.config.probe_headers)
+    };
+
+    let options = ProbeOptions {
+        jitter: Duration::ZERO,
+        ..ProbeOptions::from_config(&state.config)
+    };
+    let probe =
+        channel_checker::check_streams(&state.http, std::slice::from_ref(&target), &options)
+            .await
+            .remove(0);
+
+    let now = Utc::now();
+    let (applied, health) = {
+        let mut playlist = state.playlist.write().await;
+        let mut schedule = state.probe_schedule.write().await;
+        let mut uptime = state.uptime.write().await;
+        let outcome = HashMap::from([((id.as_str(), target.url.as_str()), probe.clone())]);
+        let applied = channel_checker::apply_outcome(
+            &mut playlist.channels,
+            &mut schedule,
+            &mut uptime,
+            &state.events,
+            &outcome,
+            &options,
+            now,
+        ) > 0;
+        let health = playlist
+            .channels
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.health.clone());
+        (applied, health)
+    };
+    if applied {
+        state.schedule_changed.notify_one();
+        state.state_changed.notify_one();
+    }
+
+    Ok(Json(json!({
+        "channel_id": id,
+        "stream_url": target.url,
+        "is_live": probe.is_live,
+        "status_code": probe.status_code,
+        "access": probe.access,
+        "latency_ms": u64::try_from(probe.latency.as_millis()).unwrap_or(u64::MAX),
+        "stream_info": probe.stream_info,
+        "checked_at": now,
+        "recorded": applied,
+        "health": health
+    })))
+}
+
+/// Returns a channel's uptime over the last 24 hours and 7 days, with an
+/// hourly series for the last 7 days (see
+/// [`UptimeReport`](crate::services::uptime::UptimeReport)).
+///
+/// Returns `404 Not Found` for an unknown ID.
+///
+/// # Route
+///
+/// `GET /api/channels/:id/uptime`
+pub async fn get_channel_uptime(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if !state
+        .playlist
+        .read()
+        .await
+        .channels
+        .iter()
+        .any(|c| c.id == id)
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Channel not found", "channel_id": id})),
+        ));
+    }
+
+    let report = state.uptime.read().await.report(&id, Utc::now());
+    Ok(Json(json!({
+        "channel_id": id,
+        "uptime_24h": report.uptime_24h,
+        "uptime_7d": report.uptime_7d,
+        "checks_24h": report.checks_24h,
+        "checks_7d": report.checks_7d,
+        "series": report.series
+    })))
+}
+
+/// Query parameters for [`get_channel_probe`].
+#[derive(Debug, Default, Deserialize)]
+pub struct ProbeQuery {
+    /// Run `ffprobe` again even when the channel was already probed.
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Returns a channel's stream metadata (codecs, resolution, frame rate).
+///
+/// The checker reads it with `ffprobe` once a channel is live. Channels not
+/// probed yet, or all of them with `?refresh=true`, are probed on the spot.
+/// Returns `404 Not Found` for an unknown ID, `502 Bad Gateway` when
+/// `ffprobe` cannot read the stream, and `501 Not Implemented` unless the
+/// backend is built with the `ffprobe` feature.
+///
+/// # Route
+///
+/// `GET /api/channels/:id/probe`
+pub async fn get_channel_probe(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ProbeQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let (stream_url, mut health) = {
+        let playlist = state.playlist.read().await;
+        let channel = playlist
+            .channels
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Channel not found", "channel_id": id})),
+                )
+            })?;
+        (channel.stream_url.clone(), channel.health.clone())
+    };
+
+    if query.refresh || health.media.is_none() {
+        let media = probe_media(&state, &stream_url).await?;
+        let mut playlist = state.playlist.write().await;
+        if let Some(channel) = playlist
+            .channels
+            .iter_mut()
+            .find(|c| c.id == id && c.stream_url == stream_url)
+        {
+            channel.health.media = Some(media.clone());
+            state.state_changed.notify_one();
+        }
+        health.media = Some(media);
+    }
+
+    Ok(Json(json!({"channel_id": id, "health": health})))
+}
+
+#[cfg(feature = "ffprobe")]
+async fn probe_media(
+    state: &AppState,
+    stream_url: &str,
+) -> Result<MediaInfo, (StatusCode, Json<Value>)> {
+    let timeout = std::time::Duration::from_secs(state.config.probe_timeout_secs);
+    stream_probe::probe(&state.config.ffprobe_path, stream_url, timeout)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({"error": e.to_string()})),
+            )
+        })
+}
+
+#[cfg(not(feature = "ffprobe"))]
+async fn probe_media(
+    _state: &AppState,
+    _stream_url: &str,
+) -> Result<MediaInfo, (StatusCode, Json<Value>)> {
+    Err((
+        StatusCode::NOT_IMPLEMENTED,
+        Json(json!({"error": "Stream probing needs the backend built with the `ffprobe` feature"})),
+    ))
+}
@@ -1,18 +1,29 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
 use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
 use chrono::{FixedOffset, Utc};
 use serde::Deserialize;
 use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 use tracing::{debug, info, warn};
 
-use crate::models::AppState;
-use crate::services::iptv_org;
+use crate::models::epg::{EpgSchedule, GuideRecord};
+use crate::models::{AppState, Channel, EpgAlias, EpgCache};
+use crate::routes::playlist::{find_playlist, ChannelView};
+use crate::services::epg_aliases;
+use crate::services::iptv_org::{self, IptvOrgIndex};
+use crate::services::xmltv_writer;
 
 /// Query parameters for EPG endpoints.
 #[derive(Debug, Deserialize)]
@@ -23,6 +34,21 @@ pub struct EpgQuery {
     pub tz: Option<String>,
 }
 
+/// Default number of results returned by `/api/epg/search`.
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Query parameters for the programme search endpoint.
+#[derive(Debug, Deserialize)]
+pub struct EpgSearchQuery {
+    /// Search terms, matched against programme titles and categories.
+    /// Every term must match the start of a word.
+    pub q: String,
+    /// Maximum number of programmes to return (default 50).
+    pub limit: Option<usize>,
+    /// Timezone offset for response times, as for [`EpgQuery::tz`].
+    pub tz: Option<String>,
+}
+
 /// Parse a timezone offset string (e.g., `"+0100"`) into a [`FixedOffset`].
 fn parse_tz_param(tz: &str) -> Option<FixedOffset> {
     let tz = tz.trim();
@@ -138,6 +164,64 @@ pub async fn get_schedule(
     ))
 }
 
+/// Query parameters for [`get_xmltv`].
+#[derive(Debug, Deserialize)]
+pub struct XmltvQuery {
+    /// Slug of a named playlist to export instead of the default one.
+    pub playlist: Option<String>,
+    /// Session token, as for the M3U export linking this guide.
+    pub token: Option<String>,
+}
+
+/// Returns the cached EPG for a playlist's channels as an XMLTV document.
+///
+/// This is the guide M3U exports link through `url-tvg`. Only the cache is
+/// consulted; channels whose guide was never fetched (see
+/// [`prefetch_epg`]) are listed without programmes. Channels are limited
+/// to the request's [`ChannelView`], as in the export. Returns
+/// `404 Not Found` for an unknown `?playlist=` slug.
+///
+/// # Route
+///
+/// `GET /api/epg/xmltv`
+pub async fn get_xmltv(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<XmltvQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let view = ChannelView::of(&state, &headers, &uri, query.token.as_deref()).await;
+    let channels = match query.playlist {
+        Some(ref slug) => {
+            let playlists = state.playlists.read().await;
+            view.visible(&find_playlist(&playlists, slug)?.channels)
+                .into_owned()
+        }
+        None => view
+            .visible(&state.playlist.read().await.channels)
+            .into_owned(),
+    };
+
+    let xml = render_guide(&*state.epg_cache.read().await, &channels);
+    Ok(([(header::CONTENT_TYPE, "application/xml")], xml))
+}
+
+/// The cached schedule of `channel`, by channel ID or `tvg-id`.
+pub fn cached_schedule<'a>(cache: &'a EpgCache, channel: &Channel) -> Option<&'a EpgSchedule> {
+    cache
+        .lookup(&channel.id)
+        .or_else(|| channel.tvg_id.as_deref().and_then(|id| cache.lookup(id)))
+}
+
+/// Render the cached schedules of `channels` as an XMLTV document.
+pub fn render_guide(cache: &EpgCache, channels: &[Channel]) -> String {
+    let entries: Vec<_> = channels
+        .iter()
+        .map(|ch| (ch, cached_schedule(cache, ch)))
+        .collect();
+    xmltv_writer::render(&entries)
+}
+
 /// Returns the currently airing and next programme for a channel.
 ///
 /// Fetches EPG data on-demand from iptv-org if not cached.
@@ -191,43 +275,89 @@ pub async fn get_now_next(
     ))
 }
 
-/// Fetch EPG data for a channel by resolving it through the iptv-org index.
+/// Searches cached programmes by title and category.
+///
+/// Only programmes that have not yet ended are returned, soonest first.
+/// Nothing is fetched: only guides already in the EPG cache are searched.
+///
+/// # Route
+///
+/// `GET /api/epg/search?q=...`
+pub async fn search_programmes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EpgSearchQuery>,
+) -> Json<Value> {
+    let now = Utc::now();
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let mut programs = {
+        let cache = state.epg_cache.read().await;
+        let hits = state.search_index.read().await.search_programmes(&query.q);
+        hits.iter()
+            .filter_map(|(key, program_id)| {
+                cache
+                    .schedules
+                    .get(key)?
+                    .programs
+                    .iter()
+                    .find(|p| &p.id == program_id)
+            })
+            .filter(|p| p.end > now)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    programs.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.id.cmp(&b.id)));
+    programs.truncate(limit);
+
+    let mut value = json!({"query": query.q, "programs": programs});
+    if let Some(ref offset) = query.tz.as_deref().and_then(parse_tz_param) {
+        apply_tz_to_schedule(&mut value, offset);
+    }
+    Json(value)
+}
+
+/// Fetch EPG data for a channel, from the playlist's own guides or by
+/// resolving it through the iptv-org index.
 ///
-/// 1. Ensures the iptv-org index is loaded (lazy init)
-/// 2. Finds the channel's iptv-org ID (by tvg_id or name)
-/// 3. Fetches the XMLTV guide and caches all programmes from it
+/// 1. Looks the channel up in the playlist's `url-tvg` guides, if any
+/// 2. Ensures the iptv-org index is loaded (lazy init)
+/// 3. Finds the channel's iptv-org ID (by tvg_id or name)
+/// 4. Fetches the XMLTV guide and caches all programmes from it
 async fn fetch_epg_for_channel(
     state: &Arc<AppState>,
     channel_id: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
-
-    // Ensure the iptv-org index is loaded.
-    {
-        let needs_refresh = state.iptv_org_index.read().await.is_stale();
-        if needs_refresh {
-            let mut index = state.iptv_org_index.write().await;
-            // Double-check after acquiring write lock.
-            if index.is_stale() {
-                iptv_org::refresh_index(&client, &mut index).await?;
-            }
-        }
-    }
+    let client = state.http.clone();
 
     // Find the channel in our M3U playlist to get tvg_id and name.
-    let (tvg_id, name) = {
+    let (tvg_id, name, guide_urls) = {
         let playlist = state.playlist.read().await;
         let channel = playlist.channels.iter().find(|ch| {
-            ch.tvg_id.as_deref() == Some(channel_id)
-                || ch.id == channel_id
-                || ch.name == channel_id
+            ch.tvg_id.as_deref() == Some(channel_id) || ch.id == channel_id || ch.name == channel_id
         });
-        match channel {
+        let (tvg_id, name) = match channel {
             Some(ch) => (ch.tvg_id.clone(), ch.name.clone()),
             None => (Some(channel_id.to_string()), channel_id.to_string()),
-        }
+        };
+        (tvg_id, name, playlist.epg_urls.clone())
     };
 
+    if !guide_urls.is_empty()
+        && fetch_playlist_guides(
+            state,
+            &client,
+            &guide_urls,
+            channel_id,
+            tvg_id.as_deref(),
+            &name,
+        )
+        .await
+    {
+        return Ok(());
+    }
+
+    ensure_index(state, &client).await?;
+
     // Resolve to iptv-org channel ID.
     let iptv_org_id = {
         let index = state.iptv_org_index.read().await;
@@ -252,11 +382,17 @@ async fn fetch_epg_for_channel(
         "Resolved {channel_id} -> iptv_org_id={iptv_org_id}, m3u_name={name}, iptv_org_names={iptv_org_names:?}"
     );
 
-    // Fetch the guide XML and parse it.
-    let fetched = {
-        let index = state.iptv_org_index.read().await;
-        iptv_org::fetch_channel_epg(&client, &index, &iptv_org_id).await?
-    };
+    // Fetch the country guide and parse it. The country also sets the
+    // default timezone for timestamps without an offset.
+    let url = state
+        .iptv_org_index
+        .read()
+        .await
+        .get_guide_url(&iptv_org_id)
+        .ok_or_else(|| iptv_org::IptvOrgError::NoGuide(iptv_org_id.clone()))?;
+    let country = iptv_org::guide_country(&iptv_org_id);
+    info!("Fetching EPG for {iptv_org_id} from {url} (country={country})");
+    let fetched = fetch_guide(state, &client, &url, country).await?;
 
     let prog_count: usize = fetched.schedules.values().map(|s| s.programs.len()).sum();
     info!(
@@ -272,55 +408,606 @@ async fn fetch_epg_for_channel(
 
     // Ensure the original channel_id also maps to a schedule.
     // Try multiple matching strategies in order of specificity.
+    let mut aliases_changed = false;
     if !cache.schedules.contains_key(channel_id) {
-        let xmltv_id = if fetched.schedules.contains_key(&iptv_org_id) {
-            // 1. Direct iptv-org ID match in XMLTV data.
-            info!("Match strategy: direct iptv-org ID '{iptv_org_id}' found in XMLTV");
-            Some(iptv_org_id.clone())
-        } else {
-            // 2. Try display-name matching with multiple name candidates.
-            let mut candidates = vec![name.clone()];
-            candidates.extend(iptv_org_names.iter().cloned());
-            // Also try the channel_id itself and without the country suffix.
-            candidates.push(channel_id.to_string());
-            if let Some(prefix) = channel_id.rsplit('.').nth(1) {
-                candidates.push(prefix.to_string());
+        let xmltv_id = match_xmltv_id(
+            &cache,
+            &fetched,
+            &iptv_org_id,
+            &name,
+            &iptv_org_names,
+            channel_id,
+        );
+
+        if let Some(ref resolved_id) = xmltv_id {
+            if let Some(count) = cache.schedules.get(resolved_id).map(|s| s.programs.len()) {
+                info!("Aliasing EPG cache: {channel_id} -> {resolved_id} ({count} programmes)");
+                aliases_changed = cache.set_alias(channel_id, resolved_id, false);
             }
+        }
+    }
+
+    cache.last_updated = Some(std::time::Instant::now());
+    drop(cache);
+    index_fetched(state, &fetched).await;
+
+    if aliases_changed {
+        persist_aliases(state).await;
+    }
 
-            let mut resolved = None;
-            for candidate in &candidates {
-                let key = candidate.to_lowercase();
-                if let Some(xmltv_ch_id) = fetched.display_names.get(&key) {
-                    info!(
-                        "Match strategy: display-name '{candidate}' -> XMLTV channel '{xmltv_ch_id}'"
-                    );
-                    resolved = Some(xmltv_ch_id.clone());
-                    break;
+    Ok(())
+}
+
+/// Look `channel_id` up in the XMLTV guides its playlist's M3U named
+/// through `url-tvg`, fetching those not fetched within the cache TTL.
+///
+/// The channel matches a guide channel by `tvg_id`, then by display name.
+/// Returns whether a schedule for it is now cached.
+async fn fetch_playlist_guides(
+    state: &Arc<AppState>,
+    client: &reqwest::Client,
+    urls: &[String],
+    channel_id: &str,
+    tvg_id: Option<&str>,
+    name: &str,
+) -> bool {
+    for url in urls {
+        if state.epg_cache.read().await.guide_is_fresh(url) {
+            continue;
+        }
+        // Failures are recorded too, so a broken guide is retried once per
+        // TTL rather than on every request.
+        let fetched = fetch_guide(state, client, url, "").await;
+        let mut cache = state.epg_cache.write().await;
+        cache.guides.insert(url.clone(), std::time::Instant::now());
+        match fetched {
+            Ok(fetched) => {
+                info!(
+                    "Fetched playlist guide {url}: {} XMLTV channels",
+                    fetched.schedules.len()
+                );
+                for (id, schedule) in &fetched.schedules {
+                    cache.schedules.insert(id.clone(), schedule.clone());
                 }
+                cache.guide_names.extend(fetched.display_names.clone());
+                cache.last_updated = Some(std::time::Instant::now());
+                drop(cache);
+                index_fetched(state, &fetched).await;
             }
+            Err(e) => warn!("Failed to fetch playlist guide {url}: {e}"),
+        }
+    }
 
-            if resolved.is_none() {
-                warn!(
-                    "No XMLTV match for {channel_id}. Tried candidates: {candidates:?}. Available display names (sample): {:?}",
-                    fetched.display_names.keys().take(20).collect::<Vec<_>>()
-                );
+    let mut cache = state.epg_cache.write().await;
+    let xmltv_id = tvg_id
+        .filter(|id| cache.schedules.contains_key(*id))
+        .map(str::to_string)
+        .or_else(|| cache.guide_names.get(&name.to_lowercase()).cloned());
+    let Some(xmltv_id) = xmltv_id else {
+        return false;
+    };
+    if cache.set_alias(channel_id, &xmltv_id, false) {
+        drop(cache);
+        persist_aliases(state).await;
+    }
+    true
+}
+
+/// Fetch and parse the guide at `url` with
+/// [`iptv_org::fetch_and_parse_guide`], conditionally when an earlier
+/// download sent validators.
+///
+/// On `304 Not Modified` the guide is rebuilt from the cached schedules of
+/// its channels rather than downloaded and parsed again.
+async fn fetch_guide(
+    state: &Arc<AppState>,
+    client: &reqwest::Client,
+    url: &str,
+    country: &str,
+) -> Result<iptv_org::FetchedEpg, iptv_org::IptvOrgError> {
+    let validators = state
+        .epg_cache
+        .read()
+        .await
+        .guide_records
+        .get(url)
+        .map(|record| record.validators.clone());
+
+    match iptv_org::fetch_and_parse_guide(client, url, country, validators.as_ref()).await? {
+        Some((fetched, validators)) => {
+            let mut cache = state.epg_cache.write().await;
+            if validators.is_empty() {
+                cache.guide_records.remove(url);
+            } else {
+                let record = GuideRecord {
+                    validators,
+                    channel_ids: fetched.schedules.keys().cloned().collect(),
+                    display_names: fetched.display_names.clone(),
+                };
+                cache.guide_records.insert(url.to_string(), record);
             }
+            Ok(fetched)
+        }
+        None => {
+            let cache = state.epg_cache.read().await;
+            let Some(record) = cache.guide_records.get(url) else {
+                return Ok(iptv_org::FetchedEpg::default());
+            };
+            let schedules: HashMap<String, EpgSchedule> = record
+                .channel_ids
+                .iter()
+                .filter_map(|id| Some((id.clone(), cache.schedules.get(id)?.clone())))
+                .collect();
+            info!(
+                "Guide {url} not modified, reusing {} cached schedules",
+                schedules.len()
+            );
+            Ok(iptv_org::FetchedEpg {
+                schedules,
+                display_names: record.display_names.clone(),
+            })
+        }
+    }
+}
+
+/// Ensure the iptv-org index is loaded, refreshing it when stale.
+async fn ensure_index(
+    state: &Arc<AppState>,
+    client: &reqwest::Client,
+) -> Result<(), iptv_org::IptvOrgError> {
+    let needs_refresh = state.iptv_org_index.read().await.is_stale();
+    if needs_refresh {
+        let mut index = state.iptv_org_index.write().await;
+        // Double-check after acquiring write lock.
+        if index.is_stale() {
+            iptv_org::refresh_index(client, &mut index).await?;
+        }
+    }
+    Ok(())
+}
 
-            resolved
+/// Add freshly fetched schedules to the programme search index.
+async fn index_fetched(state: &Arc<AppState>, fetched: &iptv_org::FetchedEpg) {
+    let mut index = state.search_index.write().await;
+    for (id, schedule) in &fetched.schedules {
+        index.index_schedule(id, schedule);
+    }
+}
+
+/// Pick the XMLTV channel for `channel_id` in a fetched guide, reusing a
+/// previously recorded alias before falling back to [`resolve_xmltv_id`].
+fn match_xmltv_id(
+    cache: &EpgCache,
+    fetched: &iptv_org::FetchedEpg,
+    iptv_org_id: &str,
+    name: &str,
+    iptv_org_names: &[String],
+    channel_id: &str,
+) -> Option<String> {
+    match cache.alias_for(channel_id) {
+        Some(known) if fetched.schedules.contains_key(known) => {
+            debug!("Reusing EPG alias {channel_id} -> {known}");
+            Some(known.to_string())
+        }
+        _ => resolve_xmltv_id(fetched, iptv_org_id, name, iptv_org_names, channel_id),
+    }
+}
+
+/// Find the XMLTV channel in a fetched guide that corresponds to a playlist channel.
+///
+/// Tries a direct iptv-org ID match first, then display-name matching against
+/// the M3U name, the iptv-org names, the requested ID, and the ID without its
+/// country suffix.
+fn resolve_xmltv_id(
+    fetched: &iptv_org::FetchedEpg,
+    iptv_org_id: &str,
+    name: &str,
+    iptv_org_names: &[String],
+    channel_id: &str,
+) -> Option<String> {
+    if fetched.schedules.contains_key(iptv_org_id) {
+        // 1. Direct iptv-org ID match in XMLTV data.
+        info!("Match strategy: direct iptv-org ID '{iptv_org_id}' found in XMLTV");
+        return Some(iptv_org_id.to_string());
+    }
+
+    // 2. Try display-name matching with multiple name candidates.
+    let mut candidates = vec![name.to_string()];
+    candidates.extend(iptv_org_names.iter().cloned());
+    // Also try the channel_id itself and without the country suffix.
+    candidates.push(channel_id.to_string());
+    if let Some(prefix) = channel_id.rsplit('.').nth(1) {
+        candidates.push(prefix.to_string());
+    }
+
+    for candidate in &candidates {
+        let key = candidate.to_lowercase();
+        if let Some(xmltv_ch_id) = fetched.display_names.get(&key) {
+            info!("Match strategy: display-name '{candidate}' -> XMLTV channel '{xmltv_ch_id}'");
+            return Some(xmltv_ch_id.clone());
+        }
+    }
+
+    warn!(
+        "No XMLTV match for {channel_id}. Tried candidates: {candidates:?}. Available display names (sample): {:?}",
+        fetched.display_names.keys().take(20).collect::<Vec<_>>()
+    );
+    None
+}
+
+/// A playlist channel resolved to an iptv-org channel, awaiting its guide.
+#[derive(Debug)]
+struct PrefetchTarget {
+    /// Cache keys the schedule should be stored under (tvg_id first, then id).
+    keys: Vec<String>,
+    /// M3U display name.
+    name: String,
+    /// Resolved iptv-org channel ID.
+    iptv_org_id: String,
+    /// Primary and alternative iptv-org names, for display-name matching.
+    iptv_org_names: Vec<String>,
+}
+
+/// Channels grouped by the guide file that covers them.
+#[derive(Debug, Default)]
+struct PrefetchPlan {
+    /// Guide URL -> (country code, channels covered by that guide).
+    guides: BTreeMap<String, (String, Vec<PrefetchTarget>)>,
+    /// Channels with no iptv-org match.
+    unresolved: usize,
+}
+
+/// Resolve every channel through the iptv-org index and group them by guide URL,
+/// so each distinct guide is downloaded once.
+fn plan_prefetch(channels: &[Channel], index: &IptvOrgIndex) -> PrefetchPlan {
+    let mut plan = PrefetchPlan::default();
+
+    for ch in channels {
+        let resolved = index
+            .find_iptv_org_id(ch.tvg_id.as_deref(), &ch.name)
+            .and_then(|id| index.get_guide_url(&id).map(|url| (id, url)));
+        let Some((iptv_org_id, url)) = resolved else {
+            plan.unresolved += 1;
+            continue;
         };
 
-        if let Some(ref resolved_id) = xmltv_id {
-            if let Some(schedule) = cache.schedules.get(resolved_id).cloned() {
-                info!(
-                    "Aliasing EPG cache: {channel_id} -> {resolved_id} ({} programmes)",
-                    schedule.programs.len()
-                );
-                cache.schedules.insert(channel_id.to_string(), schedule);
+        let mut keys: Vec<String> = ch.tvg_id.iter().cloned().collect();
+        if !keys.contains(&ch.id) {
+            keys.push(ch.id.clone());
+        }
+
+        let country = iptv_org::guide_country(&iptv_org_id).to_string();
+        plan.guides
+            .entry(url)
+            .or_insert_with(|| (country, Vec::new()))
+            .1
+            .push(PrefetchTarget {
+                keys,
+                name: ch.name.clone(),
+                iptv_org_names: index.get_channel_names(&iptv_org_id),
+                iptv_org_id,
+            });
+    }
+
+    plan
+}
+
+/// Build a named SSE event carrying a JSON payload.
+fn progress_event(name: &str, data: Value) -> Event {
+    Event::default().event(name).data(data.to_string())
+}
+
+/// Prefetch EPG data for the whole playlist in one pass.
+///
+/// Resolves every channel to its country guide, downloads each distinct guide
+/// once, and caches the schedule of every matched channel. Progress is
+/// streamed as Server-Sent Events:
+///
+/// - `start`: `{channels, resolved, unresolved, guides}`
+/// - `guide`: `{index, total, url, channels, matched}` (plus `error` on failure)
+/// - `done`: `{guides, matched, unresolved, errors}`
+/// - `error`: `{error}` when the iptv-org index cannot be loaded
+///
+/// The prefetch keeps running if the client disconnects.
+///
+/// # Route
+///
+/// `POST /api/epg/prefetch`
+pub async fn prefetch_epg(
+    State(state): State<Arc<AppState>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<Value>)> {
+    if !state.config.epg_enabled {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "EPG fetching is disabled"})),
+        ));
+    }
+
+    let (tx, rx) = mpsc::channel(32);
+    tokio::spawn(run_prefetch(state, tx));
+
+    let stream = ReceiverStream::new(rx).map(Ok);
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Body of [`prefetch_epg`], reporting progress on `tx`.
+///
+/// Send failures are ignored so a disconnected client does not abort the prefetch.
+async fn run_prefetch(state: Arc<AppState>, tx: mpsc::Sender<Event>) {
+    let client = state.http.clone();
+
+    if let Err(e) = ensure_index(&state, &client).await {
+        warn!("EPG prefetch aborted, iptv-org index unavailable: {e}");
+        let _ = tx
+            .send(progress_event("error", json!({"error": e.to_string()})))
+            .await;
+        return;
+    }
+
+    let (channel_count, plan) = {
+        let playlist = state.playlist.read().await;
+        let index = state.iptv_org_index.read().await;
+        let plan = plan_prefetch(&playlist.channels, &index);
+        (playlist.channels.len(), plan)
+    };
+
+    let total = plan.guides.len();
+    info!(
+        "EPG prefetch: {channel_count} channels, {} unresolved, {total} guides",
+        plan.unresolved
+    );
+    let _ = tx
+        .send(progress_event(
+            "start",
+            json!({
+                "channels": channel_count,
+                "resolved": channel_count - plan.unresolved,
+                "unresolved": plan.unresolved,
+                "guides": total
+            }),
+        ))
+        .await;
+
+    let mut matched_total = 0usize;
+    let mut errors = 0usize;
+
+    for (i, (url, (country, targets))) in plan.guides.into_iter().enumerate() {
+        let fetched = match fetch_guide(&state, &client, &url, &country).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                warn!("EPG prefetch failed for {url}: {e}");
+                errors += 1;
+                let _ = tx
+                    .send(progress_event(
+                        "guide",
+                        json!({
+                            "index": i + 1,
+                            "total": total,
+                            "url": url,
+                            "channels": targets.len(),
+                            "matched": 0,
+                            "error": e.to_string()
+                        }),
+                    ))
+                    .await;
+                continue;
+            }
+        };
+
+        let mut matched = 0usize;
+        let mut aliases_changed = false;
+        {
+            let mut cache = state.epg_cache.write().await;
+            for (id, schedule) in &fetched.schedules {
+                cache.schedules.insert(id.clone(), schedule.clone());
+            }
+
+            for target in &targets {
+                let Some(xmltv_id) = match_xmltv_id(
+                    &cache,
+                    &fetched,
+                    &target.iptv_org_id,
+                    &target.name,
+                    &target.iptv_org_names,
+                    &target.keys[0],
+                ) else {
+                    continue;
+                };
+                if !fetched.schedules.contains_key(&xmltv_id) {
+                    continue;
+                }
+                for key in &target.keys {
+                    aliases_changed |= cache.set_alias(key, &xmltv_id, false);
+                }
+                matched += 1;
             }
+
+            cache.last_updated = Some(std::time::Instant::now());
         }
+        index_fetched(&state, &fetched).await;
+        matched_total += matched;
+
+        if aliases_changed {
+            persist_aliases(&state).await;
+        }
+
+        info!(
+            "EPG prefetch {}/{total}: {url} matched {matched}/{} channels",
+            i + 1,
+            targets.len()
+        );
+        let _ = tx
+            .send(progress_event(
+                "guide",
+                json!({
+                    "index": i + 1,
+                    "total": total,
+                    "url": url,
+                    "channels": targets.len(),
+                    "matched": matched
+                }),
+            ))
+            .await;
     }
 
-    cache.last_updated = Some(std::time::Instant::now());
+    info!("EPG prefetch complete: {matched_total} channels cached from {total} guides ({errors} failed)");
+    let _ = tx
+        .send(progress_event(
+            "done",
+            json!({
+                "guides": total,
+                "matched": matched_total,
+                "unresolved": plan.unresolved,
+                "errors": errors
+            }),
+        ))
+        .await;
+}
 
-    Ok(())
+/// Write the alias table to `EPG_ALIASES_PATH`, if configured, and flag
+/// it for the state persister.
+///
+/// Failures are logged; the in-memory table stays authoritative.
+async fn persist_aliases(state: &Arc<AppState>) {
+    state.state_changed.notify_one();
+    let Some(path) = state.config.epg_aliases_path.as_deref() else {
+        return;
+    };
+    let aliases = state.epg_cache.read().await.aliases.clone();
+    if let Err(e) = epg_aliases::save(std::path::Path::new(path), &aliases).await {
+        warn!("Failed to persist EPG aliases to {path}: {e}");
+    }
+}
+
+/// Lists the channel → XMLTV alias table, sorted by channel ID.
+///
+/// # Route
+///
+/// `GET /api/epg/aliases`
+pub async fn list_aliases(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let cache = state.epg_cache.read().await;
+    let aliases: BTreeMap<&String, &EpgAlias> = cache.aliases.iter().collect();
+    Json(json!({ "aliases": aliases }))
+}
+
+/// Request body for [`set_alias`].
+#[derive(Debug, Deserialize)]
+pub struct SetAliasRequest {
+    /// XMLTV channel ID that should serve the channel's guide.
+    pub xmltv_id: String,
+}
+
+/// Manually aliases a playlist channel to an XMLTV channel.
+///
+/// Manual aliases take precedence over, and are never replaced by, automatic matching.
+///
+/// # Route
+///
+/// `PUT /api/epg/aliases/:channel_id`
+pub async fn set_alias(
+    State(state): State<Arc<AppState>>,
+    Path(channel_id): Path<String>,
+    Json(body): Json<SetAliasRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let xmltv_id = body.xmltv_id.trim();
+    if xmltv_id.is_empty() || xmltv_id == channel_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "xmltv_id must be non-empty and differ from the channel ID"})),
+        ));
+    }
+
+    let changed = state
+        .epg_cache
+        .write()
+        .await
+        .set_alias(&channel_id, xmltv_id, true);
+    if changed {
+        persist_aliases(&state).await;
+    }
+
+    Ok(Json(json!({
+        "channel_id": channel_id,
+        "xmltv_id": xmltv_id,
+        "manual": true
+    })))
+}
+
+/// Removes a channel's alias so it is matched automatically again.
+///
+/// # Route
+///
+/// `DELETE /api/epg/aliases/:channel_id`
+pub async fn delete_alias(
+    State(state): State<Arc<AppState>>,
+    Path(channel_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let removed = state.epg_cache.write().await.aliases.remove(&channel_id);
+    if removed.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "No alias for channel", "channel_id": channel_id})),
+        ));
+    }
+    persist_aliases(&state).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::iptv_org::{IptvOrgChannel, IptvOrgGuide};
+
+    fn make_index() -> IptvOrgIndex {
+        let channel = |id: &str, name: &str| IptvOrgChannel {
+            id: id.to_string(),
+            name: name.to_string(),
+            alt_names: vec![],
+            country: String::new(),
+            categories: vec![],
+        };
+        let guide = |id: &str| IptvOrgGuide {
+            channel: Some(id.to_string()),
+            site: "example.com".to_string(),
+            lang: "en".to_string(),
+        };
+
+        let mut index = IptvOrgIndex::new();
+        index.update(
+            vec![
+                channel("TF1.fr", "TF1"),
+                channel("France2.fr", "France 2"),
+                channel("CNN.us", "CNN"),
+            ],
+            vec![guide("TF1.fr"), guide("France2.fr"), guide("CNN.us")],
+        );
+        index
+    }
+
+    #[test]
+    fn plan_groups_channels_by_guide() {
+        let channels = vec![
+            Channel {
+                tvg_id: Some("TF1.fr".to_string()),
+                ..Channel::sample("1", "TF1 HD", "Test")
+            },
+            Channel::sample("2", "France 2", "Test"),
+            Channel::sample("3", "CNN", "Test"),
+            Channel::sample("4", "Unknown", "Test"),
+        ];
+
+        let plan = plan_prefetch(&channels, &make_index());
+        assert_eq!(plan.unresolved, 1);
+        assert_eq!(plan.guides.len(), 2);
+
+        let (country, fr) = &plan.guides["https://iptv-epg.org/files/epg-fr.xml"];
+        assert_eq!(country, "fr");
+        assert_eq!(fr.len(), 2);
+        assert_eq!(fr[0].keys, vec!["TF1.fr".to_string(), "1".to_string()]);
+        assert_eq!(fr[1].keys, vec!["2".to_string()]);
+        assert_eq!(fr[1].iptv_org_id, "France2.fr");
+
+        let (_, us) = &plan.guides["https://iptv-epg.org/files/epg-us.xml"];
+        assert_eq!(us.len(), 1);
+    }
 }
@@ -0,0 +1,47 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::Stream;
+
+use crate::models::AppState;
+
+/// Streams what happens on the server as Server-Sent Events, so clients
+/// can stay current without polling:
+///
+/// - `channel_status`: `{channel_id, is_live, status, checked_at}` when a
+///   check finds a channel live that was not, or the other way round
+/// - `check_complete`: `{probed, live, total, checked_at}` after each
+///   background check cycle
+/// - `playlist_replaced`: `{source, channels}` when the default playlist
+///   is reloaded, replaced or extended by an import
+/// - `lagged`: `{missed}` when the client fell behind and events were
+///   dropped; it should re-fetch what it shows
+///
+/// Each event's data also has its name as `type`.
+///
+/// # Route
+///
+/// `GET /api/events`
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.events.subscribe();
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        let event = match receiver.recv().await {
+            Ok(event) => Event::default()
+                .event(event.name())
+                .data(json!(event).to_string()),
+            Err(RecvError::Lagged(missed)) => Event::default()
+                .event("lagged")
+                .data(json!({"type": "lagged", "missed": missed}).to_string()),
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), receiver))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
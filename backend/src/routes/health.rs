@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use serde_json::{json, Value};
 
 use crate::models::AppState;
@@ -17,3 +17,25 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<Value> {
         "channels": count
     }))
 }
+
+/// Readiness endpoint.
+///
+/// Returns `200 OK` when every background task registered with the
+/// watchdog is heartbeating, or `503 Service Unavailable` when any task
+/// has stalled or exited. The body lists per-task status either way.
+pub async fn readyz(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    let ready = state.watchdog.all_healthy();
+    let tasks = state.watchdog.statuses();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(json!({
+            "status": if ready { "ready" } else { "degraded" },
+            "tasks": tasks
+        })),
+    )
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::models::AppState;
+
+/// Returns the status of a background import job.
+///
+/// Includes per-stage progress (`parse`, `dedup`, `probe`) and, once the
+/// job has finished, its `summary` or `error`.
+///
+/// # Route
+///
+/// `GET /api/jobs/:id`
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let jobs = state.import_jobs.read().await;
+    let job = jobs.get(&id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Job not found", "job_id": id})),
+        )
+    })?;
+    Ok(Json(serde_json::to_value(job).unwrap_or_default()))
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+
+use crate::models::AppState;
+
+/// Prometheus metrics endpoint.
+///
+/// Exposes playlist gauges and per-task watchdog state in the Prometheus
+/// text exposition format.
+///
+/// # Route
+///
+/// `GET /metrics`
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let (total, live) = {
+        let playlist = state.playlist.read().await;
+        let live = playlist.channels.iter().filter(|c| c.is_live).count();
+        (playlist.channels.len(), live)
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP iptv_channels_total Channels in the playlist.");
+    let _ = writeln!(out, "# TYPE iptv_channels_total gauge");
+    let _ = writeln!(out, "iptv_channels_total {total}");
+    let _ = writeln!(
+        out,
+        "# HELP iptv_channels_live Channels that passed the last liveness check."
+    );
+    let _ = writeln!(out, "# TYPE iptv_channels_live gauge");
+    let _ = writeln!(out, "iptv_channels_live {live}");
+
+    let tasks = state.watchdog.statuses();
+    let _ = writeln!(
+        out,
+        "# HELP iptv_task_stalled Whether a background task missed its heartbeat."
+    );
+    let _ = writeln!(out, "# TYPE iptv_task_stalled gauge");
+    for t in &tasks {
+        let _ = writeln!(
+            out,
+            "iptv_task_stalled{{task=\"{}\"}} {}",
+            t.name,
+            u8::from(t.stalled)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "# HELP iptv_task_heartbeat_age_seconds Seconds since a background task last reported in."
+    );
+    let _ = writeln!(out, "# TYPE iptv_task_heartbeat_age_seconds gauge");
+    for t in &tasks {
+        let _ = writeln!(
+            out,
+            "iptv_task_heartbeat_age_seconds{{task=\"{}\"}} {}",
+            t.name, t.seconds_since_heartbeat
+        );
+    }
+    let _ = writeln!(
+        out,
+        "# HELP iptv_task_restarts_total Watchdog-initiated restarts of a background task."
+    );
+    let _ = writeln!(out, "# TYPE iptv_task_restarts_total counter");
+    for t in &tasks {
+        let _ = writeln!(
+            out,
+            "iptv_task_restarts_total{{task=\"{}\"}} {}",
+            t.name, t.restarts
+        );
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
@@ -1,4 +1,13 @@
+pub mod auth;
 pub mod chain;
+pub mod channels;
+pub mod checker;
 pub mod epg;
+pub mod events;
 pub mod health;
+pub mod jobs;
+pub mod metrics;
 pub mod playlist;
+pub mod sources;
+pub mod users;
+pub mod xtream;
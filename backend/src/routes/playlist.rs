@@ -1,31 +1,403 @@
-use std::collections::HashSet;
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use axum::{
     extract::{Multipart, Query, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode, Uri},
     response::IntoResponse,
     Json,
 };
-use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 
-use crate::models::AppState;
-use crate::services::m3u_parser;
+use axum::extract::Path;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::models::{AppState, Channel, EpgCache, Playlist};
+use crate::routes::auth::has_api_key;
+use crate::routes::users::{current_user, session_token};
+use crate::services::accounts::User;
+use crate::services::events::ServerEvent;
+use crate::services::import_jobs::{self, ImportMode, ImportSource};
+use crate::services::xtream::XtreamCredentials;
+use crate::services::{m3u_writer, playlist_export};
 
 /// Query parameters for the playlist upload endpoint.
 #[derive(Debug, Deserialize)]
 pub struct UploadQuery {
     /// Upload mode: "append" to add new channels, "replace" to overwrite (default).
     pub mode: Option<String>,
+    /// Fail the import when the file has malformed or skipped lines.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Query parameters for the M3U export endpoint.
+#[derive(Debug, Deserialize)]
+pub struct M3uQuery {
+    /// Preferred quality variant to export (e.g. `"fhd"`, `"hd"`, `"sd"`).
+    /// Channels without a matching variant use their canonical stream URL.
+    pub prefer: Option<String>,
+    /// Session token, for players that cannot send an `Authorization`
+    /// header; the export is then that user's playlist.
+    pub token: Option<String>,
+}
+
+/// Query parameters for the playlist and channel listing endpoints.
+#[derive(Debug, Deserialize)]
+pub struct PlaylistQuery {
+    /// When set to `"now"`, each channel gets an `epg` object with the
+    /// cached now/next programme titles and progress percentage.
+    pub with_epg: Option<String>,
+    /// Channel filter. On `/api/playlist`, a case-insensitive substring of
+    /// the channel name. On `/api/channels`, a full-text filter on name,
+    /// group, and `tvg_id` where every term must match the start of a word.
+    pub q: Option<String>,
+    /// Only channels in this group (case-insensitive).
+    pub group: Option<String>,
+    /// Only live (`true`) or dead (`false`) channels.
+    pub live: Option<bool>,
+    /// Only channels with (`true`) or without (`false`) a `tvg_id` or EPG alias.
+    pub has_epg: Option<bool>,
+    /// Order of the returned channels; playlist order when omitted
+    /// (`/api/playlist` only).
+    pub sort: Option<ChannelSort>,
+    /// 1-based page to return; every channel when neither this nor
+    /// `per_page` is set (`/api/playlist` only).
+    pub page: Option<usize>,
+    /// Channels per page, up to [`MAX_PER_PAGE`] (`/api/playlist` only).
+    pub per_page: Option<usize>,
+}
+
+/// Sort orders accepted by `GET /api/playlist?sort=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelSort {
+    /// By name, ignoring case.
+    Name,
+    /// By group, then name.
+    Group,
+    /// Live channels first, then by name.
+    Live,
+}
+
+/// Page size when only `page` is given.
+const DEFAULT_PER_PAGE: usize = 100;
+
+/// Largest accepted `per_page`.
+const MAX_PER_PAGE: usize = 1000;
+
+impl PlaylistQuery {
+    fn wants_epg_now(&self) -> bool {
+        self.with_epg.as_deref() == Some("now")
+    }
+
+    /// Whether `ch` passes the `group`, `live`, and `has_epg` filters.
+    fn matches(&self, ch: &Channel, cache: &EpgCache) -> bool {
+        self.group
+            .as_deref()
+            .is_none_or(|group| ch.group.eq_ignore_ascii_case(group))
+            && self.live.is_none_or(|live| ch.is_live == live)
+            && self
+                .has_epg
+                .is_none_or(|has_epg| has_epg_source(ch, cache) == has_epg)
+    }
+}
+
+/// Whether a guide can be looked up for `ch`: it has a `tvg_id` or an alias.
+fn has_epg_source(ch: &Channel, cache: &EpgCache) -> bool {
+    ch.tvg_id.is_some() || cache.alias_for(&ch.id).is_some()
+}
+
+/// Sort `channels` in place; ties keep their playlist order.
+fn sort_channels(channels: &mut [Channel], sort: ChannelSort) {
+    match sort {
+        ChannelSort::Name => channels.sort_by_cached_key(|ch| ch.name.to_lowercase()),
+        ChannelSort::Group => {
+            channels.sort_by_cached_key(|ch| (ch.group.to_lowercase(), ch.name.to_lowercase()))
+        }
+        ChannelSort::Live => {
+            channels.sort_by_cached_key(|ch| (!ch.is_live, ch.name.to_lowercase()))
+        }
+    }
+}
+
+/// The `(page, per_page)` to return, or `None` to return every channel.
+///
+/// Pages are 1-based; `page=0` is read as the first page and `per_page` is
+/// clamped to `1..=MAX_PER_PAGE`.
+fn page_bounds(page: Option<usize>, per_page: Option<usize>) -> Option<(usize, usize)> {
+    if page.is_none() && per_page.is_none() {
+        return None;
+    }
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    Some((page, per_page))
+}
+
+/// Whether `name` contains `needle`, ignoring case.
+fn name_contains(name: &str, needle: &str) -> bool {
+    name.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// The channels of a playlist a request may see.
+///
+/// Every route serving channels filters through this, so a group hidden
+/// from a request is hidden from all of them.
+pub struct ChannelView {
+    /// The signed-in user, whose group view applies.
+    user: Option<User>,
+    /// Without a session, the only groups shown; see
+    /// [`Accounts::anonymous_groups`](crate::services::accounts::Accounts::anonymous_groups).
+    anonymous_groups: Option<HashSet<String>>,
+    /// Token-gated groups the request does not unlock.
+    locked_groups: HashSet<String>,
+}
+
+impl ChannelView {
+    /// The view of a request: the signed-in user's groups, minus the
+    /// token-gated groups its linked address does not unlock. Requests with
+    /// the API key see every group. Requests without a session, such as
+    /// Xtream players, get no gated groups and, once any account is
+    /// restricted, only the groups every restricted account may see.
+    pub async fn of(
+        state: &AppState,
+        headers: &HeaderMap,
+        uri: &Uri,
+        query_token: Option<&str>,
+    ) -> Self {
+        let user = current_user(state, session_token(headers, query_token).as_deref()).await;
+        let api_key = has_api_key(state, headers, uri);
+        let anonymous_groups = if user.is_none() && !api_key {
+            state.accounts.read().await.anonymous_groups()
+        } else {
+            None
+        };
+        let locked_groups = if state.token_gate.is_enabled() && !api_key {
+            let address = user.as_ref().and_then(|u| u.address.as_deref());
+            state.token_gate.locked_groups(address).await
+        } else {
+            HashSet::new()
+        };
+        Self {
+            user,
+            anonymous_groups,
+            locked_groups,
+        }
+    }
+
+    /// Whether the request sees every channel.
+    fn is_unfiltered(&self) -> bool {
+        self.user.is_none() && self.anonymous_groups.is_none() && self.locked_groups.is_empty()
+    }
+
+    fn shows(&self, ch: &Channel) -> bool {
+        let entitled = match self.user {
+            Some(ref user) => user.sees(ch),
+            None => self
+                .anonymous_groups
+                .as_ref()
+                .is_none_or(|groups| groups.contains(&ch.group)),
+        };
+        entitled && !self.locked_groups.contains(&ch.group)
+    }
+
+    /// The channels of `channels` the request sees, borrowed when it sees
+    /// them all.
+    pub fn visible<'a>(&self, channels: &'a [Channel]) -> Cow<'a, [Channel]> {
+        if self.is_unfiltered() {
+            Cow::Borrowed(channels)
+        } else {
+            Cow::Owned(
+                channels
+                    .iter()
+                    .filter(|ch| self.shows(ch))
+                    .cloned()
+                    .collect(),
+            )
+        }
+    }
 }
 
 /// Returns the full playlist as a JSON array of channels.
-pub async fn get_playlist(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+///
+/// The `group`, `live`, `has_epg`, and `q` (name substring) filters are
+/// applied server-side, then `sort`, then `page`/`per_page`. `total` is the
+/// number of channels matching the filters, before paging. With
+/// `?with_epg=now`, channels are enriched from the EPG cache (see
+/// [`enrich_channels_with_epg`]).
+///
+/// With a user session, only the channels in that user's view are listed
+/// (see [`User::sees`]). Token-gated groups are listed only when the user's
+/// linked address meets their gate on-chain, or with the API key.
+pub async fn get_playlist(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<PlaylistQuery>,
+) -> impl IntoResponse {
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlist = state.playlist.read().await;
+    let cache = state.epg_cache.read().await;
+
+    let needle = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let mut channels: Vec<Channel> = playlist
+        .channels
+        .iter()
+        .filter(|ch| view.shows(ch))
+        .filter(|ch| query.matches(ch, &cache))
+        .filter(|ch| needle.is_none_or(|q| name_contains(&ch.name, q)))
+        .cloned()
+        .collect();
+    if let Some(sort) = query.sort {
+        sort_channels(&mut channels, sort);
+    }
+
+    let mut value = json!({
+        "name": playlist.name,
+        "last_checked": playlist.last_checked,
+        "source": playlist.source,
+        "epg_urls": playlist.epg_urls,
+        "total": channels.len()
+    });
+    if let Some((page, per_page)) = page_bounds(query.page, query.per_page) {
+        channels = channels
+            .into_iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .collect();
+        value["page"] = json!(page);
+        value["per_page"] = json!(per_page);
+    }
+
+    value["channels"] = if query.wants_epg_now() {
+        Value::Array(enrich_channels_with_epg(&channels, &cache))
+    } else {
+        serde_json::to_value(&channels).unwrap_or_default()
+    };
+
+    Json(value)
+}
+
+/// Returns just the playlist's channels as a JSON array.
+///
+/// Accepts the same `?with_epg=now`, `group`, `live`, and `has_epg`
+/// options as [`get_playlist`]. With `?q=`, only channels matching the
+/// search index are returned, in playlist order. Channels are limited to
+/// the request's view as for [`get_playlist`].
+///
+/// # Route
+///
+/// `GET /api/channels`
+pub async fn get_channels(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<PlaylistQuery>,
+) -> impl IntoResponse {
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlist = state.playlist.read().await;
+    let hits = match query.q.as_deref() {
+        Some(q) => Some(state.search_index.read().await.search_channels(q)),
+        None => None,
+    };
+    let cache = state.epg_cache.read().await;
+
+    let channels: Vec<Channel> = playlist
+        .channels
+        .iter()
+        .filter(|ch| view.shows(ch))
+        .filter(|ch| hits.as_ref().is_none_or(|hits| hits.contains(&ch.id)))
+        .filter(|ch| query.matches(ch, &cache))
+        .cloned()
+        .collect();
+
+    if query.wants_epg_now() {
+        return Json(Value::Array(enrich_channels_with_epg(&channels, &cache)));
+    }
+
+    Json(serde_json::to_value(channels).unwrap_or_default())
+}
+
+/// Channel counts for one playlist group.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct GroupSummary {
+    /// The group name; empty for ungrouped channels.
+    pub group: String,
+    /// Number of channels in the group.
+    pub channels: usize,
+    /// Number of those channels that are live.
+    pub live: usize,
+}
+
+/// Count channels and live channels per group, sorted by group name.
+fn summarize_groups(channels: &[Channel]) -> Vec<GroupSummary> {
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for ch in channels {
+        let entry = counts.entry(ch.group.as_str()).or_default();
+        entry.0 += 1;
+        if ch.is_live {
+            entry.1 += 1;
+        }
+    }
+
+    let mut groups: Vec<GroupSummary> = counts
+        .into_iter()
+        .map(|(group, (channels, live))| GroupSummary {
+            group: group.to_string(),
+            channels,
+            live,
+        })
+        .collect();
+    groups.sort_by(|a, b| a.group.cmp(&b.group));
+    groups
+}
+
+/// Returns each distinct channel group with its channel and live counts.
+///
+/// Only groups in the request's view are listed, as for [`get_playlist`].
+///
+/// # Route
+///
+/// `GET /api/playlist/groups`
+pub async fn get_groups(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Json<Value> {
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
     let playlist = state.playlist.read().await;
-    Json(serde_json::to_value(&*playlist).unwrap_or_default())
+    Json(json!({ "groups": summarize_groups(&view.visible(&playlist.channels)) }))
 }
 
-/// Accepts an M3U file upload and updates the current in-memory playlist.
+/// Serialize channels, attaching an `epg` now/next summary where cached.
+///
+/// Only the in-memory EPG cache is consulted — no guides are fetched.
+/// Channels are looked up by `tvg_id`, then id, then name, mirroring the
+/// keys the EPG routes cache schedules under.
+fn enrich_channels_with_epg(channels: &[Channel], cache: &EpgCache) -> Vec<Value> {
+    let now = Utc::now();
+    channels
+        .iter()
+        .map(|ch| {
+            let mut value = serde_json::to_value(ch).unwrap_or_default();
+            let mut keys: Vec<&str> = Vec::with_capacity(3);
+            if let Some(ref tvg_id) = ch.tvg_id {
+                keys.push(tvg_id);
+            }
+            keys.push(&ch.id);
+            keys.push(&ch.name);
+
+            if let Some(summary) = cache.get_now_summary(&keys, now) {
+                value["epg"] = serde_json::to_value(summary).unwrap_or_default();
+            }
+            value
+        })
+        .collect()
+}
+
+/// Accepts an M3U file upload and imports it in the background.
 ///
 /// The request must be a `multipart/form-data` with a field named `file`
 /// containing valid M3U content. An optional `mode` query parameter controls
@@ -33,12 +405,19 @@ pub async fn get_playlist(State(state): State<Arc<AppState>>) -> impl IntoRespon
 /// - `"replace"` (default): replaces the entire playlist with the uploaded channels.
 /// - `"append"`: adds new channels from the upload, skipping any whose
 ///   `stream_url` already exists in the current playlist.
+///
+/// Lines the parser cannot use are listed with their line number on the
+/// job. With `?strict=true` any such line fails the job instead, leaving
+/// the playlist unchanged.
+///
+/// Responds `202 Accepted` with a `job_id` as soon as the file is read;
+/// parsing, dedup, and probing are tracked at `GET /api/jobs/:id`.
 pub async fn upload_playlist(
     State(state): State<Arc<AppState>>,
     Query(query): Query<UploadQuery>,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let append_mode = query.mode.as_deref() == Some("append");
+    let mode = ImportMode::from_param(query.mode.as_deref());
 
     while let Some(field) = multipart
         .next_field()
@@ -54,59 +433,122 @@ pub async fn upload_playlist(
             let content = String::from_utf8(bytes.to_vec())
                 .map_err(|_| (StatusCode::BAD_REQUEST, "File is not valid UTF-8".to_string()))?;
 
-            let new_channels = m3u_parser::parse_m3u(&content);
-            let new_count = new_channels.len();
-
-            let total_channels = {
-                let mut playlist = state.playlist.write().await;
-
-                if append_mode {
-                    let existing_urls: HashSet<&str> = playlist
-                        .channels
-                        .iter()
-                        .map(|ch| ch.stream_url.as_str())
-                        .collect();
-
-                    let unique_new: Vec<_> = new_channels
-                        .into_iter()
-                        .filter(|ch| !existing_urls.contains(ch.stream_url.as_str()))
-                        .collect();
-
-                    let appended = unique_new.len();
-                    playlist.channels.extend(unique_new);
-                    playlist.source = "upload".to_string();
-
-                    let total = playlist.channels.len();
-
-                    // Trigger an immediate liveness check.
-                    state.check_now.notify_one();
-
-                    return Ok(Json(serde_json::json!({
-                        "status": "ok",
-                        "channels_loaded": appended,
-                        "total_channels": total
-                    })));
-                }
-
-                playlist.channels = new_channels;
-                playlist.source = "upload".to_string();
-                playlist.channels.len()
-            };
-
-            // Trigger an immediate liveness check for the new playlist.
-            state.check_now.notify_one();
-
-            return Ok(Json(serde_json::json!({
-                "status": "ok",
-                "channels_loaded": new_count,
-                "total_channels": total_channels
-            })));
+            let job_id = import_jobs::start_import(
+                &state,
+                ImportSource::Upload(content),
+                mode,
+                query.strict,
+            )
+            .await;
+            return Ok(accepted(job_id));
         }
     }
 
     Err((StatusCode::BAD_REQUEST, "Missing 'file' field".to_string()))
 }
 
+/// Request body for [`import_playlist`].
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    /// HTTP(S) URL of the M3U playlist to import.
+    pub url: String,
+    /// `"append"` or `"replace"` (default), as for uploads.
+    pub mode: Option<String>,
+    /// Fail on malformed lines, as for uploads.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Fetches an M3U playlist from a URL and imports it in the background.
+///
+/// Responds `202 Accepted` with a `job_id` to poll at `GET /api/jobs/:id`.
+///
+/// # Route
+///
+/// `POST /api/playlist/import`
+pub async fn import_playlist(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ImportRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let url = body.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "url must be an http(s) URL"})),
+        ));
+    }
+
+    let mode = ImportMode::from_param(body.mode.as_deref());
+    let source = ImportSource::Url(url.to_string());
+    let job_id = import_jobs::start_import(&state, source, mode, body.strict).await;
+    Ok(accepted(job_id))
+}
+
+/// Request body for [`import_xtream`].
+#[derive(Debug, Deserialize)]
+pub struct XtreamImportRequest {
+    #[serde(flatten)]
+    pub credentials: XtreamCredentials,
+    /// Also import the panel's VOD library.
+    #[serde(default)]
+    pub include_vod: bool,
+    /// `"append"` or `"replace"` (default), as for uploads.
+    pub mode: Option<String>,
+}
+
+/// Pulls the live streams (and optionally the VOD library) of an Xtream
+/// Codes account and imports them in the background.
+///
+/// The panel's `xmltv.php` guide is added to the playlist's EPG sources.
+/// Responds `202 Accepted` with a `job_id` to poll at `GET /api/jobs/:id`,
+/// or `400 Bad Request` when `server` is not an http(s) URL or the
+/// username or password is empty.
+///
+/// # Route
+///
+/// `POST /api/import/xtream`
+pub async fn import_xtream(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<XtreamImportRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let creds = &body.credentials;
+    let server = creds.server.trim();
+    if !(server.starts_with("http://") || server.starts_with("https://")) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "server must be an http(s) URL"})),
+        ));
+    }
+    if creds.username.is_empty() || creds.password.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "username and password are required"})),
+        ));
+    }
+
+    let mode = ImportMode::from_param(body.mode.as_deref());
+    let source = ImportSource::Xtream {
+        credentials: XtreamCredentials {
+            server: server.to_string(),
+            ..body.credentials
+        },
+        include_vod: body.include_vod,
+    };
+    let job_id = import_jobs::start_import(&state, source, mode, false).await;
+    Ok(accepted(job_id))
+}
+
+/// `202 Accepted` response pointing at an import job.
+fn accepted(job_id: String) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "status": "accepted",
+            "job_id": job_id
+        })),
+    )
+}
+
 /// Replaces the in-memory playlist with the provided JSON payload.
 ///
 /// Accepts a full `Playlist` object and overwrites the current state.
@@ -120,9 +562,18 @@ pub async fn update_playlist(
     {
         let mut playlist = state.playlist.write().await;
         *playlist = updated;
+        state
+            .search_index
+            .write()
+            .await
+            .sync_channels(&playlist.channels);
+        state
+            .events
+            .publish(ServerEvent::playlist_replaced(&playlist));
     }
 
     state.check_now.notify_one();
+    state.state_changed.notify_one();
 
     Json(serde_json::json!({
         "status": "ok",
@@ -133,32 +584,605 @@ pub async fn update_playlist(
 /// Returns the playlist formatted as an M3U file.
 ///
 /// The response uses `Content-Type: audio/x-mpegurl` so media players
-/// can consume it directly.
+/// can consume it directly. An optional `?prefer=` query parameter selects
+/// which quality variant's stream URL is emitted for grouped channels.
+///
+/// When EPG is enabled, the `#EXTM3U` header links the backend's own
+/// XMLTV guide through `url-tvg` (see [`guide_url`]).
+///
+/// With a user session, from the `Authorization` header or `?token=`, only
+/// the channels in that user's view are exported. Token-gated groups are
+/// filtered as for [`get_playlist`].
 pub async fn get_playlist_m3u(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<M3uQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
+    let guide = guide_url(&state, &headers, None, query.token.as_deref());
+    let view = ChannelView::of(&state, &headers, &uri, query.token.as_deref()).await;
     let playlist = state.playlist.read().await;
+    let channels = view.visible(&playlist.channels);
+    let m3u = m3u_writer::render(&channels, query.prefer.as_deref(), guide.as_deref());
 
-    let mut m3u = String::from("#EXTM3U\n");
+    Ok(([(header::CONTENT_TYPE, "audio/x-mpegurl")], m3u))
+}
 
-    for ch in &playlist.channels {
-        let tvg_id_attr = ch
-            .tvg_id
-            .as_deref()
-            .map(|id| format!(" tvg-id=\"{id}\""))
-            .unwrap_or_default();
+/// Query parameters for [`export_playlist`].
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// `enigma2`, `kodi`, `json` or `csv`.
+    pub format: String,
+    /// Preferred quality variant, as for the M3U export.
+    pub prefer: Option<String>,
+    /// Session token, as for the M3U export.
+    pub token: Option<String>,
+}
 
-        let logo_attr = ch
-            .logo_url
-            .as_deref()
-            .map(|url| format!(" tvg-logo=\"{url}\""))
-            .unwrap_or_default();
+/// Exports the playlist for players that cannot use plain M3U.
+///
+/// Formats:
+/// - `enigma2`: a userbouquet for Enigma2 receivers, one section per group.
+/// - `kodi`: an M3U for Kodi's PVR IPTV Simple Client whose `tvg-id`s match
+///   the XMLTV guide it links (see [`guide_url`]).
+/// - `json`: the playlist name and channels in export order.
+/// - `csv`: one row per channel.
+///
+/// Each format is sent as an attachment. Returns `400 Bad Request` for any
+/// other format. Channels are filtered as for [`get_playlist_m3u`].
+///
+/// # Route
+///
+/// `GET /api/playlist/export`
+pub async fn export_playlist(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let prefer = query.prefer.as_deref();
+    let guide = guide_url(&state, &headers, None, query.token.as_deref());
+    let view = ChannelView::of(&state, &headers, &uri, query.token.as_deref()).await;
+    let playlist = state.playlist.read().await;
+    let channels = view.visible(&playlist.channels);
+
+    let (content_type, filename, body) = match query.format.as_str() {
+        "enigma2" => (
+            "text/plain; charset=utf-8",
+            "userbouquet.iptv.tv",
+            playlist_export::enigma2_bouquet(&playlist.name, &channels, prefer),
+        ),
+        "kodi" => (
+            "audio/x-mpegurl",
+            "kodi.m3u",
+            playlist_export::kodi_m3u(&channels, prefer, guide.as_deref()),
+        ),
+        "json" => (
+            "application/json",
+            "playlist.json",
+            json!({
+                "name": playlist.name,
+                "channels": m3u_writer::export_order(&channels)
+            })
+            .to_string(),
+        ),
+        "csv" => (
+            "text/csv; charset=utf-8",
+            "playlist.csv",
+            playlist_export::csv(&channels, prefer),
+        ),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Unknown export format",
+                    "format": other,
+                    "formats": ["enigma2", "kodi", "json", "csv"]
+                })),
+            ))
+        }
+    };
+
+    let disposition = format!("attachment; filename=\"{filename}\"");
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+/// Request body for [`reorder_playlist`].
+#[derive(Debug, Deserialize)]
+pub struct ReorderRequest {
+    /// Channel IDs in their new order.
+    pub channel_ids: Vec<String>,
+}
+
+/// Move the channels in `ids` to the front, in that order, and number every
+/// channel's [`Channel::position`] from 0.
+///
+/// Channels not in `ids` keep their relative order after the listed ones.
+fn reorder_channels(channels: &mut [Channel], ids: &[String]) {
+    let rank: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    channels.sort_by_key(|ch| rank.get(ch.id.as_str()).copied().unwrap_or(usize::MAX));
+    for (position, ch) in (0u32..).zip(channels.iter_mut()) {
+        ch.position = Some(position);
+    }
+}
+
+/// Sets the playlist's channel order, e.g. for players that list channels
+/// strictly in playlist order.
+///
+/// Listed channels move to the front in the given order; the rest follow
+/// in their current order. Every channel gets a `position`, which M3U
+/// exports follow. Unknown channel IDs are rejected with `404 Not Found`
+/// and repeated ones with `400 Bad Request`; nothing is changed on error.
+///
+/// # Route
+///
+/// `POST /api/playlist/reorder`
+pub async fn reorder_playlist(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ReorderRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut seen = std::collections::HashSet::new();
+    if let Some(repeated) = body.channel_ids.iter().find(|id| !seen.insert(id.as_str())) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Repeated channel ID", "channel_id": repeated})),
+        ));
+    }
+
+    let mut playlist = state.playlist.write().await;
+    let unknown: Vec<&String> = body
+        .channel_ids
+        .iter()
+        .filter(|id| !playlist.channels.iter().any(|c| &c.id == *id))
+        .collect();
+    if !unknown.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Unknown channel IDs", "channel_ids": unknown})),
+        ));
+    }
+
+    reorder_channels(&mut playlist.channels, &body.channel_ids);
+    state.state_changed.notify_one();
+
+    Ok(Json(json!({
+        "status": "ok",
+        "channels_count": playlist.channels.len()
+    })))
+}
+
+/// Longest slug accepted for a named playlist.
+const MAX_SLUG_LEN: usize = 64;
+
+/// Whether `slug` can name a playlist: 1 to [`MAX_SLUG_LEN`] lowercase
+/// ASCII letters, digits, and hyphens, not starting or ending with a hyphen.
+fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= MAX_SLUG_LEN
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// Look up a named playlist, or fail with `404 Not Found`.
+pub fn find_playlist<'a>(
+    playlists: &'a HashMap<String, Playlist>,
+    slug: &str,
+) -> Result<&'a Playlist, (StatusCode, Json<Value>)> {
+    playlists.get(slug).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("playlist '{slug}' not found")})),
+        )
+    })
+}
+
+/// Lists the named playlists, sorted by slug.
+///
+/// # Route
+///
+/// `GET /api/playlists`
+pub async fn list_playlists(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let playlists = state.playlists.read().await;
+
+    let mut entries: Vec<(&String, &Playlist)> = playlists.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let list: Vec<Value> = entries
+        .into_iter()
+        .map(|(slug, playlist)| {
+            json!({
+                "slug": slug,
+                "name": playlist.name,
+                "channels_count": playlist.channels.len(),
+                "source": playlist.source
+            })
+        })
+        .collect();
 
-        m3u.push_str(&format!(
-            "#EXTINF:-1{} tvg-name=\"{}\" group-title=\"{}\"{},{}\n{}\n",
-            tvg_id_attr, ch.name, ch.group, logo_attr, ch.name, ch.stream_url
+    Json(json!({ "playlists": list }))
+}
+
+/// Request body for [`put_named_playlist`].
+#[derive(Debug, Deserialize)]
+pub struct NamedPlaylistRequest {
+    /// Human-readable name; defaults to the slug.
+    pub name: Option<String>,
+    /// The playlist's channels.
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+    /// Where the channels came from, if anywhere.
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Creates or replaces a named playlist.
+///
+/// Named playlists are curated lists served alongside the default playlist.
+/// They are not probed by the background checker or added to the search
+/// index, but are saved with the rest of the state when `STORAGE_PATH` is
+/// set. Responds `201 Created` for a new slug and `200 OK` when an
+/// existing playlist is replaced, or `400 Bad Request` for an invalid slug.
+///
+/// # Route
+///
+/// `POST /api/playlists/:slug`
+pub async fn put_named_playlist(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    Json(body): Json<NamedPlaylistRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    if !is_valid_slug(&slug) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "slug must be 1-64 lowercase letters, digits, or hyphens"
+            })),
         ));
     }
 
+    let count = body.channels.len();
+    let playlist = Playlist {
+        name: body.name.unwrap_or_else(|| slug.clone()),
+        channels: body.channels,
+        last_checked: None,
+        source: body.source,
+        epg_urls: Vec::new(),
+    };
+
+    let replaced = state
+        .playlists
+        .write()
+        .await
+        .insert(slug.clone(), playlist)
+        .is_some();
+    state.state_changed.notify_one();
+
+    let status = if replaced {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    Ok((
+        status,
+        Json(json!({
+            "status": "ok",
+            "slug": slug,
+            "channels_count": count
+        })),
+    ))
+}
+
+/// Returns a named playlist, in the same shape as [`get_playlist`].
+///
+/// Its channels are limited to the request's view as for [`get_playlist`].
+///
+/// # Route
+///
+/// `GET /api/playlists/:slug`
+pub async fn get_named_playlist(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Path(slug): Path<String>,
+    Query(query): Query<PlaylistQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlists = state.playlists.read().await;
+    let playlist = find_playlist(&playlists, &slug)?;
+    let channels = view.visible(&playlist.channels);
+    let mut value = serde_json::to_value(playlist).unwrap_or_default();
+
+    value["channels"] = if query.wants_epg_now() {
+        let cache = state.epg_cache.read().await;
+        Value::Array(enrich_channels_with_epg(&channels, &cache))
+    } else {
+        serde_json::to_value(&channels).unwrap_or_default()
+    };
+
+    Ok(Json(value))
+}
+
+/// Deletes a named playlist.
+///
+/// Responds `204 No Content`, or `404 Not Found` for an unknown slug.
+///
+/// # Route
+///
+/// `DELETE /api/playlists/:slug`
+pub async fn delete_named_playlist(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let mut playlists = state.playlists.write().await;
+    find_playlist(&playlists, &slug)?;
+    playlists.remove(&slug);
+    state.state_changed.notify_one();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Public base URL of the backend: `PUBLIC_URL`, or `http://` and the
+/// request's `Host` header.
+pub fn base_url(state: &AppState, headers: &HeaderMap) -> Option<String> {
+    match state.config.public_url {
+        Some(ref url) => Some(url.clone()),
+        None => Some(format!(
+            "http://{}",
+            headers.get(header::HOST)?.to_str().ok()?
+        )),
+    }
+}
+
+/// Absolute URL of the backend's XMLTV guide for an M3U export's
+/// `url-tvg`, scoped to the named playlist `slug` when given.
+///
+/// A session `token` from the export's query is passed on, so the guide
+/// lists the same channels as the export. Built from [`base_url`]. `None`
+/// when EPG is disabled or no base URL is available.
+fn guide_url(
+    state: &AppState,
+    headers: &HeaderMap,
+    slug: Option<&str>,
+    token: Option<&str>,
+) -> Option<String> {
+    if !state.config.epg_enabled {
+        return None;
+    }
+    let base = base_url(state, headers)?;
+    let params: Vec<String> = [("playlist", slug), ("token", token)]
+        .into_iter()
+        .filter_map(|(name, value)| Some(format!("{name}={}", value?)))
+        .collect();
+    Some(if params.is_empty() {
+        format!("{base}/api/epg/xmltv")
+    } else {
+        format!("{base}/api/epg/xmltv?{}", params.join("&"))
+    })
+}
+
+/// Returns a named playlist formatted as an M3U file.
+///
+/// Accepts the same `?prefer=` and `?token=` options as
+/// [`get_playlist_m3u`], whose channel filtering applies, and links a guide
+/// covering only this playlist's channels.
+///
+/// # Route
+///
+/// `GET /api/playlists/:slug/m3u`
+pub async fn get_named_playlist_m3u(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Path(slug): Path<String>,
+    Query(query): Query<M3uQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let guide = guide_url(&state, &headers, Some(&slug), query.token.as_deref());
+    let view = ChannelView::of(&state, &headers, &uri, query.token.as_deref()).await;
+    let playlists = state.playlists.read().await;
+    let playlist = find_playlist(&playlists, &slug)?;
+    let m3u = m3u_writer::render(
+        &view.visible(&playlist.channels),
+        query.prefer.as_deref(),
+        guide.as_deref(),
+    );
+
     Ok(([(header::CONTENT_TYPE, "audio/x-mpegurl")], m3u))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(group: Option<&str>, live: Option<bool>, has_epg: Option<bool>) -> PlaylistQuery {
+        PlaylistQuery {
+            with_epg: None,
+            q: None,
+            group: group.map(str::to_string),
+            live,
+            has_epg,
+            sort: None,
+            page: None,
+            per_page: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_channels() {
+        let live_zulu = Channel {
+            is_live: true,
+            ..Channel::sample("z", "zulu", "Sports")
+        };
+        let dead_alpha = Channel::sample("a", "Alpha", "News");
+        let bravo = Channel {
+            is_live: true,
+            ..Channel::sample("b", "bravo", "News")
+        };
+        let ids = |channels: &[Channel]| channels.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+
+        let mut channels = vec![live_zulu, dead_alpha, bravo];
+        sort_channels(&mut channels, ChannelSort::Name);
+        assert_eq!(ids(&channels), ["a", "b", "z"]);
+
+        sort_channels(&mut channels, ChannelSort::Group);
+        assert_eq!(ids(&channels), ["a", "b", "z"]);
+
+        sort_channels(&mut channels, ChannelSort::Live);
+        assert_eq!(ids(&channels), ["b", "z", "a"]);
+    }
+
+    #[test]
+    fn test_summarize_groups() {
+        let espn = Channel {
+            is_live: true,
+            ..Channel::sample("espn", "ESPN", "Sports")
+        };
+        let bbc = Channel::sample("bbc", "BBC", "News");
+        let cnn = Channel {
+            is_live: true,
+            ..Channel::sample("cnn", "CNN", "News")
+        };
+
+        assert_eq!(
+            summarize_groups(&[espn, bbc, cnn]),
+            vec![
+                GroupSummary {
+                    group: "News".to_string(),
+                    channels: 2,
+                    live: 1,
+                },
+                GroupSummary {
+                    group: "Sports".to_string(),
+                    channels: 1,
+                    live: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_channel_view_hides_locked_groups() {
+        let channels = [
+            Channel::sample("espn", "ESPN", "Sports"),
+            Channel::sample("cnn", "CNN", "News"),
+        ];
+
+        let open = ChannelView {
+            user: None,
+            anonymous_groups: None,
+            locked_groups: HashSet::new(),
+        };
+        assert!(matches!(open.visible(&channels), Cow::Borrowed(_)));
+
+        let anonymous = ChannelView {
+            user: None,
+            anonymous_groups: None,
+            locked_groups: HashSet::from(["Sports".to_string()]),
+        };
+        let visible = anonymous.visible(&channels);
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, "cnn");
+
+        // Anonymous requests get no more than restricted accounts share.
+        let restricted = ChannelView {
+            user: None,
+            anonymous_groups: Some(HashSet::from(["Sports".to_string()])),
+            locked_groups: HashSet::new(),
+        };
+        let visible = restricted.visible(&channels);
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, "espn");
+    }
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(page_bounds(None, None), None);
+        assert_eq!(page_bounds(Some(3), None), Some((3, DEFAULT_PER_PAGE)));
+        assert_eq!(page_bounds(None, Some(50)), Some((1, 50)));
+        assert_eq!(page_bounds(Some(0), Some(0)), Some((1, 1)));
+        assert_eq!(page_bounds(Some(2), Some(5000)), Some((2, MAX_PER_PAGE)));
+    }
+
+    #[test]
+    fn test_filters_match_group_liveness_and_epg() {
+        let mut cache = EpgCache::new();
+        let no_tvg = Channel::sample("bbc", "BBC One", "News");
+        let cnn = Channel {
+            is_live: true,
+            tvg_id: Some("CNN.us".to_string()),
+            ..Channel::sample("cnn", "CNN", "News")
+        };
+
+        assert!(query(None, None, None).matches(&no_tvg, &cache));
+        assert!(query(Some("news"), None, None).matches(&cnn, &cache));
+        assert!(!query(Some("Sports"), None, None).matches(&cnn, &cache));
+        assert!(query(None, Some(false), None).matches(&no_tvg, &cache));
+        assert!(!query(None, Some(true), None).matches(&no_tvg, &cache));
+        assert!(query(None, None, Some(true)).matches(&cnn, &cache));
+        assert!(!query(None, None, Some(true)).matches(&no_tvg, &cache));
+
+        // An EPG alias counts as a guide source.
+        cache.set_alias("bbc", "BBCOne.uk", true);
+        assert!(query(None, None, Some(true)).matches(&no_tvg, &cache));
+    }
+
+    #[test]
+    fn test_name_contains_ignores_case() {
+        assert!(name_contains("BBC One HD", "one"));
+        assert!(name_contains("BBC One HD", "BBC ONE"));
+        assert!(!name_contains("BBC One HD", "two"));
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("sports"));
+        assert!(is_valid_slug("news-24"));
+        assert!(is_valid_slug(&"a".repeat(MAX_SLUG_LEN)));
+
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Sports"));
+        assert!(!is_valid_slug("news_24"));
+        assert!(!is_valid_slug("-news"));
+        assert!(!is_valid_slug("news-"));
+        assert!(!is_valid_slug("a/b"));
+        assert!(!is_valid_slug(&"a".repeat(MAX_SLUG_LEN + 1)));
+    }
+
+    #[test]
+    fn test_reorder_channels() {
+        let mut channels = vec![
+            Channel::sample("a", "A", "News"),
+            Channel::sample("b", "B", "News"),
+            Channel::sample("c", "C", "News"),
+            Channel::sample("d", "D", "News"),
+        ];
+        reorder_channels(&mut channels, &["c".to_string(), "a".to_string()]);
+
+        let order: Vec<(&str, Option<u32>)> = channels
+            .iter()
+            .map(|c| (c.id.as_str(), c.position))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("c", Some(0)),
+                ("a", Some(1)),
+                ("b", Some(2)),
+                ("d", Some(3))
+            ]
+        );
+    }
+}
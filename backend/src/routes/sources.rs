@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::models::AppState;
+use crate::services::sources::{M3uSource, MergePolicy, SourceConfig};
+
+/// Request body for [`put_sources`].
+#[derive(Debug, Deserialize)]
+pub struct SourcesRequest {
+    /// Sources in priority order.
+    pub sources: Vec<M3uSource>,
+    /// How stream URL conflicts are resolved; `first` when omitted.
+    #[serde(default)]
+    pub policy: MergePolicy,
+}
+
+/// `202 Accepted` response for a reload handed to the source refresher.
+fn reloading() -> (StatusCode, Json<Value>) {
+    (StatusCode::ACCEPTED, Json(json!({"status": "accepted"})))
+}
+
+/// Returns the configured M3U sources, the merge policy, and the result of
+/// the last load per source.
+///
+/// # Route
+///
+/// `GET /api/sources`
+pub async fn get_sources(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let sources = state.sources.read().await;
+    Json(json!({
+        "policy": sources.config.policy,
+        "sources": sources.config.sources,
+        "managed_by": if sources.from_api { "api" } else { "config" },
+        "last_load": sources.last_load,
+        "last_refresh": sources.last_refresh.as_ref().map(|(at, diff)| json!({
+            "refreshed_at": at,
+            "diff": diff
+        }))
+    }))
+}
+
+/// Replaces the M3U source list and reloads the default playlist from it.
+///
+/// The new list overrides `M3U_SOURCE_URL` and is persisted with the rest
+/// of the state. Responds `202 Accepted` while the sources are fetched in
+/// the background; progress is visible through [`get_sources`]. Returns
+/// `400 Bad Request` when the list is empty or a URL is not HTTP(S).
+///
+/// # Route
+///
+/// `PUT /api/sources`
+pub async fn put_sources(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SourcesRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if body.sources.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "At least one source is required"})),
+        ));
+    }
+    if let Some(bad) = body
+        .sources
+        .iter()
+        .find(|s| !s.url.starts_with("http://") && !s.url.starts_with("https://"))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Source URLs must be http(s)", "url": bad.url})),
+        ));
+    }
+
+    {
+        let mut sources = state.sources.write().await;
+        sources.config = SourceConfig {
+            sources: body.sources,
+            policy: body.policy,
+        };
+        sources.from_api = true;
+    }
+    state.state_changed.notify_one();
+    state.refresh_sources.notify_one();
+
+    Ok(reloading())
+}
+
+/// Re-fetches every M3U source and rebuilds the default playlist.
+///
+/// Responds `202 Accepted` while the sources are fetched in the background,
+/// or `400 Bad Request` when no sources are configured.
+///
+/// # Route
+///
+/// `POST /api/sources/refresh`
+pub async fn refresh_sources(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if state.sources.read().await.config.sources.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "No M3U sources configured"})),
+        ));
+    }
+    state.refresh_sources.notify_one();
+
+    Ok(reloading())
+}
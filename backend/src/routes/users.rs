@@ -0,0 +1,549 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+use crate::models::AppState;
+use crate::services::accounts::{verify_login, AccountError, Session, User};
+use crate::services::signature::SignatureError;
+
+type ApiError = (StatusCode, Json<Value>);
+
+/// Username and password, for [`register`], [`login`] and [`create_user`].
+#[derive(Debug, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+    /// Groups the account may see; every group when omitted.
+    /// Only honoured by [`create_user`].
+    pub allowed_groups: Option<Vec<String>>,
+}
+
+/// Request body for [`update_user`]. Omitted fields are left unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UserUpdate {
+    /// New password; ends the user's sessions.
+    pub password: Option<String>,
+    /// New entitled groups, or `null` to allow every group.
+    #[serde(default, deserialize_with = "present")]
+    pub allowed_groups: Option<Option<Vec<String>>>,
+}
+
+/// Request body for [`update_me`].
+#[derive(Debug, Deserialize)]
+pub struct ProfileUpdate {
+    /// Groups to hide from the user's playlist.
+    pub hidden_groups: Option<Vec<String>>,
+}
+
+/// Request body for [`challenge`].
+#[derive(Debug, Deserialize)]
+pub struct AddressRequest {
+    /// SS58 address on any network.
+    pub address: String,
+}
+
+/// Request body for [`signature_login`] and [`link_address`].
+#[derive(Debug, Deserialize)]
+pub struct SignedChallenge {
+    pub address: String,
+    /// Hex signature of the challenge message, by the address's sr25519
+    /// or ed25519 key.
+    pub signature: String,
+}
+
+/// Request body for [`record_watch`].
+#[derive(Debug, Deserialize)]
+pub struct WatchRequest {
+    pub channel_id: String,
+}
+
+/// Deserialize a field that was present, so `null` can be told apart from
+/// an omitted field.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+fn account_error(e: AccountError) -> ApiError {
+    let status = match e {
+        AccountError::InvalidUsername
+        | AccountError::WeakPassword
+        | AccountError::Signature(SignatureError::InvalidAddress)
+        | AccountError::Signature(SignatureError::MalformedSignature) => StatusCode::BAD_REQUEST,
+        AccountError::UsernameTaken
+        | AccountError::AddressTaken
+        | AccountError::AddressRequired => StatusCode::CONFLICT,
+        AccountError::NotFound | AccountError::UnknownAddress => StatusCode::NOT_FOUND,
+        AccountError::BadLogin
+        | AccountError::NoChallenge
+        | AccountError::Signature(SignatureError::Mismatch) => StatusCode::UNAUTHORIZED,
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+fn unauthorized() -> ApiError {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({"error": "Sign in required"})),
+    )
+}
+
+/// The session token of a request: an `Authorization: Bearer` token, or
+/// `query_token` for players that cannot set headers.
+pub fn session_token(headers: &HeaderMap, query_token: Option<&str>) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or(query_token)
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// The signed-in user of a request, if its token names a live session.
+///
+/// Unknown tokens are treated as anonymous, since the bearer token may
+/// also be the API key.
+pub async fn current_user(state: &AppState, token: Option<&str>) -> Option<User> {
+    let accounts = state.accounts.read().await;
+    let username = accounts.session_user(token?, Utc::now())?;
+    accounts.users.get(username).cloned()
+}
+
+/// The username of the signed-in user, or `401 Unauthorized`.
+async fn require_user(state: &AppState, headers: &HeaderMap) -> Result<String, ApiError> {
+    let token = session_token(headers, None).ok_or_else(unauthorized)?;
+    let accounts = state.accounts.read().await;
+    accounts
+        .session_user(&token, Utc::now())
+        .map(str::to_string)
+        .ok_or_else(unauthorized)
+}
+
+/// Response body for a new session.
+fn session_body(token: String, session: Session, user: &User) -> Json<Value> {
+    Json(json!({
+        "token": token,
+        "expires_at": session.expires_at,
+        "user": user.profile()
+    }))
+}
+
+fn session_ttl(state: &AppState) -> Duration {
+    Duration::hours(state.config.session_ttl_hours as i64)
+}
+
+/// Creates an account and signs it in. Only available when
+/// `ALLOW_REGISTRATION` is set; operators otherwise create accounts
+/// through [`create_user`].
+///
+/// # Route
+///
+/// `POST /api/auth/register`
+pub async fn register(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<Credentials>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    if !state.config.allow_registration {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Registration is disabled"})),
+        ));
+    }
+
+    let now = Utc::now();
+    let ttl = session_ttl(&state);
+    let mut accounts = state.accounts.write().await;
+    let username = accounts
+        .create(&body.username, &body.password, None, now)
+        .map_err(account_error)?
+        .username
+        .clone();
+    let (token, session) = accounts.open_session(&username, ttl, now);
+    let body = session_body(token, session, &accounts.users[&username]);
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok((StatusCode::CREATED, body))
+}
+
+/// Signs in with a username and password, returning a session token to
+/// send as `Authorization: Bearer <token>`.
+///
+/// The password is hashed on a blocking thread without holding the
+/// accounts lock.
+///
+/// # Route
+///
+/// `POST /api/auth/login`
+pub async fn login(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<Credentials>,
+) -> Result<Json<Value>, ApiError> {
+    let ttl = session_ttl(&state);
+    let (username, hash) = state.accounts.read().await.login_hash(&body.username);
+    let checked = hash.clone();
+    let verified =
+        tokio::task::spawn_blocking(move || verify_login(&body.password, checked.as_deref()))
+            .await
+            .unwrap_or(false);
+    let hash = hash
+        .filter(|_| verified)
+        .ok_or_else(|| account_error(AccountError::BadLogin))?;
+
+    let mut accounts = state.accounts.write().await;
+    let (token, session) = accounts
+        .finish_login(&username, &hash, ttl, Utc::now())
+        .map_err(account_error)?;
+    let body = session_body(token, session.clone(), &accounts.users[&session.username]);
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(body)
+}
+
+/// Issues the message an address must sign to sign in or be linked to an
+/// account. It expires after five minutes, and a new one replaces it.
+///
+/// # Route
+///
+/// `POST /api/auth/challenge`
+pub async fn challenge(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<AddressRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let challenge = state
+        .accounts
+        .write()
+        .await
+        .issue_challenge(&body.address, Utc::now())
+        .map_err(account_error)?;
+    Ok(Json(json!(challenge)))
+}
+
+/// Signs in with a signed challenge, as the account linked to the address.
+///
+/// An address without an account gets one when `ALLOW_REGISTRATION` is
+/// set; its username is the hex public key and it has no password.
+///
+/// # Route
+///
+/// `POST /api/auth/signature`
+pub async fn signature_login(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SignedChallenge>,
+) -> Result<Json<Value>, ApiError> {
+    let now = Utc::now();
+    let ttl = session_ttl(&state);
+    let mut accounts = state.accounts.write().await;
+    let key = accounts
+        .answer_challenge(&body.address, &body.signature, now)
+        .map_err(account_error)?;
+    let username = match accounts.user_for_key(&key) {
+        Some(username) => username.to_string(),
+        None if state.config.allow_registration => accounts
+            .create_for_address(&body.address, now)
+            .map_err(account_error)?
+            .username
+            .clone(),
+        None => return Err(account_error(AccountError::UnknownAddress)),
+    };
+    let (token, session) = accounts.open_session(&username, ttl, now);
+    let body = session_body(token, session, &accounts.users[&username]);
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(body)
+}
+
+/// Ends the session of the request's token.
+///
+/// # Route
+///
+/// `POST /api/auth/logout`
+pub async fn logout(State(state): State<Arc<AppState>>, headers: HeaderMap) -> StatusCode {
+    let Some(token) = session_token(&headers, None) else {
+        return StatusCode::NO_CONTENT;
+    };
+    if state.accounts.write().await.logout(&token) {
+        state.state_changed.notify_one();
+    }
+    StatusCode::NO_CONTENT
+}
+
+/// Returns the signed-in user's profile.
+///
+/// # Route
+///
+/// `GET /api/me`
+pub async fn get_me(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let accounts = state.accounts.read().await;
+    let user = accounts.users.get(&username).ok_or_else(unauthorized)?;
+    Ok(Json(json!(user.profile())))
+}
+
+/// Updates the signed-in user's playlist view.
+///
+/// # Route
+///
+/// `PATCH /api/me`
+pub async fn update_me(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<ProfileUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let mut accounts = state.accounts.write().await;
+    let user = accounts.users.get_mut(&username).ok_or_else(unauthorized)?;
+    if let Some(hidden) = body.hidden_groups {
+        user.hidden_groups = hidden;
+    }
+    let profile = user.profile();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!(profile)))
+}
+
+/// Links an address to the signed-in user, proven by a signed challenge.
+/// The user can then sign in with [`signature_login`].
+///
+/// # Route
+///
+/// `PUT /api/me/address`
+pub async fn link_address(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<SignedChallenge>,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let mut accounts = state.accounts.write().await;
+    accounts
+        .answer_challenge(&body.address, &body.signature, Utc::now())
+        .and_then(|_| accounts.link_address(&username, &body.address))
+        .map_err(account_error)?;
+    let profile = accounts.users[&username].profile();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!(profile)))
+}
+
+/// Unlinks the signed-in user's address. Accounts without a password
+/// keep theirs, since it is their only login.
+///
+/// # Route
+///
+/// `DELETE /api/me/address`
+pub async fn unlink_address(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let mut accounts = state.accounts.write().await;
+    accounts.unlink_address(&username).map_err(account_error)?;
+    let profile = accounts.users[&username].profile();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!(profile)))
+}
+
+/// Adds a channel to the signed-in user's favourites.
+///
+/// # Route
+///
+/// `PUT /api/me/favorites/:channel_id`
+pub async fn add_favorite(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(channel_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    if !state
+        .playlist
+        .read()
+        .await
+        .channels
+        .iter()
+        .any(|ch| ch.id == channel_id)
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Channel not found", "channel_id": channel_id})),
+        ));
+    }
+
+    let mut accounts = state.accounts.write().await;
+    let user = accounts.users.get_mut(&username).ok_or_else(unauthorized)?;
+    if !user.favorites.contains(&channel_id) {
+        user.favorites.push(channel_id);
+    }
+    let favorites = user.favorites.clone();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!({"favorites": favorites})))
+}
+
+/// Removes a channel from the signed-in user's favourites.
+///
+/// # Route
+///
+/// `DELETE /api/me/favorites/:channel_id`
+pub async fn remove_favorite(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(channel_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let mut accounts = state.accounts.write().await;
+    let user = accounts.users.get_mut(&username).ok_or_else(unauthorized)?;
+    user.favorites.retain(|id| *id != channel_id);
+    let favorites = user.favorites.clone();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!({"favorites": favorites})))
+}
+
+/// Returns the signed-in user's watch history, most recent first.
+///
+/// # Route
+///
+/// `GET /api/me/history`
+pub async fn get_history(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let accounts = state.accounts.read().await;
+    let user = accounts.users.get(&username).ok_or_else(unauthorized)?;
+    Ok(Json(json!({"history": user.history})))
+}
+
+/// Records that the signed-in user started watching a channel.
+///
+/// # Route
+///
+/// `POST /api/me/history`
+pub async fn record_watch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(body): Json<WatchRequest>,
+) -> Result<StatusCode, ApiError> {
+    let username = require_user(&state, &headers).await?;
+    let mut accounts = state.accounts.write().await;
+    let user = accounts.users.get_mut(&username).ok_or_else(unauthorized)?;
+    user.record_watch(&body.channel_id, Utc::now());
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists every account.
+///
+/// # Route
+///
+/// `GET /api/users`
+pub async fn list_users(State(state): State<Arc<AppState>>) -> Json<Value> {
+    let accounts = state.accounts.read().await;
+    let mut users: Vec<_> = accounts.users.values().map(User::profile).collect();
+    users.sort_by(|a, b| a.username.cmp(&b.username));
+    Json(json!({"users": users}))
+}
+
+/// Creates an account on behalf of a user, optionally limited to some
+/// groups.
+///
+/// # Route
+///
+/// `POST /api/users`
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<Credentials>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let profile = state
+        .accounts
+        .write()
+        .await
+        .create(
+            &body.username,
+            &body.password,
+            body.allowed_groups,
+            Utc::now(),
+        )
+        .map_err(account_error)?
+        .profile();
+
+    state.state_changed.notify_one();
+    Ok((StatusCode::CREATED, Json(json!(profile))))
+}
+
+/// Changes an account's password or entitled groups.
+///
+/// # Route
+///
+/// `PATCH /api/users/:username`
+pub async fn update_user(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Json(body): Json<UserUpdate>,
+) -> Result<Json<Value>, ApiError> {
+    let mut accounts = state.accounts.write().await;
+    if !accounts.users.contains_key(&username) {
+        return Err(account_error(AccountError::NotFound));
+    }
+    if let Some(ref password) = body.password {
+        accounts
+            .set_password(&username, password)
+            .map_err(account_error)?;
+    }
+    let user = accounts
+        .users
+        .get_mut(&username)
+        .ok_or_else(|| account_error(AccountError::NotFound))?;
+    if let Some(groups) = body.allowed_groups {
+        user.allowed_groups = groups;
+    }
+    let profile = user.profile();
+    drop(accounts);
+
+    state.state_changed.notify_one();
+    Ok(Json(json!(profile)))
+}
+
+/// Deletes an account and ends its sessions.
+///
+/// # Route
+///
+/// `DELETE /api/users/:username`
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .accounts
+        .write()
+        .await
+        .remove(&username)
+        .map_err(account_error)?;
+
+    state.state_changed.notify_one();
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,493 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::models::epg::EpgProgram;
+use crate::models::{AppState, Channel};
+use crate::routes::auth::keys_match;
+use crate::routes::epg::{cached_schedule, render_guide};
+use crate::routes::playlist::{base_url, ChannelView};
+use crate::services::{m3u_writer, xmltv_writer};
+
+/// Programmes returned by `get_short_epg` when no `limit` is given.
+const SHORT_EPG_LIMIT: usize = 4;
+
+/// Date layout of Xtream EPG listings.
+const XTREAM_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Query parameters shared by the Xtream Codes endpoints.
+#[derive(Debug, Deserialize)]
+pub struct XtreamQuery {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// `player_api.php` action, e.g. `get_live_streams`.
+    pub action: Option<String>,
+    /// Category filter for `get_live_streams`.
+    pub category_id: Option<String>,
+    /// Stream for `get_short_epg` and `get_simple_data_table`.
+    pub stream_id: Option<u32>,
+    /// Number of programmes for `get_short_epg`.
+    pub limit: Option<usize>,
+}
+
+/// Why an Xtream request was refused.
+enum Denied {
+    /// `XTREAM_USERNAME` or `XTREAM_PASSWORD` is unset.
+    Disabled,
+    /// The credentials do not match.
+    BadLogin,
+}
+
+impl Denied {
+    fn reject(self) -> (StatusCode, Json<Value>) {
+        match self {
+            Self::Disabled => (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Xtream emulation is disabled"})),
+            ),
+            Self::BadLogin => (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Invalid username or password"})),
+            ),
+        }
+    }
+}
+
+/// Check `username` and `password` against the configured Xtream login.
+/// The password is compared in constant time.
+fn authorize(
+    state: &AppState,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Result<(), Denied> {
+    let (Some(user), Some(pass)) = (
+        state.config.xtream_username.as_deref(),
+        state.config.xtream_password.as_deref(),
+    ) else {
+        return Err(Denied::Disabled);
+    };
+    if username == Some(user) && password.is_some_and(|p| keys_match(p, pass)) {
+        Ok(())
+    } else {
+        Err(Denied::BadLogin)
+    }
+}
+
+/// The playlist's groups as Xtream categories, in order of first
+/// appearance. Category IDs are 1-based positions in this list.
+fn categories<'a>(channels: &[&'a Channel]) -> Vec<&'a str> {
+    let mut groups: Vec<&str> = Vec::new();
+    for ch in channels {
+        if !groups.contains(&ch.group.as_str()) {
+            groups.push(&ch.group);
+        }
+    }
+    groups
+}
+
+/// Category ID of `group` in `groups`.
+fn category_id(groups: &[&str], group: &str) -> String {
+    groups
+        .iter()
+        .position(|g| *g == group)
+        .map_or_else(String::new, |i| (i + 1).to_string())
+}
+
+/// Largest stream ID; players may read IDs as signed 32-bit integers.
+const MAX_STREAM_ID: u32 = i32::MAX as u32;
+
+/// `channels` with their stream IDs.
+///
+/// A stream ID is derived from the channel ID, so it survives channels
+/// being added, removed or reordered, and players' favourites and
+/// `/live/` URLs keep pointing at the same channel. An ID already taken by
+/// an earlier channel moves to the next free one.
+fn streams<'a>(channels: &[&'a Channel]) -> Vec<(u32, &'a Channel)> {
+    let mut taken = HashSet::new();
+    channels
+        .iter()
+        .map(|ch| {
+            let digest = Sha256::digest(ch.id.as_bytes());
+            let mut id = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]])
+                % MAX_STREAM_ID
+                + 1;
+            while !taken.insert(id) {
+                id = id % MAX_STREAM_ID + 1;
+            }
+            (id, *ch)
+        })
+        .collect()
+}
+
+/// A channel as a `get_live_streams` entry. `num` is its 1-based position
+/// in export order.
+fn live_stream(num: usize, stream_id: u32, ch: &Channel, groups: &[&str]) -> Value {
+    let archive_days = ch
+        .attributes
+        .catchup
+        .as_ref()
+        .and(ch.attributes.catchup_days)
+        .unwrap_or(0);
+    json!({
+        "num": num,
+        "name": ch.name,
+        "stream_type": "live",
+        "stream_id": stream_id,
+        "stream_icon": ch.logo_url.as_deref().unwrap_or_default(),
+        "epg_channel_id": xmltv_writer::guide_id(ch),
+        "added": "0",
+        "category_id": category_id(groups, &ch.group),
+        "custom_sid": "",
+        "tv_archive": u8::from(archive_days > 0),
+        "direct_source": ch.stream_url,
+        "tv_archive_duration": archive_days
+    })
+}
+
+/// A programme as an Xtream EPG listing; titles and descriptions are
+/// base64-encoded, as players expect.
+fn listing(prog: &EpgProgram, guide_id: &str, now: DateTime<Utc>) -> Value {
+    json!({
+        "id": prog.id,
+        "epg_id": guide_id,
+        "title": base64(&prog.title),
+        "lang": "",
+        "start": prog.start.format(XTREAM_TIME_FORMAT).to_string(),
+        "end": prog.end.format(XTREAM_TIME_FORMAT).to_string(),
+        "description": base64(prog.description.as_deref().unwrap_or_default()),
+        "channel_id": guide_id,
+        "start_timestamp": prog.start.timestamp().to_string(),
+        "stop_timestamp": prog.end.timestamp().to_string(),
+        "now_playing": u8::from(prog.start <= now && now < prog.end),
+        "has_archive": 0
+    })
+}
+
+/// Standard base64 with padding.
+fn base64(value: &str) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let bytes = value.as_bytes();
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// The login response of `player_api.php` without an action.
+fn login_info(state: &AppState, headers: &HeaderMap, query: &XtreamQuery) -> Value {
+    let base = base_url(state, headers).unwrap_or_default();
+    let (protocol, host) = base.split_once("://").unwrap_or(("http", base.as_str()));
+    let (host, port) = match host.split_once(':') {
+        Some((host, port)) => (host, port.to_string()),
+        None if protocol == "https" => (host, "443".to_string()),
+        None => (host, "80".to_string()),
+    };
+    let now = Utc::now();
+    json!({
+        "user_info": {
+            "username": query.username,
+            "password": query.password,
+            "message": "",
+            "auth": 1,
+            "status": "Active",
+            "exp_date": null,
+            "is_trial": "0",
+            "active_cons": "0",
+            "created_at": null,
+            "max_connections": "1",
+            "allowed_output_formats": ["m3u8", "ts"]
+        },
+        "server_info": {
+            "url": host,
+            "port": port,
+            "https_port": if protocol == "https" { port.as_str() } else { "" },
+            "server_protocol": protocol,
+            "rtmp_port": "",
+            "timezone": "UTC",
+            "timestamp_now": now.timestamp(),
+            "time_now": now.format(XTREAM_TIME_FORMAT).to_string()
+        }
+    })
+}
+
+/// Xtream Codes `player_api.php`, backed by the default playlist and the
+/// EPG cache.
+///
+/// Channels are limited to the request's [`ChannelView`]; the Xtream login
+/// is not a user session, so token-gated groups are left out.
+///
+/// Without an `action`, answers the login check; wrong credentials get
+/// `{"user_info": {"auth": 0}}` as real panels send. Supported actions are
+/// `get_live_categories`, `get_live_streams` (optionally by
+/// `category_id`), `get_short_epg` and `get_simple_data_table`. VOD and
+/// series actions, and any unknown action, return an empty list.
+///
+/// Returns `404 Not Found` when the emulation is disabled.
+///
+/// # Route
+///
+/// `GET /player_api.php`
+pub async fn player_api(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<XtreamQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match authorize(&state, query.username.as_deref(), query.password.as_deref()) {
+        Ok(()) => {}
+        Err(Denied::BadLogin) => return Ok(Json(json!({"user_info": {"auth": 0}}))),
+        Err(denied) => return Err(denied.reject()),
+    }
+    let Some(action) = query.action.as_deref() else {
+        return Ok(Json(login_info(&state, &headers, &query)));
+    };
+
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlist = state.playlist.read().await;
+    let visible = view.visible(&playlist.channels);
+    let channels = m3u_writer::export_order(&visible);
+    let groups = categories(&channels);
+    let streams = streams(&channels);
+
+    let body = match action {
+        "get_live_categories" => groups
+            .iter()
+            .enumerate()
+            .map(|(i, group)| {
+                json!({
+                    "category_id": (i + 1).to_string(),
+                    "category_name": group,
+                    "parent_id": 0
+                })
+            })
+            .collect(),
+        "get_live_streams" => streams
+            .iter()
+            .enumerate()
+            .map(|(i, (id, ch))| live_stream(i + 1, *id, ch, &groups))
+            .filter(|stream| {
+                query
+                    .category_id
+                    .as_deref()
+                    .is_none_or(|id| stream["category_id"] == id)
+            })
+            .collect(),
+        "get_short_epg" | "get_simple_data_table" => {
+            let Some(&(_, ch)) = streams.iter().find(|(id, _)| query.stream_id == Some(*id)) else {
+                return Ok(Json(json!({"epg_listings": []})));
+            };
+            let now = Utc::now();
+            let guide_id = xmltv_writer::guide_id(ch);
+            let cache = state.epg_cache.read().await;
+            let programs = cached_schedule(&cache, ch)
+                .map(|schedule| schedule.programs.as_slice())
+                .unwrap_or_default();
+            let listings: Vec<Value> = if action == "get_short_epg" {
+                programs
+                    .iter()
+                    .filter(|p| p.end > now)
+                    .take(query.limit.unwrap_or(SHORT_EPG_LIMIT))
+                    .map(|p| listing(p, guide_id, now))
+                    .collect()
+            } else {
+                programs.iter().map(|p| listing(p, guide_id, now)).collect()
+            };
+            return Ok(Json(json!({"epg_listings": listings})));
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(Json(Value::Array(body)))
+}
+
+/// Xtream Codes `get.php`: the default playlist as M3U, linking
+/// `xmltv.php` as its guide.
+///
+/// Stream URLs point straight at the upstream streams. Channels are
+/// filtered as for [`player_api`]. Returns `401 Unauthorized` for wrong
+/// credentials and `404 Not Found` when the emulation is disabled.
+///
+/// # Route
+///
+/// `GET /get.php`
+pub async fn get_php(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<XtreamQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let (username, password) = (query.username.as_deref(), query.password.as_deref());
+    authorize(&state, username, password).map_err(Denied::reject)?;
+
+    let guide = base_url(&state, &headers)
+        .filter(|_| state.config.epg_enabled)
+        .and_then(|base| {
+            let login = [
+                ("username", username.unwrap_or_default()),
+                ("password", password.unwrap_or_default()),
+            ];
+            Url::parse_with_params(&format!("{base}/xmltv.php"), login).ok()
+        })
+        .map(String::from);
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlist = state.playlist.read().await;
+    let m3u = m3u_writer::render(&view.visible(&playlist.channels), None, guide.as_deref());
+
+    Ok(([(header::CONTENT_TYPE, "audio/x-mpegurl")], m3u))
+}
+
+/// Xtream Codes `xmltv.php`: the cached guide of the default playlist, as
+/// served by `GET /api/epg/xmltv`, for the channels of [`get_php`].
+///
+/// Returns `401 Unauthorized` for wrong credentials and `404 Not Found`
+/// when the emulation is disabled.
+///
+/// # Route
+///
+/// `GET /xmltv.php`
+pub async fn xmltv_php(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(query): Query<XtreamQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    authorize(&state, query.username.as_deref(), query.password.as_deref())
+        .map_err(Denied::reject)?;
+
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let channels = view
+        .visible(&state.playlist.read().await.channels)
+        .into_owned();
+    let xml = render_guide(&*state.epg_cache.read().await, &channels);
+    Ok(([(header::CONTENT_TYPE, "application/xml")], xml))
+}
+
+/// Redirects an Xtream live stream URL to the channel's upstream stream.
+///
+/// `stream` is the stream ID from `get_live_streams`, with any extension
+/// (`42.ts`, `42.m3u8`). Returns
+/// `404 Not Found` for an unknown stream or one outside the request's
+/// [`ChannelView`].
+///
+/// # Route
+///
+/// `GET /live/:username/:password/:stream`
+pub async fn play_live(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Path((username, password, stream)): Path<(String, String, String)>,
+) -> Result<Redirect, (StatusCode, Json<Value>)> {
+    authorize(&state, Some(&username), Some(&password)).map_err(Denied::reject)?;
+
+    let stream_id = stream
+        .split('.')
+        .next()
+        .and_then(|id| id.parse::<u32>().ok());
+    let view = ChannelView::of(&state, &headers, &uri, None).await;
+    let playlist = state.playlist.read().await;
+    let visible = view.visible(&playlist.channels);
+    let channels = m3u_writer::export_order(&visible);
+    match streams(&channels)
+        .into_iter()
+        .find(|(id, _)| stream_id == Some(*id))
+    {
+        Some((_, ch)) => Ok(Redirect::temporary(&ch.stream_url)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Stream not found", "stream": stream})),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_base64() {
+        assert_eq!(base64(""), "");
+        assert_eq!(base64("f"), "Zg==");
+        assert_eq!(base64("fo"), "Zm8=");
+        assert_eq!(base64("foo"), "Zm9v");
+        assert_eq!(base64("Nachrichten ü"), "TmFjaHJpY2h0ZW4gw7w=");
+    }
+
+    #[test]
+    fn streams_number_channels_and_categories() {
+        let mut bbc = Channel::sample("bbc", "BBC", "UK");
+        bbc.attributes.catchup = Some("shift".to_string());
+        bbc.attributes.catchup_days = Some(7);
+        let channels = [
+            Channel::sample("cnn", "CNN", "News"),
+            bbc,
+            Channel::sample("fox", "FOX", "News"),
+        ];
+        let ordered: Vec<&Channel> = channels.iter().collect();
+        let groups = categories(&ordered);
+        assert_eq!(groups, ["News", "UK"]);
+
+        let stream = live_stream(2, 42, ordered[1], &groups);
+        assert_eq!(stream["num"], 2);
+        assert_eq!(stream["stream_id"], 42);
+        assert_eq!(stream["category_id"], "2");
+        assert_eq!(stream["epg_channel_id"], "bbc");
+        assert_eq!(stream["tv_archive"], 1);
+        assert_eq!(stream["tv_archive_duration"], 7);
+
+        let stream = live_stream(3, 43, ordered[2], &groups);
+        assert_eq!(stream["category_id"], "1");
+        assert_eq!(stream["tv_archive"], 0);
+    }
+
+    #[test]
+    fn stream_ids_survive_reordering_and_removal() {
+        let channels = [
+            Channel::sample("cnn", "CNN", "News"),
+            Channel::sample("bbc", "BBC", "UK"),
+            Channel::sample("fox", "FOX", "News"),
+        ];
+        let ids: Vec<u32> = streams(&channels.iter().collect::<Vec<_>>())
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert!(ids.iter().all(|id| (1..=MAX_STREAM_ID).contains(id)));
+
+        let fewer = streams(&[&channels[2], &channels[0]]);
+        assert_eq!((fewer[0].0, fewer[0].1.id.as_str()), (ids[2], "fox"));
+        assert_eq!((fewer[1].0, fewer[1].1.id.as_str()), (ids[0], "cnn"));
+    }
+
+    #[test]
+    fn duplicate_stream_ids_move_to_the_next_free_one() {
+        let cnn = Channel::sample("cnn", "CNN", "News");
+        let ids: Vec<u32> = streams(&[&cnn, &cnn, &cnn])
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids[1], ids[0] % MAX_STREAM_ID + 1);
+        assert_eq!(ids[2], ids[1] % MAX_STREAM_ID + 1);
+    }
+}
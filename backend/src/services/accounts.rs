@@ -0,0 +1,683 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::models::Channel;
+use crate::services::signature::{self, SignatureError};
+
+/// PBKDF2-HMAC-SHA256 rounds for password hashes.
+const PBKDF2_ROUNDS: u32 = 100_000;
+
+/// Watch history entries kept per user; the oldest are dropped first.
+pub const MAX_HISTORY: usize = 100;
+
+/// Shortest password accepted for an account.
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// How long a sign-in challenge can be signed.
+const CHALLENGE_TTL: Duration = Duration::minutes(5);
+
+/// Pending challenges kept at once; the oldest are dropped first, since
+/// anyone can ask for one.
+const MAX_CHALLENGES: usize = 10_000;
+
+/// Errors returned by account operations.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AccountError {
+    /// The username is empty or has characters outside `[a-z0-9_.-]`.
+    #[error("Username must be 1-64 characters of a-z, 0-9, '_', '.' or '-'")]
+    InvalidUsername,
+    /// The password is shorter than [`MIN_PASSWORD_LEN`].
+    #[error("Password must be at least {MIN_PASSWORD_LEN} characters")]
+    WeakPassword,
+    /// An account with this username already exists.
+    #[error("Username is already taken")]
+    UsernameTaken,
+    /// No account has this username.
+    #[error("User not found")]
+    NotFound,
+    /// Wrong username or password.
+    #[error("Invalid username or password")]
+    BadLogin,
+    /// Another account is linked to this address.
+    #[error("Address is linked to another account")]
+    AddressTaken,
+    /// No account is linked to this address.
+    #[error("No account is linked to this address")]
+    UnknownAddress,
+    /// The account has no password, so its address is its only login.
+    #[error("An account without a password cannot unlink its address")]
+    AddressRequired,
+    /// No unexpired challenge was issued for this address.
+    #[error("No pending sign-in challenge for this address")]
+    NoChallenge,
+    /// The address or signature is invalid.
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+}
+
+/// A channel a user started watching, and when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchEvent {
+    pub channel_id: String,
+    pub watched_at: DateTime<Utc>,
+}
+
+/// A user account and its personal view of the default playlist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    /// Lowercase login name, unique across accounts.
+    pub username: String,
+    /// `pbkdf2-sha256$<rounds>$<salt hex>$<hash hex>`, or empty for an
+    /// account that only signs in with its address.
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    /// SS58 address the user proved to own, as they submitted it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Groups the user is entitled to see; `None` allows every group.
+    #[serde(default)]
+    pub allowed_groups: Option<Vec<String>>,
+    /// Groups the user chose to hide from their playlist.
+    #[serde(default)]
+    pub hidden_groups: Vec<String>,
+    /// Favourite channel IDs, in the order they were added.
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Channels watched, most recent first.
+    #[serde(default)]
+    pub history: Vec<WatchEvent>,
+}
+
+impl User {
+    /// Whether `ch` is in this user's playlist: its group is entitled and
+    /// not hidden.
+    pub fn sees(&self, ch: &Channel) -> bool {
+        self.allowed_groups
+            .as_ref()
+            .is_none_or(|groups| groups.contains(&ch.group))
+            && !self.hidden_groups.contains(&ch.group)
+    }
+
+    /// The account as served by the API, without the password hash.
+    pub fn profile(&self) -> UserProfile {
+        UserProfile {
+            username: self.username.clone(),
+            created_at: self.created_at,
+            address: self.address.clone(),
+            allowed_groups: self.allowed_groups.clone(),
+            hidden_groups: self.hidden_groups.clone(),
+            favorites: self.favorites.clone(),
+        }
+    }
+
+    /// Record that the user started watching `channel_id` at `now`.
+    ///
+    /// A channel appears in the history once, at its latest watch.
+    pub fn record_watch(&mut self, channel_id: &str, now: DateTime<Utc>) {
+        self.history.retain(|e| e.channel_id != channel_id);
+        self.history.insert(
+            0,
+            WatchEvent {
+                channel_id: channel_id.to_string(),
+                watched_at: now,
+            },
+        );
+        self.history.truncate(MAX_HISTORY);
+    }
+}
+
+/// Public view of a [`User`].
+#[derive(Debug, Clone, Serialize)]
+pub struct UserProfile {
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_groups: Option<Vec<String>>,
+    pub hidden_groups: Vec<String>,
+    pub favorites: Vec<String>,
+}
+
+/// A signed-in session. Only a hash of its token is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub username: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A message an address must sign to sign in or link itself.
+#[derive(Debug, Clone, Serialize)]
+pub struct Challenge {
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// User accounts and their sessions.
+#[derive(Debug, Default)]
+pub struct Accounts {
+    /// Accounts keyed by username.
+    pub users: HashMap<String, User>,
+    /// Sessions keyed by the SHA-256 hex digest of their token.
+    pub sessions: HashMap<String, Session>,
+    /// Pending signature challenges keyed by public key. Not persisted.
+    pub challenges: HashMap<[u8; 32], Challenge>,
+}
+
+impl Accounts {
+    /// Create an account for `username`, stored in lowercase.
+    pub fn create(
+        &mut self,
+        username: &str,
+        password: &str,
+        allowed_groups: Option<Vec<String>>,
+        now: DateTime<Utc>,
+    ) -> Result<&User, AccountError> {
+        let username = normalize_username(username)?;
+        if self.users.contains_key(&username) {
+            return Err(AccountError::UsernameTaken);
+        }
+        let user = User {
+            username: username.clone(),
+            password_hash: hash_password(password)?,
+            created_at: now,
+            address: None,
+            allowed_groups,
+            hidden_groups: Vec::new(),
+            favorites: Vec::new(),
+            history: Vec::new(),
+        };
+        Ok(self.users.entry(username).or_insert(user))
+    }
+
+    /// Create an account that signs in with `address` only. Its username
+    /// is the hex public key.
+    pub fn create_for_address(
+        &mut self,
+        address: &str,
+        now: DateTime<Utc>,
+    ) -> Result<&User, AccountError> {
+        let key = signature::decode_ss58(address)?;
+        if self.user_for_key(&key).is_some() {
+            return Err(AccountError::AddressTaken);
+        }
+        let username = hex::encode(key);
+        if self.users.contains_key(&username) {
+            return Err(AccountError::UsernameTaken);
+        }
+        let user = User {
+            username: username.clone(),
+            password_hash: String::new(),
+            created_at: now,
+            address: Some(address.trim().to_string()),
+            allowed_groups: None,
+            hidden_groups: Vec::new(),
+            favorites: Vec::new(),
+            history: Vec::new(),
+        };
+        Ok(self.users.entry(username).or_insert(user))
+    }
+
+    /// Issue the message `address` must sign within [`CHALLENGE_TTL`],
+    /// replacing any earlier one. Past [`MAX_CHALLENGES`], the oldest
+    /// pending challenge is dropped.
+    pub fn issue_challenge(
+        &mut self,
+        address: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Challenge, AccountError> {
+        let key = signature::decode_ss58(address)?;
+        self.challenges.retain(|_, c| c.expires_at > now);
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let challenge = Challenge {
+            message: format!(
+                "Sign in to the IPTV backend as {}\nNonce: {}",
+                address.trim(),
+                hex::encode(nonce)
+            ),
+            expires_at: now + CHALLENGE_TTL,
+        };
+        self.insert_challenge(key, challenge.clone());
+        Ok(challenge)
+    }
+
+    /// Store `challenge` for `key`, dropping the oldest pending challenge
+    /// when [`MAX_CHALLENGES`] are already kept.
+    fn insert_challenge(&mut self, key: [u8; 32], challenge: Challenge) {
+        if self.challenges.len() >= MAX_CHALLENGES && !self.challenges.contains_key(&key) {
+            let oldest = self
+                .challenges
+                .iter()
+                .min_by_key(|(_, c)| c.expires_at)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.challenges.remove(&oldest);
+            }
+        }
+        self.challenges.insert(key, challenge);
+    }
+
+    /// Check `signature` against the pending challenge of `address`, and
+    /// return the address's public key. The challenge is used up either way.
+    pub fn answer_challenge(
+        &mut self,
+        address: &str,
+        signature: &str,
+        now: DateTime<Utc>,
+    ) -> Result<[u8; 32], AccountError> {
+        let key = signature::decode_ss58(address)?;
+        let challenge = self
+            .challenges
+            .remove(&key)
+            .filter(|c| c.expires_at > now)
+            .ok_or(AccountError::NoChallenge)?;
+        signature::verify(address, challenge.message.as_bytes(), signature)?;
+        Ok(key)
+    }
+
+    /// The groups a request without a session may see: those every account
+    /// with restricted `allowed_groups` is entitled to. `None` when no
+    /// account is restricted, so dropping a session never widens a view.
+    pub fn anonymous_groups(&self) -> Option<HashSet<String>> {
+        self.users
+            .values()
+            .filter_map(|u| u.allowed_groups.as_ref())
+            .map(|groups| groups.iter().cloned().collect::<HashSet<_>>())
+            .reduce(|common, groups| &common & &groups)
+    }
+
+    /// The username linked to the address with public key `key`.
+    pub fn user_for_key(&self, key: &[u8; 32]) -> Option<&str> {
+        self.users
+            .values()
+            .find(|u| {
+                u.address
+                    .as_deref()
+                    .is_some_and(|a| signature::decode_ss58(a).ok().as_ref() == Some(key))
+            })
+            .map(|u| u.username.as_str())
+    }
+
+    /// Link `address` to `username`, replacing any address it had.
+    pub fn link_address(&mut self, username: &str, address: &str) -> Result<(), AccountError> {
+        let key = signature::decode_ss58(address)?;
+        if self
+            .user_for_key(&key)
+            .is_some_and(|owner| owner != username)
+        {
+            return Err(AccountError::AddressTaken);
+        }
+        let user = self.users.get_mut(username).ok_or(AccountError::NotFound)?;
+        user.address = Some(address.trim().to_string());
+        Ok(())
+    }
+
+    /// Remove the address linked to `username`.
+    pub fn unlink_address(&mut self, username: &str) -> Result<(), AccountError> {
+        let user = self.users.get_mut(username).ok_or(AccountError::NotFound)?;
+        if user.password_hash.is_empty() {
+            return Err(AccountError::AddressRequired);
+        }
+        user.address = None;
+        Ok(())
+    }
+
+    /// Replace the password of `username`, ending its sessions.
+    pub fn set_password(&mut self, username: &str, password: &str) -> Result<(), AccountError> {
+        let hash = hash_password(password)?;
+        let user = self.users.get_mut(username).ok_or(AccountError::NotFound)?;
+        user.password_hash = hash;
+        self.sessions.retain(|_, s| s.username != username);
+        Ok(())
+    }
+
+    /// Delete `username` and its sessions.
+    pub fn remove(&mut self, username: &str) -> Result<User, AccountError> {
+        let user = self.users.remove(username).ok_or(AccountError::NotFound)?;
+        self.sessions.retain(|_, s| s.username != username);
+        Ok(user)
+    }
+
+    /// The username as stored and its password hash, if the account
+    /// exists, to check a login for `username` against with
+    /// [`verify_login`] before [`Accounts::finish_login`].
+    pub fn login_hash(&self, username: &str) -> (String, Option<String>) {
+        let username = username.trim().to_lowercase();
+        let hash = self.users.get(&username).map(|u| u.password_hash.clone());
+        (username, hash)
+    }
+
+    /// Open a session lasting `ttl` for a login [`verify_login`] accepted
+    /// against `hash`, unless the account was deleted or its password
+    /// changed since. Returns the session token, which is not stored and
+    /// cannot be recovered later.
+    pub fn finish_login(
+        &mut self,
+        username: &str,
+        hash: &str,
+        ttl: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<(String, Session), AccountError> {
+        if self
+            .users
+            .get(username)
+            .is_none_or(|user| user.password_hash != hash)
+        {
+            return Err(AccountError::BadLogin);
+        }
+        Ok(self.open_session(username, ttl, now))
+    }
+
+    /// Open a session for `username` lasting `ttl`, returning its token.
+    pub fn open_session(
+        &mut self,
+        username: &str,
+        ttl: Duration,
+        now: DateTime<Utc>,
+    ) -> (String, Session) {
+        self.sessions.retain(|_, s| s.expires_at > now);
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        let session = Session {
+            username: username.to_string(),
+            expires_at: now + ttl,
+        };
+        self.sessions.insert(token_key(&token), session.clone());
+        (token, session)
+    }
+
+    /// End the session for `token`. Returns whether it existed.
+    pub fn logout(&mut self, token: &str) -> bool {
+        self.sessions.remove(&token_key(token)).is_some()
+    }
+
+    /// The username of the unexpired session for `token`.
+    pub fn session_user(&self, token: &str, now: DateTime<Utc>) -> Option<&str> {
+        self.sessions
+            .get(&token_key(token))
+            .filter(|s| s.expires_at > now && self.users.contains_key(&s.username))
+            .map(|s| s.username.as_str())
+    }
+}
+
+/// Validate `username` and fold it to lowercase.
+fn normalize_username(username: &str) -> Result<String, AccountError> {
+    let username = username.trim().to_lowercase();
+    let valid = (1..=64).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    valid
+        .then_some(username)
+        .ok_or(AccountError::InvalidUsername)
+}
+
+/// Map key for a session token.
+fn token_key(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn pbkdf2(password: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut out);
+    out
+}
+
+/// Hash `password` with a random salt.
+fn hash_password(password: &str) -> Result<String, AccountError> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(AccountError::WeakPassword);
+    }
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let hash = pbkdf2(password, &salt, PBKDF2_ROUNDS);
+    Ok(format!(
+        "pbkdf2-sha256${PBKDF2_ROUNDS}${}${}",
+        hex::encode(salt),
+        hex::encode(hash)
+    ))
+}
+
+/// Whether `password` matches `hash` from [`Accounts::login_hash`].
+///
+/// Takes as long without an account (`None`), checking against a dummy
+/// hash, so response times do not tell which usernames exist. Hashing is
+/// slow: call it outside the accounts lock and off the async runtime.
+pub fn verify_login(password: &str, hash: Option<&str>) -> bool {
+    match hash {
+        Some(hash) => verify_password(password, hash),
+        None => {
+            let dummy = format!(
+                "pbkdf2-sha256${PBKDF2_ROUNDS}${}${}",
+                "00".repeat(16),
+                "00".repeat(32)
+            );
+            verify_password(password, &dummy);
+            false
+        }
+    }
+}
+
+/// Check `password` against a hash from [`hash_password`].
+fn verify_password(password: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some("pbkdf2-sha256"), Some(rounds), Some(salt), Some(hash), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return false;
+    };
+    let (Ok(rounds), Ok(salt), Ok(hash)) = (rounds.parse(), hex::decode(salt), hex::decode(hash))
+    else {
+        return false;
+    };
+    let computed = pbkdf2(password, &salt, rounds);
+    computed.len() == hash.len()
+        && computed
+            .iter()
+            .zip(&hash)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_accounts_and_logs_in() {
+        let mut accounts = Accounts::default();
+        let now = Utc::now();
+        accounts
+            .create("Alice", "correct horse", None, now)
+            .unwrap();
+        assert_eq!(
+            accounts
+                .create("alice", "another one", None, now)
+                .unwrap_err(),
+            AccountError::UsernameTaken
+        );
+        assert_eq!(
+            accounts
+                .create("bob smith", "long enough", None, now)
+                .unwrap_err(),
+            AccountError::InvalidUsername
+        );
+        assert_eq!(
+            accounts.create("bob", "short", None, now).unwrap_err(),
+            AccountError::WeakPassword
+        );
+
+        let (username, hash) = accounts.login_hash("ALICE");
+        assert_eq!(username, "alice");
+        let hash = hash.unwrap();
+        assert!(!verify_login("wrong password", Some(&hash)));
+        assert!(verify_login("correct horse", Some(&hash)));
+        assert!(!verify_login(
+            "correct horse",
+            accounts.login_hash("bob").1.as_deref()
+        ));
+        let (token, _) = accounts
+            .finish_login("alice", &hash, Duration::hours(1), now)
+            .unwrap();
+        assert_eq!(accounts.session_user(&token, now), Some("alice"));
+        assert!(accounts
+            .session_user(&token, now + Duration::hours(2))
+            .is_none());
+
+        // A login checked against the old password no longer opens a session.
+        accounts.set_password("alice", "battery staple").unwrap();
+        assert!(accounts.session_user(&token, now).is_none());
+        assert_eq!(
+            accounts
+                .finish_login("alice", &hash, Duration::hours(1), now)
+                .unwrap_err(),
+            AccountError::BadLogin
+        );
+        let hash = accounts.login_hash("alice").1.unwrap();
+        assert!(verify_login("battery staple", Some(&hash)));
+        assert!(accounts
+            .finish_login("alice", &hash, Duration::hours(1), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn signs_in_with_address_challenges() {
+        let mut accounts = Accounts::default();
+        let now = Utc::now();
+        let signing = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        // `//Alice` encodes a different key, so it must not match.
+        let other = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        let address = {
+            use blake2::{Blake2b512, Digest};
+            let mut data = vec![42u8];
+            data.extend_from_slice(signing.verifying_key().as_bytes());
+            let hash = Blake2b512::new()
+                .chain_update(b"SS58PRE")
+                .chain_update(&data)
+                .finalize();
+            data.extend_from_slice(&hash[..2]);
+            bs58::encode(data).into_string()
+        };
+        let sign = |message: &str| {
+            hex::encode(ed25519_dalek::Signer::sign(&signing, message.as_bytes()).to_bytes())
+        };
+
+        assert_eq!(
+            accounts.answer_challenge(&address, "00", now).unwrap_err(),
+            AccountError::NoChallenge
+        );
+        let challenge = accounts.issue_challenge(&address, now).unwrap();
+        let key = accounts
+            .answer_challenge(&address, &sign(&challenge.message), now)
+            .unwrap();
+        // Challenges are single-use.
+        assert!(accounts
+            .answer_challenge(&address, &sign(&challenge.message), now)
+            .is_err());
+
+        let username = accounts
+            .create_for_address(&address, now)
+            .unwrap()
+            .username
+            .clone();
+        assert_eq!(accounts.user_for_key(&key), Some(username.as_str()));
+        assert_eq!(
+            accounts.unlink_address(&username).unwrap_err(),
+            AccountError::AddressRequired
+        );
+
+        accounts.create("dave", "password123", None, now).unwrap();
+        assert_eq!(
+            accounts.link_address("dave", &address).unwrap_err(),
+            AccountError::AddressTaken
+        );
+        accounts.link_address("dave", other).unwrap();
+        accounts.unlink_address("dave").unwrap();
+
+        let challenge = accounts.issue_challenge(&address, now).unwrap();
+        assert_eq!(
+            accounts
+                .answer_challenge(
+                    &address,
+                    &sign(&challenge.message),
+                    now + Duration::hours(1)
+                )
+                .unwrap_err(),
+            AccountError::NoChallenge
+        );
+    }
+
+    #[test]
+    fn drops_the_oldest_challenges_past_the_cap() {
+        let mut accounts = Accounts::default();
+        let now = Utc::now();
+        let key = |i: usize| {
+            let mut key = [0u8; 32];
+            key[..8].copy_from_slice(&i.to_le_bytes());
+            key
+        };
+        let challenge = |i: usize| Challenge {
+            message: i.to_string(),
+            expires_at: now + Duration::seconds(i as i64),
+        };
+        for i in 0..=MAX_CHALLENGES {
+            accounts.insert_challenge(key(i), challenge(i));
+        }
+        assert_eq!(accounts.challenges.len(), MAX_CHALLENGES);
+        assert!(!accounts.challenges.contains_key(&key(0)));
+        assert!(accounts.challenges.contains_key(&key(MAX_CHALLENGES)));
+
+        // Replacing a pending challenge drops nothing else.
+        accounts.insert_challenge(key(1), challenge(MAX_CHALLENGES + 1));
+        assert_eq!(accounts.challenges.len(), MAX_CHALLENGES);
+        assert!(accounts.challenges.contains_key(&key(2)));
+    }
+
+    #[test]
+    fn filters_playlist_and_keeps_history_unique() {
+        let mut accounts = Accounts::default();
+        let now = Utc::now();
+        let sports = Some(vec!["Sports".to_string(), "News".to_string()]);
+        let mut user = accounts
+            .create("carol", "password123", sports, now)
+            .unwrap()
+            .clone();
+        user.hidden_groups.push("News".to_string());
+
+        let channel = |group: &str| Channel::sample(&group.to_lowercase(), group, group);
+        assert!(user.sees(&channel("Sports")));
+        assert!(!user.sees(&channel("News")));
+        assert!(!user.sees(&channel("Movies")));
+
+        // Anonymous requests get the groups every restricted account shares.
+        assert_eq!(
+            accounts.anonymous_groups(),
+            Some(HashSet::from(["Sports".to_string(), "News".to_string()]))
+        );
+        accounts.create("dave", "password123", None, now).unwrap();
+        let news = Some(vec!["News".to_string()]);
+        accounts.create("erin", "password123", news, now).unwrap();
+        assert_eq!(
+            accounts.anonymous_groups(),
+            Some(HashSet::from(["News".to_string()]))
+        );
+        assert_eq!(Accounts::default().anonymous_groups(), None);
+
+        user.record_watch("a", now);
+        user.record_watch("b", now);
+        user.record_watch("a", now);
+        let ids: Vec<&str> = user.history.iter().map(|e| e.channel_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::services::chain_rpc::ChainRpc;
+
+/// Name of the network configured by `SUBSCAN_API_URL`, `CHAIN_RPC_URL`
+/// and `CHAIN_PLAYLIST_SOURCE`, looked up when a request names none.
+pub const DEFAULT_NETWORK: &str = "default";
+
+/// A chain listed in `CHAIN_NETWORKS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkProfile {
+    pub name: String,
+    /// Subscan API base URL; playlists are looked up there when set.
+    pub subscan_api_url: Option<String>,
+    /// HTTP JSON-RPC URL of a node, scanned when there is no Subscan URL.
+    pub rpc_url: Option<String>,
+    /// SS58 prefix that addresses looked up on this network must carry.
+    pub ss58_prefix: Option<u16>,
+}
+
+/// Where a network's on-chain playlists are looked up.
+#[derive(Debug)]
+pub struct ChainNetwork {
+    /// Subscan API base URL; when unset, recent blocks are read from `rpc`.
+    pub subscan_api_url: Option<String>,
+    pub rpc: Option<ChainRpc>,
+    /// SS58 prefix that addresses must carry, when checked.
+    pub ss58_prefix: Option<u16>,
+}
+
+/// The networks on-chain playlists can be looked up on, with the recent
+/// lookup responses of each.
+///
+/// Responses are reused for `ttl` per network and address, so clients
+/// polling a lookup do not each reach Subscan or the node.
+#[derive(Debug)]
+pub struct ChainNetworks {
+    networks: HashMap<String, ChainNetwork>,
+    responses: Mutex<Responses>,
+    ttl: Duration,
+}
+
+/// Lookup responses by network name and address, with when they were made.
+type Responses = HashMap<(String, String), (Value, Instant)>;
+
+impl ChainNetworks {
+    /// The `default` network and the networks of `profiles`, whose nodes
+    /// are called with `client`, reusing responses for `ttl`; zero disables
+    /// the cache. A profile named [`DEFAULT_NETWORK`] replaces the default one.
+    pub fn new(
+        client: &reqwest::Client,
+        default: ChainNetwork,
+        profiles: &[NetworkProfile],
+        ttl: Duration,
+    ) -> Self {
+        let mut networks = HashMap::from([(DEFAULT_NETWORK.to_string(), default)]);
+        for profile in profiles {
+            networks.insert(
+                profile.name.clone(),
+                ChainNetwork {
+                    subscan_api_url: profile.subscan_api_url.clone(),
+                    rpc: profile
+                        .rpc_url
+                        .clone()
+                        .map(|url| ChainRpc::new(client.clone(), url)),
+                    ss58_prefix: profile.ss58_prefix,
+                },
+            );
+        }
+        Self {
+            networks,
+            responses: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// The network called `name`.
+    pub fn get(&self, name: &str) -> Option<&ChainNetwork> {
+        self.networks.get(name)
+    }
+
+    /// The response of a lookup of `address` on `network` made within the
+    /// cache's time to live.
+    pub fn cached(&self, network: &str, address: &str) -> Option<Value> {
+        self.responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(network.to_string(), address.to_string()))
+            .filter(|(_, at)| at.elapsed() < self.ttl)
+            .map(|(response, _)| response.clone())
+    }
+
+    /// Remember the `response` of a lookup of `address` on `network`.
+    pub fn store(&self, network: &str, address: &str, response: Value) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        responses.retain(|_, (_, at)| at.elapsed() < self.ttl);
+        responses.insert(
+            (network.to_string(), address.to_string()),
+            (response, Instant::now()),
+        );
+    }
+}
+
+/// Parse `CHAIN_NETWORKS`: comma-separated
+/// `name|subscan_url|rpc_url|ss58_prefix` entries, where any field but the
+/// name may be empty and trailing ones left out. Entries without a name, or
+/// without either URL, are skipped.
+pub fn parse_networks(value: &str) -> Vec<NetworkProfile> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let mut fields = entry.split('|').map(str::trim);
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let mut url = || {
+                fields
+                    .next()
+                    .filter(|url| !url.is_empty())
+                    .map(|url| url.trim_end_matches('/').to_string())
+            };
+            let subscan_api_url = url();
+            let rpc_url = url();
+            let ss58_prefix = fields.next().and_then(|prefix| prefix.parse().ok());
+            (subscan_api_url.is_some() || rpc_url.is_some()).then(|| NetworkProfile {
+                name: name.to_string(),
+                subscan_api_url,
+                rpc_url,
+                ss58_prefix,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_network_profiles() {
+        let profiles = parse_networks(
+            "polkadot|https://polkadot.api.subscan.io/|https://rpc.polkadot.io|0, \
+             private||http://localhost:9933, kusama|https://kusama.api.subscan.io||2, \
+             broken, |https://nameless.example",
+        );
+        assert_eq!(
+            profiles,
+            vec![
+                NetworkProfile {
+                    name: "polkadot".into(),
+                    subscan_api_url: Some("https://polkadot.api.subscan.io".into()),
+                    rpc_url: Some("https://rpc.polkadot.io".into()),
+                    ss58_prefix: Some(0),
+                },
+                NetworkProfile {
+                    name: "private".into(),
+                    subscan_api_url: None,
+                    rpc_url: Some("http://localhost:9933".into()),
+                    ss58_prefix: None,
+                },
+                NetworkProfile {
+                    name: "kusama".into(),
+                    subscan_api_url: Some("https://kusama.api.subscan.io".into()),
+                    rpc_url: None,
+                    ss58_prefix: Some(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn caches_responses_per_network() {
+        let default = ChainNetwork {
+            subscan_api_url: Some("https://paseo.api.subscan.io".into()),
+            rpc: None,
+            ss58_prefix: None,
+        };
+        let networks = ChainNetworks::new(
+            &reqwest::Client::new(),
+            default,
+            &parse_networks("kusama|https://kusama.api.subscan.io||2"),
+            Duration::from_secs(60),
+        );
+        assert!(networks.get(DEFAULT_NETWORK).is_some());
+        assert_eq!(networks.get("kusama").unwrap().ss58_prefix, Some(2));
+        assert!(networks.get("polkadot").is_none());
+
+        networks.store("kusama", "addr", json!({"found": false}));
+        assert_eq!(
+            networks.cached("kusama", "addr"),
+            Some(json!({"found": false}))
+        );
+        assert_eq!(networks.cached(DEFAULT_NETWORK, "addr"), None);
+
+        let uncached = ChainNetworks::new(
+            &reqwest::Client::new(),
+            ChainNetwork {
+                subscan_api_url: None,
+                rpc: None,
+                ss58_prefix: None,
+            },
+            &[],
+            Duration::ZERO,
+        );
+        uncached.store(DEFAULT_NETWORK, "addr", json!({"found": false}));
+        assert_eq!(uncached.cached(DEFAULT_NETWORK, "addr"), None);
+    }
+}
@@ -0,0 +1,326 @@
+use codec::{Compact, Decode, Encode};
+use iptv_payload::{PayloadError, PlaylistPayload, REMARK_PREFIX};
+use thiserror::Error;
+
+use crate::models::Channel;
+use crate::services::ipfs::{IpfsError, IpfsGateway};
+use crate::services::m3u_parser;
+
+/// Name of on-chain playlists that have none.
+const DEFAULT_NAME: &str = "On-Chain Playlist";
+
+/// Errors that can occur while reading the playlist a remark points to.
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    /// The content could not be fetched from IPFS.
+    #[error(transparent)]
+    Ipfs(#[from] IpfsError),
+    /// The content is a playlist payload that does not validate.
+    #[error("Invalid playlist payload in {cid}: {source}")]
+    Payload { cid: String, source: PayloadError },
+    /// The content is neither a playlist payload nor an M3U playlist.
+    #[error("Content of {0} is not a playlist")]
+    NotAPlaylist(String),
+}
+
+/// A playlist remark: `IPTV:{...}` carrying the playlist, or
+/// `IPTV:ipfs://<cid>` naming one stored on IPFS.
+#[derive(Debug)]
+pub enum ChainRemark {
+    Inline(PlaylistPayload),
+    Ipfs(String),
+}
+
+impl ChainRemark {
+    /// Parse a playlist remark; `None` for any other remark.
+    pub fn parse(remark: &str) -> Option<Self> {
+        if let Some(cid) = iptv_payload::remark_cid(remark) {
+            return Some(Self::Ipfs(cid.to_string()));
+        }
+        PlaylistPayload::from_remark(remark).ok().map(Self::Inline)
+    }
+
+    /// The playlist the remark publishes, fetched through `ipfs` when it
+    /// names a CID.
+    pub async fn resolve(self, ipfs: &IpfsGateway) -> Result<ChainPlaylist, ResolveError> {
+        match self {
+            Self::Inline(payload) => Ok(ChainPlaylist::from_payload(&payload, None)),
+            Self::Ipfs(cid) => {
+                let content = ipfs.fetch(&cid).await?;
+                ChainPlaylist::from_content(cid, &content)
+            }
+        }
+    }
+}
+
+/// A playlist published on-chain, ready to serve.
+#[derive(Debug)]
+pub struct ChainPlaylist {
+    pub name: String,
+    pub channels: Vec<Channel>,
+    /// CID the playlist was fetched from, when stored on IPFS.
+    pub cid: Option<String>,
+}
+
+impl ChainPlaylist {
+    fn from_payload(payload: &PlaylistPayload, cid: Option<String>) -> Self {
+        Self {
+            name: playlist_name(payload).to_string(),
+            channels: parse_chain_channels(payload),
+            cid,
+        }
+    }
+
+    /// Read content stored on IPFS under `cid`: a playlist payload, with or
+    /// without the remark prefix, or an M3U playlist.
+    fn from_content(cid: String, content: &[u8]) -> Result<Self, ResolveError> {
+        let Ok(text) = std::str::from_utf8(content) else {
+            return Err(ResolveError::NotAPlaylist(cid));
+        };
+        let text = text.trim_start_matches('\u{feff}').trim();
+
+        let json = text.strip_prefix(REMARK_PREFIX).unwrap_or(text);
+        if json.starts_with('{') {
+            return match PlaylistPayload::from_json(json) {
+                Ok(payload) => Ok(Self::from_payload(&payload, Some(cid))),
+                Err(source) => Err(ResolveError::Payload { cid, source }),
+            };
+        }
+        if !text.starts_with("#EXTM3U") && !text.starts_with("#EXTINF") {
+            return Err(ResolveError::NotAPlaylist(cid));
+        }
+        Ok(Self {
+            name: DEFAULT_NAME.to_string(),
+            channels: m3u_parser::parse_m3u(text).channels,
+            cid: Some(cid),
+        })
+    }
+}
+
+/// The `IPTV:` remark of `extrinsic`, if it is signed by `signer` and ends
+/// with one.
+///
+/// Signed extensions differ between runtimes, so instead of decoding the
+/// call this looks for a prefixed remark whose SCALE length covers the rest
+/// of the extrinsic, which is where `system.remark` and
+/// `system.remark_with_event` put it. Remarks inside batches are not found.
+pub fn signed_remark<'a>(extrinsic: &'a [u8], signer: &[u8; 32]) -> Option<&'a str> {
+    let mut body = extrinsic;
+    let len = Compact::<u32>::decode(&mut body).ok()?.0;
+    if body.len() != len as usize {
+        return None;
+    }
+    // A signed v4 extrinsic from a `MultiAddress::Id`.
+    if body.first() != Some(&0x84) || body.get(1) != Some(&0) || body.get(2..34) != Some(signer) {
+        return None;
+    }
+
+    (34..body.len())
+        .filter(|&i| body[i..].starts_with(REMARK_PREFIX.as_bytes()))
+        .find_map(|i| {
+            let length = Compact(u32::try_from(body.len() - i).ok()?).encode();
+            if !body[..i].ends_with(&length) {
+                return None;
+            }
+            std::str::from_utf8(&body[i..]).ok()
+        })
+}
+
+/// Name of an on-chain playlist, with a default for unnamed ones.
+fn playlist_name(payload: &PlaylistPayload) -> &str {
+    if payload.name.is_empty() {
+        DEFAULT_NAME
+    } else {
+        payload.name.as_str()
+    }
+}
+
+/// Build a list of [`Channel`] structs from a decoded on-chain payload.
+///
+/// The wire format (short keys, limits, versioning) is defined by the shared
+/// `iptv-payload` crate; channels without a group are placed in `On-Chain`.
+fn parse_chain_channels(payload: &PlaylistPayload) -> Vec<Channel> {
+    payload
+        .channels
+        .iter()
+        .enumerate()
+        .map(|(i, ch)| Channel {
+            id: format!("chain-{i}-{}", hash_url(&ch.stream_url)),
+            name: ch.name.clone(),
+            group: ch.group.clone().unwrap_or_else(|| "On-Chain".to_string()),
+            logo_url: ch.logo_url.clone(),
+            stream_url: ch.stream_url.clone(),
+            is_live: true,
+            tvg_id: None,
+            variants: Vec::new(),
+            health_score: None,
+            stream_info: None,
+            health: Default::default(),
+            position: None,
+            attributes: Default::default(),
+        })
+        .collect()
+}
+
+/// Simple hash of a URL to generate a stable channel ID component.
+fn hash_url(url: &str) -> u64 {
+    let mut hash: u64 = 5381;
+    for byte in url.bytes() {
+        hash = hash.wrapping_mul(33).wrapping_add(u64::from(byte));
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn payload_from(data: &Value) -> PlaylistPayload {
+        PlaylistPayload::from_json(&data.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_chain_channels_valid() {
+        let data = json!({
+            "v": 1,
+            "n": "My Playlist",
+            "c": [
+                {
+                    "n": "Channel One",
+                    "s": "http://example.com/stream1.m3u8",
+                    "g": "News",
+                    "l": "http://example.com/logo1.png"
+                },
+                {
+                    "n": "Channel Two",
+                    "s": "http://example.com/stream2.m3u8",
+                    "g": "Sports",
+                    "l": null
+                }
+            ]
+        });
+
+        let channels = parse_chain_channels(&payload_from(&data));
+        assert_eq!(channels.len(), 2);
+
+        assert_eq!(channels[0].name, "Channel One");
+        assert_eq!(channels[0].stream_url, "http://example.com/stream1.m3u8");
+        assert_eq!(channels[0].group, "News");
+        assert_eq!(
+            channels[0].logo_url.as_deref(),
+            Some("http://example.com/logo1.png")
+        );
+
+        assert_eq!(channels[1].name, "Channel Two");
+        assert_eq!(channels[1].stream_url, "http://example.com/stream2.m3u8");
+        assert_eq!(channels[1].group, "Sports");
+        assert!(channels[1].logo_url.is_none());
+    }
+
+    #[test]
+    fn test_parse_chain_channels_missing_channels_key() {
+        let data = json!({"n": "Empty"});
+        let channels = parse_chain_channels(&payload_from(&data));
+        assert!(channels.is_empty());
+    }
+
+    #[test]
+    fn test_parse_chain_channels_skips_invalid() {
+        let data = json!({
+            "c": [
+                {"n": "Valid", "s": "http://example.com/ok.m3u8"},
+                {"n": "No URL"},
+                {"s": "http://example.com/no-name.m3u8"}
+            ]
+        });
+
+        let channels = parse_chain_channels(&payload_from(&data));
+        // Only the first has both name and stream_url
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].name, "Valid");
+    }
+
+    /// A signed extrinsic from `signer` whose call ends with `remark`.
+    fn signed_extrinsic(signer: &[u8; 32], remark: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x84, 0x00];
+        body.extend_from_slice(signer);
+        // Signature type and signature, era, nonce, tip, then the call index.
+        body.push(0x01);
+        body.extend_from_slice(&[0xaa; 64]);
+        body.extend_from_slice(&[0x00, 0x04, 0x00, 0x00, 0x07]);
+        body.extend(remark.to_vec().encode());
+        let mut extrinsic = Compact(body.len() as u32).encode();
+        extrinsic.extend(body);
+        extrinsic
+    }
+
+    #[test]
+    fn test_signed_remark() {
+        let signer = [7u8; 32];
+        let remark = br#"IPTV:{"v":1,"n":"Mine","c":[]}"#;
+        let extrinsic = signed_extrinsic(&signer, remark);
+        assert_eq!(
+            signed_remark(&extrinsic, &signer),
+            Some(std::str::from_utf8(remark).unwrap())
+        );
+        assert_eq!(signed_remark(&extrinsic, &[8u8; 32]), None);
+
+        let other = signed_extrinsic(&signer, b"hello IPTV: world");
+        assert_eq!(signed_remark(&other, &signer), None);
+
+        // Unsigned extrinsics carry no signer.
+        let mut unsigned = extrinsic.clone();
+        let version = unsigned.iter().position(|&b| b == 0x84).unwrap();
+        unsigned[version] = 0x04;
+        assert_eq!(signed_remark(&unsigned, &signer), None);
+    }
+
+    #[test]
+    fn reads_playlist_remarks() {
+        let inline = ChainRemark::parse(r#"IPTV:{"v":1,"n":"Mine","c":[]}"#);
+        assert!(matches!(inline, Some(ChainRemark::Inline(p)) if p.name == "Mine"));
+        let ipfs = ChainRemark::parse("IPTV:ipfs://bafkreiexample");
+        assert!(matches!(ipfs, Some(ChainRemark::Ipfs(cid)) if cid == "bafkreiexample"));
+        assert!(ChainRemark::parse("IPTV:not json").is_none());
+    }
+
+    #[test]
+    fn reads_ipfs_content() {
+        let m3u =
+            b"#EXTM3U\n#EXTINF:-1 group-title=\"News\",News One\nhttps://example.com/news.m3u8\n";
+        let playlist = ChainPlaylist::from_content("cid".into(), m3u).unwrap();
+        assert_eq!(playlist.name, DEFAULT_NAME);
+        assert_eq!(playlist.channels.len(), 1);
+        assert_eq!(playlist.channels[0].group, "News");
+        assert_eq!(playlist.cid.as_deref(), Some("cid"));
+
+        let json = br#"{"v":1,"n":"Stored","c":[{"n":"One","s":"https://example.com/1.m3u8"}]}"#;
+        let playlist = ChainPlaylist::from_content("cid".into(), json).unwrap();
+        assert_eq!(playlist.name, "Stored");
+        assert_eq!(playlist.channels.len(), 1);
+
+        assert!(matches!(
+            ChainPlaylist::from_content("cid".into(), br#"{"v":99}"#),
+            Err(ResolveError::Payload { .. })
+        ));
+        assert!(matches!(
+            ChainPlaylist::from_content("cid".into(), b"hello"),
+            Err(ResolveError::NotAPlaylist(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_url_deterministic() {
+        let url = "http://example.com/stream.m3u8";
+        assert_eq!(hash_url(url), hash_url(url));
+    }
+
+    #[test]
+    fn test_hash_url_different_for_different_urls() {
+        assert_ne!(
+            hash_url("http://example.com/a.m3u8"),
+            hash_url("http://example.com/b.m3u8")
+        );
+    }
+}
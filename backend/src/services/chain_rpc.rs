@@ -0,0 +1,219 @@
+use std::hash::Hasher;
+use std::time::Duration;
+
+use blake2::digest::consts::{U16, U32};
+use blake2::{Blake2b, Digest};
+use codec::Decode;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Timeout for a single node RPC request.
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that can occur while talking to a node over JSON-RPC.
+#[derive(Debug, Error)]
+pub enum RpcError {
+    /// No node RPC endpoint is configured.
+    #[error("CHAIN_RPC_URL is not set")]
+    NotConfigured,
+    /// HTTP request to the node failed.
+    #[error("RPC request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The node answered with a JSON-RPC error or an unexpected result.
+    #[error("RPC error: {0}")]
+    Rpc(String),
+    /// A storage value did not decode as the expected type.
+    #[error("Failed to decode {0}")]
+    Decode(&'static str),
+}
+
+/// A Substrate node's HTTP JSON-RPC endpoint.
+#[derive(Debug, Clone)]
+pub struct ChainRpc {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl ChainRpc {
+    /// A client for the node at `url`, sending requests with `client`.
+    pub fn new(client: reqwest::Client, url: String) -> Self {
+        Self { url, client }
+    }
+
+    /// Call `method` with `params` and return its result.
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({"jsonrpc": "2.0", "id": 0, "method": method, "params": params});
+        let response = self.post(&body).await?;
+        into_result(response)
+    }
+
+    /// Send several calls in one request, returning their results in order.
+    pub async fn batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Value>, RpcError> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+            })
+            .collect();
+        let Value::Array(mut responses) = self.post(&Value::Array(body)).await? else {
+            return Err(RpcError::Rpc(
+                "batch answered with a single response".into(),
+            ));
+        };
+        if responses.len() != calls.len() {
+            return Err(RpcError::Rpc(format!(
+                "batch of {} calls answered with {} responses",
+                calls.len(),
+                responses.len()
+            )));
+        }
+        // Responses may come in any order.
+        responses.sort_by_key(|r| r.get("id").and_then(Value::as_u64).unwrap_or(u64::MAX));
+        responses.into_iter().map(into_result).collect()
+    }
+
+    /// Raw storage value at `key` at the node's best block; `None` when unset.
+    pub async fn get_storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, RpcError> {
+        let params = json!([format!("0x{}", hex::encode(key))]);
+        match self.call("state_getStorage", params).await? {
+            Value::Null => Ok(None),
+            Value::String(value) => decode_hex(&value).map(Some),
+            other => Err(RpcError::Rpc(format!("unexpected storage value {other}"))),
+        }
+    }
+
+    /// Read and decode the storage value at `key`; `None` when unset.
+    pub async fn read<T: Decode>(
+        &self,
+        key: &[u8],
+        what: &'static str,
+    ) -> Result<Option<T>, RpcError> {
+        let Some(bytes) = self.get_storage(key).await? else {
+            return Ok(None);
+        };
+        T::decode(&mut bytes.as_slice())
+            .map(Some)
+            .map_err(|_| RpcError::Decode(what))
+    }
+
+    /// Call the runtime API function `method` with SCALE-encoded `args`,
+    /// returning its encoded result.
+    pub async fn state_call(&self, method: &str, args: &[u8]) -> Result<Vec<u8>, RpcError> {
+        let params = json!([method, format!("0x{}", hex::encode(args))]);
+        match self.call("state_call", params).await? {
+            Value::String(value) => decode_hex(&value),
+            other => Err(RpcError::Rpc(format!("unexpected call result {other}"))),
+        }
+    }
+
+    /// Number of the node's best block.
+    pub async fn best_block(&self) -> Result<u32, RpcError> {
+        let header = self.call("chain_getHeader", json!([])).await?;
+        header
+            .get("number")
+            .and_then(Value::as_str)
+            .and_then(|n| u32::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+            .ok_or(RpcError::Decode("block header"))
+    }
+
+    async fn post(&self, body: &Value) -> Result<Value, RpcError> {
+        Ok(self
+            .client
+            .post(&self.url)
+            .json(body)
+            .timeout(RPC_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// The result of a JSON-RPC response, or its error.
+fn into_result(mut response: Value) -> Result<Value, RpcError> {
+    if let Some(error) = response.get("error") {
+        return Err(RpcError::Rpc(error.to_string()));
+    }
+    Ok(response
+        .get_mut("result")
+        .map(Value::take)
+        .unwrap_or_default())
+}
+
+/// Bytes of a `0x`-prefixed hex string from the node.
+pub fn decode_hex(value: &str) -> Result<Vec<u8>, RpcError> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|_| RpcError::Rpc(format!("invalid hex {value}")))
+}
+
+/// Storage key of `pallet`'s `item`, followed by the already hashed map keys.
+pub fn storage_key(pallet: &str, item: &str, keys: &[Vec<u8>]) -> Vec<u8> {
+    let mut key = twox_128(pallet.as_bytes()).to_vec();
+    key.extend_from_slice(&twox_128(item.as_bytes()));
+    for map_key in keys {
+        key.extend_from_slice(map_key);
+    }
+    key
+}
+
+/// The `Twox128` hash of storage prefixes.
+fn twox_128(data: &[u8]) -> [u8; 16] {
+    let mut out = [0u8; 16];
+    for (seed, chunk) in out.chunks_mut(8).enumerate() {
+        let mut hasher = twox_hash::XxHash64::with_seed(seed as u64);
+        hasher.write(data);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    out
+}
+
+/// The `Blake2_128Concat` hash of a map key: its 128-bit BLAKE2b hash,
+/// then the key itself.
+pub fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+    let mut out = Blake2b::<U16>::digest(data).to_vec();
+    out.extend_from_slice(data);
+    out
+}
+
+/// The 256-bit BLAKE2b hash Substrate uses for blocks and extrinsics.
+pub fn blake2_256(data: &[u8]) -> [u8; 32] {
+    Blake2b::<U32>::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+
+    #[test]
+    fn builds_storage_keys() {
+        // `System::Number`, as in every Substrate runtime.
+        assert_eq!(
+            hex::encode(storage_key("System", "Number", &[])),
+            "26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac"
+        );
+        let map_key = blake2_128_concat(&7u32.encode());
+        assert_eq!(map_key.len(), 16 + 4);
+        assert_eq!(&map_key[16..], &[7, 0, 0, 0]);
+    }
+
+    #[test]
+    fn reads_results_and_errors() {
+        assert_eq!(
+            into_result(json!({"id": 0, "result": "0x01"})).unwrap(),
+            json!("0x01")
+        );
+        assert_eq!(into_result(json!({"id": 0})).unwrap(), Value::Null);
+        assert!(matches!(
+            into_result(json!({"id": 0, "error": {"code": -32601}})),
+            Err(RpcError::Rpc(_))
+        ));
+        assert_eq!(decode_hex("0x0a0b").unwrap(), vec![10, 11]);
+        assert!(decode_hex("0xzz").is_err());
+    }
+}
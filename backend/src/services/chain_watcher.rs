@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tracing::{info, warn};
+
+use crate::models::{AppState, Channel, Playlist};
+use crate::services::chain_playlist::{signed_remark, ChainPlaylist, ChainRemark};
+use crate::services::chain_rpc::{decode_hex, ChainRpc, RpcError};
+use crate::services::events::ServerEvent;
+use crate::services::{playlist_registry, watchdog};
+
+/// Delay before reconnecting after the subscription fails or drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Longest gap between heartbeats of a healthy watcher: it beats on every
+/// finalized block and every reconnect attempt.
+pub const MAX_SILENCE: Duration = Duration::from_secs(60);
+
+/// Most finalized blocks read at once, e.g. when catching up after a
+/// reconnect; older ones are skipped.
+const MAX_CATCH_UP: u32 = 100;
+
+/// Errors that end a subscription.
+#[derive(Debug, Error)]
+enum WatchError {
+    /// The WebSocket connection failed.
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tungstenite::Error),
+    /// A block or registry read failed.
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    /// The node closed the subscription.
+    #[error("Subscription closed by the node")]
+    Closed,
+}
+
+/// What the watcher has seen of the watched account so far.
+struct Watched {
+    /// Public key of the watched address.
+    signer: [u8; 32],
+    /// Last finalized block that was read.
+    last_block: Option<u32>,
+    /// Latest registry version of each of the account's playlists.
+    versions: Option<Vec<(u32, u32)>>,
+}
+
+/// Spawn a task following finalized blocks through a WebSocket
+/// subscription at `ws_url`, and read each new block through `rpc`.
+///
+/// When `address` publishes a playlist remark, the on-chain playlist
+/// replaces the default one and a liveness check runs; channels it keeps
+/// keep their liveness. When one of its playlist-registry playlists
+/// publishes a new content version, the M3U sources are reloaded. The
+/// subscription reconnects after [`RECONNECT_DELAY`] when it drops, and
+/// catches up on the blocks it missed. `signer` is the public key of the
+/// watched address.
+///
+/// Beats [`watchdog::CHAIN_WATCHER`] on each finalized block and each
+/// reconnect attempt.
+pub fn start_chain_watcher(
+    state: Arc<AppState>,
+    rpc: ChainRpc,
+    ws_url: String,
+    signer: [u8; 32],
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut watched = Watched {
+            signer,
+            last_block: None,
+            versions: None,
+        };
+
+        loop {
+            state.watchdog.beat(watchdog::CHAIN_WATCHER);
+            if let Err(e) = follow(&state, &rpc, &ws_url, &mut watched).await {
+                warn!("Chain subscription at {ws_url} failed: {e}");
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    })
+}
+
+/// Subscribe to finalized heads and process each new block until the
+/// subscription ends.
+async fn follow(
+    state: &AppState,
+    rpc: &ChainRpc,
+    ws_url: &str,
+    watched: &mut Watched,
+) -> Result<(), WatchError> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(ws_url).await?;
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "chain_subscribeFinalizedHeads",
+        "params": []
+    });
+    socket.send(Message::Text(subscribe.to_string())).await?;
+    info!("Watching finalized blocks at {ws_url}");
+
+    while let Some(message) = socket.next().await {
+        let Message::Text(text) = message? else {
+            continue;
+        };
+        if let Some(number) = finalized_number(&text) {
+            state.watchdog.beat(watchdog::CHAIN_WATCHER);
+            catch_up(state, rpc, watched, number).await?;
+        }
+    }
+    Err(WatchError::Closed)
+}
+
+/// Number of the block in a `chain_finalizedHead` notification.
+fn finalized_number(message: &str) -> Option<u32> {
+    let value: Value = serde_json::from_str(message).ok()?;
+    if value.get("method")?.as_str()? != "chain_finalizedHead" {
+        return None;
+    }
+    let number = value.pointer("/params/result/number")?.as_str()?;
+    u32::from_str_radix(number.trim_start_matches("0x"), 16).ok()
+}
+
+/// Read the blocks finalized since the last one seen, up to `number`, then
+/// check the registry for new versions.
+async fn catch_up(
+    state: &AppState,
+    rpc: &ChainRpc,
+    watched: &mut Watched,
+    number: u32,
+) -> Result<(), WatchError> {
+    let oldest = number.saturating_sub(MAX_CATCH_UP - 1);
+    let first = watched
+        .last_block
+        .map_or(number, |last| last + 1)
+        .max(oldest);
+    for block in first..=number {
+        let hash = rpc.call("chain_getBlockHash", json!([block])).await?;
+        let body = rpc.call("chain_getBlock", json!([hash])).await?;
+        if let Some(remark) = newest_playlist(&body, &watched.signer)? {
+            match remark.resolve(&state.ipfs).await {
+                Ok(playlist) => {
+                    info!("Playlist published on-chain in block {block}; replacing the playlist");
+                    apply_chain_playlist(state, playlist).await;
+                }
+                Err(e) => warn!("Playlist published in block {block} is unreadable: {e}"),
+            }
+        }
+        watched.last_block = Some(block);
+    }
+
+    let versions = playlist_registry::latest_versions(rpc, &watched.signer).await?;
+    if watched
+        .versions
+        .as_ref()
+        .is_some_and(|seen| *seen != versions)
+    {
+        info!("Registry playlist version published; reloading M3U sources");
+        state.refresh_sources.notify_one();
+    }
+    watched.versions = Some(versions);
+    Ok(())
+}
+
+/// The newest playlist remark signed by `signer` in a `chain_getBlock`
+/// result.
+fn newest_playlist(block: &Value, signer: &[u8; 32]) -> Result<Option<ChainRemark>, RpcError> {
+    let extrinsics = block
+        .pointer("/block/extrinsics")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for extrinsic in extrinsics.iter().rev().filter_map(Value::as_str) {
+        let bytes = decode_hex(extrinsic)?;
+        let remark = signed_remark(&bytes, signer).and_then(ChainRemark::parse);
+        if remark.is_some() {
+            return Ok(remark);
+        }
+    }
+    Ok(None)
+}
+
+/// Replace the default playlist with an on-chain one. Channels already in
+/// the playlist keep what the checker learned about them.
+async fn apply_chain_playlist(state: &AppState, chain_playlist: ChainPlaylist) {
+    let mut channels = chain_playlist.channels;
+    {
+        let mut playlist = state.playlist.write().await;
+        let known: HashMap<&str, &Channel> = playlist
+            .channels
+            .iter()
+            .map(|ch| (ch.id.as_str(), ch))
+            .collect();
+        for ch in &mut channels {
+            if let Some(old) = known.get(ch.id.as_str()) {
+                ch.is_live = old.is_live;
+                ch.health_score = old.health_score;
+                ch.stream_info = old.stream_info.clone();
+                ch.health = old.health.clone();
+            }
+        }
+        *playlist = Playlist {
+            name: chain_playlist.name,
+            channels,
+            last_checked: playlist.last_checked.take(),
+            source: "on-chain".to_string(),
+            epg_urls: Vec::new(),
+        };
+        state
+            .search_index
+            .write()
+            .await
+            .sync_channels(&playlist.channels);
+        state
+            .events
+            .publish(ServerEvent::playlist_replaced(&playlist));
+    }
+
+    state.check_now.notify_one();
+    state.state_changed.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_finalized_head_notifications() {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "chain_finalizedHead",
+            "params": {"subscription": "abc", "result": {"number": "0x1a2b", "parentHash": "0x00"}}
+        });
+        assert_eq!(finalized_number(&notification.to_string()), Some(0x1a2b));
+        assert_eq!(
+            finalized_number(&json!({"jsonrpc": "2.0", "id": 1, "result": "abc"}).to_string()),
+            None
+        );
+        assert_eq!(finalized_number("not json"), None);
+    }
+
+    #[test]
+    fn finds_the_newest_playlist_in_a_block() {
+        let block = json!({"block": {"extrinsics": ["0x280403000b"]}});
+        assert!(newest_playlist(&block, &[7u8; 32]).unwrap().is_none());
+        assert!(newest_playlist(&json!({}), &[7u8; 32]).unwrap().is_none());
+    }
+}
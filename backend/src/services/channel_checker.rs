@@ -1,96 +1,797 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, LOCATION, RANGE};
+use reqwest::{Client, IntoUrl, Method, RequestBuilder, Response, StatusCode, Url};
+use thiserror::Error;
 use tokio::sync::Semaphore;
-use tracing::{info, warn};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, info, warn};
 
-use crate::models::AppState;
+use crate::config::{Config, ProbeMode};
+use crate::models::{AccessIssue, AppState, Channel, HealthStatus, StreamInfo};
+use crate::services::events::{EventBus, ServerEvent};
+use crate::services::hls_health::{self, HlsPlaylist};
+use crate::services::probe_schedule::ProbeSchedule;
+use crate::services::stream_headers;
+#[cfg(feature = "ffprobe")]
+use crate::services::stream_probe;
+use crate::services::uptime::UptimeHistory;
+use crate::services::watchdog;
 
-/// Check whether a single channel stream URL is reachable.
+/// Delay before the first retry of a failed probe, doubled for each later one.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Byte range requested by GET probes; enough to see the stream start.
+const PROBE_RANGE: &str = "bytes=0-1024";
+
+/// Path segments, without extension, of the pages providers send viewers
+/// to when they must sign in or subscribe first.
+const LOGIN_PAGES: &[&str] = &[
+    "login",
+    "signin",
+    "sign-in",
+    "sign_in",
+    "logon",
+    "auth",
+    "authorize",
+    "subscribe",
+];
+
+/// Why a deep HLS probe found a stream unplayable.
+#[derive(Debug, Error)]
+pub enum HlsProbeError {
+    /// A playlist could not be fetched.
+    #[error("HLS playlist {0} is unreachable")]
+    Unreachable(String),
+    /// A playlist or segment request was answered with an error status.
+    #[error("{0} answered {1}")]
+    Status(String, StatusCode),
+    /// A playlist or segment request was redirected to a login page.
+    #[error("{0} redirects to a login page")]
+    LoginRedirect(String),
+    /// A playlist was fetched but is not an HLS playlist.
+    #[error("{0} is not an HLS playlist")]
+    NotAPlaylist(String),
+    /// The media playlist lists no segments.
+    #[error("HLS playlist {0} lists no segments")]
+    NoSegments(String),
+    /// The newest segment could not be fetched, or was empty.
+    #[error("HLS segment {0} is unreachable")]
+    SegmentUnreachable(String),
+}
+
+impl HlsProbeError {
+    /// The error status that failed the probe, if one did.
+    fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status(_, status) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// Result of probing one channel.
+#[derive(Debug, Clone, Default)]
+pub struct Probe {
+    /// Whether the stream is reachable, or for a deep HLS probe, playable.
+    pub is_live: bool,
+    /// HTTP status of the response that decided the probe, if one arrived.
+    pub status_code: Option<u16>,
+    /// How long the probe took.
+    pub latency: Duration,
+    /// Attributes of the HLS variant a deep probe followed.
+    pub stream_info: Option<StreamInfo>,
+    /// Why a failed probe was refused, when the stream looks up but out of
+    /// reach (see [`Answer::access`]).
+    pub access: Option<AccessIssue>,
+}
+
+/// How the request that decided a probe was answered.
+#[derive(Debug, Clone, Copy, Default)]
+struct Answer {
+    is_live: bool,
+    /// Status of the response, if one arrived.
+    status: Option<StatusCode>,
+    /// Whether the response sent the viewer to a login page.
+    login_redirect: bool,
+}
+
+impl Answer {
+    /// The answer `resp` gives for the stream at `url`, live as `is_live`
+    /// says unless it redirects to a login page.
+    fn of(resp: &Response, url: &str, is_live: bool) -> Self {
+        let login_redirect = redirected_to_login(resp, url);
+        Self {
+            is_live: is_live && !login_redirect,
+            status: Some(resp.status()),
+            login_redirect,
+        }
+    }
+
+    /// The access issue a refused stream points at: geo-blocking for `403`
+    /// and `451`, missing credentials for `401`, `402` and login redirects.
+    fn access(&self) -> Option<AccessIssue> {
+        if self.is_live {
+            return None;
+        }
+        if self.login_redirect {
+            return Some(AccessIssue::AuthRequired);
+        }
+        match self.status? {
+            StatusCode::FORBIDDEN | StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => {
+                Some(AccessIssue::GeoBlocked)
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::PAYMENT_REQUIRED => {
+                Some(AccessIssue::AuthRequired)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A stream to probe, with the headers to request it with.
+#[derive(Debug, Clone)]
+pub struct ProbeTarget {
+    pub url: String,
+    pub headers: HeaderMap,
+}
+
+impl ProbeTarget {
+    /// The stream of `channel`, requested with the `global` headers and the
+    /// channel's own (see [`stream_headers::for_channel`]).
+    pub fn of(channel: &Channel, global: &HeaderMap) -> Self {
+        Self {
+            url: channel.stream_url.clone(),
+            headers: stream_headers::for_channel(global, &channel.attributes),
+        }
+    }
+}
+
+/// Builds the requests of one probe, each carrying the target's headers
+/// and the probe timeout.
+#[derive(Clone, Copy)]
+struct ProbeRequests<'a> {
+    client: &'a Client,
+    headers: &'a HeaderMap,
+    timeout: Duration,
+}
+
+impl ProbeRequests<'_> {
+    fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .headers(self.headers.clone())
+            .timeout(self.timeout)
+    }
+}
+
+/// Probe a single channel the way `mode` says, with the shared `client`.
 ///
-/// Sends an HTTP HEAD request to `url` with the given `timeout`.
-/// Returns `true` if the server responds with a 2xx or 3xx status code.
-pub async fn check_channel(url: &str, timeout: Duration) -> bool {
-    let client = reqwest::Client::builder()
-        .timeout(timeout)
-        .build()
-        .unwrap_or_default();
+/// With [`ProbeMode::Hls`], HLS streams are validated by
+/// [`validate_hls`]; everything else goes through [`check_channel`].
+pub async fn probe_channel(
+    client: &Client,
+    target: &ProbeTarget,
+    timeout: Duration,
+    mode: ProbeMode,
+) -> Probe {
+    let started = Instant::now();
+    let requests = ProbeRequests {
+        client,
+        headers: &target.headers,
+        timeout,
+    };
+    let url = target.url.as_str();
+    let (answer, stream_info) = if mode == ProbeMode::Hls && hls_health::is_hls(url) {
+        match validate_hls(requests, url).await {
+            Ok((status, stream_info)) => (
+                Answer {
+                    is_live: true,
+                    status: Some(status),
+                    login_redirect: false,
+                },
+                stream_info,
+            ),
+            Err(e) => {
+                debug!("{e}");
+                let answer = Answer {
+                    is_live: false,
+                    status: e.status(),
+                    login_redirect: matches!(e, HlsProbeError::LoginRedirect(_)),
+                };
+                (answer, None)
+            }
+        }
+    } else {
+        (check_channel(requests, url).await, None)
+    };
+    Probe {
+        is_live: answer.is_live,
+        status_code: answer.status.map(|s| s.as_u16()),
+        latency: started.elapsed(),
+        stream_info,
+        access: answer.access(),
+    }
+}
 
-    match client.head(url).send().await {
+/// Check whether a single channel stream URL is reachable.
+///
+/// Sends an HTTP HEAD request to `url`, and reports it live if the server
+/// responds with a 2xx or 3xx status code, unless it redirects to a login
+/// page. Many stream servers reject or mishandle HEAD, so when it is
+/// refused (see [`head_unsupported`]) or times out, a ranged GET is sent
+/// instead and the channel is live if the first bytes of the body arrive.
+async fn check_channel(requests: ProbeRequests<'_>, url: &str) -> Answer {
+    match requests.request(Method::HEAD, url).send().await {
+        Ok(resp) if head_unsupported(resp.status()) => {}
         Ok(resp) => {
             let status = resp.status();
-            status.is_success() || status.is_redirection()
+            return Answer::of(&resp, url, status.is_success() || status.is_redirection());
+        }
+        Err(e) if e.is_timeout() => {}
+        Err(_) => return Answer::default(),
+    }
+
+    debug!("HEAD not answered for {url}; probing with a ranged GET");
+    fetch_start(requests, url).await
+}
+
+/// Whether a HEAD answered with `status` says nothing about the stream,
+/// because the server does not allow HEAD on it.
+fn head_unsupported(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::METHOD_NOT_ALLOWED | StatusCode::FORBIDDEN | StatusCode::NOT_IMPLEMENTED
+    )
+}
+
+/// Whether the first bytes of `url` arrive for a ranged GET, and how it
+/// was answered.
+async fn fetch_start(requests: ProbeRequests<'_>, url: &str) -> Answer {
+    let request = requests
+        .request(Method::GET, url)
+        .header(RANGE, PROBE_RANGE);
+    match request.send().await {
+        Ok(mut resp) if resp.status().is_success() => {
+            let answer = Answer::of(&resp, url, true);
+            let started = answer.is_live
+                && matches!(resp.chunk().await, Ok(Some(bytes)) if !bytes.is_empty());
+            Answer {
+                is_live: started,
+                ..answer
+            }
+        }
+        Ok(resp) => Answer::of(&resp, url, resp.status().is_redirection()),
+        Err(_) => Answer::default(),
+    }
+}
+
+/// Whether `resp`, answering a request for `url`, sends the viewer to a
+/// login page: it was redirected to one, or is a redirect to one that was
+/// not followed.
+fn redirected_to_login(resp: &Response, url: &str) -> bool {
+    let target = if resp.status().is_redirection() {
+        resp.headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| resp.url().join(location).ok())
+    } else {
+        Url::parse(url)
+            .ok()
+            .filter(|requested| requested != resp.url())
+            .map(|_| resp.url().clone())
+    };
+    target.is_some_and(|target| is_login_page(&target))
+}
+
+/// Whether `url` looks like a sign-in or subscription page (see
+/// [`LOGIN_PAGES`]).
+fn is_login_page(url: &Url) -> bool {
+    url.path_segments().into_iter().flatten().any(|segment| {
+        let stem = segment.split('.').next().unwrap_or(segment);
+        LOGIN_PAGES.contains(&stem.to_ascii_lowercase().as_str())
+    })
+}
+
+/// Check that an HLS stream plays, not just that its playlist answers.
+///
+/// Downloads and parses the playlist at `url`, follows the first variant
+/// of a master playlist, and fetches the start of the newest segment of
+/// the media playlist. Returns the status the playlist at `url` answered
+/// with, and the followed variant's attributes, or `None` when `url` is a
+/// media playlist itself.
+async fn validate_hls(
+    requests: ProbeRequests<'_>,
+    url: &str,
+) -> Result<(StatusCode, Option<StreamInfo>), HlsProbeError> {
+    let mut url = Url::parse(url).map_err(|_| HlsProbeError::Unreachable(url.to_string()))?;
+    let (status, mut playlist) = fetch_hls_playlist(requests, &url).await?;
+    let mut stream_info = None;
+    if let Some(variant) = playlist.variants.first() {
+        url = url
+            .join(&variant.uri)
+            .map_err(|_| HlsProbeError::Unreachable(variant.uri.clone()))?;
+        stream_info = Some(variant.info.clone());
+        playlist = fetch_hls_playlist(requests, &url).await?.1;
+    }
+
+    let (_, segment) = playlist
+        .segments
+        .last()
+        .ok_or_else(|| HlsProbeError::NoSegments(url.to_string()))?;
+    let segment_url = url
+        .join(segment)
+        .map_err(|_| HlsProbeError::SegmentUnreachable(segment.clone()))?;
+    let answer = fetch_start(requests, segment_url.as_str()).await;
+    match answer.status {
+        _ if answer.is_live => Ok((status, stream_info)),
+        _ if answer.login_redirect => Err(HlsProbeError::LoginRedirect(segment_url.to_string())),
+        Some(status) if !status.is_success() => {
+            Err(HlsProbeError::Status(segment_url.to_string(), status))
+        }
+        _ => Err(HlsProbeError::SegmentUnreachable(segment_url.to_string())),
+    }
+}
+
+/// Download and parse the HLS playlist at `url`, with the status it was
+/// answered with.
+async fn fetch_hls_playlist(
+    requests: ProbeRequests<'_>,
+    url: &Url,
+) -> Result<(StatusCode, HlsPlaylist), HlsProbeError> {
+    let unreachable = || HlsProbeError::Unreachable(url.to_string());
+    let resp = requests
+        .request(Method::GET, url.clone())
+        .send()
+        .await
+        .map_err(|_| unreachable())?;
+    if redirected_to_login(&resp, url.as_str()) {
+        return Err(HlsProbeError::LoginRedirect(url.to_string()));
+    }
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(HlsProbeError::Status(url.to_string(), status));
+    }
+    let text = resp.text().await.map_err(|_| unreachable())?;
+    if !is_hls_playlist(&text) {
+        return Err(HlsProbeError::NotAPlaylist(url.to_string()));
+    }
+    Ok((status, hls_health::parse_playlist(&text)))
+}
+
+/// Whether `text` starts with the `#EXTM3U` tag every HLS playlist opens with.
+fn is_hls_playlist(text: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with("#EXTM3U")
+}
+
+/// How a batch of channels is probed.
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeOptions {
+    /// Timeout of each probe request.
+    pub timeout: Duration,
+    /// How deep probes go.
+    pub mode: ProbeMode,
+    /// Most probes in flight at once.
+    pub concurrency: usize,
+    /// Most probes in flight at once against a single host.
+    pub per_host: usize,
+    /// Longest random delay before a probe starts.
+    pub jitter: Duration,
+    /// Retries of a probe that failed without a definite answer, e.g. on
+    /// a timeout or a `5xx`.
+    pub retries: u32,
+    /// Failed probes in a row before a live channel is marked dead.
+    pub failure_threshold: u32,
+    /// Failed probes in a row before a channel is quarantined; 0 never.
+    pub quarantine_after: u32,
+}
+
+impl ProbeOptions {
+    /// The options set by `PROBE_*` variables.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            timeout: Duration::from_secs(config.probe_timeout_secs),
+            mode: config.probe_mode,
+            concurrency: config.probe_concurrency,
+            per_host: config.probe_host_concurrency,
+            jitter: Duration::from_millis(config.probe_jitter_ms),
+            retries: config.probe_retries,
+            failure_threshold: config.probe_failure_threshold,
+            quarantine_after: config.probe_quarantine_after,
         }
-        Err(_) => false,
     }
 }
 
-/// Check all channels for liveness concurrently.
+/// Probe a batch of streams concurrently with the shared `client`.
 ///
-/// Uses a semaphore to limit concurrency to 20 simultaneous probe requests.
-/// Each channel's `is_live` field is updated in place.
-pub async fn check_all_channels(channels: &mut [crate::models::Channel], timeout: Duration) {
-    let semaphore = Arc::new(Semaphore::new(20));
-    let mut handles = Vec::with_capacity(channels.len());
-
-    for channel in channels.iter() {
-        let url = channel.stream_url.clone();
+/// At most `options.concurrency` probes run at once, and at most
+/// `options.per_host` of them against the same host, so one provider is
+/// not hit by the whole batch in parallel. Each probe starts after a
+/// random delay of up to `options.jitter`, so a batch does not arrive at
+/// once. Probes that fail without a definite answer (see
+/// [`is_transient`]) are retried up to `options.retries` times, after
+/// [`RETRY_BACKOFF`] doubled for each retry. Returns one result per
+/// target, in input order. Dropping the future aborts the probes still in
+/// flight.
+pub async fn check_streams(
+    client: &Client,
+    targets: &[ProbeTarget],
+    options: &ProbeOptions,
+) -> Vec<Probe> {
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let mut hosts: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let mut tasks = JoinSet::new();
+
+    for (i, target) in targets.iter().enumerate() {
+        let target = target.clone();
+        let client = client.clone();
         let sem = Arc::clone(&semaphore);
-        let t = timeout;
+        let host_sem = Arc::clone(
+            hosts
+                .entry(host_key(&target.url))
+                .or_insert_with(|| Arc::new(Semaphore::new(options.per_host.max(1)))),
+        );
+        let delay = options.jitter.mul_f64(rand::random::<f64>());
+        let ProbeOptions {
+            timeout,
+            mode,
+            retries,
+            ..
+        } = *options;
 
-        handles.push(tokio::spawn(async move {
-            let _permit = sem.acquire().await;
-            check_channel(&url, t).await
-        }));
+        tasks.spawn(async move {
+            tokio::time::sleep(delay).await;
+            let mut attempt = 0;
+            let probe = loop {
+                let probe = {
+                    // Wait for the host before taking a global slot, so probes
+                    // queued behind a busy host do not hold up other hosts.
+                    let _host_permit = host_sem.acquire().await;
+                    let _permit = sem.acquire().await;
+                    probe_channel(&client, &target, timeout, mode).await
+                };
+                if probe.is_live || attempt >= retries || !is_transient(&probe) {
+                    break probe;
+                }
+                tokio::time::sleep(RETRY_BACKOFF * 2u32.pow(attempt.min(10))).await;
+                attempt += 1;
+            };
+            (i, probe)
+        });
+    }
+
+    let mut results = vec![Probe::default(); targets.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((i, probe)) => results[i] = probe,
+            Err(e) => warn!("Channel check task panicked: {e}"),
+        }
+    }
+    results
+}
+
+/// Whether a failed probe may succeed when retried: no response arrived,
+/// or the server was overloaded or failing (`408`, `429`, `5xx`).
+fn is_transient(probe: &Probe) -> bool {
+    probe
+        .status_code
+        .is_none_or(|code| code == 408 || code == 429 || (500..600).contains(&code))
+}
+
+/// The host, with any explicit port, that the per-host limit applies to;
+/// the whole URL when it has none.
+fn host_key(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?.to_ascii_lowercase();
+            Some(match u.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Store the outcome of a `probe` finished at `now` on `channel`.
+///
+/// Besides liveness, this keeps the channel's [`ChannelHealth`]: a live
+/// channel is [`HealthStatus::Degraded`] when the probe took over half of
+/// the timeout, and a failing one records when its failures started and
+/// whether the stream was refused as geo-blocked or for credentials. A
+/// live channel is only marked dead after `options.failure_threshold`
+/// failed probes in a row, and is degraded until then. After
+/// `options.quarantine_after` failures it is
+/// [`HealthStatus::Quarantined`] and no longer probed.
+///
+/// [`ChannelHealth`]: crate::models::ChannelHealth
+pub fn record_probe(
+    channel: &mut Channel,
+    probe: &Probe,
+    options: &ProbeOptions,
+    now: DateTime<Utc>,
+) {
+    let health = &mut channel.health;
+    health.last_status_code = probe.status_code;
+    health.latency_ms = Some(u64::try_from(probe.latency.as_millis()).unwrap_or(u64::MAX));
+    health.last_checked = Some(now);
+    health.access = probe.access;
+    if probe.is_live {
+        health.status = Some(if probe.latency > options.timeout / 2 {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Up
+        });
+        health.consecutive_failures = 0;
+        health.last_ok = Some(now);
+        health.down_since = None;
+        channel.is_live = true;
+    } else {
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        health.down_since.get_or_insert(now);
+        let failures = health.consecutive_failures;
+        if failures >= options.failure_threshold {
+            channel.is_live = false;
+        }
+        health.status = Some(
+            if options.quarantine_after > 0 && failures >= options.quarantine_after {
+                HealthStatus::Quarantined
+            } else if channel.is_live {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Down
+            },
+        );
     }
+    channel.stream_info = probe.stream_info.clone();
+}
 
-    for (i, handle) in handles.into_iter().enumerate() {
-        match handle.await {
-            Ok(is_live) => {
-                channels[i].is_live = is_live;
+/// Probe results keyed by channel ID and the stream URL that was probed.
+pub type Outcome<'a> = HashMap<(&'a str, &'a str), Probe>;
+
+/// Record `outcome` on the channels of a playlist that may have changed
+/// while they were probed, and in their schedule and uptime history,
+/// returning how many results were applied. Channels that went live or
+/// stopped being live are announced on `events`.
+///
+/// Results only apply to channels that still have the probed stream URL:
+/// channels removed meanwhile are skipped, and a channel whose stream URL
+/// was edited waits for a probe of the new one. Channels added meanwhile
+/// are left for the next pass.
+pub fn apply_outcome(
+    channels: &mut [Channel],
+    schedule: &mut ProbeSchedule,
+    uptime: &mut UptimeHistory,
+    events: &EventBus,
+    outcome: &Outcome<'_>,
+    options: &ProbeOptions,
+    now: DateTime<Utc>,
+) -> usize {
+    let mut applied = 0;
+    for channel in channels {
+        let key = (channel.id.as_str(), channel.stream_url.as_str());
+        if let Some(probe) = outcome.get(&key).cloned() {
+            let was_live = channel.is_live;
+            record_probe(channel, &probe, options, now);
+            if channel.is_live != was_live {
+                events.publish(ServerEvent::channel_status(channel, now));
             }
-            Err(e) => {
-                warn!("Channel check task panicked: {e}");
-                channels[i].is_live = false;
+            schedule.record(&channel.id, probe.is_live, now);
+            uptime.record(&channel.id, probe.is_live, now);
+            if channel.health.status == Some(HealthStatus::Quarantined) {
+                schedule.set_quarantined(&channel.id, true, now);
             }
+            applied += 1;
         }
     }
+    applied
 }
 
-/// Spawn a background tokio task that checks all channels for liveness.
+/// Spawn a background tokio task that checks channels for liveness.
+///
+/// Each channel is probed when it falls due in [`AppState::probe_schedule`]:
+/// every `interval` normally, or more often when pinned. An explicit
+/// [`AppState::check_now`] notification, sent when the playlist is replaced
+/// or by `POST /api/checks/run`, makes every channel due at once, so a
+/// fresh playlist is checked immediately. When it arrives while channels
+/// are being probed, the probes are abandoned and the cycle restarts with
+/// every channel, rather than finishing a check of a stale playlist. The
+/// task never sleeps longer than `interval`, and wakes early on
+/// [`AppState::schedule_changed`].
+///
+/// Probes run on a snapshot of the due channels without holding the
+/// playlist lock; results are merged back in a short write section
+/// afterwards (see [`apply_outcome`]), so the API stays responsive and
+/// edits made during a check are kept.
 ///
-/// The task runs a check cycle whenever it is explicitly notified via
-/// [`AppState::check_now`] **or** when `interval` elapses — whichever
-/// comes first. This means a fresh playlist triggers an immediate check
-/// while the periodic schedule still fires as a safety net.
-pub fn start_background_checker(state: Arc<AppState>, interval: Duration, timeout: Duration) {
+/// When `HLS_HEALTH_ENABLED` is set, live HLS channels from each cycle are
+/// then deep-probed for a segment-level health score. With the `ffprobe`
+/// feature, live channels without stream metadata are then read with
+/// `ffprobe`.
+///
+/// The task reports heartbeats to [`AppState::watchdog`] under
+/// [`watchdog::CHANNEL_CHECKER`] before waiting and after each cycle.
+pub fn start_background_checker(
+    state: Arc<AppState>,
+    interval: Duration,
+    timeout: Duration,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
+        // Set when a cycle was canceled, to start over without waiting.
+        let mut restart = false;
         loop {
-            // Wait for either an explicit signal or the periodic timer.
-            tokio::select! {
+            state.watchdog.beat(watchdog::CHANNEL_CHECKER);
+
+            // Sleep until the next channel is due, but never longer than `interval`.
+            let wait = state
+                .probe_schedule
+                .read()
+                .await
+                .next_due()
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(interval, |d| d.min(interval));
+
+            // Wait for an explicit signal, a schedule change, or the next due check.
+            let check_all = std::mem::take(&mut restart)
+                || tokio::select! {
+                    () = state.check_now.notified() => {
+                        info!("Liveness check of every channel triggered");
+                        true
+                    }
+                    () = state.schedule_changed.notified() => false,
+                    () = tokio::time::sleep(wait) => false,
+                };
+
+            let now = Utc::now();
+            let (ids, targets): (Vec<String>, Vec<ProbeTarget>) = {
+                let playlist = state.playlist.read().await;
+                let mut schedule = state.probe_schedule.write().await;
+                schedule.sync(&playlist.channels, now);
+                if check_all {
+                    schedule.mark_all_due(now);
+                }
+                let due: HashSet<String> = schedule.due(now).into_iter().collect();
+                playlist
+                    .channels
+                    .iter()
+                    .filter(|c| due.contains(&c.id))
+                    .map(|c| {
+                        (
+                            c.id.clone(),
+                            ProbeTarget::of(c, &state.config.probe_headers),
+                        )
+                    })
+                    .unzip()
+            };
+
+            if ids.is_empty() {
+                continue;
+            }
+
+            debug!("Probing {} due channels", ids.len());
+            let options = ProbeOptions {
+                timeout,
+                ..ProbeOptions::from_config(&state.config)
+            };
+            let results = tokio::select! {
+                results = check_streams(&state.http, &targets, &options) => results,
                 () = state.check_now.notified() => {
-                    info!("Liveness check triggered by playlist load");
+                    info!(
+                        "Check of {} channels canceled; checking every channel again",
+                        ids.len()
+                    );
+                    restart = true;
+                    continue;
                 }
-                () = tokio::time::sleep(interval) => {
-                    info!("Starting periodic channel liveness check");
+            };
+            let urls: Vec<String> = targets.into_iter().map(|t| t.url).collect();
+            let outcome: Outcome = ids
+                .iter()
+                .map(String::as_str)
+                .zip(urls.iter().map(String::as_str))
+                .zip(results)
+                .collect();
+
+            let now = Utc::now();
+            {
+                let mut playlist = state.playlist.write().await;
+                let mut schedule = state.probe_schedule.write().await;
+                let mut uptime = state.uptime.write().await;
+                let applied = apply_outcome(
+                    &mut playlist.channels,
+                    &mut schedule,
+                    &mut uptime,
+                    &state.events,
+                    &outcome,
+                    &options,
+                    now,
+                );
+                if applied < outcome.len() {
+                    debug!(
+                        "{} probed channels were removed or edited during the check",
+                        outcome.len() - applied
+                    );
                 }
+                uptime.sync(&playlist.channels);
+
+                let live_count = playlist.channels.iter().filter(|c| c.is_live).count();
+                let total = playlist.channels.len();
+                playlist.last_checked = Some(chrono_now_iso8601());
+
+                info!(
+                    "Channel check complete: {live_count}/{total} live ({} probed)",
+                    ids.len()
+                );
+                state.events.publish(ServerEvent::CheckComplete {
+                    probed: ids.len(),
+                    live: live_count,
+                    total,
+                    checked_at: now,
+                });
             }
+            state.state_changed.notify_one();
 
-            let mut playlist = state.playlist.write().await;
+            if state.config.hls_health_enabled {
+                state.watchdog.beat(watchdog::CHANNEL_CHECKER);
+                score_hls_channels(&state, &ids, &urls, &outcome, timeout).await;
+            }
 
-            if playlist.channels.is_empty() {
-                continue;
+            #[cfg(feature = "ffprobe")]
+            {
+                state.watchdog.beat(watchdog::CHANNEL_CHECKER);
+                stream_probe::probe_new_channels(&state).await;
             }
+        }
+    })
+}
 
-            check_all_channels(&mut playlist.channels, timeout).await;
+/// Deep-probe the live HLS channels among `ids` and store their health scores.
+///
+/// Duplicate channels are then reordered so the healthiest backup comes
+/// first (see [`hls_health::order_backups_by_health`]).
+async fn score_hls_channels(
+    state: &AppState,
+    ids: &[String],
+    urls: &[String],
+    outcome: &Outcome<'_>,
+    timeout: Duration,
+) {
+    let (hls_ids, hls_urls): (Vec<&str>, Vec<String>) = ids
+        .iter()
+        .zip(urls)
+        .filter(|(id, url)| {
+            outcome
+                .get(&(id.as_str(), url.as_str()))
+                .is_some_and(|probe| probe.is_live)
+                && hls_health::is_hls(url)
+        })
+        .map(|(id, url)| (id.as_str(), url.clone()))
+        .unzip();
+    if hls_ids.is_empty() {
+        return;
+    }
 
-            let live_count = playlist.channels.iter().filter(|c| c.is_live).count();
-            let total = playlist.channels.len();
-            playlist.last_checked = Some(chrono_now_iso8601());
+    debug!("Scoring {} live HLS channels", hls_ids.len());
+    let scores = hls_health::score_urls(&state.http, &hls_urls, timeout).await;
+    let by_stream: HashMap<(&str, &str), u8> = hls_ids
+        .into_iter()
+        .zip(hls_urls.iter().map(String::as_str))
+        .zip(scores)
+        .collect();
 
-            info!("Channel check complete: {live_count}/{total} live");
+    let mut playlist = state.playlist.write().await;
+    for channel in playlist.channels.iter_mut() {
+        if let Some(&score) = by_stream.get(&(channel.id.as_str(), channel.stream_url.as_str())) {
+            channel.health_score = Some(score);
         }
-    });
+    }
+    hls_health::order_backups_by_health(&mut playlist.channels);
+    state.state_changed.notify_one();
 }
 
 /// Return the current UTC time as an ISO-8601 string.
@@ -153,3 +854,278 @@ fn days_to_ymd(mut days: u64) -> (u64, u64, u64) {
 fn is_leap_year(year: u64) -> bool {
     (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::m3u_parser;
+
+    fn options() -> ProbeOptions {
+        ProbeOptions {
+            timeout: Duration::from_secs(4),
+            mode: ProbeMode::Basic,
+            concurrency: 20,
+            per_host: 4,
+            jitter: Duration::ZERO,
+            retries: 0,
+            failure_threshold: 2,
+            quarantine_after: 4,
+        }
+    }
+
+    #[test]
+    fn records_health_across_probes() {
+        let mut channel =
+            m3u_parser::parse_m3u("#EXTM3U\n#EXTINF:-1,One\nhttp://example.com/one.ts\n")
+                .channels
+                .remove(0);
+        let options = options();
+        let t0 = Utc::now();
+        let ok = Probe {
+            is_live: true,
+            status_code: Some(200),
+            latency: Duration::from_millis(300),
+            stream_info: None,
+            access: None,
+        };
+        record_probe(&mut channel, &ok, &options, t0);
+        assert!(channel.is_live);
+        assert_eq!(channel.health.status, Some(HealthStatus::Up));
+        assert_eq!(channel.health.latency_ms, Some(300));
+        assert_eq!(channel.health.last_ok, Some(t0));
+
+        let failed = Probe {
+            is_live: false,
+            status_code: Some(404),
+            latency: Duration::from_millis(80),
+            stream_info: None,
+            access: None,
+        };
+        let t1 = t0 + chrono::Duration::minutes(10);
+        record_probe(&mut channel, &failed, &options, t1);
+        // A single failure is not enough to mark the channel dead.
+        assert!(channel.is_live);
+        assert_eq!(channel.health.status, Some(HealthStatus::Degraded));
+        record_probe(
+            &mut channel,
+            &failed,
+            &options,
+            t1 + chrono::Duration::minutes(10),
+        );
+        assert!(!channel.is_live);
+        assert_eq!(channel.health.status, Some(HealthStatus::Down));
+        assert_eq!(channel.health.last_status_code, Some(404));
+        assert_eq!(channel.health.consecutive_failures, 2);
+        assert_eq!(channel.health.down_since, Some(t1));
+        assert_eq!(channel.health.last_ok, Some(t0));
+        assert_eq!(channel.health.access, None);
+
+        let blocked = Probe {
+            status_code: Some(451),
+            access: Some(AccessIssue::GeoBlocked),
+            ..failed
+        };
+        record_probe(
+            &mut channel,
+            &blocked,
+            &options,
+            t1 + chrono::Duration::minutes(15),
+        );
+        assert!(!channel.is_live);
+        assert_eq!(channel.health.access, Some(AccessIssue::GeoBlocked));
+
+        let slow = Probe {
+            latency: Duration::from_secs(3),
+            ..ok
+        };
+        record_probe(
+            &mut channel,
+            &slow,
+            &options,
+            t1 + chrono::Duration::minutes(20),
+        );
+        assert_eq!(channel.health.status, Some(HealthStatus::Degraded));
+        assert_eq!(channel.health.consecutive_failures, 0);
+        assert_eq!(channel.health.down_since, None);
+        assert_eq!(channel.health.access, None);
+    }
+
+    #[test]
+    fn quarantines_channels_that_keep_failing() {
+        let mut channels =
+            m3u_parser::parse_m3u("#EXTM3U\n#EXTINF:-1,Gone\nhttp://example.com/gone.ts\n")
+                .channels;
+        let key = (channels[0].id.clone(), channels[0].stream_url.clone());
+        let outcome: Outcome = HashMap::from([(
+            (key.0.as_str(), key.1.as_str()),
+            Probe {
+                status_code: Some(404),
+                ..Probe::default()
+            },
+        )]);
+        let now = Utc::now();
+        let mut schedule = ProbeSchedule::new(Duration::from_secs(600), Duration::from_secs(60));
+        schedule.sync(&channels, now);
+        let mut uptime = UptimeHistory::default();
+
+        for _ in 0..3 {
+            apply_outcome(
+                &mut channels,
+                &mut schedule,
+                &mut uptime,
+                &EventBus::default(),
+                &outcome,
+                &options(),
+                now,
+            );
+        }
+        assert_eq!(channels[0].health.status, Some(HealthStatus::Down));
+        assert!(!schedule.is_quarantined(&key.0));
+
+        apply_outcome(
+            &mut channels,
+            &mut schedule,
+            &mut uptime,
+            &EventBus::default(),
+            &outcome,
+            &options(),
+            now,
+        );
+        assert_eq!(channels[0].health.status, Some(HealthStatus::Quarantined));
+        assert!(schedule.is_quarantined(&key.0));
+        assert_eq!(schedule.next_due(), None);
+
+        // Disabled quarantine leaves the channel down.
+        let never = ProbeOptions {
+            quarantine_after: 0,
+            ..options()
+        };
+        record_probe(
+            &mut channels[0],
+            &outcome[&(key.0.as_str(), key.1.as_str())],
+            &never,
+            now,
+        );
+        assert_eq!(channels[0].health.status, Some(HealthStatus::Down));
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let failed = |status_code| Probe {
+            status_code,
+            ..Probe::default()
+        };
+        assert!(is_transient(&failed(None)));
+        assert!(is_transient(&failed(Some(503))));
+        assert!(is_transient(&failed(Some(429))));
+        assert!(!is_transient(&failed(Some(404))));
+        assert!(!is_transient(&failed(Some(403))));
+    }
+
+    #[test]
+    fn merges_results_into_a_changed_playlist() {
+        let mut channels = m3u_parser::parse_m3u(
+            "#EXTM3U\n#EXTINF:-1,Kept\nhttp://example.com/kept.ts\n\
+             #EXTINF:-1,Edited\nhttp://example.com/edited.ts\n\
+             #EXTINF:-1,Added\nhttp://example.com/added.ts\n",
+        )
+        .channels;
+        // Probed before the second channel's stream URL was edited.
+        let probed: Vec<(String, String)> = channels[..2]
+            .iter()
+            .map(|c| (c.id.clone(), c.stream_url.clone()))
+            .chain([("removed".into(), "http://example.com/removed.ts".into())])
+            .collect();
+        let live = Probe {
+            is_live: true,
+            ..Probe::default()
+        };
+        let outcome: Outcome = probed
+            .iter()
+            .map(|(id, url)| ((id.as_str(), url.as_str()), live.clone()))
+            .collect();
+        channels[1].stream_url = "http://example.com/fixed.ts".to_string();
+
+        let now = Utc::now();
+        let mut schedule = ProbeSchedule::new(Duration::from_secs(600), Duration::from_secs(60));
+        schedule.sync(&channels, now);
+        let mut uptime = UptimeHistory::default();
+        let events = EventBus::default();
+        let mut received = events.subscribe();
+        let applied = apply_outcome(
+            &mut channels,
+            &mut schedule,
+            &mut uptime,
+            &events,
+            &outcome,
+            &options(),
+            now,
+        );
+        assert_eq!(applied, 1);
+        let live: Vec<bool> = channels.iter().map(|c| c.is_live).collect();
+        assert_eq!(live, vec![true, false, false]);
+        assert!(channels[1].health.last_checked.is_none());
+        assert_eq!(uptime.report(&channels[0].id, now).checks_24h, 1);
+        assert_eq!(uptime.report(&channels[1].id, now).checks_24h, 0);
+        assert_eq!(
+            received.try_recv().unwrap(),
+            ServerEvent::channel_status(&channels[0], now)
+        );
+        assert!(received.try_recv().is_err());
+    }
+
+    #[test]
+    fn limits_probes_per_host() {
+        assert_eq!(host_key("http://CDN.example.com/a.m3u8"), "cdn.example.com");
+        assert_eq!(
+            host_key("http://cdn.example.com:8080/b.ts?token=1"),
+            "cdn.example.com:8080"
+        );
+        assert_eq!(host_key("not a url"), "not a url");
+    }
+
+    #[test]
+    fn recognises_hls_playlists() {
+        assert!(is_hls_playlist("#EXTM3U\n#EXT-X-TARGETDURATION:6\n"));
+        assert!(is_hls_playlist("\u{feff}\r\n#EXTM3U\n"));
+        assert!(!is_hls_playlist("<html><body>Not found</body></html>"));
+        assert!(!is_hls_playlist(""));
+    }
+
+    #[test]
+    fn falls_back_to_get_when_head_is_refused() {
+        assert!(head_unsupported(StatusCode::METHOD_NOT_ALLOWED));
+        assert!(head_unsupported(StatusCode::FORBIDDEN));
+        assert!(head_unsupported(StatusCode::NOT_IMPLEMENTED));
+        assert!(!head_unsupported(StatusCode::OK));
+        assert!(!head_unsupported(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn tells_access_issues_from_downtime() {
+        let refused = |code: u16| Answer {
+            is_live: false,
+            status: StatusCode::from_u16(code).ok(),
+            login_redirect: false,
+        };
+        assert_eq!(refused(403).access(), Some(AccessIssue::GeoBlocked));
+        assert_eq!(refused(451).access(), Some(AccessIssue::GeoBlocked));
+        assert_eq!(refused(401).access(), Some(AccessIssue::AuthRequired));
+        assert_eq!(refused(402).access(), Some(AccessIssue::AuthRequired));
+        assert_eq!(refused(404).access(), None);
+        assert_eq!(refused(503).access(), None);
+        assert_eq!(Answer::default().access(), None);
+        let login = Answer {
+            login_redirect: true,
+            ..refused(200)
+        };
+        assert_eq!(login.access(), Some(AccessIssue::AuthRequired));
+
+        let page = |url: &str| is_login_page(&Url::parse(url).unwrap());
+        assert!(page("https://example.com/login"));
+        assert!(page("https://example.com/account/Sign-In.php?next=/live"));
+        assert!(page("https://example.com/oauth/authorize"));
+        assert!(!page("https://example.com/live/auth_token/stream.m3u8"));
+        assert!(!page("https://example.com/live/loginless.ts"));
+    }
+}
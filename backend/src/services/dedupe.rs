@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Channel;
+
+/// Which duplicate to keep when several channels collide.
+///
+/// Criteria apply in order; remaining ties go to the earliest channel.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct DedupePolicy {
+    /// Prefer a live channel over a dead one.
+    pub prefer_live: bool,
+    /// Prefer an `https://` stream over any other scheme.
+    pub prefer_https: bool,
+}
+
+impl Default for DedupePolicy {
+    fn default() -> Self {
+        Self {
+            prefer_live: true,
+            prefer_https: true,
+        }
+    }
+}
+
+/// Why a channel was considered a duplicate of the kept one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    /// Same stream URL.
+    StreamUrl,
+    /// Same normalised name in the same group.
+    Name,
+}
+
+/// A channel removed as a duplicate.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovedChannel {
+    /// ID of the removed channel.
+    pub id: String,
+    /// Name of the removed channel.
+    pub name: String,
+    /// Group of the removed channel.
+    pub group: String,
+    /// Stream URL of the removed channel.
+    pub stream_url: String,
+    /// ID of the channel kept in its place.
+    pub kept_id: String,
+    /// What made it a duplicate.
+    pub reason: DuplicateReason,
+}
+
+/// Lowercase `name` and collapse punctuation and whitespace runs to one space.
+fn normalize_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Root of `i` in the union-find `parent` table, compressing the path.
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Remove duplicate channels, keeping one per cluster according to `policy`.
+///
+/// Channels are duplicates when they share a stream URL, or a normalised
+/// name within the same group; duplicates of duplicates form one cluster.
+/// The kept channel takes the position of the cluster's first member.
+/// Returns the remaining channels and a report of those removed.
+pub fn dedupe(channels: Vec<Channel>, policy: DedupePolicy) -> (Vec<Channel>, Vec<RemovedChannel>) {
+    let mut parent: Vec<usize> = (0..channels.len()).collect();
+    let mut by_url: HashMap<&str, usize> = HashMap::new();
+    let mut by_name: HashMap<(String, String), usize> = HashMap::new();
+
+    for (i, ch) in channels.iter().enumerate() {
+        let name_key = (ch.group.to_lowercase(), normalize_name(&ch.name));
+        let firsts = [
+            *by_url.entry(ch.stream_url.as_str()).or_insert(i),
+            *by_name.entry(name_key).or_insert(i),
+        ];
+        for first in firsts {
+            let (a, b) = (find(&mut parent, first), find(&mut parent, i));
+            // Keep the earliest index as the root so it marks the cluster's slot.
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..channels.len() {
+        let root = find(&mut parent, i);
+        clusters.entry(root).or_default().push(i);
+    }
+
+    let rank = |ch: &Channel| {
+        (
+            policy.prefer_live && ch.is_live,
+            policy.prefer_https && ch.stream_url.starts_with("https://"),
+        )
+    };
+
+    // For each cluster root: the member to keep.
+    let mut keep: HashMap<usize, usize> = HashMap::new();
+    for (&root, members) in &clusters {
+        let best = members
+            .iter()
+            .copied()
+            .max_by(|&a, &b| rank(&channels[a]).cmp(&rank(&channels[b])).then(b.cmp(&a)))
+            .unwrap_or(root);
+        keep.insert(root, best);
+    }
+
+    let mut removed = Vec::new();
+    for (root, members) in &clusters {
+        let kept = &channels[keep[root]];
+        for &i in members.iter().filter(|&&i| i != keep[root]) {
+            let ch = &channels[i];
+            removed.push(RemovedChannel {
+                id: ch.id.clone(),
+                name: ch.name.clone(),
+                group: ch.group.clone(),
+                stream_url: ch.stream_url.clone(),
+                kept_id: kept.id.clone(),
+                reason: if ch.stream_url == kept.stream_url {
+                    DuplicateReason::StreamUrl
+                } else {
+                    DuplicateReason::Name
+                },
+            });
+        }
+    }
+    removed.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut slots: Vec<Option<Channel>> = channels.into_iter().map(Some).collect();
+    let mut roots: Vec<usize> = clusters.into_keys().collect();
+    roots.sort_unstable();
+    let kept = roots
+        .into_iter()
+        .filter_map(|root| slots[keep[&root]].take())
+        .collect();
+
+    (kept, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(channels: &[Channel]) -> Vec<&str> {
+        channels.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn normalizes_case_punctuation_and_spacing() {
+        assert_eq!(normalize_name("  BBC  One! "), "bbc one");
+        assert_eq!(normalize_name("BBC-One"), "bbc one");
+    }
+
+    #[test]
+    fn removes_same_url_and_same_name_duplicates() {
+        let channels = vec![
+            Channel {
+                stream_url: "http://a.example/cnn".to_string(),
+                is_live: false,
+                ..Channel::sample("a", "CNN", "News")
+            },
+            Channel {
+                stream_url: "http://b.example/bbc".to_string(),
+                is_live: true,
+                ..Channel::sample("b", "BBC One", "News")
+            },
+            Channel {
+                stream_url: "https://c.example/cnn".to_string(),
+                is_live: true,
+                ..Channel::sample("c", "cnn", "News")
+            },
+            Channel {
+                stream_url: "http://b.example/bbc".to_string(),
+                is_live: true,
+                ..Channel::sample("d", "Other", "News")
+            },
+        ];
+
+        let (kept, removed) = dedupe(channels, DedupePolicy::default());
+
+        // The live https CNN replaces the first one in its slot.
+        assert_eq!(ids(&kept), ["c", "b"]);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].id, "a");
+        assert_eq!(removed[0].kept_id, "c");
+        assert_eq!(removed[0].reason, DuplicateReason::Name);
+        assert_eq!(removed[1].id, "d");
+        assert_eq!(removed[1].kept_id, "b");
+        assert_eq!(removed[1].reason, DuplicateReason::StreamUrl);
+    }
+
+    #[test]
+    fn same_name_in_other_groups_is_kept() {
+        let mut sports = Channel {
+            stream_url: "http://b.example/cnn".to_string(),
+            is_live: true,
+            ..Channel::sample("b", "CNN", "News")
+        };
+        sports.group = "Sports".to_string();
+        let channels = vec![
+            Channel {
+                stream_url: "http://a.example/cnn".to_string(),
+                is_live: true,
+                ..Channel::sample("a", "CNN", "News")
+            },
+            sports,
+        ];
+
+        let (kept, removed) = dedupe(channels, DedupePolicy::default());
+        assert_eq!(kept.len(), 2);
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn policy_controls_which_duplicate_is_kept() {
+        let channels = || {
+            vec![
+                Channel {
+                    stream_url: "http://a.example/cnn".to_string(),
+                    is_live: true,
+                    ..Channel::sample("a", "CNN", "News")
+                },
+                Channel {
+                    stream_url: "https://b.example/cnn".to_string(),
+                    is_live: false,
+                    ..Channel::sample("b", "CNN", "News")
+                },
+            ]
+        };
+
+        let (kept, _) = dedupe(channels(), DedupePolicy::default());
+        assert_eq!(ids(&kept), ["a"]);
+
+        let https_only = DedupePolicy {
+            prefer_live: false,
+            prefer_https: true,
+        };
+        let (kept, _) = dedupe(channels(), https_only);
+        assert_eq!(ids(&kept), ["b"]);
+
+        let neither = DedupePolicy {
+            prefer_live: false,
+            prefer_https: false,
+        };
+        let (kept, _) = dedupe(channels(), neither);
+        assert_eq!(ids(&kept), ["a"]);
+    }
+}
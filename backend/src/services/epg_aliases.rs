@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::models::EpgAlias;
+
+/// Errors that can occur while loading or saving the EPG alias table.
+#[derive(Debug, Error)]
+pub enum EpgAliasError {
+    /// Reading or writing the alias file failed.
+    #[error("alias file I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The alias file is not valid JSON.
+    #[error("alias file JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Load the alias table from a JSON file.
+///
+/// A missing file yields an empty table so a fresh deployment starts clean.
+pub fn load(path: &Path) -> Result<HashMap<String, EpgAlias>, EpgAliasError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write the alias table to a JSON file, replacing it atomically.
+pub async fn save(path: &Path, aliases: &HashMap<String, EpgAlias>) -> Result<(), EpgAliasError> {
+    let json = serde_json::to_vec_pretty(aliases)?;
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_through_file() {
+        let path = std::env::temp_dir().join(format!("epg-aliases-{}.json", std::process::id()));
+        let mut aliases = HashMap::new();
+        aliases.insert(
+            "cnn-hd".to_string(),
+            EpgAlias {
+                xmltv_id: "CNN.us".to_string(),
+                manual: true,
+            },
+        );
+
+        save(&path, &aliases).await.unwrap();
+        assert_eq!(load(&path).unwrap(), aliases);
+        std::fs::remove_file(&path).unwrap();
+
+        // A missing file is an empty table.
+        assert!(load(&path).unwrap().is_empty());
+    }
+}
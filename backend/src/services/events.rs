@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::models::{Channel, HealthStatus, Playlist};
+
+/// Events kept for subscribers that fall behind; older ones are dropped.
+const CAPACITY: usize = 256;
+
+/// Something clients of `GET /api/events` are told about as it happens.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// A check found a channel live that was not, or the other way round.
+    ChannelStatus {
+        channel_id: String,
+        is_live: bool,
+        status: Option<HealthStatus>,
+        checked_at: DateTime<Utc>,
+    },
+    /// The background checker finished a cycle.
+    CheckComplete {
+        /// Channels probed in the cycle.
+        probed: usize,
+        /// Live channels in the playlist afterwards.
+        live: usize,
+        total: usize,
+        checked_at: DateTime<Utc>,
+    },
+    /// The default playlist was reloaded from the M3U sources, replaced by
+    /// an on-chain playlist or `PUT /api/playlist`, or replaced or extended
+    /// by an import.
+    PlaylistReplaced { source: String, channels: usize },
+}
+
+impl ServerEvent {
+    /// A `channel_status` event for `channel` after a check at `at`.
+    pub fn channel_status(channel: &Channel, at: DateTime<Utc>) -> Self {
+        ServerEvent::ChannelStatus {
+            channel_id: channel.id.clone(),
+            is_live: channel.is_live,
+            status: channel.health.status,
+            checked_at: at,
+        }
+    }
+
+    /// A `playlist_replaced` event for the new default `playlist`.
+    pub fn playlist_replaced(playlist: &Playlist) -> Self {
+        ServerEvent::PlaylistReplaced {
+            source: playlist.source.clone(),
+            channels: playlist.channels.len(),
+        }
+    }
+
+    /// The SSE event name, which is also the `type` field of its data.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServerEvent::ChannelStatus { .. } => "channel_status",
+            ServerEvent::CheckComplete { .. } => "check_complete",
+            ServerEvent::PlaylistReplaced { .. } => "playlist_replaced",
+        }
+    }
+}
+
+/// Fan-out of [`ServerEvent`]s to every connected `GET /api/events` client.
+///
+/// Publishing never blocks: a subscriber that falls more than [`CAPACITY`]
+/// events behind skips the oldest and is told how many it missed.
+#[derive(Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<ServerEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    /// Send `event` to the current subscribers, if any.
+    pub fn publish(&self, event: ServerEvent) {
+        // An error only means nobody is listening.
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive the events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn delivers_events_to_every_subscriber() {
+        let bus = EventBus::default();
+        // Nobody is listening yet.
+        bus.publish(ServerEvent::PlaylistReplaced {
+            source: "before".to_string(),
+            channels: 0,
+        });
+
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        let event = ServerEvent::PlaylistReplaced {
+            source: "upload".to_string(),
+            channels: 3,
+        };
+        bus.publish(event.clone());
+        assert_eq!(first.recv().await.unwrap(), event);
+        assert_eq!(second.recv().await.unwrap(), event);
+
+        let data = serde_json::to_value(&event).unwrap();
+        assert_eq!(data["type"], event.name());
+        assert_eq!(data["channels"], 3);
+    }
+}
@@ -0,0 +1,246 @@
+use std::io::Read;
+use std::time::Duration;
+
+use reqwest::header::{
+    ACCEPT_ENCODING, CONTENT_ENCODING, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Timeout for downloading an M3U playlist.
+pub const PLAYLIST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Errors that can occur while fetching a remote playlist or guide.
+#[derive(Debug, Error)]
+pub enum FetchError {
+    /// HTTP request failed or returned an error status.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The body could not be decompressed.
+    #[error("Failed to decode {encoding} body: {source}")]
+    Decode {
+        encoding: String,
+        source: std::io::Error,
+    },
+    /// The server used a `Content-Encoding` we cannot decode.
+    #[error("Unsupported Content-Encoding: {0}")]
+    UnsupportedEncoding(String),
+}
+
+/// Cache validators a server sent with a response, replayed on the next
+/// fetch of the same URL so an unchanged resource is not downloaded again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// `ETag` response header, sent back as `If-None-Match`.
+    pub etag: Option<String>,
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Whether the server sent neither validator.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Outcome of [`fetch_if_modified`].
+#[derive(Debug)]
+pub enum Conditional {
+    /// The resource changed, or no validators were sent.
+    Modified {
+        /// The decoded body, as for [`fetch_bytes`].
+        body: Vec<u8>,
+        /// Validators to send on the next fetch.
+        validators: Validators,
+    },
+    /// The server answered `304 Not Modified`.
+    NotModified,
+}
+
+/// Download `url` unless it is unchanged since the response `validators`
+/// came from.
+///
+/// The validators are sent as `If-None-Match` and `If-Modified-Since`;
+/// providers answer `304 Not Modified` without a body when nothing
+/// changed. The body is decoded as for [`fetch_bytes`].
+pub async fn fetch_if_modified(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Duration,
+    validators: Option<&Validators>,
+) -> Result<Conditional, FetchError> {
+    let mut request = client
+        .get(url)
+        .header(ACCEPT_ENCODING, "gzip, deflate")
+        .timeout(timeout);
+    if let Some(validators) = validators {
+        if let Some(ref etag) = validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref modified) = validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, modified);
+        }
+    }
+
+    let response = request.send().await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Conditional::NotModified);
+    }
+    let response = response.error_for_status()?;
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let encoding = header(CONTENT_ENCODING);
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let body = response.bytes().await?;
+    Ok(Conditional::Modified {
+        body: decode_body(encoding.as_deref(), &body)?,
+        validators,
+    })
+}
+
+/// Download `url` and return its decoded body.
+///
+/// Compressed responses are accepted: a gzip or deflate
+/// `Content-Encoding` is undone, and a body that is itself a gzip file
+/// (such as `playlist.m3u.gz`) is decompressed too.
+pub async fn fetch_bytes(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, FetchError> {
+    match fetch_if_modified(client, url, timeout, None).await? {
+        Conditional::Modified { body, .. } => Ok(body),
+        // Not sent without validators; treat a stray one as empty.
+        Conditional::NotModified => Ok(Vec::new()),
+    }
+}
+
+/// Download `url` as text, decoded as for [`fetch_bytes`]. Invalid UTF-8
+/// is replaced rather than rejected.
+pub async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Duration,
+) -> Result<String, FetchError> {
+    Ok(into_text(fetch_bytes(client, url, timeout).await?))
+}
+
+/// `body` as text, replacing invalid UTF-8.
+pub fn into_text(body: Vec<u8>) -> String {
+    match String::from_utf8(body) {
+        Ok(text) => text,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    }
+}
+
+/// Undo the `Content-Encoding` of a response body, then decompress it if
+/// it is a gzip file.
+///
+/// Encodings are listed in the order they were applied, so they are
+/// undone last to first.
+pub fn decode_body(encoding: Option<&str>, body: &[u8]) -> Result<Vec<u8>, FetchError> {
+    let mut data = body.to_vec();
+    for coding in encoding.unwrap_or_default().split(',').rev() {
+        let coding = coding.trim().to_ascii_lowercase();
+        let decoded = match coding.as_str() {
+            "" | "identity" => continue,
+            "gzip" | "x-gzip" => read_all(flate2::read::GzDecoder::new(data.as_slice())),
+            "deflate" => inflate(&data),
+            _ => return Err(FetchError::UnsupportedEncoding(coding)),
+        };
+        data = decoded.map_err(|source| FetchError::Decode {
+            encoding: coding,
+            source,
+        })?;
+    }
+    Ok(decompress_gzip(&data).unwrap_or(data))
+}
+
+/// Inflate a `deflate` body. The encoding is meant to be zlib-wrapped,
+/// but some servers send raw deflate, so that is tried as well.
+fn inflate(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    read_all(flate2::read::ZlibDecoder::new(data))
+        .or_else(|_| read_all(flate2::read::DeflateDecoder::new(data)))
+}
+
+fn read_all(mut reader: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Attempt to decompress gzip data. Returns `None` if the data is not gzipped.
+pub fn decompress_gzip(data: &[u8]) -> Option<Vec<u8>> {
+    // Check gzip magic bytes.
+    if data.len() < 2 || data[0] != 0x1f || data[1] != 0x8b {
+        return None;
+    }
+
+    read_all(flate2::read::GzDecoder::new(data)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use std::io::Write;
+
+    const M3U: &[u8] = b"#EXTM3U\n#EXTINF:-1,CNN\nhttp://example.com/cnn.m3u8\n";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompress_gzip_rejects_non_gzip() {
+        let plain = b"hello world";
+        assert!(decompress_gzip(plain).is_none());
+    }
+
+    #[test]
+    fn decodes_content_encodings() {
+        assert_eq!(decode_body(None, M3U).unwrap(), M3U);
+        assert_eq!(decode_body(Some("identity"), M3U).unwrap(), M3U);
+        assert_eq!(decode_body(Some("GZIP"), &gzip(M3U)).unwrap(), M3U);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(M3U).unwrap();
+        assert_eq!(
+            decode_body(Some("deflate"), &zlib.finish().unwrap()).unwrap(),
+            M3U
+        );
+
+        let mut raw = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw.write_all(M3U).unwrap();
+        assert_eq!(
+            decode_body(Some("deflate"), &raw.finish().unwrap()).unwrap(),
+            M3U
+        );
+
+        assert!(matches!(
+            decode_body(Some("br"), M3U),
+            Err(FetchError::UnsupportedEncoding(_))
+        ));
+        assert!(decode_body(Some("gzip"), M3U).is_err());
+    }
+
+    #[test]
+    fn decompresses_gzip_files() {
+        // A .m3u.gz served as-is, and one gzipped again in transit.
+        assert_eq!(decode_body(None, &gzip(M3U)).unwrap(), M3U);
+        assert_eq!(decode_body(Some("gzip"), &gzip(&gzip(M3U))).unwrap(), M3U);
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::{Client, Url};
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::models::{Channel, StreamInfo};
+
+/// Number of trailing segments downloaded per measurement.
+const SAMPLE_SEGMENTS: usize = 2;
+
+/// Longest wait before re-reading a live playlist to check that it advances.
+const MAX_CADENCE_WAIT: Duration = Duration::from_secs(10);
+
+/// Download speed, as a multiple of real time, that earns the full throughput score.
+const FULL_SPEED_RATIO: f64 = 2.0;
+
+/// Whether `url` looks like an HLS playlist.
+pub fn is_hls(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// The parts of an HLS playlist the health score needs.
+#[derive(Debug, Default, PartialEq)]
+pub struct HlsPlaylist {
+    /// `#EXT-X-TARGETDURATION`, in seconds.
+    pub target_duration: f64,
+    /// `#EXT-X-MEDIA-SEQUENCE` of the first segment.
+    pub media_sequence: u64,
+    /// `(duration, URI)` of each media segment, in order.
+    pub segments: Vec<(f64, String)>,
+    /// Whether `#EXT-X-ENDLIST` is present (the playlist will not change).
+    pub ended: bool,
+    /// Variant streams, when this is a master playlist.
+    pub variants: Vec<HlsVariant>,
+}
+
+/// A variant stream of an HLS master playlist.
+#[derive(Debug, Default, PartialEq)]
+pub struct HlsVariant {
+    /// URI of the variant's media playlist.
+    pub uri: String,
+    /// Attributes of its `#EXT-X-STREAM-INF` tag.
+    pub info: StreamInfo,
+}
+
+/// Parse the tags of an HLS master or media playlist that the scorer uses.
+pub fn parse_playlist(text: &str) -> HlsPlaylist {
+    let mut playlist = HlsPlaylist::default();
+    let mut pending_duration: Option<f64> = None;
+    let mut pending_variant: Option<StreamInfo> = None;
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(v) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            playlist.target_duration = v.trim().parse().unwrap_or_default();
+        } else if let Some(v) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            playlist.media_sequence = v.trim().parse().unwrap_or_default();
+        } else if let Some(v) = line.strip_prefix("#EXTINF:") {
+            let duration = v.split(',').next().unwrap_or_default().trim();
+            pending_duration = Some(duration.parse().unwrap_or_default());
+        } else if let Some(attributes) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            pending_variant = Some(StreamInfo {
+                bandwidth: attribute(attributes, "BANDWIDTH").and_then(|v| v.parse().ok()),
+                resolution: attribute(attributes, "RESOLUTION").map(str::to_string),
+            });
+        } else if line == "#EXT-X-ENDLIST" {
+            playlist.ended = true;
+        } else if !line.starts_with('#') {
+            if let Some(info) = pending_variant.take() {
+                playlist.variants.push(HlsVariant {
+                    uri: line.to_string(),
+                    info,
+                });
+            } else if let Some(duration) = pending_duration.take() {
+                playlist.segments.push((duration, line.to_string()));
+            }
+        }
+    }
+    playlist
+}
+
+/// The value of `name` in an HLS attribute list such as
+/// `BANDWIDTH=800000,CODECS="avc1.4d401f,mp4a.40.2"`, without quotes.
+fn attribute<'a>(list: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = list;
+    while !rest.is_empty() {
+        let (key, value) = rest.split_once('=')?;
+        let (value, next) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, next) = quoted.split_once('"')?;
+                (value, next.trim_start_matches(','))
+            }
+            None => value.split_once(',').unwrap_or((value, "")),
+        };
+        if key.trim() == name {
+            return Some(value);
+        }
+        rest = next;
+    }
+    None
+}
+
+/// Raw measurements taken from one HLS stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HlsSample {
+    /// Segments whose download was attempted.
+    pub segments_tried: usize,
+    /// Segments downloaded successfully.
+    pub segments_ok: usize,
+    /// Media seconds downloaded per wall-clock second; `None` when no
+    /// segment could be downloaded.
+    pub speed_ratio: Option<f64>,
+    /// Whether the playlist advanced over the window (always true for an
+    /// ended playlist); `None` when the re-read failed.
+    pub advancing: Option<bool>,
+}
+
+/// Combine a sample into a 0–100 health score.
+///
+/// Up to 60 points for segment throughput (full marks at
+/// [`FULL_SPEED_RATIO`]× real time), 20 for the share of segments that
+/// downloaded, and 20 for a playlist that advances (10 when unknown).
+/// A stream with no downloadable segment scores 0.
+pub fn score(sample: &HlsSample) -> u8 {
+    let Some(ratio) = sample.speed_ratio else {
+        return 0;
+    };
+    if sample.segments_tried == 0 {
+        return 0;
+    }
+
+    let throughput = 60.0 * (ratio / FULL_SPEED_RATIO).clamp(0.0, 1.0);
+    let reliability = 20.0 * sample.segments_ok as f64 / sample.segments_tried as f64;
+    let cadence = match sample.advancing {
+        Some(true) => 20.0,
+        Some(false) => 0.0,
+        None => 10.0,
+    };
+    (throughput + reliability + cadence)
+        .round()
+        .clamp(0.0, 100.0) as u8
+}
+
+async fn fetch_playlist(client: &Client, url: &Url, timeout: Duration) -> Option<HlsPlaylist> {
+    let resp = client.get(url.clone()).timeout(timeout).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    Some(parse_playlist(&resp.text().await.ok()?))
+}
+
+/// Measure one HLS stream: download its last segments, then re-read the
+/// playlist after one target duration to see whether it advanced.
+///
+/// Master playlists are followed to their first variant. Each request
+/// may take up to `timeout`. Returns `None` when the playlist itself
+/// cannot be fetched.
+pub async fn measure(client: &Client, url: &str, timeout: Duration) -> Option<HlsSample> {
+    let mut url = Url::parse(url).ok()?;
+    let mut playlist = fetch_playlist(client, &url, timeout).await?;
+    if let Some(variant) = playlist.variants.first() {
+        url = url.join(&variant.uri).ok()?;
+        playlist = fetch_playlist(client, &url, timeout).await?;
+    }
+
+    let started = Instant::now();
+    let sample_from = playlist.segments.len().saturating_sub(SAMPLE_SEGMENTS);
+    let mut segments_ok = 0;
+    let mut media_secs = 0.0;
+    let mut download_secs = 0.0;
+    for (duration, uri) in &playlist.segments[sample_from..] {
+        let Ok(segment_url) = url.join(uri) else {
+            continue;
+        };
+        let t = Instant::now();
+        let ok = match client.get(segment_url).timeout(timeout).send().await {
+            Ok(resp) if resp.status().is_success() => resp.bytes().await.is_ok(),
+            _ => false,
+        };
+        if ok {
+            segments_ok += 1;
+            media_secs += duration;
+            download_secs += t.elapsed().as_secs_f64();
+        }
+    }
+    let segments_tried = playlist.segments.len() - sample_from;
+    let speed_ratio = (segments_ok > 0).then(|| media_secs / download_secs.max(0.001));
+
+    let advancing = if playlist.ended {
+        Some(true)
+    } else {
+        let window =
+            Duration::from_secs_f64(playlist.target_duration.max(1.0)).min(MAX_CADENCE_WAIT);
+        tokio::time::sleep(window.saturating_sub(started.elapsed())).await;
+        fetch_playlist(client, &url, timeout).await.map(|next| {
+            next.media_sequence > playlist.media_sequence
+                || next.segments.last() != playlist.segments.last()
+        })
+    };
+
+    Some(HlsSample {
+        segments_tried,
+        segments_ok,
+        speed_ratio,
+        advancing,
+    })
+}
+
+/// Score a batch of HLS stream URLs concurrently.
+///
+/// Uses a semaphore to limit concurrency to 20 simultaneous measurements.
+/// Returns one score per URL, in input order; unreachable playlists score 0.
+pub async fn score_urls(client: &Client, urls: &[String], timeout: Duration) -> Vec<u8> {
+    let semaphore = Arc::new(Semaphore::new(20));
+    let mut handles = Vec::with_capacity(urls.len());
+
+    for url in urls {
+        let url = url.clone();
+        let client = client.clone();
+        let sem = Arc::clone(&semaphore);
+
+        handles.push(tokio::spawn(async move {
+            let _permit = sem.acquire().await;
+            measure(&client, &url, timeout)
+                .await
+                .map_or(0, |s| score(&s))
+        }));
+    }
+
+    let mut scores = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(score) => scores.push(score),
+            Err(e) => {
+                warn!("HLS health task panicked: {e}");
+                scores.push(0);
+            }
+        }
+    }
+    scores
+}
+
+/// Reorder duplicate channels (same group and name) so the healthiest comes first.
+///
+/// Duplicates are usually backup sources for the same channel. They swap
+/// places among the slots they already occupy; every other channel keeps
+/// its position. Unscored channels sort after scored ones, and ties keep
+/// their current order.
+pub fn order_backups_by_health(channels: &mut [Channel]) {
+    let mut groups: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for (i, ch) in channels.iter().enumerate() {
+        groups
+            .entry((ch.group.clone(), ch.name.to_lowercase()))
+            .or_default()
+            .push(i);
+    }
+
+    for slots in groups.into_values().filter(|s| s.len() > 1) {
+        let mut members: Vec<Channel> = slots.iter().map(|&i| channels[i].clone()).collect();
+        members.sort_by_key(|ch| std::cmp::Reverse(ch.health_score));
+        for (slot, member) in slots.into_iter().zip(members) {
+            channels[slot] = member;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_media_and_master_playlists() {
+        let media = parse_playlist(
+            "#EXTM3U\n#EXT-X-TARGETDURATION:6\n#EXT-X-MEDIA-SEQUENCE:42\n\
+             #EXTINF:6.0,\nseg42.ts\n#EXTINF:5.5,\nseg43.ts\n",
+        );
+        assert_eq!(media.target_duration, 6.0);
+        assert_eq!(media.media_sequence, 42);
+        assert_eq!(
+            media.segments,
+            vec![(6.0, "seg42.ts".to_string()), (5.5, "seg43.ts".to_string())]
+        );
+        assert!(!media.ended);
+
+        let master = parse_playlist(
+            "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=800000\nlow/index.m3u8\n\
+             #EXT-X-STREAM-INF:CODECS=\"avc1.64001f,mp4a.40.2\",BANDWIDTH=2400000,\
+             RESOLUTION=1280x720\nhigh/index.m3u8\n",
+        );
+        let uris: Vec<&str> = master.variants.iter().map(|v| v.uri.as_str()).collect();
+        assert_eq!(uris, vec!["low/index.m3u8", "high/index.m3u8"]);
+        assert_eq!(
+            master.variants[1].info,
+            StreamInfo {
+                bandwidth: Some(2_400_000),
+                resolution: Some("1280x720".to_string()),
+            }
+        );
+        assert_eq!(master.variants[0].info.resolution, None);
+        assert!(master.segments.is_empty());
+    }
+
+    #[test]
+    fn score_penalises_slow_segments_and_stalls() {
+        let healthy = HlsSample {
+            segments_tried: 2,
+            segments_ok: 2,
+            speed_ratio: Some(4.0),
+            advancing: Some(true),
+        };
+        assert_eq!(score(&healthy), 100);
+
+        // Segments arrive slower than real time and the playlist is frozen.
+        let stalling = HlsSample {
+            speed_ratio: Some(0.5),
+            advancing: Some(false),
+            ..healthy.clone()
+        };
+        assert_eq!(score(&stalling), 35);
+
+        let dead = HlsSample {
+            segments_ok: 0,
+            speed_ratio: None,
+            ..healthy
+        };
+        assert_eq!(score(&dead), 0);
+    }
+
+    #[test]
+    fn backups_are_ordered_by_health_in_place() {
+        let mut channels = vec![
+            Channel {
+                health_score: Some(20),
+                ..Channel::sample("a1", "Alpha", "News")
+            },
+            Channel::sample("b", "Beta", "News"),
+            Channel {
+                health_score: Some(90),
+                ..Channel::sample("a2", "alpha", "News")
+            },
+            Channel::sample("a3", "Alpha", "News"),
+        ];
+        order_backups_by_health(&mut channels);
+
+        let ids: Vec<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["a2", "b", "a1", "a3"]);
+    }
+
+    #[test]
+    fn detects_hls_urls() {
+        assert!(is_hls("http://example.com/live/index.M3U8?token=1"));
+        assert!(!is_hls("http://example.com/live.ts"));
+    }
+}
@@ -0,0 +1,22 @@
+use reqwest::{redirect, Client, Proxy};
+
+use crate::config::Config;
+
+/// Build the HTTP client shared by every outbound request: probes,
+/// playlist, guide and IPFS downloads, and chain lookups.
+///
+/// Sharing one client lets requests to the same host reuse pooled
+/// connections. It follows up to `HTTP_MAX_REDIRECTS` redirects and, when
+/// `HTTP_PROXY_URL` is set, sends everything through that proxy. Timeouts
+/// are set per request, since each caller has its own.
+pub fn shared_client(config: &Config) -> Result<Client, reqwest::Error> {
+    let redirects = match config.http_max_redirects {
+        0 => redirect::Policy::none(),
+        max => redirect::Policy::limited(max),
+    };
+    let mut builder = Client::builder().redirect(redirects);
+    if let Some(ref url) = config.http_proxy_url {
+        builder = builder.proxy(Proxy::all(url)?);
+    }
+    builder.build()
+}
@@ -0,0 +1,602 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::models::{AppState, Channel};
+use crate::services::channel_checker::{Outcome, ProbeOptions, ProbeTarget};
+use crate::services::events::ServerEvent;
+use crate::services::fetch;
+use crate::services::m3u_parser::ParseIssue;
+use crate::services::xtream::{self, XtreamCredentials};
+use crate::services::{channel_checker, m3u_parser, variant_grouper};
+
+/// Finished jobs kept for status queries; the oldest are dropped first.
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// Parse issues kept per job; the total is still counted past this.
+const MAX_REPORTED_ISSUES: usize = 100;
+
+/// Channels probed between progress updates in the probe stage.
+const PROBE_BATCH: usize = 200;
+
+/// Overall state of an import job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Accepted but not started yet.
+    Queued,
+    /// Working through its stages.
+    Running,
+    /// Finished; `summary` is set.
+    Completed,
+    /// Stopped early; `error` is set.
+    Failed,
+}
+
+/// A processing stage of an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Fetch (for URL imports) and parse the M3U into channels.
+    Parse,
+    /// Drop channels whose stream URL is repeated or already in the playlist.
+    Dedup,
+    /// Probe the imported channels for liveness.
+    Probe,
+}
+
+/// State of a single stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Done,
+}
+
+/// Progress of a single stage.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageProgress {
+    pub stage: Stage,
+    pub status: StageStatus,
+    /// Items handled so far.
+    pub processed: usize,
+    /// Items to handle, once known.
+    pub total: usize,
+}
+
+/// How imported channels are merged into the playlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Replace every channel in the playlist.
+    Replace,
+    /// Add channels whose stream URL is not in the playlist yet.
+    Append,
+}
+
+impl ImportMode {
+    /// Parse the `mode` query parameter; anything but `"append"` replaces.
+    pub fn from_param(mode: Option<&str>) -> Self {
+        if mode == Some("append") {
+            Self::Append
+        } else {
+            Self::Replace
+        }
+    }
+}
+
+/// Where the M3U content of an import comes from.
+#[derive(Debug)]
+pub enum ImportSource {
+    /// Content uploaded with the request.
+    Upload(String),
+    /// URL to fetch the content from.
+    Url(String),
+    /// Xtream Codes panel to pull the stream lists from.
+    Xtream {
+        credentials: XtreamCredentials,
+        include_vod: bool,
+    },
+}
+
+impl ImportSource {
+    /// Value recorded as the playlist source: `"upload"`, the URL, or
+    /// `xtream:{username}@{server}`.
+    fn label(&self) -> String {
+        match self {
+            Self::Upload(_) => "upload".to_string(),
+            Self::Url(url) => url.clone(),
+            Self::Xtream { credentials, .. } => credentials.label(),
+        }
+    }
+}
+
+/// Final outcome of a completed import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    /// Channels added to the playlist.
+    pub channels_loaded: usize,
+    /// Channels dropped by the dedup stage.
+    pub duplicates_skipped: usize,
+    /// Added channels that answered their first probe.
+    pub live_channels: usize,
+    /// Playlist size after the import.
+    pub total_channels: usize,
+}
+
+/// Malformed and skipped lines found by the parse stage.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseIssues {
+    /// Number of issues found.
+    pub total: usize,
+    /// The first [`MAX_REPORTED_ISSUES`] issues, in file order.
+    pub issues: Vec<ParseIssue>,
+}
+
+/// Status of an import job, as returned by `GET /api/jobs/:id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJob {
+    pub id: String,
+    /// `"upload"`, the imported URL, or the Xtream account.
+    pub source: String,
+    pub mode: ImportMode,
+    /// Whether any parse issue fails the job.
+    pub strict: bool,
+    pub status: JobStatus,
+    /// Per-stage progress, in processing order.
+    pub stages: Vec<StageProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ImportSummary>,
+    /// Set once parsing has found at least one issue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_issues: Option<ParseIssues>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// In-memory registry of import jobs.
+///
+/// Running jobs are always kept; only the most recent
+/// [`MAX_FINISHED_JOBS`] finished ones are retained.
+#[derive(Debug, Default)]
+pub struct ImportJobs {
+    jobs: HashMap<String, ImportJob>,
+    /// Finished job IDs, oldest first.
+    finished: VecDeque<String>,
+    next_id: u64,
+}
+
+impl ImportJobs {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The job with `id`, if it is still retained.
+    pub fn get(&self, id: &str) -> Option<&ImportJob> {
+        self.jobs.get(id)
+    }
+
+    /// Register a queued job and return its ID.
+    fn create(
+        &mut self,
+        source: String,
+        mode: ImportMode,
+        strict: bool,
+        now: DateTime<Utc>,
+    ) -> String {
+        self.next_id += 1;
+        let id = self.next_id.to_string();
+        let stages = [Stage::Parse, Stage::Dedup, Stage::Probe]
+            .into_iter()
+            .map(|stage| StageProgress {
+                stage,
+                status: StageStatus::Pending,
+                processed: 0,
+                total: 0,
+            })
+            .collect();
+        self.jobs.insert(
+            id.clone(),
+            ImportJob {
+                id: id.clone(),
+                source,
+                mode,
+                strict,
+                status: JobStatus::Queued,
+                stages,
+                summary: None,
+                parse_issues: None,
+                error: None,
+                created_at: now,
+                finished_at: None,
+            },
+        );
+        id
+    }
+
+    /// Update the progress of `stage`, marking the job as running.
+    fn progress(
+        &mut self,
+        id: &str,
+        stage: Stage,
+        status: StageStatus,
+        processed: usize,
+        total: usize,
+    ) {
+        let Some(job) = self.jobs.get_mut(id) else {
+            return;
+        };
+        job.status = JobStatus::Running;
+        if let Some(progress) = job.stages.iter_mut().find(|s| s.stage == stage) {
+            progress.status = status;
+            progress.processed = processed;
+            progress.total = total;
+        }
+    }
+
+    /// Attach the parse stage's issues to job `id`, keeping at most
+    /// [`MAX_REPORTED_ISSUES`] of them.
+    fn report_issues(&mut self, id: &str, mut issues: Vec<ParseIssue>) {
+        let Some(job) = self.jobs.get_mut(id) else {
+            return;
+        };
+        if issues.is_empty() {
+            return;
+        }
+        let total = issues.len();
+        issues.truncate(MAX_REPORTED_ISSUES);
+        job.parse_issues = Some(ParseIssues { total, issues });
+    }
+
+    /// Record the outcome of a job and drop the oldest finished jobs over the limit.
+    fn finish(&mut self, id: &str, result: Result<ImportSummary, String>, now: DateTime<Utc>) {
+        let Some(job) = self.jobs.get_mut(id) else {
+            return;
+        };
+        match result {
+            Ok(summary) => {
+                job.status = JobStatus::Completed;
+                job.summary = Some(summary);
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+        job.finished_at = Some(now);
+
+        self.finished.push_back(id.to_string());
+        while self.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(old) = self.finished.pop_front() {
+                self.jobs.remove(&old);
+            }
+        }
+    }
+}
+
+/// Drop channels whose stream URL is in `existing` or repeats an earlier
+/// channel. Returns the kept channels and how many were dropped.
+pub fn dedup_channels(channels: Vec<Channel>, existing: &HashSet<&str>) -> (Vec<Channel>, usize) {
+    let total = channels.len();
+    let mut seen: HashSet<String> = HashSet::with_capacity(total);
+    let unique: Vec<Channel> = channels
+        .into_iter()
+        .filter(|ch| {
+            !existing.contains(ch.stream_url.as_str()) && seen.insert(ch.stream_url.clone())
+        })
+        .collect();
+    let skipped = total - unique.len();
+    (unique, skipped)
+}
+
+/// Queue an import and process it in the background.
+///
+/// In `strict` mode the job fails without touching the playlist when the
+/// parser reports any issue. Returns the job ID to poll with
+/// `GET /api/jobs/:id`.
+pub async fn start_import(
+    state: &Arc<AppState>,
+    source: ImportSource,
+    mode: ImportMode,
+    strict: bool,
+) -> String {
+    let id = state
+        .import_jobs
+        .write()
+        .await
+        .create(source.label(), mode, strict, Utc::now());
+    tokio::spawn(run_import(
+        Arc::clone(state),
+        id.clone(),
+        source,
+        mode,
+        strict,
+    ));
+    id
+}
+
+async fn run_import(
+    state: Arc<AppState>,
+    id: String,
+    source: ImportSource,
+    mode: ImportMode,
+    strict: bool,
+) {
+    let result = import(&state, &id, source, mode, strict).await;
+    match result {
+        Ok(ref summary) => info!(
+            "Import job {id} done: {} channels loaded, {} duplicates skipped, {} live",
+            summary.channels_loaded, summary.duplicates_skipped, summary.live_channels
+        ),
+        Err(ref e) => warn!("Import job {id} failed: {e}"),
+    }
+    state
+        .import_jobs
+        .write()
+        .await
+        .finish(&id, result, Utc::now());
+}
+
+async fn progress(
+    state: &AppState,
+    id: &str,
+    stage: Stage,
+    status: StageStatus,
+    processed: usize,
+    total: usize,
+) {
+    state
+        .import_jobs
+        .write()
+        .await
+        .progress(id, stage, status, processed, total);
+}
+
+/// Run the parse, dedup, and probe stages of job `id`.
+async fn import(
+    state: &Arc<AppState>,
+    id: &str,
+    source: ImportSource,
+    mode: ImportMode,
+    strict: bool,
+) -> Result<ImportSummary, String> {
+    let label = source.label();
+
+    // Parse.
+    progress(state, id, Stage::Parse, StageStatus::Running, 0, 0).await;
+    let (channels, issues, guides) = parse_source(&state.http, source).await?;
+    let issue_count = issues.len();
+    state.import_jobs.write().await.report_issues(id, issues);
+    if strict && issue_count > 0 {
+        return Err(format!(
+            "Strict parse found {issue_count} malformed or skipped lines"
+        ));
+    }
+    let parsed = channels.len();
+    progress(state, id, Stage::Parse, StageStatus::Done, parsed, parsed).await;
+
+    // Dedup, then apply to the playlist.
+    progress(state, id, Stage::Dedup, StageStatus::Running, 0, parsed).await;
+    let (targets, duplicates_skipped) = {
+        let mut playlist = state.playlist.write().await;
+        let (unique, skipped) = match mode {
+            ImportMode::Append => {
+                let existing: HashSet<&str> = playlist
+                    .channels
+                    .iter()
+                    .map(|ch| ch.stream_url.as_str())
+                    .collect();
+                dedup_channels(channels, &existing)
+            }
+            ImportMode::Replace => dedup_channels(channels, &HashSet::new()),
+        };
+        let targets: Vec<(String, ProbeTarget)> = unique
+            .iter()
+            .map(|ch| {
+                (
+                    ch.id.clone(),
+                    ProbeTarget::of(ch, &state.config.probe_headers),
+                )
+            })
+            .collect();
+
+        match mode {
+            ImportMode::Append => {
+                playlist.channels.extend(unique);
+                for url in guides {
+                    if !playlist.epg_urls.contains(&url) {
+                        playlist.epg_urls.push(url);
+                    }
+                }
+            }
+            ImportMode::Replace => {
+                playlist.channels = unique;
+                playlist.epg_urls = guides;
+            }
+        }
+        playlist.source = label;
+
+        state
+            .search_index
+            .write()
+            .await
+            .sync_channels(&playlist.channels);
+        state
+            .probe_schedule
+            .write()
+            .await
+            .sync(&playlist.channels, Utc::now());
+        state
+            .events
+            .publish(ServerEvent::playlist_replaced(&playlist));
+        (targets, skipped)
+    };
+    state.state_changed.notify_one();
+    let loaded = targets.len();
+    progress(state, id, Stage::Dedup, StageStatus::Done, loaded, parsed).await;
+
+    // Probe the imported channels in batches so progress stays current.
+    let options = ProbeOptions::from_config(&state.config);
+    let mut live_channels = 0;
+    progress(state, id, Stage::Probe, StageStatus::Running, 0, loaded).await;
+    for (done, batch) in targets.chunks(PROBE_BATCH).enumerate() {
+        let batch_targets: Vec<ProbeTarget> =
+            batch.iter().map(|(_, target)| target.clone()).collect();
+        let results = channel_checker::check_streams(&state.http, &batch_targets, &options).await;
+        let outcome: Outcome = batch
+            .iter()
+            .map(|(id, target)| (id.as_str(), target.url.as_str()))
+            .zip(results)
+            .collect();
+        live_channels += outcome.values().filter(|probe| probe.is_live).count();
+
+        let now = Utc::now();
+        {
+            let mut playlist = state.playlist.write().await;
+            let mut schedule = state.probe_schedule.write().await;
+            let mut uptime = state.uptime.write().await;
+            channel_checker::apply_outcome(
+                &mut playlist.channels,
+                &mut schedule,
+                &mut uptime,
+                &state.events,
+                &outcome,
+                &options,
+                now,
+            );
+        }
+
+        let processed = (done * PROBE_BATCH + batch.len()).min(loaded);
+        progress(
+            state,
+            id,
+            Stage::Probe,
+            StageStatus::Running,
+            processed,
+            loaded,
+        )
+        .await;
+    }
+    progress(state, id, Stage::Probe, StageStatus::Done, loaded, loaded).await;
+    state.state_changed.notify_one();
+
+    let total_channels = state.playlist.read().await.channels.len();
+    Ok(ImportSummary {
+        channels_loaded: loaded,
+        duplicates_skipped,
+        live_channels,
+        total_channels,
+    })
+}
+
+/// Fetch and parse the channels of `source`, with the parse issues and
+/// guide URLs found along the way.
+///
+/// Xtream panels are read through their API, so they report no issues;
+/// their guide is the panel's `xmltv.php`.
+async fn parse_source(
+    client: &reqwest::Client,
+    source: ImportSource,
+) -> Result<(Vec<Channel>, Vec<ParseIssue>, Vec<String>), String> {
+    let content = match source {
+        ImportSource::Upload(content) => content,
+        ImportSource::Url(url) => fetch::fetch_text(client, &url, fetch::PLAYLIST_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to fetch {url}: {e}"))?,
+        ImportSource::Xtream {
+            credentials,
+            include_vod,
+        } => {
+            let channels = xtream::fetch_channels(client, &credentials, include_vod)
+                .await
+                .map_err(|e| e.to_string())?;
+            let channels = variant_grouper::group_variants(channels);
+            return Ok((channels, Vec::new(), vec![credentials.guide_url()]));
+        }
+    };
+    tokio::task::spawn_blocking(move || {
+        let report = m3u_parser::parse_m3u(&content);
+        let guides = m3u_parser::parse_guide_urls(&content);
+        let channels = variant_grouper::group_variants(report.channels);
+        (channels, report.issues, guides)
+    })
+    .await
+    .map_err(|e| format!("Parser task failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_drops_repeated_and_existing_urls() {
+        let existing = HashSet::from(["http://a"]);
+        let (kept, skipped) = dedup_channels(
+            vec![
+                Channel {
+                    stream_url: "http://a".to_string(),
+                    ..Channel::sample("1", "1", "Test")
+                },
+                Channel {
+                    stream_url: "http://b".to_string(),
+                    ..Channel::sample("2", "2", "Test")
+                },
+                Channel {
+                    stream_url: "http://b".to_string(),
+                    ..Channel::sample("3", "3", "Test")
+                },
+                Channel {
+                    stream_url: "http://c".to_string(),
+                    ..Channel::sample("4", "4", "Test")
+                },
+            ],
+            &existing,
+        );
+
+        let ids: Vec<&str> = kept.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "4"]);
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn finished_jobs_are_capped() {
+        let now = Utc::now();
+        let mut jobs = ImportJobs::new();
+        let running = jobs.create("upload".to_string(), ImportMode::Replace, false, now);
+        jobs.progress(&running, Stage::Parse, StageStatus::Running, 0, 0);
+
+        let first = jobs.create("upload".to_string(), ImportMode::Append, false, now);
+        jobs.finish(&first, Err("boom".to_string()), now);
+        assert_eq!(jobs.get(&first).unwrap().status, JobStatus::Failed);
+
+        for _ in 0..MAX_FINISHED_JOBS {
+            let id = jobs.create("upload".to_string(), ImportMode::Append, false, now);
+            jobs.finish(&id, Err("boom".to_string()), now);
+        }
+
+        // The oldest finished job is evicted; the running one is kept.
+        assert!(jobs.get(&first).is_none());
+        assert_eq!(jobs.get(&running).unwrap().status, JobStatus::Running);
+    }
+
+    #[test]
+    fn reported_issues_are_capped() {
+        let mut jobs = ImportJobs::new();
+        let id = jobs.create("upload".to_string(), ImportMode::Replace, true, Utc::now());
+        let content = "#EXTM3U\n".to_string() + &"http://example.com/orphan\n".repeat(150);
+        jobs.report_issues(&id, m3u_parser::parse_m3u(&content).issues);
+
+        let reported = jobs.get(&id).unwrap().parse_issues.as_ref().unwrap();
+        assert_eq!(reported.total, 150);
+        assert_eq!(reported.issues.len(), MAX_REPORTED_ISSUES);
+        assert_eq!(reported.issues[0].line, 2);
+    }
+}
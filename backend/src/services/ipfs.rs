@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Timeout for downloading content from the gateway.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest content fetched from IPFS.
+const MAX_CONTENT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Multicodec of content addressed as raw bytes.
+const RAW_CODEC: u64 = 0x55;
+
+/// Multicodec of content addressed as JSON bytes.
+const JSON_CODEC: u64 = 0x0200;
+
+/// Multihash code of SHA-256.
+const SHA2_256: u64 = 0x12;
+
+/// Errors that can occur while fetching content from IPFS.
+#[derive(Debug, Error)]
+pub enum IpfsError {
+    /// HTTP request to the gateway failed or returned an error status.
+    #[error("IPFS gateway request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// Not a CID.
+    #[error("Invalid CID {0}")]
+    InvalidCid(String),
+    /// A CID whose block is not the content itself hashed with SHA-256,
+    /// such as a CIDv0 or a UnixFS directory, so the content cannot be checked.
+    #[error("CID {0} is not a raw SHA-256 CID, so its content cannot be verified")]
+    Unverifiable(String),
+    /// The gateway served content that does not hash to the CID.
+    #[error("Content served for {0} does not match its CID")]
+    HashMismatch(String),
+    /// The content exceeds [`MAX_CONTENT_BYTES`].
+    #[error("Content of {0} exceeds {MAX_CONTENT_BYTES} bytes")]
+    TooLarge(String),
+}
+
+/// An IPFS HTTP gateway, e.g. `https://ipfs.io`.
+///
+/// Gateways are not trusted: content is only returned when it hashes to
+/// its CID. That requires CIDv1 with the `raw` or `json` codec and a
+/// SHA-256 multihash, as made by `ipfs add --cid-version 1 --raw-leaves`
+/// for files up to one chunk, or by `ipfs block put`.
+#[derive(Debug, Clone)]
+pub struct IpfsGateway {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl IpfsGateway {
+    /// A client of the gateway at `url`, sending requests with `client`.
+    pub fn new(client: reqwest::Client, url: String) -> Self {
+        Self { url, client }
+    }
+
+    /// The content named by `cid`, once checked against it.
+    pub async fn fetch(&self, cid: &str) -> Result<Vec<u8>, IpfsError> {
+        let digest = cid_digest(cid)?;
+        let mut response = self
+            .client
+            .get(format!("{}/ipfs/{cid}", self.url))
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let mut content = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if content.len() + chunk.len() > MAX_CONTENT_BYTES {
+                return Err(IpfsError::TooLarge(cid.to_string()));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        if Sha256::digest(&content)[..] != digest {
+            return Err(IpfsError::HashMismatch(cid.to_string()));
+        }
+        Ok(content)
+    }
+}
+
+/// The SHA-256 digest of the content named by a CIDv1 whose block is the
+/// content itself.
+fn cid_digest(cid: &str) -> Result<[u8; 32], IpfsError> {
+    let invalid = || IpfsError::InvalidCid(cid.to_string());
+    // CIDv0: base58 `Qm…`, always a UnixFS node.
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        return Err(IpfsError::Unverifiable(cid.to_string()));
+    }
+
+    // The multibase prefix names the encoding of the rest.
+    let mut chars = cid.chars();
+    let base = chars.next();
+    let encoded = chars.as_str();
+    let bytes = match base {
+        Some('b') => decode_base32(encoded),
+        Some('B') => decode_base32(&encoded.to_ascii_lowercase()),
+        Some('z') => bs58::decode(encoded).into_vec().ok(),
+        Some('f') => hex::decode(encoded).ok(),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+
+    let mut rest = bytes.as_slice();
+    if read_varint(&mut rest) != Some(1) {
+        return Err(invalid());
+    }
+    let codec = read_varint(&mut rest).ok_or_else(invalid)?;
+    let hash = read_varint(&mut rest).ok_or_else(invalid)?;
+    let length = read_varint(&mut rest).ok_or_else(invalid)?;
+    if length != rest.len() as u64 {
+        return Err(invalid());
+    }
+    if !matches!(codec, RAW_CODEC | JSON_CODEC) || hash != SHA2_256 {
+        return Err(IpfsError::Unverifiable(cid.to_string()));
+    }
+    rest.try_into().map_err(|_| invalid())
+}
+
+/// Read an unsigned LEB128 varint, as used in CIDs and multihashes.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..63).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Decode unpadded lowercase RFC 4648 base32, the default CIDv1 encoding.
+fn decode_base32(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in encoded.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5 | u32::from(value)) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] =
+        b"#EXTM3U\n#EXTINF:-1 group-title=\"News\",News One\nhttps://example.com/news.m3u8\n";
+
+    #[test]
+    fn reads_raw_cids() {
+        let digest: [u8; 32] = Sha256::digest(CONTENT).into();
+        let cid = "bafkreigk7tuy57fef3rmcmxobo3l7xtm7odh55lwcyvmt2hcdnctk2vq3e";
+        assert_eq!(cid_digest(cid).unwrap(), digest);
+        assert_eq!(cid_digest(&cid.to_ascii_uppercase()).unwrap(), digest);
+        let hex_cid = format!("f01551220{}", hex::encode(digest));
+        assert_eq!(cid_digest(&hex_cid).unwrap(), digest);
+    }
+
+    #[test]
+    fn rejects_unverifiable_and_invalid_cids() {
+        // The same digest as a UnixFS (dag-pb) node.
+        let dag_pb = "bafybeigk7tuy57fef3rmcmxobo3l7xtm7odh55lwcyvmt2hcdnctk2vq3e";
+        assert!(matches!(
+            cid_digest(dag_pb),
+            Err(IpfsError::Unverifiable(_))
+        ));
+        let v0 = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        assert!(matches!(cid_digest(v0), Err(IpfsError::Unverifiable(_))));
+        for cid in [
+            "",
+            "b",
+            "bafkrei",
+            "x0155",
+            "bafkreigk7tuy57fef3rmcmxobo3l7xtm7odh55lwcyvmt2hcdnctk2vq3",
+        ] {
+            assert!(
+                matches!(cid_digest(cid), Err(IpfsError::InvalidCid(_))),
+                "{cid}"
+            );
+        }
+    }
+}
@@ -7,6 +7,7 @@ use tracing::{info, warn};
 
 use crate::models::epg::EpgSchedule;
 use crate::services::epg_parser;
+use crate::services::fetch::{self, Conditional, FetchError, Validators};
 
 /// Base URL for the iptv-org API.
 const IPTV_ORG_API: &str = "https://iptv-org.github.io/api";
@@ -32,6 +33,9 @@ pub enum IptvOrgError {
     /// HTTP request failed.
     #[error("HTTP request failed: {0}")]
     Http(#[from] reqwest::Error),
+    /// Guide download or decompression failed.
+    #[error("{0}")]
+    Fetch(#[from] FetchError),
     /// JSON deserialization failed.
     #[error("JSON parse error: {0}")]
     Json(#[from] serde_json::Error),
@@ -218,6 +222,7 @@ pub async fn refresh_index(
 }
 
 /// Result of fetching EPG data: schedules plus a display-name map for matching.
+#[derive(Debug, Default)]
 pub struct FetchedEpg {
     /// Programme schedules keyed by XMLTV channel ID.
     pub schedules: HashMap<String, EpgSchedule>,
@@ -225,96 +230,51 @@ pub struct FetchedEpg {
     pub display_names: HashMap<String, String>,
 }
 
-/// Fetch EPG schedule for a single channel on-demand.
-///
-/// 1. Looks up the channel in the iptv-org index
-/// 2. Builds the country-level EPG URL (epg.pw)
-/// 3. Fetches and parses the full XMLTV guide
-/// 4. Returns all schedules and a display-name map for channel matching
-///
-/// The country code is derived from the channel ID suffix (e.g., `TF1.fr` → `FR`)
-/// and used as the default timezone when XMLTV timestamps lack explicit offsets.
-pub async fn fetch_channel_epg(
-    client: &reqwest::Client,
-    index: &IptvOrgIndex,
-    iptv_org_id: &str,
-) -> Result<FetchedEpg, IptvOrgError> {
-    let url = index
-        .get_guide_url(iptv_org_id)
-        .ok_or_else(|| IptvOrgError::NoGuide(iptv_org_id.to_string()))?;
-
-    let country = iptv_org_id.rsplit('.').next().unwrap_or("us");
-    info!("Fetching EPG for {iptv_org_id} from {url} (country={country})");
-
-    fetch_and_parse_guide(client, &url, country).await
+/// Country code of an iptv-org channel ID, taken from its suffix
+/// (e.g., `TF1.fr` → `fr`).
+pub fn guide_country(iptv_org_id: &str) -> &str {
+    iptv_org_id.rsplit('.').next().unwrap_or("us")
 }
 
-/// Fetch a single gzipped XMLTV guide, decompress, and parse all channels.
+/// Fetch a single XMLTV guide, decompress it if needed, and parse all channels.
 ///
 /// `country_code` is used to infer a default timezone offset when XMLTV
 /// timestamps do not include an explicit offset.
-async fn fetch_and_parse_guide(
+///
+/// With `validators` from a previous download the request is conditional,
+/// and `None` is returned when the guide has not been modified. Otherwise
+/// the parsed guide comes back with the validators of the new response.
+pub async fn fetch_and_parse_guide(
     client: &reqwest::Client,
     url: &str,
     country_code: &str,
-) -> Result<FetchedEpg, IptvOrgError> {
-    let response = client
-        .get(url)
-        .timeout(GUIDE_TIMEOUT)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(IptvOrgError::Http(
-            response.error_for_status().unwrap_err(),
-        ));
-    }
-
-    let bytes = response.bytes().await?;
+    validators: Option<&Validators>,
+) -> Result<Option<(FetchedEpg, Validators)>, IptvOrgError> {
+    let (bytes, validators) =
+        match fetch::fetch_if_modified(client, url, GUIDE_TIMEOUT, validators).await? {
+            Conditional::Modified { body, validators } => (body, validators),
+            Conditional::NotModified => return Ok(None),
+        };
 
     if bytes.len() > MAX_GUIDE_SIZE {
         warn!("Guide {url} exceeds {MAX_GUIDE_SIZE} bytes, skipping");
-        return Ok(FetchedEpg {
-            schedules: HashMap::new(),
-            display_names: HashMap::new(),
-        });
+        return Ok(Some((FetchedEpg::default(), validators)));
     }
 
-    // Try gzip decompression first, fall back to plain text.
-    let xml_str = match decompress_gzip(&bytes) {
-        Some(decompressed) => String::from_utf8(decompressed).unwrap_or_default(),
-        None => String::from_utf8(bytes.to_vec()).unwrap_or_default(),
-    };
+    let xml_str = String::from_utf8(bytes).unwrap_or_default();
 
     if xml_str.is_empty() {
-        return Ok(FetchedEpg {
-            schedules: HashMap::new(),
-            display_names: HashMap::new(),
-        });
+        return Ok(Some((FetchedEpg::default(), validators)));
     }
 
     // Parse all channels (empty filter = accept all).
     let default_offset = epg_parser::country_utc_offset(country_code);
     let parsed = epg_parser::parse_xmltv(&xml_str, &[], default_offset)?;
-    Ok(FetchedEpg {
+    let fetched = FetchedEpg {
         schedules: parsed.schedules,
         display_names: parsed.display_names,
-    })
-}
-
-/// Attempt to decompress gzip data. Returns `None` if the data is not gzipped.
-fn decompress_gzip(data: &[u8]) -> Option<Vec<u8>> {
-    use std::io::Read;
-
-    // Check gzip magic bytes.
-    if data.len() < 2 || data[0] != 0x1f || data[1] != 0x8b {
-        return None;
-    }
-
-    let mut decoder = flate2::read::GzDecoder::new(data);
-    let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed).ok()?;
-    Some(decompressed)
+    };
+    Ok(Some((fetched, validators)))
 }
 
 #[cfg(test)]
@@ -437,10 +397,4 @@ mod tests {
             "https://iptv-epg.org/files/epg-xx.xml"
         );
     }
-
-    #[test]
-    fn decompress_gzip_rejects_non_gzip() {
-        let plain = b"hello world";
-        assert!(decompress_gzip(plain).is_none());
-    }
 }
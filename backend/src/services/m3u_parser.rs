@@ -1,8 +1,59 @@
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
+use serde::Serialize;
+
+use crate::models::channel::M3uAttributes;
 use crate::models::Channel;
 
+/// Longest excerpt of an offending line kept in a [`ParseIssue`].
+const MAX_ISSUE_TEXT: usize = 120;
+
+/// Why [`parse_m3u`] reported a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueReason {
+    /// The first line is not `#EXTM3U`. Parsing carries on.
+    MissingHeader,
+    /// An `#EXTINF` entry has no stream URL before the next `#EXTINF` or
+    /// the end of the file. The entry is skipped.
+    MissingUrl,
+    /// A stream URL with no `#EXTINF` before it. The line is skipped.
+    UrlWithoutExtinf,
+    /// An `#EXTINF` entry has neither `tvg-name` nor a display name. The
+    /// channel is kept without a name.
+    MissingName,
+}
+
+/// A malformed or skipped line found while parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseIssue {
+    /// 1-based line number.
+    pub line: usize,
+    pub reason: IssueReason,
+    /// The offending line, trimmed and shortened to [`MAX_ISSUE_TEXT`]
+    /// characters.
+    pub text: String,
+}
+
+impl ParseIssue {
+    fn new(index: usize, reason: IssueReason, text: &str) -> Self {
+        Self {
+            line: index + 1,
+            reason,
+            text: text.chars().take(MAX_ISSUE_TEXT).collect(),
+        }
+    }
+}
+
+/// Result of [`parse_m3u`]: the parsed channels and every line that could
+/// not be used as-is, in file order.
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    pub channels: Vec<Channel>,
+    pub issues: Vec<ParseIssue>,
+}
+
 /// Parse raw M3U/M3U8 content into a list of [`Channel`] structs.
 ///
 /// The parser handles the standard `#EXTINF` directive format:
@@ -12,28 +63,65 @@ use crate::models::Channel;
 /// https://stream.example.com/cnn.m3u8
 /// ```
 ///
-/// Malformed entries (missing URL, unparseable lines) are silently skipped.
-pub fn parse_m3u(content: &str) -> Vec<Channel> {
-    let mut channels = Vec::new();
+/// Extended attributes (`tvg-chno`, `tvg-shift`, `catchup`, `catchup-days`,
+/// `catchup-source`, `radio`) are kept in [`Channel::attributes`], as are
+/// `#EXTVLCOPT` user agent and referrer options between an `#EXTINF` and
+/// its URL. An `#EXTGRP` directive there sets the group when the `#EXTINF`
+/// has no `group-title`.
+///
+/// Each `#EXTINF` starts a new entry, which ends at the first URL line.
+/// An entry that reaches another `#EXTINF` or the end of the file without
+/// a URL is dropped, so it can never take the next entry's URL. Malformed
+/// entries (missing URL, unparseable lines) are skipped and listed in
+/// [`ParseReport::issues`] with their line number.
+pub fn parse_m3u(content: &str) -> ParseReport {
+    let mut report = ParseReport::default();
     let lines: Vec<&str> = content.lines().collect();
     let mut i = 0;
 
+    if let Some((first, line)) = lines
+        .iter()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .enumerate()
+        .find(|(_, line)| !line.is_empty())
+    {
+        if !line.starts_with("#EXTM3U") {
+            report
+                .issues
+                .push(ParseIssue::new(first, IssueReason::MissingHeader, line));
+        }
+    }
+
     while i < lines.len() {
         let line = lines[i].trim();
 
         if line.starts_with("#EXTINF:") {
+            let extinf = i;
             // Extract metadata from the EXTINF line.
             let name = extract_attribute(line, "tvg-name")
                 .or_else(|| extract_display_name(line))
                 .unwrap_or_default();
             let logo_url = extract_attribute(line, "tvg-logo");
-            let group = extract_attribute(line, "group-title").unwrap_or_default();
+            let mut group = extract_attribute(line, "group-title").unwrap_or_default();
             let tvg_id = extract_attribute(line, "tvg-id");
+            let mut attributes = extract_extended_attributes(line);
 
-            // Advance past any blank or comment lines to find the stream URL.
+            // Advance past any blank or comment lines to find the stream URL,
+            // picking up the directives that apply to this entry. A new
+            // #EXTINF ends the entry without one.
             i += 1;
             while i < lines.len() {
                 let next = lines[i].trim();
+                if next.starts_with("#EXTINF:") {
+                    break;
+                }
+                if let Some(extgrp) = next.strip_prefix("#EXTGRP:") {
+                    if group.is_empty() {
+                        group = extgrp.trim().to_string();
+                    }
+                } else if let Some(option) = next.strip_prefix("#EXTVLCOPT:") {
+                    apply_vlc_option(&mut attributes, option);
+                }
                 if next.is_empty() || next.starts_with('#') {
                     i += 1;
                     continue;
@@ -41,27 +129,72 @@ pub fn parse_m3u(content: &str) -> Vec<Channel> {
                 break;
             }
 
-            if i < lines.len() {
-                let stream_url = lines[i].trim().to_string();
-                if !stream_url.is_empty() {
-                    let id = hash_url(&stream_url);
-                    channels.push(Channel {
-                        id,
-                        name,
-                        group,
-                        logo_url,
-                        stream_url,
-                        is_live: false,
-                        tvg_id,
-                    });
-                }
+            if i == lines.len() || lines[i].trim().starts_with("#EXTINF:") {
+                report
+                    .issues
+                    .push(ParseIssue::new(extinf, IssueReason::MissingUrl, line));
+                // Parse the #EXTINF that ended this entry as an entry of its own.
+                continue;
+            }
+
+            let stream_url = lines[i].trim().to_string();
+            if name.is_empty() {
+                report
+                    .issues
+                    .push(ParseIssue::new(extinf, IssueReason::MissingName, line));
             }
+            let id = hash_url(&stream_url);
+            report.channels.push(Channel {
+                id,
+                name,
+                group,
+                logo_url,
+                stream_url,
+                is_live: false,
+                tvg_id,
+                variants: Vec::new(),
+                health_score: None,
+                stream_info: None,
+                health: Default::default(),
+                position: None,
+                attributes,
+            });
+        } else if !line.is_empty() && !line.starts_with('#') {
+            report
+                .issues
+                .push(ParseIssue::new(i, IssueReason::UrlWithoutExtinf, line));
         }
 
         i += 1;
     }
 
-    channels
+    report
+}
+
+/// XMLTV guide URLs named in the `#EXTM3U` header's `url-tvg` (or
+/// `x-tvg-url`) attribute.
+///
+/// Providers often list several guides separated by commas; only http(s)
+/// URLs are kept.
+pub fn parse_guide_urls(content: &str) -> Vec<String> {
+    let header = content
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .find(|line| !line.is_empty());
+    let Some(header) = header.filter(|h| h.starts_with("#EXTM3U")) else {
+        return Vec::new();
+    };
+    extract_attribute(header, "url-tvg")
+        .or_else(|| extract_attribute(header, "x-tvg-url"))
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// Extract a quoted attribute value from an EXTINF line.
@@ -82,6 +215,38 @@ fn extract_attribute(line: &str, key: &str) -> Option<String> {
     }
 }
 
+/// Extract the extended player attributes from an EXTINF line.
+fn extract_extended_attributes(line: &str) -> M3uAttributes {
+    M3uAttributes {
+        tvg_chno: extract_attribute(line, "tvg-chno"),
+        tvg_shift: extract_attribute(line, "tvg-shift"),
+        catchup: extract_attribute(line, "catchup"),
+        catchup_days: extract_attribute(line, "catchup-days").and_then(|v| v.parse().ok()),
+        catchup_source: extract_attribute(line, "catchup-source"),
+        radio: extract_attribute(line, "radio").is_some_and(|v| v.eq_ignore_ascii_case("true")),
+        user_agent: None,
+        referrer: None,
+    }
+}
+
+/// Apply an `#EXTVLCOPT:key=value` option to `attributes`.
+///
+/// Only the HTTP user agent and referrer are kept; other options are ignored.
+fn apply_vlc_option(attributes: &mut M3uAttributes, option: &str) {
+    let Some((key, value)) = option.split_once('=') else {
+        return;
+    };
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    match key.trim() {
+        "http-user-agent" => attributes.user_agent = Some(value.to_string()),
+        "http-referrer" | "http-referer" => attributes.referrer = Some(value.to_string()),
+        _ => {}
+    }
+}
+
 /// Extract the display name that appears after the last comma in an EXTINF line.
 ///
 /// For `#EXTINF:-1 ...,My Channel Name` this returns `Some("My Channel Name")`.
@@ -96,7 +261,7 @@ fn extract_display_name(line: &str) -> Option<String> {
 }
 
 /// Produce a deterministic hex-encoded hash of the stream URL to use as a channel id.
-fn hash_url(url: &str) -> String {
+pub fn hash_url(url: &str) -> String {
     let mut hasher = DefaultHasher::new();
     url.hash(&mut hasher);
     format!("{:016x}", hasher.finish())
@@ -114,7 +279,7 @@ https://stream.example.com/test.m3u8
 #EXTINF:-1 tvg-name="Another" group-title="Sports",Another
 https://stream.example.com/another.m3u8
 "#;
-        let channels = parse_m3u(content);
+        let channels = parse_m3u(content).channels;
         assert_eq!(channels.len(), 2);
         assert_eq!(channels[0].name, "Test Channel");
         assert_eq!(channels[0].group, "News");
@@ -135,7 +300,7 @@ https://stream.example.com/another.m3u8
 
     #[test]
     fn parse_empty_content() {
-        let channels = parse_m3u("");
+        let channels = parse_m3u("").channels;
         assert!(channels.is_empty());
     }
 
@@ -148,13 +313,82 @@ https://stream.example.com/good.m3u8
 #EXTINF:-1 tvg-name="Also Good",Also Good Channel
 https://stream.example.com/also-good.m3u8
 "#;
-        let channels = parse_m3u(content);
-        // "Bad Channel" has no URL line before the next EXTINF, so it gets
-        // the next EXTINF line as a URL candidate which starts with '#' and is
-        // skipped, ultimately consuming "Also Good"'s URL. Depending on
-        // parser behavior, we may get 1 or 2 channels. The important thing
-        // is that no panic occurs.
-        assert!(!channels.is_empty());
+        let report = parse_m3u(content);
+        // "Bad Channel" has no URL before the next EXTINF, so it is dropped
+        // and "Also Good" keeps its own URL.
+        let names: Vec<&str> = report.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Good", "Also Good"]);
+        assert_eq!(
+            report.channels[1].stream_url,
+            "https://stream.example.com/also-good.m3u8"
+        );
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].line, 4);
+        assert_eq!(report.issues[0].reason, IssueReason::MissingUrl);
+    }
+
+    #[test]
+    fn directives_of_dropped_entry_do_not_leak() {
+        let content = r#"#EXTM3U
+#EXTINF:-1 tvg-name="Dropped",Dropped
+#EXTGRP:Lost
+#EXTVLCOPT:http-user-agent=Dropped/1.0
+
+#EXTINF:-1 tvg-name="Kept",Kept
+https://stream.example.com/kept.m3u8
+"#;
+        let report = parse_m3u(content);
+        assert_eq!(report.channels.len(), 1);
+        assert_eq!(report.channels[0].name, "Kept");
+        assert_eq!(report.channels[0].group, "");
+        assert!(report.channels[0].attributes.user_agent.is_none());
+        assert_eq!(report.issues[0].line, 2);
+    }
+
+    #[test]
+    fn consecutive_and_trailing_entries_without_urls() {
+        let content = r#"#EXTM3U
+#EXTINF:-1,One
+#EXTINF:-1,Two
+#EXTINF:-1,Three
+https://stream.example.com/three.m3u8
+#EXTINF:-1,Four
+"#;
+        let report = parse_m3u(content);
+        assert_eq!(report.channels.len(), 1);
+        assert_eq!(report.channels[0].name, "Three");
+        let missing: Vec<usize> = report
+            .issues
+            .iter()
+            .filter(|i| i.reason == IssueReason::MissingUrl)
+            .map(|i| i.line)
+            .collect();
+        assert_eq!(missing, [2, 3, 6]);
+    }
+
+    #[test]
+    fn report_lists_skipped_lines() {
+        let content = "#EXTINF:-1,Headerless\n\
+                       http://example.com/a.m3u8\n\
+                       http://example.com/orphan.m3u8\n\
+                       #EXTINF:-1 group-title=\"News\",\n\
+                       http://example.com/b.m3u8\n\
+                       #EXTINF:-1,Dangling\n";
+        let report = parse_m3u(content);
+        assert_eq!(report.channels.len(), 2);
+
+        let found: Vec<(usize, IssueReason)> =
+            report.issues.iter().map(|i| (i.line, i.reason)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, IssueReason::MissingHeader),
+                (3, IssueReason::UrlWithoutExtinf),
+                (4, IssueReason::MissingName),
+                (6, IssueReason::MissingUrl),
+            ]
+        );
+        assert_eq!(report.issues[1].text, "http://example.com/orphan.m3u8");
     }
 
     #[test]
@@ -181,12 +415,70 @@ https://stream.example.com/cnn.m3u8
 #EXTINF:-1 tvg-name="NoId" group-title="Music",NoId
 https://stream.example.com/noid.m3u8
 "#;
-        let channels = parse_m3u(content);
+        let channels = parse_m3u(content).channels;
         assert_eq!(channels.len(), 2);
         assert_eq!(channels[0].tvg_id.as_deref(), Some("CNN.us"));
         assert!(channels[1].tvg_id.is_none());
     }
 
+    #[test]
+    fn parse_extended_attributes_and_directives() {
+        let content = r#"#EXTM3U
+#EXTINF:-1 tvg-id="BBC1.uk" tvg-chno="101" tvg-shift="-1" catchup="shift" catchup-days="7" catchup-source="?utc={utc}",BBC One
+#EXTGRP:UK
+#EXTVLCOPT:http-user-agent=Mozilla/5.0
+#EXTVLCOPT:http-referrer=https://example.com/
+#EXTVLCOPT:network-caching=1000
+https://stream.example.com/bbc1.m3u8
+#EXTINF:-1 radio="true" group-title="Radio",Jazz FM
+#EXTGRP:Ignored
+https://stream.example.com/jazz.mp3
+"#;
+        let channels = parse_m3u(content).channels;
+        assert_eq!(channels.len(), 2);
+
+        let bbc = &channels[0];
+        assert_eq!(bbc.group, "UK");
+        assert_eq!(
+            bbc.attributes,
+            M3uAttributes {
+                tvg_chno: Some("101".to_string()),
+                tvg_shift: Some("-1".to_string()),
+                catchup: Some("shift".to_string()),
+                catchup_days: Some(7),
+                catchup_source: Some("?utc={utc}".to_string()),
+                radio: false,
+                user_agent: Some("Mozilla/5.0".to_string()),
+                referrer: Some("https://example.com/".to_string()),
+            }
+        );
+
+        // group-title wins over #EXTGRP.
+        assert_eq!(channels[1].group, "Radio");
+        assert!(channels[1].attributes.radio);
+    }
+
+    #[test]
+    fn parse_guide_urls_from_header() {
+        let content = "\u{feff}#EXTM3U url-tvg=\"https://a.example/guide.xml.gz, https://b.example/epg.xml,ftp://x\"\n\
+                       #EXTINF:-1,CNN\nhttp://example.com/cnn.m3u8\n";
+        assert_eq!(
+            parse_guide_urls(content),
+            vec![
+                "https://a.example/guide.xml.gz".to_string(),
+                "https://b.example/epg.xml".to_string(),
+            ]
+        );
+
+        let alias = "#EXTM3U x-tvg-url=\"http://c.example/tv.xml\"\n";
+        assert_eq!(
+            parse_guide_urls(alias),
+            vec!["http://c.example/tv.xml".to_string()]
+        );
+        assert!(parse_guide_urls("#EXTM3U\n").is_empty());
+        assert!(parse_guide_urls("#EXTINF:-1 url-tvg=\"http://x\",X\n").is_empty());
+    }
+
     #[test]
     fn extract_display_name_from_extinf() {
         let line = r#"#EXTINF:-1 tvg-name="CNN",CNN International"#;
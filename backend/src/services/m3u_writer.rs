@@ -0,0 +1,293 @@
+use crate::models::Channel;
+use crate::services::variant_grouper;
+
+/// Incrementally builds M3U text, sanitising everything it writes.
+///
+/// Attribute values have double quotes replaced and control characters
+/// removed, so a channel name or group cannot break out of its
+/// `key="value"` pair or onto a new line. Every export path writes through
+/// this type instead of formatting `#EXTINF` lines itself.
+#[derive(Debug)]
+pub struct M3uWriter {
+    out: String,
+}
+
+impl M3uWriter {
+    /// Start a playlist with the `#EXTM3U` header, pointing players at the
+    /// XMLTV guide at `url_tvg` when given.
+    pub fn new(url_tvg: Option<&str>) -> Self {
+        let mut writer = Self {
+            out: String::from("#EXTM3U"),
+        };
+        if let Some(url) = url_tvg {
+            writer.attr("url-tvg", url);
+        }
+        writer.out.push('\n');
+        writer
+    }
+
+    /// Append `channel` as an `#EXTINF` entry streaming from `stream_url`,
+    /// followed by an `#EXTGRP` directive when it has a group and
+    /// `#EXTVLCOPT` options for its user agent and referrer.
+    ///
+    /// Entries whose URL is empty after sanitising are skipped, since a
+    /// blank URL line would shift every following entry.
+    pub fn channel(&mut self, channel: &Channel, stream_url: &str) {
+        let url = sanitize_url(stream_url);
+        if url.is_empty() {
+            return;
+        }
+
+        self.out.push_str("#EXTINF:-1");
+        if let Some(ref id) = channel.tvg_id {
+            self.attr("tvg-id", id);
+        }
+        self.attr("tvg-name", &channel.name);
+        let attrs = &channel.attributes;
+        if let Some(ref chno) = attrs.tvg_chno {
+            self.attr("tvg-chno", chno);
+        }
+        if let Some(ref shift) = attrs.tvg_shift {
+            self.attr("tvg-shift", shift);
+        }
+        self.attr("group-title", &channel.group);
+        if let Some(ref logo) = channel.logo_url {
+            self.attr("tvg-logo", logo);
+        }
+        if let Some(ref catchup) = attrs.catchup {
+            self.attr("catchup", catchup);
+        }
+        if let Some(days) = attrs.catchup_days {
+            self.attr("catchup-days", &days.to_string());
+        }
+        if let Some(ref source) = attrs.catchup_source {
+            self.attr("catchup-source", source);
+        }
+        if attrs.radio {
+            self.attr("radio", "true");
+        }
+        self.out.push(',');
+        self.out.push_str(&sanitize_title(&channel.name));
+        self.out.push('\n');
+        let group = sanitize_title(&channel.group);
+        if !group.is_empty() {
+            self.out.push_str("#EXTGRP:");
+            self.out.push_str(&group);
+            self.out.push('\n');
+        }
+        if let Some(ref agent) = attrs.user_agent {
+            self.out.push_str("#EXTVLCOPT:http-user-agent=");
+            self.out.push_str(&sanitize_title(agent));
+            self.out.push('\n');
+        }
+        if let Some(ref referrer) = attrs.referrer {
+            self.out.push_str("#EXTVLCOPT:http-referrer=");
+            self.out.push_str(&sanitize_title(referrer));
+            self.out.push('\n');
+        }
+        self.out.push_str(&url);
+        self.out.push('\n');
+    }
+
+    fn attr(&mut self, key: &str, value: &str) {
+        self.out.push(' ');
+        self.out.push_str(key);
+        self.out.push_str("=\"");
+        self.out.push_str(&escape_attr(value));
+        self.out.push('"');
+    }
+
+    /// The finished M3U text.
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Render channels as M3U text, emitting the `prefer`red variant's stream
+/// URL where a channel has one and linking the guide at `url_tvg`.
+///
+/// Channels are listed in [`export_order`].
+pub fn render(channels: &[Channel], prefer: Option<&str>, url_tvg: Option<&str>) -> String {
+    let mut writer = M3uWriter::new(url_tvg);
+    for ch in export_order(channels) {
+        writer.channel(ch, variant_grouper::preferred_stream_url(ch, prefer));
+    }
+    writer.finish()
+}
+
+/// `channels` in the order exports list them: by [`Channel::position`],
+/// then in playlist order.
+pub fn export_order(channels: &[Channel]) -> Vec<&Channel> {
+    let mut ordered: Vec<&Channel> = channels.iter().collect();
+    ordered.sort_by_key(|ch| ch.position.unwrap_or(u32::MAX));
+    ordered
+}
+
+/// Make `value` safe inside a double-quoted M3U attribute.
+///
+/// M3U has no escape syntax, so double quotes become single quotes.
+/// Line breaks and tabs become spaces and other control characters are
+/// dropped.
+pub fn escape_attr(value: &str) -> String {
+    value
+        .chars()
+        .filter_map(|c| match c {
+            '"' => Some('\''),
+            '\r' | '\n' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Make `value` safe as the display title after an `#EXTINF` comma.
+fn sanitize_title(value: &str) -> String {
+    value
+        .chars()
+        .filter_map(|c| match c {
+            '\r' | '\n' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Make `value` safe as a stream URL line: control characters are
+/// dropped and inner spaces percent-encoded.
+fn sanitize_url(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .replace(' ', "%20")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::channel::M3uAttributes;
+    use crate::services::m3u_parser;
+
+    #[test]
+    fn renders_channels() {
+        let m3u = render(
+            &[Channel {
+                tvg_id: Some("cnn.us".to_string()),
+                ..Channel::sample("cnn", "CNN", "News")
+            }],
+            None,
+            None,
+        );
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n\
+             #EXTINF:-1 tvg-id=\"cnn.us\" tvg-name=\"CNN\" group-title=\"News\",CNN\n\
+             #EXTGRP:News\n\
+             http://example.com/cnn.m3u8\n"
+        );
+    }
+
+    #[test]
+    fn renders_empty_playlist() {
+        assert_eq!(render(&[], Some("hd"), None), "#EXTM3U\n");
+    }
+
+    #[test]
+    fn follows_positions() {
+        let mut channels = vec![
+            Channel::sample("a", "A", "News"),
+            Channel::sample("b", "B", "News"),
+            Channel::sample("c", "C", "News"),
+        ];
+        channels[1].position = Some(0);
+        channels[2].position = Some(1);
+
+        let m3u = render(&channels, None, None);
+        let names: Vec<&str> = m3u
+            .lines()
+            .filter_map(|line| line.rsplit_once(',').map(|(_, name)| name))
+            .collect();
+        assert_eq!(names, ["B", "C", "A"]);
+    }
+
+    #[test]
+    fn escapes_quotes_and_control_characters() {
+        let mut ch = Channel {
+            tvg_id: Some("x.us".to_string()),
+            ..Channel::sample("x", "The \"Best\"\nChannel\u{7}", "News")
+        };
+        ch.group = "Movies\r\n#EXTINF:-1,Injected".to_string();
+        ch.stream_url = " http://example.com/my stream.m3u8\n".to_string();
+
+        let m3u = render(&[ch], None, None);
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n\
+             #EXTINF:-1 tvg-id=\"x.us\" tvg-name=\"The 'Best' Channel\" \
+             group-title=\"Movies  #EXTINF:-1,Injected\",The \"Best\" Channel\n\
+             #EXTGRP:Movies  #EXTINF:-1,Injected\n\
+             http://example.com/my%20stream.m3u8\n"
+        );
+        assert_eq!(m3u.lines().count(), 4);
+    }
+
+    #[test]
+    fn skips_channels_without_a_url() {
+        let mut ch = Channel::sample("x", "X", "News");
+        ch.stream_url = "\n".to_string();
+        assert_eq!(render(&[ch], None, None), "#EXTM3U\n");
+    }
+
+    #[test]
+    fn writes_guide_header_and_extended_attributes() {
+        let mut ch = Channel {
+            tvg_id: Some("bbc.us".to_string()),
+            ..Channel::sample("bbc", "BBC One", "News")
+        };
+        ch.attributes = M3uAttributes {
+            tvg_chno: Some("101".to_string()),
+            tvg_shift: Some("-1".to_string()),
+            catchup: Some("shift".to_string()),
+            catchup_days: Some(7),
+            ..Default::default()
+        };
+
+        let m3u = render(&[ch], None, Some("http://tv.local/api/epg/xmltv"));
+        assert_eq!(
+            m3u,
+            "#EXTM3U url-tvg=\"http://tv.local/api/epg/xmltv\"\n\
+             #EXTINF:-1 tvg-id=\"bbc.us\" tvg-name=\"BBC One\" tvg-chno=\"101\" \
+             tvg-shift=\"-1\" group-title=\"News\" catchup=\"shift\" \
+             catchup-days=\"7\",BBC One\n\
+             #EXTGRP:News\n\
+             http://example.com/bbc.m3u8\n"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_the_parser() {
+        let content = "#EXTM3U\n\
+            #EXTINF:-1 tvg-id=\"jazz.fm\" tvg-chno=\"7\" catchup=\"append\" \
+            catchup-source=\"?t={utc}\" radio=\"true\",Jazz FM\n\
+            #EXTGRP:Radio\n\
+            #EXTVLCOPT:http-user-agent=VLC/3.0\n\
+            #EXTVLCOPT:http-referrer=https://example.com/\n\
+            https://stream.example.com/jazz.mp3\n";
+        let parsed = m3u_parser::parse_m3u(content).channels;
+        let reparsed = m3u_parser::parse_m3u(&render(&parsed, None, None)).channels;
+
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed[0].group, "Radio");
+        assert_eq!(reparsed[0].attributes, parsed[0].attributes);
+        assert!(reparsed[0].attributes.radio);
+        assert_eq!(
+            reparsed[0].attributes.user_agent.as_deref(),
+            Some("VLC/3.0")
+        );
+    }
+}
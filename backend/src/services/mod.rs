@@ -1,4 +1,37 @@
+pub mod accounts;
+pub mod chain_networks;
+pub mod chain_playlist;
+pub mod chain_rpc;
+pub mod chain_watcher;
 pub mod channel_checker;
+pub mod dedupe;
+pub mod epg_aliases;
 pub mod epg_parser;
+pub mod events;
+pub mod fetch;
+pub mod hls_health;
+pub mod http_client;
+pub mod import_jobs;
+pub mod ipfs;
 pub mod iptv_org;
 pub mod m3u_parser;
+pub mod m3u_writer;
+pub mod playlist_export;
+pub mod playlist_registry;
+pub mod probe_schedule;
+pub mod search_index;
+pub mod signature;
+pub mod sources;
+#[cfg(feature = "sql")]
+pub mod sql_storage;
+pub mod storage;
+pub mod stream_headers;
+#[cfg(feature = "ffprobe")]
+pub mod stream_probe;
+pub mod subscan;
+pub mod token_gate;
+pub mod uptime;
+pub mod variant_grouper;
+pub mod watchdog;
+pub mod xmltv_writer;
+pub mod xtream;
@@ -0,0 +1,159 @@
+use crate::models::Channel;
+use crate::services::m3u_writer::{self, M3uWriter};
+use crate::services::{variant_grouper, xmltv_writer};
+
+/// Enigma2 service type for IPTV streams played through GStreamer.
+const ENIGMA2_STREAM_SERVICE: u32 = 4097;
+
+/// An Enigma2 userbouquet listing `channels` under the bouquet `name`.
+///
+/// Each group opens with a marker line, so receivers show the groups as
+/// sections. Stream URLs are percent-encoded where Enigma2 treats `:` as
+/// a field separator. Channels are listed in
+/// [`export_order`](m3u_writer::export_order).
+pub fn enigma2_bouquet(name: &str, channels: &[Channel], prefer: Option<&str>) -> String {
+    let mut out = format!("#NAME {}\n", single_line(name));
+    let mut group: Option<&str> = None;
+    let mut markers = 0;
+
+    for (i, ch) in m3u_writer::export_order(channels).into_iter().enumerate() {
+        if group != Some(ch.group.as_str()) && !ch.group.is_empty() {
+            markers += 1;
+            let title = single_line(&ch.group);
+            out.push_str(&format!(
+                "#SERVICE 1:64:{markers:X}:0:0:0:0:0:0:0::{title}\n#DESCRIPTION {title}\n"
+            ));
+        }
+        group = Some(&ch.group);
+
+        let url = variant_grouper::preferred_stream_url(ch, prefer);
+        let title = single_line(&ch.name);
+        out.push_str(&format!(
+            "#SERVICE {ENIGMA2_STREAM_SERVICE}:0:1:{:X}:0:0:0:0:0:0:{}:{title}\n#DESCRIPTION {title}\n",
+            i + 1,
+            enigma2_url(url)
+        ));
+    }
+    out
+}
+
+/// Percent-encode the characters Enigma2 service references reserve.
+fn enigma2_url(url: &str) -> String {
+    single_line(url)
+        .replace('%', "%25")
+        .replace(':', "%3a")
+        .replace(' ', "%20")
+}
+
+/// `value` on one line, with control characters removed.
+fn single_line(value: &str) -> String {
+    value
+        .chars()
+        .filter_map(|c| match c {
+            '\r' | '\n' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// An M3U for Kodi's PVR IPTV Simple Client, linking the XMLTV guide at
+/// `url_tvg`.
+///
+/// Every channel gets a `tvg-id` matching its entry in the guide from
+/// [`xmltv_writer::render`], and a `tvg-chno` numbering it in export order
+/// unless it has one, so the client maps guide data and channel numbers
+/// without manual setup.
+pub fn kodi_m3u(channels: &[Channel], prefer: Option<&str>, url_tvg: Option<&str>) -> String {
+    let mut writer = M3uWriter::new(url_tvg);
+    for (i, ch) in m3u_writer::export_order(channels).into_iter().enumerate() {
+        let mut ch = ch.clone();
+        ch.tvg_id = Some(xmltv_writer::guide_id(&ch).to_string());
+        ch.attributes
+            .tvg_chno
+            .get_or_insert_with(|| (i + 1).to_string());
+        writer.channel(&ch, variant_grouper::preferred_stream_url(&ch, prefer));
+    }
+    writer.finish()
+}
+
+/// `channels` as CSV, one row per channel in export order.
+///
+/// Columns: `number`, `name`, `group`, `stream_url`, `logo_url`, `tvg_id`,
+/// `is_live`.
+pub fn csv(channels: &[Channel], prefer: Option<&str>) -> String {
+    let mut out = String::from("number,name,group,stream_url,logo_url,tvg_id,is_live\n");
+    for (i, ch) in m3u_writer::export_order(channels).into_iter().enumerate() {
+        let fields = [
+            (i + 1).to_string(),
+            csv_field(&ch.name),
+            csv_field(&ch.group),
+            csv_field(variant_grouper::preferred_stream_url(ch, prefer)),
+            csv_field(ch.logo_url.as_deref().unwrap_or_default()),
+            csv_field(ch.tvg_id.as_deref().unwrap_or_default()),
+            ch.is_live.to_string(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote `value` for CSV when it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_enigma2_bouquet_with_group_markers() {
+        let channels = [
+            Channel::sample("a", "CNN", "News"),
+            Channel::sample("b", "BBC", "News"),
+            Channel::sample("c", "ESPN", "Sports"),
+        ];
+        assert_eq!(
+            enigma2_bouquet("IPTV", &channels, None),
+            "#NAME IPTV\n\
+             #SERVICE 1:64:1:0:0:0:0:0:0:0::News\n#DESCRIPTION News\n\
+             #SERVICE 4097:0:1:1:0:0:0:0:0:0:http%3a//example.com/a.m3u8:CNN\n#DESCRIPTION CNN\n\
+             #SERVICE 4097:0:1:2:0:0:0:0:0:0:http%3a//example.com/b.m3u8:BBC\n#DESCRIPTION BBC\n\
+             #SERVICE 1:64:2:0:0:0:0:0:0:0::Sports\n#DESCRIPTION Sports\n\
+             #SERVICE 4097:0:1:3:0:0:0:0:0:0:http%3a//example.com/c.m3u8:ESPN\n#DESCRIPTION ESPN\n"
+        );
+    }
+
+    #[test]
+    fn kodi_m3u_fills_guide_ids_and_numbers() {
+        let mut cnn = Channel::sample("a", "CNN", "News");
+        cnn.tvg_id = Some("CNN.us".to_string());
+        let mut bbc = Channel::sample("b", "BBC", "News");
+        bbc.attributes.tvg_chno = Some("101".to_string());
+
+        let m3u = kodi_m3u(&[cnn, bbc], None, Some("http://tv.local/guide.xml"));
+        let lines: Vec<&str> = m3u.lines().collect();
+        assert_eq!(lines[0], "#EXTM3U url-tvg=\"http://tv.local/guide.xml\"");
+        assert!(lines[1].contains("tvg-id=\"CNN.us\"") && lines[1].contains("tvg-chno=\"1\""));
+        assert!(lines[4].contains("tvg-id=\"b\"") && lines[4].contains("tvg-chno=\"101\""));
+    }
+
+    #[test]
+    fn csv_quotes_fields() {
+        let mut ch = Channel::sample("a", "News, \"Live\"", "News");
+        ch.tvg_id = Some("CNN.us".to_string());
+        assert_eq!(
+            csv(&[ch], None),
+            "number,name,group,stream_url,logo_url,tvg_id,is_live\n\
+             1,\"News, \"\"Live\"\"\",News,http://example.com/a.m3u8,,CNN.us,false\n"
+        );
+    }
+}
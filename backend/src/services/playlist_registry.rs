@@ -0,0 +1,250 @@
+use codec::{Decode, Encode};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::services::chain_rpc::{blake2_128_concat, decode_hex, storage_key, ChainRpc, RpcError};
+
+/// Name of the playlist-registry pallet in the runtime.
+const REGISTRY_PALLET: &str = "PlaylistRegistry";
+
+/// Runtime API function listing an account's playlists.
+const PLAYLISTS_OF: &str = "PlaylistRegistryApi_playlists_of";
+
+/// Playlist metadata as returned by `PlaylistRegistryApi::playlists_of`.
+#[derive(Debug, Decode)]
+struct PlaylistView {
+    // Always the account looked up.
+    #[allow(dead_code)]
+    owner: [u8; 32],
+    name: Vec<u8>,
+    source_url_hash: [u8; 32],
+    channel_count: u32,
+    created_at: u64,
+    updated_at: u64,
+    description: Vec<u8>,
+    artwork_hash: Option<[u8; 32]>,
+    visibility: Visibility,
+    tags: Vec<Vec<u8>>,
+}
+
+/// Whether a playlist is listed for discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+/// A channel listed on-chain for a playlist.
+#[derive(Debug, Encode, Decode)]
+struct ChannelEntry {
+    name_hash: [u8; 32],
+    stream_url_hash: [u8; 32],
+}
+
+/// A published version of a playlist's content.
+#[derive(Debug, Encode, Decode)]
+struct ContentVersion {
+    version: u32,
+    content_hash: [u8; 32],
+    published_at: u64,
+}
+
+/// A playlist registered in the playlist-registry pallet, as served by the API.
+///
+/// Hashes are `0x`-prefixed hex; block numbers are the pallet's.
+#[derive(Debug, Serialize)]
+pub struct RegistryPlaylist {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    /// SHA-256 hash of the source URL.
+    pub source_url_hash: String,
+    pub channel_count: u32,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub artwork_hash: Option<String>,
+    pub visibility: Visibility,
+    pub tags: Vec<String>,
+    /// Channels listed on-chain, as SHA-256 hashes of their name and
+    /// stream URL.
+    pub channels: Vec<Value>,
+    /// Published content versions, oldest first.
+    pub versions: Vec<Value>,
+}
+
+/// The playlists registered by the account with public key `owner`, oldest
+/// first, with their on-chain channels and content versions.
+pub async fn playlists_of(
+    rpc: &ChainRpc,
+    owner: &[u8; 32],
+) -> Result<Vec<RegistryPlaylist>, RpcError> {
+    let result = rpc.state_call(PLAYLISTS_OF, owner).await?;
+    let views = Vec::<(u32, PlaylistView)>::decode(&mut result.as_slice())
+        .map_err(|_| RpcError::Decode("registry playlists"))?;
+
+    // Channels and versions of every playlist, in one request.
+    let calls: Vec<_> = views
+        .iter()
+        .flat_map(|(id, _)| {
+            let by_id = [blake2_128_concat(&id.encode())];
+            ["Channels", "ContentVersions"].map(|item| {
+                let key = storage_key(REGISTRY_PALLET, item, &by_id);
+                (
+                    "state_getStorage",
+                    json!([format!("0x{}", hex::encode(key))]),
+                )
+            })
+        })
+        .collect();
+    let values = rpc.batch(&calls).await?;
+
+    views
+        .into_iter()
+        .zip(values.chunks(2))
+        .map(|((id, view), values)| {
+            let channels: Vec<ChannelEntry> = decode_list(&values[0], "registry channels")?;
+            let versions: Vec<ContentVersion> = decode_list(&values[1], "content versions")?;
+            Ok(RegistryPlaylist {
+                id,
+                name: String::from_utf8_lossy(&view.name).into_owned(),
+                description: String::from_utf8_lossy(&view.description).into_owned(),
+                source_url_hash: hex_hash(&view.source_url_hash),
+                channel_count: view.channel_count,
+                created_at: view.created_at,
+                updated_at: view.updated_at,
+                artwork_hash: view.artwork_hash.as_ref().map(hex_hash),
+                visibility: view.visibility,
+                tags: view
+                    .tags
+                    .iter()
+                    .map(|tag| String::from_utf8_lossy(tag).into_owned())
+                    .collect(),
+                channels: channels
+                    .iter()
+                    .map(|c| {
+                        json!({
+                            "name_hash": hex_hash(&c.name_hash),
+                            "stream_url_hash": hex_hash(&c.stream_url_hash)
+                        })
+                    })
+                    .collect(),
+                versions: versions
+                    .iter()
+                    .map(|v| {
+                        json!({
+                            "version": v.version,
+                            "content_hash": hex_hash(&v.content_hash),
+                            "published_at": v.published_at
+                        })
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// The latest published version of each playlist registered by `owner`,
+/// as `(playlist_id, version)`; unpublished playlists are left out.
+pub async fn latest_versions(
+    rpc: &ChainRpc,
+    owner: &[u8; 32],
+) -> Result<Vec<(u32, u32)>, RpcError> {
+    let by_owner = [blake2_128_concat(owner)];
+    let ids: Vec<u32> = rpc
+        .read(
+            &storage_key(REGISTRY_PALLET, "PlaylistMap", &by_owner),
+            "playlist ids",
+        )
+        .await?
+        .unwrap_or_default();
+
+    let calls: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            let by_id = [blake2_128_concat(&id.encode())];
+            let key = storage_key(REGISTRY_PALLET, "ContentVersions", &by_id);
+            (
+                "state_getStorage",
+                json!([format!("0x{}", hex::encode(key))]),
+            )
+        })
+        .collect();
+    let values = rpc.batch(&calls).await?;
+
+    let mut latest = Vec::new();
+    for (id, value) in ids.into_iter().zip(&values) {
+        let versions: Vec<ContentVersion> = decode_list(value, "content versions")?;
+        if let Some(version) = versions.last() {
+            latest.push((id, version.version));
+        }
+    }
+    Ok(latest)
+}
+
+/// Decode a list stored with `ValueQuery`, which reads as empty when unset.
+fn decode_list<T: Decode>(value: &Value, what: &'static str) -> Result<Vec<T>, RpcError> {
+    let Some(hex_value) = value.as_str() else {
+        return Ok(Vec::new());
+    };
+    Vec::<T>::decode(&mut decode_hex(hex_value)?.as_slice()).map_err(|_| RpcError::Decode(what))
+}
+
+fn hex_hash(hash: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_stored_lists() {
+        let versions = vec![ContentVersion {
+            version: 1,
+            content_hash: [3; 32],
+            published_at: 42,
+        }];
+        let stored = json!(format!("0x{}", hex::encode(versions.encode())));
+        let decoded: Vec<ContentVersion> = decode_list(&stored, "versions").unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].published_at, 42);
+
+        let unset: Vec<ChannelEntry> = decode_list(&Value::Null, "channels").unwrap();
+        assert!(unset.is_empty());
+        assert!(decode_list::<ChannelEntry>(&json!("0x0401"), "channels").is_err());
+    }
+
+    #[test]
+    fn decodes_playlist_views() {
+        let encoded = (
+            [1u8; 32],
+            b"News".to_vec(),
+            [2u8; 32],
+            12u32,
+            5u64,
+            9u64,
+            b"Daily news".to_vec(),
+            None::<[u8; 32]>,
+            Visibility::Public,
+            vec![b"news".to_vec()],
+        )
+            .encode();
+        let listed = vec![(7u32, encoded)];
+        // A tuple list encodes as its length, then each element in turn.
+        let mut bytes = codec::Compact(listed.len() as u32).encode();
+        for (id, view) in &listed {
+            bytes.extend(id.encode());
+            bytes.extend(view);
+        }
+
+        let views = Vec::<(u32, PlaylistView)>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(views.len(), 1);
+        let (id, view) = &views[0];
+        assert_eq!(*id, 7);
+        assert_eq!(view.name, b"News");
+        assert_eq!(view.channel_count, 12);
+        assert_eq!(view.visibility, Visibility::Public);
+        assert_eq!(view.tags, vec![b"news".to_vec()]);
+    }
+}
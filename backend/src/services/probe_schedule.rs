@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::{Channel, HealthStatus};
+
+/// How often a channel is probed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntervalClass {
+    /// Probed on the global `PROBE_INTERVAL_MINS` schedule.
+    Normal,
+    /// Pinned by an operator to the high-frequency `PROBE_PINNED_INTERVAL_SECS` schedule.
+    Pinned,
+}
+
+/// Scheduling state of a single channel.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelSchedule {
+    /// When the channel is next due for a probe.
+    pub next_check: DateTime<Utc>,
+    /// When the channel was last probed, if ever.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<DateTime<Utc>>,
+    /// Number of failed probes since the last success.
+    pub consecutive_failures: u32,
+}
+
+/// Per-channel probe schedule used by the background checker.
+///
+/// Channels are keyed by their playlist ID. Pins are kept separately so they
+/// survive playlist reloads. Quarantined channels are never due; they are
+/// the channels whose health is [`HealthStatus::Quarantined`].
+#[derive(Debug)]
+pub struct ProbeSchedule {
+    /// Interval for channels in [`IntervalClass::Normal`].
+    normal_interval: Duration,
+    /// Interval for channels in [`IntervalClass::Pinned`].
+    pinned_interval: Duration,
+    /// Schedule entries keyed by channel ID.
+    entries: HashMap<String, ChannelSchedule>,
+    /// IDs of channels pinned to the high-frequency schedule.
+    pinned: HashSet<String>,
+    /// IDs of quarantined channels, which are not probed.
+    quarantined: HashSet<String>,
+}
+
+impl ProbeSchedule {
+    /// Create an empty schedule with the given intervals.
+    pub fn new(normal_interval: Duration, pinned_interval: Duration) -> Self {
+        Self {
+            normal_interval,
+            pinned_interval,
+            entries: HashMap::new(),
+            pinned: HashSet::new(),
+            quarantined: HashSet::new(),
+        }
+    }
+
+    /// Interval for channels in [`IntervalClass::Normal`].
+    pub fn normal_interval(&self) -> Duration {
+        self.normal_interval
+    }
+
+    /// Interval for channels in [`IntervalClass::Pinned`].
+    pub fn pinned_interval(&self) -> Duration {
+        self.pinned_interval
+    }
+
+    /// The interval class of `channel_id`.
+    pub fn class_of(&self, channel_id: &str) -> IntervalClass {
+        if self.pinned.contains(channel_id) {
+            IntervalClass::Pinned
+        } else {
+            IntervalClass::Normal
+        }
+    }
+
+    fn interval_for(&self, channel_id: &str) -> Duration {
+        match self.class_of(channel_id) {
+            IntervalClass::Normal => self.normal_interval,
+            IntervalClass::Pinned => self.pinned_interval,
+        }
+    }
+
+    /// The schedule entry of `channel_id`, if it is in the playlist.
+    pub fn get(&self, channel_id: &str) -> Option<&ChannelSchedule> {
+        self.entries.get(channel_id)
+    }
+
+    /// Align the schedule with the current playlist.
+    ///
+    /// New channels become due at `now`; entries for removed channels are
+    /// dropped. Quarantine follows the channels' health.
+    pub fn sync(&mut self, channels: &[Channel], now: DateTime<Utc>) {
+        self.quarantined = channels
+            .iter()
+            .filter(|c| c.health.status == Some(HealthStatus::Quarantined))
+            .map(|c| c.id.clone())
+            .collect();
+        let ids: HashSet<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+        self.entries.retain(|id, _| ids.contains(id.as_str()));
+        for id in ids {
+            self.entries
+                .entry(id.to_string())
+                .or_insert_with(|| ChannelSchedule {
+                    next_check: now,
+                    last_checked: None,
+                    consecutive_failures: 0,
+                });
+        }
+    }
+
+    /// Make `channel_id` due at `now`, e.g. after its stream URL changed.
+    pub fn mark_due(&mut self, channel_id: &str, now: DateTime<Utc>) {
+        if let Some(entry) = self.entries.get_mut(channel_id) {
+            entry.next_check = now;
+        }
+    }
+
+    /// Make every channel due at `now`.
+    pub fn mark_all_due(&mut self, now: DateTime<Utc>) {
+        for entry in self.entries.values_mut() {
+            entry.next_check = now;
+        }
+    }
+
+    /// IDs of channels whose next check is at or before `now`, other than
+    /// quarantined ones.
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<String> {
+        self.scheduled()
+            .filter(|(_, e)| e.next_check <= now)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Earliest upcoming check, if any channel is scheduled.
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.scheduled().map(|(_, e)| e.next_check).min()
+    }
+
+    /// Entries of the channels that are not quarantined.
+    fn scheduled(&self) -> impl Iterator<Item = (&String, &ChannelSchedule)> {
+        self.entries
+            .iter()
+            .filter(|(id, _)| !self.quarantined.contains(id.as_str()))
+    }
+
+    /// Whether `channel_id` is quarantined.
+    pub fn is_quarantined(&self, channel_id: &str) -> bool {
+        self.quarantined.contains(channel_id)
+    }
+
+    /// Quarantine a channel, or release it to be probed at `now`.
+    ///
+    /// The channel's health must say the same, or the next
+    /// [`sync`](Self::sync) undoes this.
+    pub fn set_quarantined(&mut self, channel_id: &str, quarantined: bool, now: DateTime<Utc>) {
+        if quarantined {
+            self.quarantined.insert(channel_id.to_string());
+        } else if self.quarantined.remove(channel_id) {
+            if let Some(entry) = self.entries.get_mut(channel_id) {
+                entry.consecutive_failures = 0;
+            }
+            self.mark_due(channel_id, now);
+        }
+    }
+
+    /// Record a probe result and schedule the channel's next check.
+    pub fn record(&mut self, channel_id: &str, is_live: bool, now: DateTime<Utc>) {
+        let interval = self.interval_for(channel_id);
+        if let Some(entry) = self.entries.get_mut(channel_id) {
+            entry.last_checked = Some(now);
+            entry.consecutive_failures = if is_live {
+                0
+            } else {
+                entry.consecutive_failures.saturating_add(1)
+            };
+            entry.next_check = now + chrono::Duration::from_std(interval).unwrap_or_default();
+        }
+    }
+
+    /// IDs of pinned channels, sorted.
+    pub fn pinned_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.pinned.iter().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Pin or unpin a channel.
+    ///
+    /// Pinning pulls the next check forward to at most one pinned interval
+    /// from `now`; unpinning leaves the current next check in place.
+    pub fn set_pinned(&mut self, channel_id: &str, pinned: bool, now: DateTime<Utc>) {
+        if !pinned {
+            self.pinned.remove(channel_id);
+            return;
+        }
+        self.pinned.insert(channel_id.to_string());
+        let soonest = now + chrono::Duration::from_std(self.pinned_interval).unwrap_or_default();
+        if let Some(entry) = self.entries.get_mut(channel_id) {
+            entry.next_check = entry.next_check.min(soonest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule() -> ProbeSchedule {
+        ProbeSchedule::new(Duration::from_secs(600), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn new_channels_are_due_immediately() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 12, 0, 0).unwrap();
+        let mut s = schedule();
+        s.sync(
+            &[
+                Channel::sample("a", "a", "Test"),
+                Channel::sample("b", "b", "Test"),
+            ],
+            now,
+        );
+
+        let mut due = s.due(now);
+        due.sort();
+        assert_eq!(due, vec!["a".to_string(), "b".to_string()]);
+
+        // Removed channels are dropped on the next sync.
+        s.sync(&[Channel::sample("a", "a", "Test")], now);
+        assert!(s.get("b").is_none());
+    }
+
+    #[test]
+    fn record_tracks_failures_and_reschedules() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 12, 0, 0).unwrap();
+        let mut s = schedule();
+        s.sync(&[Channel::sample("a", "a", "Test")], now);
+
+        s.record("a", false, now);
+        s.record("a", false, now);
+        let entry = s.get("a").unwrap();
+        assert_eq!(entry.consecutive_failures, 2);
+        assert_eq!(entry.next_check, now + chrono::Duration::seconds(600));
+        assert!(s.due(now).is_empty());
+
+        s.record("a", true, now);
+        assert_eq!(s.get("a").unwrap().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn pinned_channels_use_short_interval() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 12, 0, 0).unwrap();
+        let mut s = schedule();
+        s.sync(
+            &[
+                Channel::sample("a", "a", "Test"),
+                Channel::sample("b", "b", "Test"),
+            ],
+            now,
+        );
+        s.record("a", true, now);
+        s.record("b", true, now);
+
+        s.set_pinned("a", true, now);
+        assert_eq!(s.class_of("a"), IntervalClass::Pinned);
+        assert_eq!(s.next_due(), Some(now + chrono::Duration::seconds(60)));
+
+        s.record("a", true, now);
+        assert_eq!(
+            s.get("a").unwrap().next_check,
+            now + chrono::Duration::seconds(60)
+        );
+
+        // Pins survive the channel leaving and rejoining the playlist.
+        s.sync(&[Channel::sample("b", "b", "Test")], now);
+        s.sync(
+            &[
+                Channel::sample("a", "a", "Test"),
+                Channel::sample("b", "b", "Test"),
+            ],
+            now,
+        );
+        assert_eq!(s.class_of("a"), IntervalClass::Pinned);
+
+        s.set_pinned("a", false, now);
+        assert_eq!(s.class_of("a"), IntervalClass::Normal);
+    }
+
+    #[test]
+    fn quarantined_channels_are_never_due() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 12, 0, 0).unwrap();
+        let mut s = schedule();
+        let mut gone = Channel::sample("a", "a", "Test");
+        gone.health.status = Some(HealthStatus::Quarantined);
+        s.sync(&[gone.clone(), Channel::sample("b", "b", "Test")], now);
+        assert!(s.is_quarantined("a"));
+        assert_eq!(s.due(now), vec!["b".to_string()]);
+
+        // Released channels are probed straight away.
+        s.record("b", true, now);
+        s.set_quarantined("a", false, now);
+        assert_eq!(s.due(now), vec!["a".to_string()]);
+
+        // Until the health says otherwise, a sync quarantines it again.
+        s.sync(&[gone, Channel::sample("b", "b", "Test")], now);
+        assert!(s.due(now).is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::models::epg::EpgSchedule;
+use crate::models::Channel;
+
+/// Split text into lowercase alphanumeric tokens, without duplicates.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .filter(|t| seen.insert(t.clone()))
+        .collect()
+}
+
+/// In-memory inverted index from tokens to document keys.
+///
+/// Tokens are kept sorted so each query term matches as a prefix with a
+/// single range scan. Documents can be replaced or removed individually.
+#[derive(Debug)]
+pub struct TextIndex<K> {
+    /// Token -> documents containing it.
+    postings: BTreeMap<String, HashSet<K>>,
+    /// Document -> its tokens, for removal and change detection.
+    docs: HashMap<K, Vec<String>>,
+}
+
+impl<K> Default for TextIndex<K> {
+    fn default() -> Self {
+        Self {
+            postings: BTreeMap::new(),
+            docs: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> TextIndex<K> {
+    /// Index `text` under `key`, replacing any previous text for that key.
+    pub fn insert(&mut self, key: K, text: &str) {
+        let tokens = tokenize(text);
+        if self.docs.get(&key) == Some(&tokens) {
+            return;
+        }
+        self.remove(&key);
+        for token in &tokens {
+            self.postings
+                .entry(token.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        self.docs.insert(key, tokens);
+    }
+
+    /// Remove `key` from the index.
+    pub fn remove(&mut self, key: &K) {
+        let Some(tokens) = self.docs.remove(key) else {
+            return;
+        };
+        for token in tokens {
+            if let Some(keys) = self.postings.get_mut(&token) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Documents matching every term of `query`, each term as a token prefix.
+    ///
+    /// An empty query matches nothing.
+    pub fn search(&self, query: &str) -> HashSet<K> {
+        let mut result: Option<HashSet<K>> = None;
+        for term in tokenize(query) {
+            let mut matches = HashSet::new();
+            for (_, keys) in self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(token, _)| token.starts_with(&term))
+            {
+                matches.extend(keys.iter().cloned());
+            }
+            let narrowed = match result {
+                Some(prev) => prev.intersection(&matches).cloned().collect(),
+                None => matches,
+            };
+            if narrowed.is_empty() {
+                return narrowed;
+            }
+            result = Some(narrowed);
+        }
+        result.unwrap_or_default()
+    }
+}
+
+/// Key of an indexed programme: (EPG cache key, programme ID).
+pub type ProgrammeKey = (String, String);
+
+/// Full-text indexes over playlist channels and cached EPG programmes.
+///
+/// Channels are indexed by name, group, and `tvg_id`; programmes by title
+/// and category. Both are updated incrementally: only changed documents
+/// are re-tokenized.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    channels: TextIndex<String>,
+    programmes: TextIndex<ProgrammeKey>,
+    /// EPG cache key -> programme keys indexed for it.
+    programmes_by_schedule: HashMap<String, Vec<ProgrammeKey>>,
+}
+
+impl SearchIndex {
+    /// Create empty indexes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the channel index in line with `channels`.
+    pub fn sync_channels(&mut self, channels: &[Channel]) {
+        let current: HashSet<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+        let stale: Vec<String> = self
+            .channels
+            .docs
+            .keys()
+            .filter(|id| !current.contains(id.as_str()))
+            .cloned()
+            .collect();
+        for id in &stale {
+            self.channels.remove(id);
+        }
+
+        for ch in channels {
+            let text = format!(
+                "{} {} {}",
+                ch.name,
+                ch.group,
+                ch.tvg_id.as_deref().unwrap_or_default()
+            );
+            self.channels.insert(ch.id.clone(), &text);
+        }
+    }
+
+    /// Index the programmes of `schedule`, cached under `key`, replacing any
+    /// programmes previously indexed for that key.
+    pub fn index_schedule(&mut self, key: &str, schedule: &EpgSchedule) {
+        let keys: Vec<ProgrammeKey> = schedule
+            .programs
+            .iter()
+            .map(|p| (key.to_string(), p.id.clone()))
+            .collect();
+
+        if let Some(previous) = self.programmes_by_schedule.get(key) {
+            let kept: HashSet<&ProgrammeKey> = keys.iter().collect();
+            for old in previous.iter().filter(|k| !kept.contains(k)) {
+                self.programmes.remove(old);
+            }
+        }
+
+        for (pk, program) in keys.iter().zip(&schedule.programs) {
+            let text = format!(
+                "{} {}",
+                program.title,
+                program.category.as_deref().unwrap_or_default()
+            );
+            self.programmes.insert(pk.clone(), &text);
+        }
+        self.programmes_by_schedule.insert(key.to_string(), keys);
+    }
+
+    /// IDs of channels matching `query`.
+    pub fn search_channels(&self, query: &str) -> HashSet<String> {
+        self.channels.search(query)
+    }
+
+    /// Keys of programmes matching `query`.
+    pub fn search_programmes(&self, query: &str) -> HashSet<ProgrammeKey> {
+        self.programmes.search(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::epg::EpgProgram;
+    use chrono::{TimeZone, Utc};
+
+    fn make_program(id: &str, title: &str) -> EpgProgram {
+        EpgProgram {
+            id: id.to_string(),
+            channel_id: "CNN.us".to_string(),
+            title: title.to_string(),
+            description: None,
+            start: Utc.with_ymd_and_hms(2026, 2, 11, 10, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2026, 2, 11, 11, 0, 0).unwrap(),
+            category: Some("News".to_string()),
+            icon_url: None,
+        }
+    }
+
+    #[test]
+    fn tokenize_lowercases_and_dedups() {
+        assert_eq!(
+            tokenize("BBC One HD - bbc (UK)"),
+            vec!["bbc", "one", "hd", "uk"]
+        );
+    }
+
+    #[test]
+    fn search_matches_all_terms_as_prefixes() {
+        let mut index = SearchIndex::new();
+        index.sync_channels(&[
+            Channel::sample("1", "BBC One", "UK"),
+            Channel::sample("2", "BBC News", "News"),
+            Channel::sample("3", "CNN", "News"),
+        ]);
+
+        let hits = index.search_channels("bbc");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            index.search_channels("bb new"),
+            HashSet::from(["2".to_string()])
+        );
+        assert!(index.search_channels("bbc sport").is_empty());
+        assert!(index.search_channels("  ").is_empty());
+    }
+
+    #[test]
+    fn sync_channels_is_incremental() {
+        let mut index = SearchIndex::new();
+        index.sync_channels(&[
+            Channel::sample("1", "Alpha", "A"),
+            Channel::sample("2", "Beta", "B"),
+        ]);
+        index.sync_channels(&[Channel::sample("2", "Gamma", "B")]);
+
+        assert!(index.search_channels("alpha").is_empty());
+        assert!(index.search_channels("beta").is_empty());
+        assert_eq!(
+            index.search_channels("gam"),
+            HashSet::from(["2".to_string()])
+        );
+    }
+
+    #[test]
+    fn index_schedule_replaces_previous_programmes() {
+        let mut index = SearchIndex::new();
+        let schedule = |programs| EpgSchedule {
+            channel_id: "CNN.us".to_string(),
+            programs,
+        };
+
+        index.index_schedule(
+            "CNN.us",
+            &schedule(vec![make_program("p1", "Morning Report")]),
+        );
+        assert_eq!(index.search_programmes("morning").len(), 1);
+
+        index.index_schedule(
+            "CNN.us",
+            &schedule(vec![make_program("p2", "Evening Report")]),
+        );
+        assert!(index.search_programmes("morning").is_empty());
+        assert_eq!(
+            index.search_programmes("report news"),
+            HashSet::from([("CNN.us".to_string(), "p2".to_string())])
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use blake2::{Blake2b512, Digest};
+use thiserror::Error;
+
+/// Prefix hashed into SS58 address checksums.
+const SS58_CHECKSUM_PREFIX: &[u8] = b"SS58PRE";
+
+/// Signing context Substrate uses for sr25519 signatures.
+const SR25519_CONTEXT: &[u8] = b"substrate";
+
+/// Errors that can occur while checking a signature by a Substrate account.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignatureError {
+    /// Not a valid SS58 address of a 32-byte account.
+    #[error("Invalid SS58 address")]
+    InvalidAddress,
+    /// Not 64 bytes of hex.
+    #[error("Signature must be 64 bytes of hex")]
+    MalformedSignature,
+    /// Neither an sr25519 nor an ed25519 signature by the address.
+    #[error("Signature does not match the address")]
+    Mismatch,
+}
+
+/// The 32-byte public key of an SS58 `address`, with its checksum checked.
+///
+/// Any network prefix is accepted, so the same key yields the same account
+/// on every network.
+pub fn decode_ss58(address: &str) -> Result<[u8; 32], SignatureError> {
+    decode(address).map(|(_, key)| key)
+}
+
+/// The network prefix of an SS58 `address`, with its checksum checked.
+pub fn ss58_prefix(address: &str) -> Result<u16, SignatureError> {
+    decode(address).map(|(prefix, _)| prefix)
+}
+
+/// The network prefix and public key of an SS58 `address`.
+fn decode(address: &str) -> Result<(u16, [u8; 32]), SignatureError> {
+    let data = bs58::decode(address.trim())
+        .into_vec()
+        .map_err(|_| SignatureError::InvalidAddress)?;
+    let (prefix, prefix_len) = match *data.as_slice() {
+        [first @ 0..=63, ..] => (u16::from(first), 1),
+        // Two-byte prefixes pack 14 bits across both bytes.
+        [first @ 64..=127, second, ..] => {
+            let lower = (first & 0b0011_1111) << 2 | second >> 6;
+            let upper = second & 0b0011_1111;
+            (u16::from(lower) | u16::from(upper) << 8, 2)
+        }
+        _ => return Err(SignatureError::InvalidAddress),
+    };
+    if data.len() != prefix_len + 32 + 2 {
+        return Err(SignatureError::InvalidAddress);
+    }
+
+    let (body, checksum) = data.split_at(data.len() - 2);
+    let hash = Blake2b512::new()
+        .chain_update(SS58_CHECKSUM_PREFIX)
+        .chain_update(body)
+        .finalize();
+    if hash[..2] != *checksum {
+        return Err(SignatureError::InvalidAddress);
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&body[prefix_len..]);
+    Ok((prefix, key))
+}
+
+/// Check that `signature` (hex, with or without `0x`) is `address`
+/// signing `message`.
+///
+/// Both sr25519 and ed25519 are accepted. Wallet extensions wrap raw
+/// messages in `<Bytes>…</Bytes>` before signing, so that form is accepted
+/// too.
+pub fn verify(address: &str, message: &[u8], signature: &str) -> Result<(), SignatureError> {
+    let public = decode_ss58(address)?;
+    let signature = signature.trim();
+    let signature: [u8; 64] = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SignatureError::MalformedSignature)?;
+
+    let wrapped = [b"<Bytes>".as_slice(), message, b"</Bytes>"].concat();
+    let matches = [message, wrapped.as_slice()].into_iter().any(|message| {
+        verify_sr25519(&public, message, &signature) || verify_ed25519(&public, message, &signature)
+    });
+    if matches {
+        Ok(())
+    } else {
+        Err(SignatureError::Mismatch)
+    }
+}
+
+fn verify_sr25519(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let (Ok(key), Ok(signature)) = (
+        schnorrkel::PublicKey::from_bytes(public),
+        schnorrkel::Signature::from_bytes(signature),
+    ) else {
+        return false;
+    };
+    key.verify_simple(SR25519_CONTEXT, message, &signature)
+        .is_ok()
+}
+
+fn verify_ed25519(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let Ok(key) = ed25519_dalek::VerifyingKey::from_bytes(public) else {
+        return false;
+    };
+    key.verify_strict(message, &ed25519_dalek::Signature::from_bytes(signature))
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Well-known development account `//Alice`.
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    /// SS58 address of `public` on the generic Substrate network (prefix 42).
+    fn encode_ss58(public: &[u8; 32]) -> String {
+        let mut data = vec![42u8];
+        data.extend_from_slice(public);
+        let hash = Blake2b512::new()
+            .chain_update(SS58_CHECKSUM_PREFIX)
+            .chain_update(&data)
+            .finalize();
+        data.extend_from_slice(&hash[..2]);
+        bs58::encode(data).into_string()
+    }
+
+    #[test]
+    fn decodes_ss58_addresses() {
+        assert_eq!(
+            hex::encode(decode_ss58(ALICE).unwrap()),
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        );
+        let mut corrupted = ALICE.to_string();
+        corrupted.replace_range(10..11, "x");
+        assert_eq!(
+            decode_ss58(&corrupted).unwrap_err(),
+            SignatureError::InvalidAddress
+        );
+        assert!(decode_ss58("not an address").is_err());
+    }
+
+    #[test]
+    fn reads_network_prefixes() {
+        assert_eq!(ss58_prefix(ALICE), Ok(42));
+        // `//Alice` on Polkadot and Kusama.
+        let polkadot = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+        let kusama = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+        assert_eq!(ss58_prefix(polkadot), Ok(0));
+        assert_eq!(ss58_prefix(kusama), Ok(2));
+        assert_eq!(decode_ss58(polkadot), decode_ss58(ALICE));
+        assert!(ss58_prefix("not an address").is_err());
+    }
+
+    #[test]
+    fn verifies_sr25519_and_ed25519_signatures() {
+        let message = b"Sign in to IPTV";
+
+        let keypair = schnorrkel::Keypair::generate();
+        let address = encode_ss58(&keypair.public.to_bytes());
+        let signature = keypair.sign_simple(SR25519_CONTEXT, b"<Bytes>Sign in to IPTV</Bytes>");
+        let signature = format!("0x{}", hex::encode(signature.to_bytes()));
+        assert_eq!(verify(&address, message, &signature), Ok(()));
+        assert_eq!(
+            verify(&address, b"something else", &signature),
+            Err(SignatureError::Mismatch)
+        );
+
+        let signing = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let address = encode_ss58(signing.verifying_key().as_bytes());
+        let signature = ed25519_dalek::Signer::sign(&signing, message);
+        assert_eq!(
+            verify(&address, message, &hex::encode(signature.to_bytes())),
+            Ok(())
+        );
+        assert_eq!(
+            verify(&address, message, "0x1234"),
+            Err(SignatureError::MalformedSignature)
+        );
+    }
+}
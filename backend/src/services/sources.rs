@@ -0,0 +1,607 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{info, warn};
+
+use crate::models::{AppState, Channel};
+use crate::services::events::ServerEvent;
+use crate::services::{fetch, m3u_parser, variant_grouper, watchdog};
+
+/// A remote M3U playlist merged into the default playlist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct M3uSource {
+    /// HTTP(S) URL of the M3U playlist.
+    pub url: String,
+    /// Prepended to each channel's group as `"<prefix> / <group>"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_prefix: Option<String>,
+}
+
+/// Which channel wins when several sources carry the same stream URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// The earliest source in the list wins.
+    #[default]
+    First,
+    /// The latest source in the list wins, e.g. for a list of overrides.
+    Last,
+}
+
+impl MergePolicy {
+    /// Parse `first` or `last`, defaulting to [`MergePolicy::First`].
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value {
+            Some("last") => Self::Last,
+            _ => Self::First,
+        }
+    }
+}
+
+/// The sources the default playlist is built from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceConfig {
+    /// Sources in priority order.
+    pub sources: Vec<M3uSource>,
+    /// How stream URL conflicts between sources are resolved.
+    #[serde(default)]
+    pub policy: MergePolicy,
+}
+
+/// Outcome of the last fetch of one source.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceStatus {
+    /// The source's URL.
+    pub url: String,
+    /// Channels parsed from the source; 0 when the fetch failed.
+    pub channels: usize,
+    /// Why the fetch failed, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the fetch finished.
+    pub fetched_at: DateTime<Utc>,
+    /// Whether the server answered `304 Not Modified`, so the channels
+    /// of the previous fetch were reused.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_modified: bool,
+}
+
+/// The parsed content of a source and the validators it was served with,
+/// kept so an unchanged source is neither downloaded nor parsed again.
+#[derive(Debug, Clone)]
+pub struct FetchedSource {
+    /// `ETag` and `Last-Modified` of the response.
+    pub validators: fetch::Validators,
+    /// Channels parsed from the source, with variants grouped.
+    pub channels: Vec<Channel>,
+    /// Guide URLs from the source's `url-tvg` header.
+    pub guides: Vec<String>,
+}
+
+/// Which playlist channels came from the sources, so a refresh can tell
+/// upstream changes from manual ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceTracking {
+    /// IDs of the channels the last load took from the sources.
+    #[serde(default)]
+    pub loaded: HashSet<String>,
+    /// IDs of source channels edited through the API; refreshes leave
+    /// them as they are.
+    #[serde(default)]
+    pub edited: HashSet<String>,
+}
+
+/// What a load changed in the default playlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RefreshDiff {
+    /// Channels new in the sources.
+    pub added: usize,
+    /// Source channels whose name, group, logo, EPG ID, variants, or
+    /// extended M3U attributes changed.
+    pub updated: usize,
+    /// Channels dropped because they left the sources.
+    pub removed: usize,
+    /// Source channels unchanged since the last load.
+    pub unchanged: usize,
+    /// Edited channels and channels not from a source, kept as they are.
+    pub kept_manual: usize,
+    /// Source channels not re-added because they were deleted from the playlist.
+    pub skipped_deleted: usize,
+}
+
+/// Configured sources and the result of their last load.
+#[derive(Debug, Default)]
+pub struct SourceSet {
+    /// The current source list and merge policy.
+    pub config: SourceConfig,
+    /// Whether `config` was set through the API rather than from
+    /// `M3U_SOURCE_URL`; only then is it persisted.
+    pub from_api: bool,
+    /// Source and edited channel IDs, carried from load to load.
+    pub tracking: SourceTracking,
+    /// Per-source results of the last load, in source order.
+    pub last_load: Vec<SourceStatus>,
+    /// What the last successful load changed, and when it ran.
+    pub last_refresh: Option<(DateTime<Utc>, RefreshDiff)>,
+    /// Last fetch of each source served with validators, by URL.
+    pub fetched: HashMap<String, FetchedSource>,
+}
+
+/// Parse a comma-separated `M3U_SOURCE_URL` value.
+///
+/// Each entry is a URL, or `prefix|url` to prefix that source's groups.
+/// Blank entries are skipped.
+pub fn parse_source_list(value: &str) -> Vec<M3uSource> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('|') {
+            Some((prefix, url)) => M3uSource {
+                url: url.trim().to_string(),
+                group_prefix: Some(prefix.trim().to_string()).filter(|p| !p.is_empty()),
+            },
+            None => M3uSource {
+                url: entry.to_string(),
+                group_prefix: None,
+            },
+        })
+        .collect()
+}
+
+/// Merge per-source channel lists, in source order, into one list.
+///
+/// Groups are prefixed with each source's `group_prefix`. Channels sharing
+/// a stream URL are resolved by `policy`; the winner takes the slot of the
+/// first occurrence.
+pub fn merge_sources(lists: Vec<(M3uSource, Vec<Channel>)>, policy: MergePolicy) -> Vec<Channel> {
+    let mut merged: Vec<Channel> = Vec::new();
+    let mut slot_of: HashMap<String, usize> = HashMap::new();
+
+    for (source, channels) in lists {
+        for mut ch in channels {
+            if let Some(ref prefix) = source.group_prefix {
+                ch.group = if ch.group.is_empty() {
+                    prefix.clone()
+                } else {
+                    format!("{prefix} / {}", ch.group)
+                };
+            }
+            match slot_of.get(&ch.stream_url) {
+                Some(&slot) if policy == MergePolicy::Last => merged[slot] = ch,
+                Some(_) => {}
+                None => {
+                    slot_of.insert(ch.stream_url.clone(), merged.len());
+                    merged.push(ch);
+                }
+            }
+        }
+    }
+
+    merged
+}
+
+fn same_metadata(a: &Channel, b: &Channel) -> bool {
+    a.name == b.name
+        && a.group == b.group
+        && a.logo_url == b.logo_url
+        && a.tvg_id == b.tvg_id
+        && a.variants == b.variants
+        && a.attributes == b.attributes
+}
+
+/// Apply freshly `fetched` source channels to the `current` playlist.
+///
+/// Channels keep their liveness, health score, and position. Source
+/// channels take the upstream metadata unless they were edited, and are
+/// dropped once they leave the sources. Channels that never came from a
+/// source (manual additions, imports) are kept, and source channels
+/// deleted from the playlist are not re-added. New channels are appended
+/// in source order.
+pub fn apply_refresh(
+    current: Vec<Channel>,
+    fetched: Vec<Channel>,
+    tracking: &SourceTracking,
+) -> (Vec<Channel>, RefreshDiff) {
+    let order: Vec<String> = fetched.iter().map(|c| c.id.clone()).collect();
+    let mut incoming: HashMap<String, Channel> =
+        fetched.into_iter().map(|c| (c.id.clone(), c)).collect();
+    let mut diff = RefreshDiff::default();
+    let mut channels = Vec::with_capacity(current.len().max(order.len()));
+
+    for mut ch in current {
+        let edited = tracking.edited.contains(&ch.id);
+        match incoming.remove(&ch.id) {
+            Some(_) if edited => diff.kept_manual += 1,
+            Some(upstream) if same_metadata(&ch, &upstream) => diff.unchanged += 1,
+            Some(upstream) => {
+                ch.name = upstream.name;
+                ch.group = upstream.group;
+                ch.logo_url = upstream.logo_url;
+                ch.tvg_id = upstream.tvg_id;
+                ch.variants = upstream.variants;
+                ch.attributes = upstream.attributes;
+                diff.updated += 1;
+            }
+            None if tracking.loaded.contains(&ch.id) && !edited => {
+                diff.removed += 1;
+                continue;
+            }
+            None => diff.kept_manual += 1,
+        }
+        channels.push(ch);
+    }
+
+    for id in order {
+        let Some(ch) = incoming.remove(&id) else {
+            continue;
+        };
+        if tracking.loaded.contains(&id) {
+            diff.skipped_deleted += 1;
+        } else {
+            diff.added += 1;
+            channels.push(ch);
+        }
+    }
+
+    (channels, diff)
+}
+
+/// Fetch and parse one source, returning its channels and the guide URLs
+/// from its `url-tvg` header.
+///
+/// When `previous` is given its validators make the request conditional,
+/// and a `304 Not Modified` returns it unchanged; the flag is then set.
+async fn fetch_source(
+    client: &reqwest::Client,
+    url: &str,
+    previous: Option<FetchedSource>,
+) -> Result<(FetchedSource, bool), String> {
+    let response = fetch::fetch_if_modified(
+        client,
+        url,
+        fetch::PLAYLIST_TIMEOUT,
+        previous.as_ref().map(|p| &p.validators),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let (body, validators) = match (response, previous) {
+        (fetch::Conditional::NotModified, Some(previous)) => return Ok((previous, true)),
+        (fetch::Conditional::NotModified, None) => {
+            return Err("Server answered 304 Not Modified to an unconditional request".to_string())
+        }
+        (fetch::Conditional::Modified { body, validators }, _) => (body, validators),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let content = fetch::into_text(body);
+        let channels = variant_grouper::group_variants(m3u_parser::parse_m3u(&content).channels);
+        let source = FetchedSource {
+            validators,
+            channels,
+            guides: m3u_parser::parse_guide_urls(&content),
+        };
+        (source, false)
+    })
+    .await
+    .map_err(|e| format!("Parser task failed: {e}"))
+}
+
+/// Fetch every configured source and apply their merged channels to the
+/// default playlist with [`apply_refresh`]. The sources' `url-tvg` guides
+/// replace the playlist's [`Playlist::epg_urls`](crate::models::Playlist::epg_urls).
+///
+/// Sources are fetched concurrently, and conditionally when the server
+/// sent an `ETag` or `Last-Modified` last time: an unchanged source is not
+/// parsed again. Failed sources are recorded in
+/// [`SourceSet::last_load`] and skipped; if all of them fail the playlist
+/// is left unchanged. Returns what changed. Callers trigger the liveness
+/// check.
+pub async fn load_sources(state: &Arc<AppState>) -> Result<RefreshDiff, String> {
+    let config = state.sources.read().await.config.clone();
+    if config.sources.is_empty() {
+        return Err("No M3U sources configured".to_string());
+    }
+
+    let mut previous = std::mem::take(&mut state.sources.write().await.fetched);
+    let mut tasks = JoinSet::new();
+    for (i, source) in config.sources.iter().cloned().enumerate() {
+        let cached = previous.remove(&source.url);
+        let client = state.http.clone();
+        tasks.spawn(async move {
+            let result = fetch_source(&client, &source.url, cached).await;
+            (i, source, result)
+        });
+    }
+    let mut results = Vec::with_capacity(config.sources.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => warn!("Source fetch task panicked: {e}"),
+        }
+    }
+    results.sort_by_key(|(i, _, _)| *i);
+
+    let now = Utc::now();
+    let mut statuses = Vec::with_capacity(results.len());
+    let mut lists = Vec::with_capacity(results.len());
+    let mut guides: Vec<String> = Vec::new();
+    let mut fetched_sources = HashMap::new();
+    for (_, source, result) in results {
+        match result {
+            Ok((fetched, not_modified)) => {
+                for url in &fetched.guides {
+                    if !guides.contains(url) {
+                        guides.push(url.clone());
+                    }
+                }
+                if not_modified {
+                    info!("M3U source {} not modified", source.url);
+                } else {
+                    info!(
+                        "Parsed {} channels from {}",
+                        fetched.channels.len(),
+                        source.url
+                    );
+                }
+                statuses.push(SourceStatus {
+                    url: source.url.clone(),
+                    channels: fetched.channels.len(),
+                    error: None,
+                    fetched_at: now,
+                    not_modified,
+                });
+                let channels = fetched.channels.clone();
+                if !fetched.validators.is_empty() {
+                    fetched_sources.insert(source.url.clone(), fetched);
+                }
+                lists.push((source, channels));
+            }
+            Err(e) => {
+                warn!("Failed to fetch M3U source {}: {e}", source.url);
+                statuses.push(SourceStatus {
+                    url: source.url,
+                    channels: 0,
+                    error: Some(e),
+                    fetched_at: now,
+                    not_modified: false,
+                });
+            }
+        }
+    }
+    {
+        let mut sources = state.sources.write().await;
+        sources.last_load = statuses;
+        sources.fetched = fetched_sources;
+    }
+
+    if lists.is_empty() {
+        return Err("Every M3U source failed to load".to_string());
+    }
+
+    let label = lists
+        .iter()
+        .map(|(source, _)| source.url.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let fetched = merge_sources(lists, config.policy);
+    let fetched_ids: HashSet<String> = fetched.iter().map(|c| c.id.clone()).collect();
+
+    let mut playlist = state.playlist.write().await;
+    let diff = {
+        let mut sources = state.sources.write().await;
+        let current = std::mem::take(&mut playlist.channels);
+        let (channels, diff) = apply_refresh(current, fetched, &sources.tracking);
+        playlist.channels = channels;
+        let kept: HashSet<&str> = playlist.channels.iter().map(|c| c.id.as_str()).collect();
+        sources
+            .tracking
+            .edited
+            .retain(|id| kept.contains(id.as_str()));
+        sources.tracking.loaded = fetched_ids;
+        sources.last_refresh = Some((Utc::now(), diff.clone()));
+        diff
+    };
+    playlist.source = label;
+    playlist.epg_urls = guides;
+    state
+        .search_index
+        .write()
+        .await
+        .sync_channels(&playlist.channels);
+    state
+        .probe_schedule
+        .write()
+        .await
+        .sync(&playlist.channels, Utc::now());
+    state
+        .events
+        .publish(ServerEvent::playlist_replaced(&playlist));
+    state.state_changed.notify_one();
+
+    Ok(diff)
+}
+
+/// Spawn the task that reloads the sources every `interval`, and whenever
+/// [`AppState::refresh_sources`] fires. With no `interval` it only reloads
+/// on request.
+///
+/// Each successful reload triggers a liveness check. The task reports
+/// heartbeats to [`AppState::watchdog`] under
+/// [`watchdog::SOURCE_REFRESHER`] before waiting and after each reload.
+pub fn start_source_refresher(state: Arc<AppState>, interval: Option<Duration>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            state.watchdog.beat(watchdog::SOURCE_REFRESHER);
+
+            match interval {
+                Some(interval) => tokio::select! {
+                    () = state.refresh_sources.notified() => {}
+                    () = tokio::time::sleep(interval) => {}
+                },
+                None => state.refresh_sources.notified().await,
+            }
+
+            if state.sources.read().await.config.sources.is_empty() {
+                continue;
+            }
+            match load_sources(&state).await {
+                Ok(diff) => {
+                    info!(
+                        "Refreshed M3U sources: {} added, {} updated, {} removed",
+                        diff.added, diff.updated, diff.removed
+                    );
+                    state.check_now.notify_one();
+                }
+                Err(e) => warn!("Failed to refresh M3U sources: {e}"),
+            }
+            state.watchdog.beat(watchdog::SOURCE_REFRESHER);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(url: &str, prefix: Option<&str>) -> M3uSource {
+        M3uSource {
+            url: url.to_string(),
+            group_prefix: prefix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_source_lists() {
+        assert_eq!(
+            parse_source_list(" http://a/list.m3u , Sports|http://b/list.m3u,, |http://c/x.m3u"),
+            vec![
+                source("http://a/list.m3u", None),
+                source("http://b/list.m3u", Some("Sports")),
+                source("http://c/x.m3u", None),
+            ]
+        );
+        assert!(parse_source_list("").is_empty());
+    }
+
+    #[test]
+    fn merge_prefixes_groups() {
+        let merged = merge_sources(
+            vec![
+                (
+                    source("http://a", Some("Sports")),
+                    vec![
+                        Channel::parsed("ESPN", "US", "http://a/espn"),
+                        Channel::parsed("Eurosport", "", "http://a/euro"),
+                    ],
+                ),
+                (
+                    source("http://b", None),
+                    vec![Channel::parsed("CNN", "News", "http://b/cnn")],
+                ),
+            ],
+            MergePolicy::First,
+        );
+
+        let groups: Vec<&str> = merged.iter().map(|c| c.group.as_str()).collect();
+        assert_eq!(groups, ["Sports / US", "Sports", "News"]);
+    }
+
+    #[test]
+    fn merge_policy_resolves_conflicts() {
+        let lists = || {
+            vec![
+                (
+                    source("http://a", None),
+                    vec![
+                        Channel::parsed("CNN", "News", "http://x/cnn"),
+                        Channel::parsed("BBC", "News", "http://x/bbc"),
+                    ],
+                ),
+                (
+                    source("http://b", None),
+                    vec![Channel::parsed(
+                        "CNN International",
+                        "World",
+                        "http://x/cnn",
+                    )],
+                ),
+            ]
+        };
+
+        let first = merge_sources(lists(), MergePolicy::First);
+        let names: Vec<&str> = first.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["CNN", "BBC"]);
+
+        let last = merge_sources(lists(), MergePolicy::Last);
+        let names: Vec<&str> = last.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["CNN International", "BBC"]);
+    }
+
+    fn names_of(channels: &[Channel]) -> Vec<&str> {
+        channels.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn refresh_applies_upstream_changes() {
+        let mut cnn = Channel::parsed("CNN", "News", "http://x/cnn");
+        cnn.is_live = true;
+        cnn.position = Some(0);
+        let current = vec![cnn, Channel::parsed("BBC", "News", "http://x/bbc")];
+        let tracking = SourceTracking {
+            loaded: current.iter().map(|c| c.id.clone()).collect(),
+            edited: HashSet::new(),
+        };
+        let fetched = vec![
+            Channel::parsed("CNN", "World", "http://x/cnn"),
+            Channel::parsed("Al Jazeera", "News", "http://x/aj"),
+        ];
+
+        let (channels, diff) = apply_refresh(current, fetched, &tracking);
+
+        assert_eq!(names_of(&channels), ["CNN", "Al Jazeera"]);
+        assert_eq!(channels[0].group, "World");
+        assert!(channels[0].is_live);
+        assert_eq!(channels[0].position, Some(0));
+        assert_eq!(
+            diff,
+            RefreshDiff {
+                added: 1,
+                updated: 1,
+                removed: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn refresh_preserves_manual_edits() {
+        let renamed = Channel::parsed("My CNN", "Favourites", "http://x/cnn");
+        let manual = Channel::parsed("Local", "Home", "http://lan/cam");
+        let current = vec![renamed, manual];
+        let tracking = SourceTracking {
+            // BBC was loaded before and has since been deleted.
+            loaded: ["http://x/cnn", "http://x/bbc"]
+                .iter()
+                .map(|u| m3u_parser::hash_url(u))
+                .collect(),
+            edited: [m3u_parser::hash_url("http://x/cnn")].into_iter().collect(),
+        };
+        let fetched = vec![
+            Channel::parsed("CNN", "News", "http://x/cnn"),
+            Channel::parsed("BBC", "News", "http://x/bbc"),
+        ];
+
+        let (channels, diff) = apply_refresh(current, fetched, &tracking);
+
+        assert_eq!(names_of(&channels), ["My CNN", "Local"]);
+        assert_eq!(channels[0].group, "Favourites");
+        assert_eq!(diff.kept_manual, 2);
+        assert_eq!(diff.skipped_deleted, 1);
+        assert_eq!(diff.added, 0);
+    }
+}
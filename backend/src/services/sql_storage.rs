@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{PgPool, SqlitePool};
+
+use super::storage::{Snapshot, Storage, StorageError};
+
+/// Single-row table holding the latest [`Snapshot`] as JSON.
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS iptv_state (
+    id INTEGER PRIMARY KEY,
+    snapshot TEXT NOT NULL,
+    saved_at TEXT NOT NULL
+)";
+
+const SELECT_SNAPSHOT: &str = "SELECT snapshot FROM iptv_state WHERE id = 1";
+
+const UPSERT_SQLITE: &str = "INSERT INTO iptv_state (id, snapshot, saved_at) VALUES (1, ?1, ?2)
+    ON CONFLICT (id) DO UPDATE SET snapshot = excluded.snapshot, saved_at = excluded.saved_at";
+
+const UPSERT_POSTGRES: &str = "INSERT INTO iptv_state (id, snapshot, saved_at) VALUES (1, $1, $2)
+    ON CONFLICT (id) DO UPDATE SET snapshot = excluded.snapshot, saved_at = excluded.saved_at";
+
+/// [`Storage`] in a SQLite or Postgres database.
+///
+/// The backend is the database's only writer: state is read once at
+/// startup and each save replaces the whole snapshot. Instances pointed at
+/// the same database overwrite each other's changes.
+#[derive(Debug)]
+pub enum SqlStorage {
+    /// A SQLite database file.
+    Sqlite(SqlitePool),
+    /// A Postgres database.
+    Postgres(PgPool),
+}
+
+impl SqlStorage {
+    /// Open (creating if needed) the SQLite database at `path`.
+    pub async fn sqlite(path: &str) -> Result<Self, StorageError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+        Ok(Self::Sqlite(pool))
+    }
+
+    /// Connect to the Postgres database at `url`.
+    pub async fn postgres(url: &str) -> Result<Self, StorageError> {
+        let pool = PgPoolOptions::new().max_connections(2).connect(url).await?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+        Ok(Self::Postgres(pool))
+    }
+}
+
+#[async_trait]
+impl Storage for SqlStorage {
+    async fn load(&self) -> Result<Option<Snapshot>, StorageError> {
+        let json: Option<String> = match self {
+            Self::Sqlite(pool) => {
+                sqlx::query_scalar(SELECT_SNAPSHOT)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            Self::Postgres(pool) => {
+                sqlx::query_scalar(SELECT_SNAPSHOT)
+                    .fetch_optional(pool)
+                    .await?
+            }
+        };
+        Ok(json.map(|j| serde_json::from_str(&j)).transpose()?)
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<(), StorageError> {
+        let json = serde_json::to_string(snapshot)?;
+        let saved_at = Utc::now().to_rfc3339();
+        match self {
+            Self::Sqlite(pool) => {
+                sqlx::query(UPSERT_SQLITE)
+                    .bind(&json)
+                    .bind(&saved_at)
+                    .execute(pool)
+                    .await?;
+            }
+            Self::Postgres(pool) => {
+                sqlx::query(UPSERT_POSTGRES)
+                    .bind(&json)
+                    .bind(&saved_at)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Playlist;
+
+    #[tokio::test]
+    async fn sqlite_round_trips_snapshots() {
+        let path = std::env::temp_dir().join(format!("iptv-state-{}.db", std::process::id()));
+        let storage = SqlStorage::sqlite(path.to_str().unwrap()).await.unwrap();
+        assert!(storage.load().await.unwrap().is_none());
+
+        let mut snapshot = Snapshot {
+            playlist: Playlist {
+                name: "default".to_string(),
+                channels: Vec::new(),
+                last_checked: None,
+                source: String::new(),
+                epg_urls: Vec::new(),
+            },
+            playlists: Default::default(),
+            pinned: vec!["cnn".to_string()],
+            epg_aliases: Default::default(),
+            sources: None,
+            source_tracking: Default::default(),
+            users: Vec::new(),
+            sessions: Default::default(),
+            uptime: Default::default(),
+        };
+        storage.save(&snapshot).await.unwrap();
+
+        // A second save replaces the first.
+        snapshot.pinned.push("bbc".to_string());
+        storage.save(&snapshot).await.unwrap();
+
+        let loaded = storage.load().await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.pinned, vec!["cnn".to_string(), "bbc".to_string()]);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+#[cfg(feature = "sql")]
+use super::sql_storage::SqlStorage;
+
+use crate::config::{Config, StorageBackend};
+use crate::models::{AppState, EpgAlias, Playlist};
+use crate::services::accounts::{Session, User};
+use crate::services::sources::{SourceConfig, SourceTracking};
+use crate::services::uptime::UptimeHistory;
+
+/// Errors that can occur while loading or saving persisted state.
+#[derive(Debug, Error)]
+pub enum StorageError {
+    /// Reading or writing the state file failed.
+    #[error("state file I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The state file is not valid JSON.
+    #[error("state file JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The database query failed.
+    #[cfg(feature = "sql")]
+    #[error("state database error: {0}")]
+    Sql(#[from] sqlx::Error),
+    /// The selected backend is missing settings or was not compiled in.
+    #[error("storage configuration error: {0}")]
+    Config(&'static str),
+}
+
+/// Everything the backend keeps across restarts.
+///
+/// Liveness results travel with the channels (`is_live`, `health_score`)
+/// and the playlist's `last_checked`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The default playlist.
+    pub playlist: Playlist,
+    /// Named playlists keyed by slug.
+    #[serde(default)]
+    pub playlists: HashMap<String, Playlist>,
+    /// IDs of channels pinned to high-frequency checking.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// Channel → XMLTV alias table.
+    #[serde(default)]
+    pub epg_aliases: HashMap<String, EpgAlias>,
+    /// M3U sources set through the API; `None` when they come from
+    /// `M3U_SOURCE_URL`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sources: Option<SourceConfig>,
+    /// Which channels came from the sources and which were edited since.
+    #[serde(default)]
+    pub source_tracking: SourceTracking,
+    /// User accounts.
+    #[serde(default)]
+    pub users: Vec<User>,
+    /// Open sessions, keyed by the hash of their token.
+    #[serde(default)]
+    pub sessions: HashMap<String, Session>,
+    /// Hourly liveness history of the channels.
+    #[serde(default)]
+    pub uptime: UptimeHistory,
+}
+
+/// A place the backend's [`Snapshot`] is saved to and restored from.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Load the last saved snapshot, or `None` if nothing was saved yet.
+    async fn load(&self) -> Result<Option<Snapshot>, StorageError>;
+
+    /// Save `snapshot`, replacing whatever was saved before.
+    async fn save(&self, snapshot: &Snapshot) -> Result<(), StorageError>;
+}
+
+/// [`Storage`] in a single JSON file, replaced atomically on every save.
+#[derive(Debug)]
+pub struct JsonFileStorage {
+    path: PathBuf,
+}
+
+impl JsonFileStorage {
+    /// Store state in the JSON file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Storage for JsonFileStorage {
+    async fn load(&self) -> Result<Option<Snapshot>, StorageError> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<(), StorageError> {
+        let json = serde_json::to_vec(snapshot)?;
+        let tmp = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// Open the storage selected by `STORAGE_BACKEND`, or `None` when state is
+/// kept in memory only.
+pub async fn open(config: &Config) -> Result<Option<Arc<dyn Storage>>, StorageError> {
+    match config.storage_backend {
+        StorageBackend::Memory => Ok(None),
+        StorageBackend::Json => {
+            let path = config
+                .storage_path
+                .as_deref()
+                .ok_or(StorageError::Config("STORAGE_PATH is required"))?;
+            Ok(Some(Arc::new(JsonFileStorage::new(path))))
+        }
+        #[cfg(feature = "sql")]
+        StorageBackend::Sqlite => {
+            let path = config
+                .storage_path
+                .as_deref()
+                .ok_or(StorageError::Config("STORAGE_PATH is required"))?;
+            Ok(Some(Arc::new(SqlStorage::sqlite(path).await?)))
+        }
+        #[cfg(feature = "sql")]
+        StorageBackend::Postgres => {
+            let url = config
+                .storage_database_url
+                .as_deref()
+                .ok_or(StorageError::Config("STORAGE_DATABASE_URL is required"))?;
+            Ok(Some(Arc::new(SqlStorage::postgres(url).await?)))
+        }
+        #[cfg(not(feature = "sql"))]
+        StorageBackend::Sqlite | StorageBackend::Postgres => Err(StorageError::Config(
+            "database backends need the backend built with the `sql` feature",
+        )),
+    }
+}
+
+/// Capture the persisted parts of `state`.
+pub async fn snapshot(state: &AppState) -> Snapshot {
+    let playlist = state.playlist.read().await.clone();
+    let playlists = state.playlists.read().await.clone();
+    let pinned = state.probe_schedule.read().await.pinned_ids();
+    let epg_aliases = state.epg_cache.read().await.aliases.clone();
+    let uptime = state.uptime.read().await.clone();
+    let (sources, source_tracking) = {
+        let set = state.sources.read().await;
+        (
+            set.from_api.then(|| set.config.clone()),
+            set.tracking.clone(),
+        )
+    };
+    let (users, sessions) = {
+        let accounts = state.accounts.read().await;
+        (
+            accounts.users.values().cloned().collect(),
+            accounts.sessions.clone(),
+        )
+    };
+    Snapshot {
+        playlist,
+        playlists,
+        pinned,
+        epg_aliases,
+        sources,
+        source_tracking,
+        users,
+        sessions,
+        uptime,
+    }
+}
+
+/// Load `snapshot` into `state`, re-indexing and re-scheduling its channels.
+///
+/// Saved EPG aliases are merged over any loaded from `EPG_ALIASES_PATH`,
+/// and sources saved from the API replace those from `M3U_SOURCE_URL`.
+pub async fn restore(state: &AppState, snapshot: Snapshot) {
+    let now = Utc::now();
+    let mut playlist = state.playlist.write().await;
+    *playlist = snapshot.playlist;
+    state
+        .search_index
+        .write()
+        .await
+        .sync_channels(&playlist.channels);
+
+    let mut schedule = state.probe_schedule.write().await;
+    schedule.sync(&playlist.channels, now);
+    for id in &snapshot.pinned {
+        schedule.set_pinned(id, true, now);
+    }
+
+    *state.playlists.write().await = snapshot.playlists;
+    state
+        .epg_cache
+        .write()
+        .await
+        .aliases
+        .extend(snapshot.epg_aliases);
+
+    let mut sources = state.sources.write().await;
+    sources.tracking = snapshot.source_tracking;
+    if let Some(config) = snapshot.sources {
+        sources.config = config;
+        sources.from_api = true;
+    }
+
+    let mut accounts = state.accounts.write().await;
+    accounts.users = snapshot
+        .users
+        .into_iter()
+        .map(|user| (user.username.clone(), user))
+        .collect();
+    accounts.sessions = snapshot.sessions;
+
+    *state.uptime.write().await = snapshot.uptime;
+}
+
+/// Spawn the task that saves state whenever [`AppState::state_changed`] fires.
+///
+/// Writes are debounced: after a change the task waits `debounce` so a
+/// burst of edits (an import, a checker cycle) is saved once.
+pub fn start_persister(
+    state: Arc<AppState>,
+    storage: Arc<dyn Storage>,
+    debounce: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            state.state_changed.notified().await;
+            tokio::time::sleep(debounce).await;
+
+            let snapshot = snapshot(&state).await;
+            match storage.save(&snapshot).await {
+                Ok(()) => debug!(
+                    "Saved state: {} channels, {} named playlists",
+                    snapshot.playlist.channels.len(),
+                    snapshot.playlists.len()
+                ),
+                Err(e) => warn!("Failed to save state: {e}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Channel;
+
+    fn make_playlist(name: &str, channel_ids: &[&str]) -> Playlist {
+        Playlist {
+            name: name.to_string(),
+            channels: channel_ids
+                .iter()
+                .map(|id| Channel {
+                    is_live: true,
+                    health_score: Some(80),
+                    ..Channel::sample(id, &id.to_uppercase(), "News")
+                })
+                .collect(),
+            last_checked: Some("2026-02-11T12:00:00Z".to_string()),
+            source: "http://example.com/list.m3u".to_string(),
+            epg_urls: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn json_file_round_trips_snapshots() {
+        let path = std::env::temp_dir().join(format!("iptv-state-{}.json", std::process::id()));
+        let storage = JsonFileStorage::new(&path);
+        assert!(storage.load().await.unwrap().is_none());
+
+        let mut playlists = HashMap::new();
+        playlists.insert("sports".to_string(), make_playlist("Sports", &["espn"]));
+        let saved = Snapshot {
+            playlist: make_playlist("default", &["cnn", "bbc"]),
+            playlists,
+            pinned: vec!["cnn".to_string()],
+            epg_aliases: HashMap::new(),
+            sources: None,
+            source_tracking: SourceTracking::default(),
+            users: Vec::new(),
+            sessions: HashMap::new(),
+            uptime: UptimeHistory::default(),
+        };
+        storage.save(&saved).await.unwrap();
+
+        let loaded = storage.load().await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.playlist.channels.len(), 2);
+        assert!(loaded.playlist.channels[0].is_live);
+        assert_eq!(loaded.playlist.channels[0].health_score, Some(80));
+        assert_eq!(loaded.playlist.last_checked, saved.playlist.last_checked);
+        assert_eq!(loaded.playlists["sports"].name, "Sports");
+        assert_eq!(loaded.pinned, vec!["cnn".to_string()]);
+    }
+}
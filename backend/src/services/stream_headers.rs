@@ -0,0 +1,65 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, REFERER, USER_AGENT};
+
+use crate::models::channel::M3uAttributes;
+
+/// Parse `PROBE_HEADERS`: `|`-separated `Name: value` pairs, e.g.
+/// `User-Agent: VLC/3.0.20|Referer: https://example.com/`. Pairs that are
+/// not valid HTTP headers are skipped.
+pub fn parse_headers(value: &str) -> HeaderMap {
+    value
+        .split('|')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once(':')?;
+            let name = HeaderName::from_bytes(name.trim().as_bytes()).ok()?;
+            let value = HeaderValue::from_str(value.trim()).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// Headers a channel's stream is requested with: `global`, with the
+/// channel's own user agent and referrer (from `#EXTVLCOPT` or an edit)
+/// taking precedence. Values that cannot be sent as headers are ignored.
+pub fn for_channel(global: &HeaderMap, attributes: &M3uAttributes) -> HeaderMap {
+    let mut headers = global.clone();
+    let own = [
+        (USER_AGENT, &attributes.user_agent),
+        (REFERER, &attributes.referrer),
+    ];
+    for (name, value) in own {
+        if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_headers_override_global_ones() {
+        let global = parse_headers(
+            "User-Agent: Mozilla/5.0 (X11; Linux x86_64; rv:128.0)|X-Token:abc| broken |Bad Name: x",
+        );
+        assert_eq!(global.len(), 2);
+        assert_eq!(
+            global["user-agent"],
+            "Mozilla/5.0 (X11; Linux x86_64; rv:128.0)"
+        );
+        assert_eq!(global["x-token"], "abc");
+        assert!(parse_headers("").is_empty());
+
+        let attributes = M3uAttributes {
+            user_agent: Some("VLC/3.0.20".to_string()),
+            referrer: Some("https://example.com/".to_string()),
+            ..M3uAttributes::default()
+        };
+        let headers = for_channel(&global, &attributes);
+        assert_eq!(headers[USER_AGENT], "VLC/3.0.20");
+        assert_eq!(headers[REFERER], "https://example.com/");
+        assert_eq!(headers["x-token"], "abc");
+        assert_eq!(for_channel(&global, &M3uAttributes::default()), global);
+    }
+}
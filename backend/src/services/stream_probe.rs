@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::models::{AppState, MediaInfo};
+
+/// Longest an `ffprobe` run may take before it is killed.
+const MAX_PROBE_TIME: Duration = Duration::from_secs(30);
+
+/// How many `ffprobe` processes run at once.
+const CONCURRENCY: usize = 4;
+
+/// Errors that can occur while probing a stream with `ffprobe`.
+#[derive(Debug, Error)]
+pub enum StreamProbeError {
+    /// `ffprobe` could not be started.
+    #[error("Could not run ffprobe: {0}")]
+    Spawn(#[from] std::io::Error),
+    /// `ffprobe` did not finish within [`MAX_PROBE_TIME`].
+    #[error("ffprobe timed out")]
+    Timeout,
+    /// `ffprobe` exited with an error, e.g. because the stream is unreachable.
+    #[error("ffprobe failed: {0}")]
+    Failed(String),
+    /// `ffprobe` printed something other than the expected JSON.
+    #[error("Unexpected ffprobe output: {0}")]
+    Output(#[from] serde_json::Error),
+}
+
+/// Read the codecs, resolution and frame rate of the stream at `url`.
+///
+/// Runs the `ffprobe` executable at `ffprobe`, giving up on reads that
+/// stall for `timeout` and on runs longer than [`MAX_PROBE_TIME`].
+pub async fn probe(
+    ffprobe: &str,
+    url: &str,
+    timeout: Duration,
+) -> Result<MediaInfo, StreamProbeError> {
+    let run = Command::new(ffprobe)
+        .args(["-v", "error", "-print_format", "json", "-show_streams"])
+        .arg("-rw_timeout")
+        .arg(timeout.as_micros().to_string())
+        .arg(url)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(MAX_PROBE_TIME, run)
+        .await
+        .map_err(|_| StreamProbeError::Timeout)??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(StreamProbeError::Failed(stderr.trim().to_string()));
+    }
+    Ok(parse_output(&serde_json::from_slice(&output.stdout)?))
+}
+
+/// Probe the live channels that have no media metadata yet, and store what
+/// `ffprobe` reads on their [`ChannelHealth`](crate::models::ChannelHealth).
+///
+/// Channels whose stream URL changed while they were probed are left alone.
+pub async fn probe_new_channels(state: &AppState) {
+    let targets: Vec<(String, String)> = state
+        .playlist
+        .read()
+        .await
+        .channels
+        .iter()
+        .filter(|ch| ch.is_live && ch.health.media.is_none())
+        .map(|ch| (ch.id.clone(), ch.stream_url.clone()))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    debug!("Reading stream metadata of {} channels", targets.len());
+    let timeout = Duration::from_secs(state.config.probe_timeout_secs);
+    let semaphore = Arc::new(Semaphore::new(CONCURRENCY));
+    let mut handles = Vec::with_capacity(targets.len());
+    for (id, url) in targets {
+        let ffprobe = state.config.ffprobe_path.clone();
+        let sem = Arc::clone(&semaphore);
+        handles.push(tokio::spawn(async move {
+            let _permit = sem.acquire().await;
+            let media = probe(&ffprobe, &url, timeout).await;
+            (id, url, media)
+        }));
+    }
+
+    let mut probed = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok((id, url, Ok(media))) => probed.push((id, url, media)),
+            Ok((id, _, Err(e))) => debug!("No stream metadata for channel {id}: {e}"),
+            Err(e) => warn!("Stream probe task panicked: {e}"),
+        }
+    }
+    if probed.is_empty() {
+        return;
+    }
+
+    let mut playlist = state.playlist.write().await;
+    for (id, url, media) in probed {
+        if let Some(channel) = playlist
+            .channels
+            .iter_mut()
+            .find(|ch| ch.id == id && ch.stream_url == url)
+        {
+            channel.health.media = Some(media);
+        }
+    }
+    state.state_changed.notify_one();
+}
+
+/// Read the video stream with the largest frame, and the first audio
+/// stream, from `ffprobe -show_streams` JSON output.
+fn parse_output(output: &Value) -> MediaInfo {
+    let streams = output
+        .get("streams")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let of_type = |kind: &'static str| {
+        streams
+            .iter()
+            .filter(move |s| s.get("codec_type").and_then(Value::as_str) == Some(kind))
+    };
+    let dimension = |stream: &Value, key: &str| {
+        stream
+            .get(key)
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+    };
+    let codec = |stream: &Value| {
+        stream
+            .get("codec_name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let video = of_type("video").max_by_key(|s| {
+        u64::from(dimension(s, "width").unwrap_or(0))
+            * u64::from(dimension(s, "height").unwrap_or(0))
+    });
+    let audio = of_type("audio").next();
+    MediaInfo {
+        video_codec: video.and_then(codec),
+        audio_codec: audio.and_then(codec),
+        width: video.and_then(|s| dimension(s, "width")),
+        height: video.and_then(|s| dimension(s, "height")),
+        fps: video.and_then(|s| {
+            ["avg_frame_rate", "r_frame_rate"]
+                .iter()
+                .find_map(|key| s.get(*key).and_then(Value::as_str).and_then(frame_rate))
+        }),
+        probed_at: Utc::now(),
+    }
+}
+
+/// Frames per second from an `ffprobe` rate such as `"30000/1001"`,
+/// rounded to two decimals; `None` for an unknown `"0/0"` rate.
+fn frame_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+    let fps = num / den;
+    (fps.is_finite() && fps > 0.0).then(|| (fps * 100.0).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_ffprobe_streams() {
+        let output = json!({"streams": [
+            {"index": 0, "codec_type": "video", "codec_name": "h264", "width": 640, "height": 360,
+             "avg_frame_rate": "25/1"},
+            {"index": 1, "codec_type": "audio", "codec_name": "aac"},
+            {"index": 2, "codec_type": "video", "codec_name": "hevc", "width": 1920, "height": 1080,
+             "avg_frame_rate": "0/0", "r_frame_rate": "30000/1001"},
+            {"index": 3, "codec_type": "audio", "codec_name": "ac3"}
+        ]});
+        let media = parse_output(&output);
+        assert_eq!(media.video_codec.as_deref(), Some("hevc"));
+        assert_eq!(media.audio_codec.as_deref(), Some("aac"));
+        assert_eq!((media.width, media.height), (Some(1920), Some(1080)));
+        assert_eq!(media.fps, Some(29.97));
+
+        let radio =
+            parse_output(&json!({"streams": [{"codec_type": "audio", "codec_name": "mp3"}]}));
+        assert_eq!(radio.video_codec, None);
+        assert_eq!(radio.fps, None);
+        assert_eq!(radio.audio_codec.as_deref(), Some("mp3"));
+    }
+
+    #[test]
+    fn parses_frame_rates() {
+        assert_eq!(frame_rate("50/1"), Some(50.0));
+        assert_eq!(frame_rate("24000/1001"), Some(23.98));
+        assert_eq!(frame_rate("0/0"), None);
+        assert_eq!(frame_rate("25"), None);
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tracing::warn;
+
+/// Timeout for a single Subscan request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Attempts made at a Subscan request before giving up.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for each later one.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest `Retry-After` delay waited for; longer ones fail the request.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Errors that can occur while querying the Subscan API.
+#[derive(Debug, Error)]
+pub enum SubscanError {
+    /// HTTP request failed.
+    #[error("Subscan request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// Subscan answered with an error status, after any retries.
+    #[error("Subscan answered {0}")]
+    Status(StatusCode),
+}
+
+/// Client of the Subscan API, shared by every network's lookups.
+///
+/// Requests carry the `X-API-Key` header when a key is configured. Rate
+/// limited (`429`) and failed (`5xx`) requests, and ones that could not be
+/// sent, are retried up to [`MAX_ATTEMPTS`] times with exponential backoff,
+/// waiting for `Retry-After` when Subscan sends it.
+#[derive(Debug, Clone)]
+pub struct Subscan {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl Subscan {
+    /// A client sending requests with `client`, and `api_key`, when set,
+    /// with every request.
+    pub fn new(client: reqwest::Client, api_key: Option<String>) -> Self {
+        Self { client, api_key }
+    }
+
+    /// The last 25 `system.remark_with_event` extrinsics signed by
+    /// `address`, as answered by the Subscan API at `api_url`.
+    pub async fn remark_extrinsics(
+        &self,
+        api_url: &str,
+        address: &str,
+    ) -> Result<Value, SubscanError> {
+        let body = json!({
+            "module": "system",
+            "call": "remark_with_event",
+            "address": address,
+            "page": 0,
+            "row": 25
+        });
+        self.post(&format!("{api_url}/api/v2/scan/extrinsics"), &body)
+            .await
+    }
+
+    /// POST `body` to `url`, retrying as described on [`Subscan`].
+    async fn post(&self, url: &str, body: &Value) -> Result<Value, SubscanError> {
+        let mut attempt = 1;
+        loop {
+            let mut request = self.client.post(url).json(body).timeout(REQUEST_TIMEOUT);
+            if let Some(ref key) = self.api_key {
+                request = request.header("X-API-Key", key);
+            }
+
+            let (error, retry_after) = match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.json().await?);
+                }
+                Ok(response) if is_retryable(response.status()) => {
+                    let retry_after = retry_after(response.headers());
+                    (SubscanError::Status(response.status()), retry_after)
+                }
+                Ok(response) => return Err(SubscanError::Status(response.status())),
+                Err(e) => (SubscanError::Http(e), None),
+            };
+
+            let delay = retry_after.unwrap_or(INITIAL_BACKOFF * 2u32.pow(attempt - 1));
+            if attempt == MAX_ATTEMPTS || delay > MAX_RETRY_AFTER {
+                return Err(error);
+            }
+            warn!("{error} for {url}; retrying in {}s", delay.as_secs_f32());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Whether a request answered with `status` may succeed when retried.
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// The delay asked for by a `Retry-After` header given in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn retries_rate_limits_and_server_errors() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2026 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
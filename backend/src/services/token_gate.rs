@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use codec::{Decode, Encode};
+use tracing::warn;
+
+use crate::services::chain_rpc::{blake2_128_concat, storage_key, ChainRpc, RpcError};
+use crate::services::signature;
+
+/// Name of the token-gate pallet in the runtime.
+const TOKEN_GATE_PALLET: &str = "TokenGate";
+
+/// Name of the assets pallet whose balances gates check.
+const ASSETS_PALLET: &str = "Assets";
+
+/// How long an access decision is reused before the chain is asked again.
+const DECISION_TTL: Duration = Duration::from_secs(60);
+
+/// One requirement of a gate, as stored by the token-gate pallet.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct GateRequirement {
+    token_id: u32,
+    min_balance: u128,
+}
+
+/// How the requirements of a gate are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+enum Combinator {
+    All,
+    Any,
+}
+
+/// The requirements guarding a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct Gate {
+    requirements: Vec<GateRequirement>,
+    combinator: Combinator,
+}
+
+impl Gate {
+    /// Whether an account holding `balances[token_id]` of each token meets the gate.
+    fn is_met_with(&self, balances: &HashMap<u32, u128>) -> bool {
+        let met =
+            |r: &GateRequirement| balances.get(&r.token_id).copied().unwrap_or(0) >= r.min_balance;
+        match self.combinator {
+            Combinator::All => self.requirements.iter().all(met),
+            Combinator::Any => self.requirements.iter().any(met),
+        }
+    }
+}
+
+/// A superseded gate that still grants access until `expires_at`.
+#[derive(Debug, Decode)]
+struct GracePeriod {
+    previous: Gate,
+    expires_at: u32,
+}
+
+/// A recurring subscription to a resource. Its `auto_renew` flag follows
+/// and is not needed.
+#[derive(Debug, Decode)]
+struct Subscription {
+    paid_until: u32,
+}
+
+/// Decides which token-gated groups of the default playlist an account may
+/// see, by reading the token-gate pallet through a node's JSON-RPC.
+///
+/// Each gated group names the resource ID of its gate. Access follows the
+/// pallet's `check_access`: a valid access pass, a paid subscription, or
+/// balances meeting the gate (or, during a grace period, the previous gate).
+/// Resources without a gate, or whose gate has expired, are open.
+///
+/// Decisions are cached per account and resource for [`DECISION_TTL`].
+/// Failed lookups deny access and are not cached.
+#[derive(Debug)]
+pub struct TokenGate {
+    rpc: Option<ChainRpc>,
+    gated_groups: HashMap<String, u32>,
+    decisions: Mutex<Decisions>,
+}
+
+/// Access decisions by account key and resource ID, with when they were made.
+type Decisions = HashMap<([u8; 32], u32), (bool, Instant)>;
+
+impl TokenGate {
+    /// A gate for `gated_groups` (group name to resource ID), read through
+    /// `rpc`.
+    pub fn new(rpc: Option<ChainRpc>, gated_groups: HashMap<String, u32>) -> Self {
+        Self {
+            rpc,
+            gated_groups,
+            decisions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether any group is gated.
+    pub fn is_enabled(&self) -> bool {
+        !self.gated_groups.is_empty()
+    }
+
+    /// The gated groups the account at `address` may not see. Without an
+    /// address, or with an invalid one, every gated group is locked.
+    pub async fn locked_groups(&self, address: Option<&str>) -> HashSet<String> {
+        let Some(key) = address.and_then(|a| signature::decode_ss58(a).ok()) else {
+            return self.gated_groups.keys().cloned().collect();
+        };
+
+        let resources: HashSet<u32> = self.gated_groups.values().copied().collect();
+        let mut denied = HashSet::new();
+        for resource_id in resources {
+            if !self.may_access(&key, resource_id).await {
+                denied.insert(resource_id);
+            }
+        }
+        self.gated_groups
+            .iter()
+            .filter(|(_, resource_id)| denied.contains(*resource_id))
+            .map(|(group, _)| group.clone())
+            .collect()
+    }
+
+    /// Whether the account with public key `key` may access `resource_id`,
+    /// from the cache or the chain.
+    async fn may_access(&self, key: &[u8; 32], resource_id: u32) -> bool {
+        let cached = self
+            .decisions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(*key, resource_id))
+            .filter(|(_, at)| at.elapsed() < DECISION_TTL)
+            .map(|(allowed, _)| *allowed);
+        if let Some(allowed) = cached {
+            return allowed;
+        }
+
+        match self.check_access(key, resource_id).await {
+            Ok(allowed) => {
+                let mut decisions = self.decisions.lock().unwrap_or_else(|e| e.into_inner());
+                decisions.retain(|_, (_, at)| at.elapsed() < DECISION_TTL);
+                decisions.insert((*key, resource_id), (allowed, Instant::now()));
+                allowed
+            }
+            Err(e) => {
+                warn!("Token gate check of resource {resource_id} failed: {e}");
+                false
+            }
+        }
+    }
+
+    /// Read the pallet state deciding whether `key` may access `resource_id`.
+    async fn check_access(&self, key: &[u8; 32], resource_id: u32) -> Result<bool, RpcError> {
+        let rpc = self.rpc.as_ref().ok_or(RpcError::NotConfigured)?;
+        let now: u32 = rpc
+            .read(&storage_key("System", "Number", &[]), "block number")
+            .await?
+            .unwrap_or_default();
+        let by_account = [
+            blake2_128_concat(key),
+            blake2_128_concat(&resource_id.encode()),
+        ];
+
+        let pass: Option<u32> = rpc
+            .read(
+                &storage_key(TOKEN_GATE_PALLET, "AccessPasses", &by_account),
+                "access pass",
+            )
+            .await?;
+        if pass.is_some_and(|expires_at| now < expires_at) {
+            return Ok(true);
+        }
+        let subscription: Option<Subscription> = rpc
+            .read(
+                &storage_key(TOKEN_GATE_PALLET, "Subscriptions", &by_account),
+                "subscription",
+            )
+            .await?;
+        if subscription.is_some_and(|s| now < s.paid_until) {
+            return Ok(true);
+        }
+
+        let by_resource = [blake2_128_concat(&resource_id.encode())];
+        let Some(gate) = rpc
+            .read::<Gate>(
+                &storage_key(TOKEN_GATE_PALLET, "Gates", &by_resource),
+                "gate",
+            )
+            .await?
+        else {
+            return Ok(true);
+        };
+        let expiry: Option<u32> = rpc
+            .read(
+                &storage_key(TOKEN_GATE_PALLET, "GateExpiry", &by_resource),
+                "gate expiry",
+            )
+            .await?;
+        if expiry.is_some_and(|at| now >= at) {
+            return Ok(true);
+        }
+
+        let grace: Option<GracePeriod> = rpc
+            .read(
+                &storage_key(TOKEN_GATE_PALLET, "GracePeriods", &by_resource),
+                "grace period",
+            )
+            .await?;
+        let grace = grace.filter(|g| now < g.expires_at);
+        let tokens: HashSet<u32> = gate
+            .requirements
+            .iter()
+            .chain(grace.iter().flat_map(|g| &g.previous.requirements))
+            .map(|r| r.token_id)
+            .collect();
+        let mut balances = HashMap::new();
+        for token_id in tokens {
+            balances.insert(token_id, Self::balance_of(rpc, key, token_id).await?);
+        }
+        Ok(gate.is_met_with(&balances) || grace.is_some_and(|g| g.previous.is_met_with(&balances)))
+    }
+
+    /// Balance of `token_id` held by `key` in the assets pallet.
+    async fn balance_of(rpc: &ChainRpc, key: &[u8; 32], token_id: u32) -> Result<u128, RpcError> {
+        let path = [
+            blake2_128_concat(&token_id.encode()),
+            blake2_128_concat(key),
+        ];
+        // An asset account starts with its balance; the rest is not needed.
+        let balance: Option<u128> = rpc
+            .read(
+                &storage_key(ASSETS_PALLET, "Account", &path),
+                "asset balance",
+            )
+            .await?;
+        Ok(balance.unwrap_or(0))
+    }
+}
+
+/// Parse `TOKEN_GATED_GROUPS`: comma-separated `group=resource_id` entries.
+/// Entries without a valid resource ID are skipped.
+pub fn parse_gated_groups(value: &str) -> HashMap<String, u32> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (group, resource_id) = entry.rsplit_once('=')?;
+            let group = group.trim();
+            let resource_id = resource_id.trim().parse().ok()?;
+            (!group.is_empty()).then(|| (group.to_string(), resource_id))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_gates_against_balances() {
+        let gate = Gate {
+            requirements: vec![
+                GateRequirement {
+                    token_id: 1,
+                    min_balance: 100,
+                },
+                GateRequirement {
+                    token_id: 7,
+                    min_balance: 1,
+                },
+            ],
+            combinator: Combinator::All,
+        };
+        let decoded = Gate::decode(&mut gate.encode().as_slice()).unwrap();
+        assert_eq!(decoded, gate);
+
+        let balances = HashMap::from([(1, 150)]);
+        assert!(!gate.is_met_with(&balances));
+        let any = Gate {
+            combinator: Combinator::Any,
+            ..gate.clone()
+        };
+        assert!(any.is_met_with(&balances));
+        assert!(gate.is_met_with(&HashMap::from([(1, 100), (7, 1)])));
+    }
+
+    #[test]
+    fn parses_gated_groups() {
+        let groups = parse_gated_groups("Sports=3, Movies & TV = 7,broken,=4,News=x");
+        assert_eq!(
+            groups,
+            HashMap::from([("Sports".to_string(), 3), ("Movies & TV".to_string(), 7)])
+        );
+    }
+
+    #[tokio::test]
+    async fn locks_every_gated_group_without_an_address() {
+        let gate = TokenGate::new(None, parse_gated_groups("Sports=3,Movies=7"));
+        assert!(gate.is_enabled());
+        assert_eq!(gate.locked_groups(None).await.len(), 2);
+        assert_eq!(gate.locked_groups(Some("not an address")).await.len(), 2);
+        // Without an RPC endpoint, lookups fail and access is denied.
+        let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        assert_eq!(gate.locked_groups(Some(alice)).await.len(), 2);
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Channel;
+
+/// Hours of check outcomes kept per channel.
+pub const HISTORY_HOURS: i64 = 7 * 24;
+
+/// Hours covered by [`UptimeReport::uptime_24h`].
+const DAY_HOURS: i64 = 24;
+
+/// Check outcomes of one channel within one hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourBucket {
+    /// The hour, counted in hours since the Unix epoch.
+    pub hour: i64,
+    /// Checks made in the hour.
+    pub checks: u32,
+    /// Checks that found the channel live.
+    pub up: u32,
+}
+
+/// Liveness history of every channel: the outcomes of its checks over the
+/// last [`HISTORY_HOURS`], counted per hour.
+///
+/// Hourly buckets keep the history small whatever the check interval, so
+/// it is saved with the rest of the state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UptimeHistory {
+    channels: HashMap<String, VecDeque<HourBucket>>,
+}
+
+/// Uptime of one channel, as served by `GET /api/channels/:id/uptime`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UptimeReport {
+    /// Percentage of checks in the last 24 hours that found the channel
+    /// live; `None` without checks.
+    pub uptime_24h: Option<f64>,
+    /// Percentage of checks in the last 7 days that found the channel live.
+    pub uptime_7d: Option<f64>,
+    /// Checks made in the last 24 hours.
+    pub checks_24h: u32,
+    /// Checks made in the last 7 days.
+    pub checks_7d: u32,
+    /// One point per hour of the last 7 days, oldest first, including hours
+    /// without checks.
+    pub series: Vec<UptimePoint>,
+}
+
+/// One hour of an [`UptimeReport`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UptimePoint {
+    /// Start of the hour.
+    pub hour: DateTime<Utc>,
+    pub checks: u32,
+    /// Percentage of the hour's checks that found the channel live.
+    pub uptime: Option<f64>,
+}
+
+impl UptimeHistory {
+    /// Record a check of `channel_id` made at `at`, dropping the channel's
+    /// outcomes older than [`HISTORY_HOURS`].
+    pub fn record(&mut self, channel_id: &str, is_live: bool, at: DateTime<Utc>) {
+        let hour = hour_of(at);
+        let buckets = self.channels.entry(channel_id.to_string()).or_default();
+        match buckets.back_mut() {
+            Some(last) if last.hour == hour => {
+                last.checks += 1;
+                last.up += u32::from(is_live);
+            }
+            _ => buckets.push_back(HourBucket {
+                hour,
+                checks: 1,
+                up: u32::from(is_live),
+            }),
+        }
+        while buckets
+            .front()
+            .is_some_and(|b| b.hour <= hour - HISTORY_HOURS)
+        {
+            buckets.pop_front();
+        }
+    }
+
+    /// Forget the channels that are no longer in `channels`.
+    pub fn sync(&mut self, channels: &[Channel]) {
+        let ids: HashSet<&str> = channels.iter().map(|c| c.id.as_str()).collect();
+        self.channels.retain(|id, _| ids.contains(id.as_str()));
+    }
+
+    /// The uptime of `channel_id` over the 7 days up to `now`.
+    pub fn report(&self, channel_id: &str, now: DateTime<Utc>) -> UptimeReport {
+        let current = hour_of(now);
+        let by_hour: HashMap<i64, &HourBucket> = self
+            .channels
+            .get(channel_id)
+            .into_iter()
+            .flatten()
+            .map(|b| (b.hour, b))
+            .collect();
+        let series: Vec<UptimePoint> = (current - HISTORY_HOURS + 1..=current)
+            .filter_map(|hour| {
+                let (checks, up) = by_hour.get(&hour).map_or((0, 0), |b| (b.checks, b.up));
+                Some(UptimePoint {
+                    hour: DateTime::from_timestamp(hour * 3600, 0)?,
+                    checks,
+                    uptime: percentage(up, checks),
+                })
+            })
+            .collect();
+
+        let totals = |hours: i64| {
+            by_hour
+                .values()
+                .filter(|b| b.hour > current - hours && b.hour <= current)
+                .fold((0, 0), |(checks, up), b| (checks + b.checks, up + b.up))
+        };
+        let (checks_24h, up_24h) = totals(DAY_HOURS);
+        let (checks_7d, up_7d) = totals(HISTORY_HOURS);
+        UptimeReport {
+            uptime_24h: percentage(up_24h, checks_24h),
+            uptime_7d: percentage(up_7d, checks_7d),
+            checks_24h,
+            checks_7d,
+            series,
+        }
+    }
+}
+
+/// Hours since the Unix epoch at `at`.
+fn hour_of(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(3600)
+}
+
+/// `up` out of `checks` as a percentage rounded to two decimals.
+fn percentage(up: u32, checks: u32) -> Option<f64> {
+    (checks > 0).then(|| (f64::from(up) * 10_000.0 / f64::from(checks)).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn reports_uptime_over_a_day_and_a_week() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 11, 12, 30, 0).unwrap();
+        let mut history = UptimeHistory::default();
+        // Down for the first check three days ago, then up twice an hour.
+        history.record("a", false, now - chrono::Duration::days(3));
+        for hours in 0..3 {
+            let at = now - chrono::Duration::hours(hours);
+            history.record("a", true, at);
+            history.record("a", true, at + chrono::Duration::minutes(10));
+        }
+
+        let report = history.report("a", now);
+        assert_eq!((report.checks_24h, report.checks_7d), (6, 7));
+        assert_eq!(report.uptime_24h, Some(100.0));
+        assert_eq!(report.uptime_7d, Some(85.71));
+        assert_eq!(report.series.len(), HISTORY_HOURS as usize);
+        let last = report.series.last().unwrap();
+        assert_eq!(
+            last.hour,
+            Utc.with_ymd_and_hms(2026, 2, 11, 12, 0, 0).unwrap()
+        );
+        assert_eq!((last.checks, last.uptime), (2, Some(100.0)));
+        assert_eq!(report.series[0].uptime, None);
+
+        let unknown = history.report("b", now);
+        assert_eq!((unknown.uptime_7d, unknown.checks_7d), (None, 0));
+    }
+
+    #[test]
+    fn drops_outcomes_older_than_a_week() {
+        let start = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        let mut history = UptimeHistory::default();
+        history.record("a", false, start);
+        history.record("a", true, start + chrono::Duration::hours(HISTORY_HOURS));
+        assert_eq!(history.channels["a"].len(), 1);
+
+        history.sync(&[]);
+        assert!(history.channels.is_empty());
+    }
+}
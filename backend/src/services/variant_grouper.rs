@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::models::{Channel, ChannelVariant};
+
+/// Recognised quality suffixes, mapped to their normalised label and rank
+/// (higher rank = better quality).
+const QUALITY_SUFFIXES: &[(&str, &str, u8)] = &[
+    ("uhd", "uhd", 4),
+    ("4k", "uhd", 4),
+    ("2160p", "uhd", 4),
+    ("fhd", "fhd", 3),
+    ("1080p", "fhd", 3),
+    ("1080i", "fhd", 3),
+    ("hd", "hd", 2),
+    ("720p", "hd", 2),
+    ("sd", "sd", 1),
+    ("576p", "sd", 1),
+    ("480p", "sd", 1),
+];
+
+/// Split a channel name into its stem and a normalised quality label.
+///
+/// Returns `None` when the name does not end in a recognised quality suffix
+/// (e.g. `"Channel HD"` → `Some(("Channel", "hd", 2))`, `"Channel"` → `None`).
+/// Suffixes wrapped in brackets or parentheses (`"Channel (FHD)"`) are accepted.
+pub fn detect_quality(name: &str) -> Option<(String, &'static str, u8)> {
+    let trimmed = name.trim();
+    let (stem, last) = trimmed.rsplit_once(char::is_whitespace)?;
+    let token = last
+        .trim_matches(|c: char| matches!(c, '(' | ')' | '[' | ']'))
+        .to_lowercase();
+
+    let (_, quality, rank) = QUALITY_SUFFIXES.iter().find(|(s, _, _)| *s == token)?;
+    let stem = stem.trim().trim_end_matches(['-', '|']).trim();
+    if stem.is_empty() {
+        return None;
+    }
+    Some((stem.to_string(), quality, *rank))
+}
+
+/// Merge channels that only differ by a quality suffix into a single canonical entry.
+///
+/// Channels are clustered by `(group, lowercase name stem)`. Each cluster with
+/// at least two members becomes one channel named after the stem, keeping the
+/// best-quality member's id, stream URL, and metadata, and listing every
+/// member in [`Channel::variants`] ordered best quality first. Channels without
+/// a quality suffix, and single-member clusters, are passed through unchanged.
+/// The canonical channel takes the position of the first member in the input.
+pub fn group_variants(channels: Vec<Channel>) -> Vec<Channel> {
+    // Map cluster key -> indices of members, in input order.
+    let mut clusters: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    let mut detected: Vec<Option<(String, &'static str, u8)>> = Vec::with_capacity(channels.len());
+
+    for (i, ch) in channels.iter().enumerate() {
+        let quality = detect_quality(&ch.name);
+        if let Some((ref stem, _, _)) = quality {
+            clusters
+                .entry((ch.group.clone(), stem.to_lowercase()))
+                .or_default()
+                .push(i);
+        }
+        detected.push(quality);
+    }
+
+    // Index of the first member -> remaining member indices, for multi-member clusters.
+    let mut leaders: HashMap<usize, Vec<usize>> = HashMap::new();
+    for members in clusters.into_values() {
+        if members.len() > 1 {
+            leaders.insert(members[0], members);
+        }
+    }
+
+    let mut slots: Vec<Option<Channel>> = channels.into_iter().map(Some).collect();
+    let mut result = Vec::with_capacity(slots.len());
+
+    for i in 0..slots.len() {
+        if let Some(members) = leaders.get(&i) {
+            let mut ranked: Vec<(u8, &'static str, Channel)> = members
+                .iter()
+                .filter_map(|&m| {
+                    let ch = slots[m].take()?;
+                    let (_, quality, rank) = detected[m].clone()?;
+                    Some((rank, quality, ch))
+                })
+                .collect();
+            // Stable sort keeps input order among equal qualities.
+            ranked.sort_by_key(|(rank, _, _)| std::cmp::Reverse(*rank));
+
+            let stem = detected[i]
+                .as_ref()
+                .map(|(stem, _, _)| stem.clone())
+                .unwrap_or_default();
+            let variants = ranked
+                .iter()
+                .map(|(_, quality, ch)| ChannelVariant {
+                    quality: (*quality).to_string(),
+                    stream_url: ch.stream_url.clone(),
+                })
+                .collect();
+
+            let mut iter = ranked.into_iter();
+            if let Some((_, _, mut canonical)) = iter.next() {
+                // Fill in metadata missing on the best variant from the others.
+                for (_, _, other) in iter {
+                    if canonical.logo_url.is_none() {
+                        canonical.logo_url = other.logo_url;
+                    }
+                    if canonical.tvg_id.is_none() {
+                        canonical.tvg_id = other.tvg_id;
+                    }
+                }
+                canonical.name = stem;
+                canonical.variants = variants;
+                result.push(canonical);
+            }
+        } else if let Some(ch) = slots[i].take() {
+            result.push(ch);
+        }
+    }
+
+    result
+}
+
+/// Pick the stream URL to export for `channel` given a preferred quality.
+///
+/// Falls back to the canonical `stream_url` when no preference is given or
+/// the channel has no variant with that quality.
+pub fn preferred_stream_url<'a>(channel: &'a Channel, prefer: Option<&str>) -> &'a str {
+    prefer
+        .and_then(|q| {
+            let q = q.to_lowercase();
+            channel.variants.iter().find(|v| v.quality == q)
+        })
+        .map(|v| v.stream_url.as_str())
+        .unwrap_or(&channel.stream_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_quality_suffixes() {
+        assert_eq!(
+            detect_quality("Channel HD"),
+            Some(("Channel".to_string(), "hd", 2))
+        );
+        assert_eq!(
+            detect_quality("Channel (FHD)"),
+            Some(("Channel".to_string(), "fhd", 3))
+        );
+        assert_eq!(
+            detect_quality("Sport 1 - 4K"),
+            Some(("Sport 1".to_string(), "uhd", 4))
+        );
+        assert_eq!(detect_quality("Channel"), None);
+        assert_eq!(detect_quality("HD"), None);
+    }
+
+    #[test]
+    fn groups_variants_into_canonical_channel() {
+        let channels = vec![
+            Channel::parsed("News SD", "News", "http://a/sd"),
+            Channel::parsed("Other", "News", "http://a/other"),
+            Channel::parsed("News FHD", "News", "http://a/fhd"),
+            Channel::parsed("News HD", "News", "http://a/hd"),
+        ];
+
+        let grouped = group_variants(channels);
+        assert_eq!(grouped.len(), 2);
+
+        let news = &grouped[0];
+        assert_eq!(news.name, "News");
+        assert_eq!(news.stream_url, "http://a/fhd");
+        let qualities: Vec<&str> = news.variants.iter().map(|v| v.quality.as_str()).collect();
+        assert_eq!(qualities, vec!["fhd", "hd", "sd"]);
+
+        assert_eq!(grouped[1].name, "Other");
+        assert!(grouped[1].variants.is_empty());
+    }
+
+    #[test]
+    fn does_not_group_across_groups_or_singletons() {
+        let channels = vec![
+            Channel::parsed("Movies HD", "Movies", "http://a/1"),
+            Channel::parsed("Movies SD", "Kids", "http://a/2"),
+        ];
+
+        let grouped = group_variants(channels);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].name, "Movies HD");
+        assert!(grouped[0].variants.is_empty());
+    }
+
+    #[test]
+    fn preferred_stream_url_falls_back_to_canonical() {
+        let grouped = group_variants(vec![
+            Channel::parsed("News HD", "News", "http://a/hd"),
+            Channel::parsed("News SD", "News", "http://a/sd"),
+        ]);
+        let news = &grouped[0];
+
+        assert_eq!(preferred_stream_url(news, Some("SD")), "http://a/sd");
+        assert_eq!(preferred_stream_url(news, Some("fhd")), "http://a/hd");
+        assert_eq!(preferred_stream_url(news, None), "http://a/hd");
+    }
+}
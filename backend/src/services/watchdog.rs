@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::task::{AbortHandle, JoinHandle};
+use tracing::{error, info, warn};
+
+use crate::models::AppState;
+
+/// Watchdog name of the background channel liveness checker.
+pub const CHANNEL_CHECKER: &str = "channel_checker";
+
+/// Watchdog name of the scheduled M3U source refresher.
+pub const SOURCE_REFRESHER: &str = "source_refresher";
+
+/// Watchdog name of the on-chain playlist watcher.
+pub const CHAIN_WATCHER: &str = "chain_watcher";
+
+/// Restarts a background task, returning the handle of the fresh task.
+pub type RestartFn = Box<dyn Fn(Arc<AppState>) -> JoinHandle<()> + Send + Sync>;
+
+/// Heartbeat bookkeeping for a single background task.
+#[derive(Debug)]
+struct TaskEntry {
+    /// Longest allowed gap between heartbeats before the task counts as stalled.
+    max_silence: Duration,
+    /// When the task last reported in.
+    last_beat: Instant,
+    /// Abort handle of the running task, used to detect death and to restart.
+    handle: Option<AbortHandle>,
+    /// How many times the watchdog has restarted this task.
+    restarts: u32,
+}
+
+/// Point-in-time health of a background task, as exposed by `/readyz` and `/metrics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    /// Task name (e.g. `"channel_checker"`).
+    pub name: String,
+    /// Seconds since the last heartbeat.
+    pub seconds_since_heartbeat: u64,
+    /// Allowed silence before the task is considered stalled.
+    pub max_silence_secs: u64,
+    /// Whether the task missed its heartbeat deadline or has exited.
+    pub stalled: bool,
+    /// Whether the underlying tokio task has exited.
+    pub finished: bool,
+    /// Number of watchdog-initiated restarts.
+    pub restarts: u32,
+}
+
+/// Tracks heartbeats from long-running background tasks.
+///
+/// Tasks [`register`](Watchdog::register) with a maximum silence, then call
+/// [`beat`](Watchdog::beat) whenever they make progress. A task that misses
+/// its deadline, or whose tokio task has exited, is reported as stalled.
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    tasks: Mutex<HashMap<&'static str, TaskEntry>>,
+}
+
+impl Watchdog {
+    /// Create a watchdog with no registered tasks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<&'static str, TaskEntry>> {
+        // A panic while holding the lock cannot leave the map inconsistent.
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a task, or update its deadline if already registered.
+    pub fn register(&self, name: &'static str, max_silence: Duration) {
+        let mut tasks = self.lock();
+        let entry = tasks.entry(name).or_insert_with(|| TaskEntry {
+            max_silence,
+            last_beat: Instant::now(),
+            handle: None,
+            restarts: 0,
+        });
+        entry.max_silence = max_silence;
+        entry.last_beat = Instant::now();
+    }
+
+    /// Attach the running tokio task so death can be detected and restarts can abort it.
+    pub fn attach(&self, name: &'static str, handle: AbortHandle) {
+        if let Some(entry) = self.lock().get_mut(name) {
+            entry.handle = Some(handle);
+        }
+    }
+
+    /// Record that `name` is alive and making progress.
+    pub fn beat(&self, name: &'static str) {
+        if let Some(entry) = self.lock().get_mut(name) {
+            entry.last_beat = Instant::now();
+        }
+    }
+
+    /// Snapshot the health of every registered task, sorted by name.
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        let tasks = self.lock();
+        let mut statuses: Vec<TaskStatus> = tasks
+            .iter()
+            .map(|(name, entry)| {
+                let silence = entry.last_beat.elapsed();
+                let finished = entry.handle.as_ref().is_some_and(|h| h.is_finished());
+                TaskStatus {
+                    name: (*name).to_string(),
+                    seconds_since_heartbeat: silence.as_secs(),
+                    max_silence_secs: entry.max_silence.as_secs(),
+                    stalled: finished || silence > entry.max_silence,
+                    finished,
+                    restarts: entry.restarts,
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    /// Whether every registered task is healthy.
+    pub fn all_healthy(&self) -> bool {
+        self.statuses().iter().all(|s| !s.stalled)
+    }
+
+    /// Abort the current task for `name` and record a restart.
+    fn begin_restart(&self, name: &'static str) {
+        if let Some(entry) = self.lock().get_mut(name) {
+            if let Some(handle) = entry.handle.take() {
+                handle.abort();
+            }
+            entry.restarts = entry.restarts.saturating_add(1);
+            entry.last_beat = Instant::now();
+        }
+    }
+}
+
+/// Spawn the watchdog loop.
+///
+/// Every `check_every`, stalled tasks are logged. When `restart` is enabled,
+/// stalled tasks that have an entry in `restarts` are aborted and respawned.
+pub fn start_watchdog(
+    state: Arc<AppState>,
+    check_every: Duration,
+    restart: bool,
+    restarts: HashMap<&'static str, RestartFn>,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(check_every);
+        loop {
+            ticker.tick().await;
+
+            for status in state.watchdog.statuses().into_iter().filter(|s| s.stalled) {
+                if status.finished {
+                    error!("Background task '{}' has exited", status.name);
+                } else {
+                    warn!(
+                        "Background task '{}' missed its heartbeat ({}s silent, limit {}s)",
+                        status.name, status.seconds_since_heartbeat, status.max_silence_secs
+                    );
+                }
+
+                if !restart {
+                    continue;
+                }
+                let Some((name, respawn)) = restarts.get_key_value(status.name.as_str()) else {
+                    continue;
+                };
+                info!("Restarting background task '{name}'");
+                state.watchdog.begin_restart(name);
+                let handle = respawn(Arc::clone(&state));
+                state.watchdog.attach(name, handle.abort_handle());
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_task_starts_healthy() {
+        let watchdog = Watchdog::new();
+        watchdog.register("task", Duration::from_secs(60));
+        assert!(watchdog.all_healthy());
+        assert_eq!(watchdog.statuses()[0].name, "task");
+    }
+
+    #[test]
+    fn missed_heartbeat_is_stalled() {
+        let watchdog = Watchdog::new();
+        watchdog.register("task", Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!watchdog.all_healthy());
+
+        watchdog.register("task", Duration::from_secs(60));
+        watchdog.beat("task");
+        assert!(watchdog.all_healthy());
+    }
+
+    #[tokio::test]
+    async fn finished_task_is_stalled() {
+        let watchdog = Watchdog::new();
+        watchdog.register("task", Duration::from_secs(60));
+        let handle = tokio::spawn(async {});
+        watchdog.attach("task", handle.abort_handle());
+        let _ = handle.await;
+
+        let status = &watchdog.statuses()[0];
+        assert!(status.finished);
+        assert!(status.stalled);
+    }
+
+    #[test]
+    fn beat_for_unknown_task_is_ignored() {
+        let watchdog = Watchdog::new();
+        watchdog.beat("missing");
+        assert!(watchdog.statuses().is_empty());
+    }
+}
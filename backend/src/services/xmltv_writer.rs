@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::epg::EpgSchedule;
+use crate::models::Channel;
+
+/// XMLTV timestamp layout, always written in UTC.
+const XMLTV_TIME_FORMAT: &str = "%Y%m%d%H%M%S +0000";
+
+/// The XMLTV channel ID a playlist channel is exported under: its
+/// `tvg-id`, or the channel ID when it has none.
+pub fn guide_id(channel: &Channel) -> &str {
+    channel.tvg_id.as_deref().unwrap_or(&channel.id)
+}
+
+/// Render `channels` and their cached schedules as an XMLTV document.
+///
+/// Each channel is written under [`guide_id`], so an M3U export linking
+/// this guide through `url-tvg` matches it by `tvg-id`. Channels without a
+/// schedule are listed with no programmes.
+pub fn render(channels: &[(&Channel, Option<&EpgSchedule>)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tv generator-info-name=\"iptv-backend\">\n",
+    );
+
+    for (ch, _) in channels {
+        out.push_str(&format!(
+            "  <channel id=\"{}\">\n    <display-name>{}</display-name>\n",
+            escape(guide_id(ch)),
+            escape(&ch.name)
+        ));
+        if let Some(ref logo) = ch.logo_url {
+            out.push_str(&format!("    <icon src=\"{}\"/>\n", escape(logo)));
+        }
+        out.push_str("  </channel>\n");
+    }
+
+    for (ch, schedule) in channels {
+        let Some(schedule) = schedule else {
+            continue;
+        };
+        let id = escape(guide_id(ch));
+        for prog in &schedule.programs {
+            out.push_str(&format!(
+                "  <programme start=\"{}\" stop=\"{}\" channel=\"{id}\">\n    <title>{}</title>\n",
+                format_time(prog.start),
+                format_time(prog.end),
+                escape(&prog.title)
+            ));
+            if let Some(ref desc) = prog.description {
+                out.push_str(&format!("    <desc>{}</desc>\n", escape(desc)));
+            }
+            if let Some(ref category) = prog.category {
+                out.push_str(&format!("    <category>{}</category>\n", escape(category)));
+            }
+            if let Some(ref icon) = prog.icon_url {
+                out.push_str(&format!("    <icon src=\"{}\"/>\n", escape(icon)));
+            }
+            out.push_str("  </programme>\n");
+        }
+    }
+
+    out.push_str("</tv>\n");
+    out
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format(XMLTV_TIME_FORMAT).to_string()
+}
+
+/// Escape `value` for XML text and attributes, dropping control characters
+/// XML 1.0 does not allow.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::epg::EpgProgram;
+    use chrono::TimeZone;
+
+    #[test]
+    fn renders_channels_and_programmes() {
+        let ch = Channel {
+            tvg_id: Some("News.us".to_string()),
+            ..Channel::sample("abc", "News & Co", "")
+        };
+        let schedule = EpgSchedule {
+            channel_id: "News.us".to_string(),
+            programs: vec![EpgProgram {
+                id: "News.us-1".to_string(),
+                channel_id: "News.us".to_string(),
+                title: "<Live>".to_string(),
+                description: Some("Headlines".to_string()),
+                start: Utc.with_ymd_and_hms(2026, 2, 11, 10, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2026, 2, 11, 11, 0, 0).unwrap(),
+                category: None,
+                icon_url: None,
+            }],
+        };
+        let bare = Channel::sample("xyz", "News & Co", "");
+
+        let xml = render(&[(&ch, Some(&schedule)), (&bare, None)]);
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <tv generator-info-name=\"iptv-backend\">\n  \
+             <channel id=\"News.us\">\n    <display-name>News &amp; Co</display-name>\n  </channel>\n  \
+             <channel id=\"xyz\">\n    <display-name>News &amp; Co</display-name>\n  </channel>\n  \
+             <programme start=\"20260211100000 +0000\" stop=\"20260211110000 +0000\" channel=\"News.us\">\n    \
+             <title>&lt;Live&gt;</title>\n    <desc>Headlines</desc>\n  </programme>\n\
+             </tv>\n"
+        );
+    }
+}
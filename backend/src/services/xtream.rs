@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use tracing::info;
+
+use crate::models::channel::M3uAttributes;
+use crate::models::Channel;
+use crate::services::m3u_parser;
+
+/// Timeout for each `player_api.php` request. Stream lists of large
+/// providers run to tens of megabytes.
+const API_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Catch-up mode players use for Xtream archives.
+const XTREAM_CATCHUP: &str = "xc";
+
+/// Errors that can occur while importing from an Xtream Codes panel.
+#[derive(Debug, Error)]
+pub enum XtreamError {
+    /// HTTP request failed.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The panel answered with something other than the expected JSON.
+    #[error("Unexpected player_api.php response: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The panel rejected the credentials or the account is not active.
+    #[error("Xtream login rejected: {0}")]
+    Auth(String),
+}
+
+/// Login for an Xtream Codes panel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct XtreamCredentials {
+    /// Panel base URL, e.g. `http://provider.example:8080`.
+    pub server: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl XtreamCredentials {
+    /// The panel URL without a trailing slash.
+    fn base(&self) -> &str {
+        self.server.trim_end_matches('/')
+    }
+
+    /// Playback URL of a stream, as Xtream panels lay them out:
+    /// `{server}/{kind}/{username}/{password}/{id}.{extension}`.
+    fn stream_url(&self, kind: &str, stream_id: &str, extension: &str) -> String {
+        format!(
+            "{}/{kind}/{}/{}/{stream_id}.{extension}",
+            self.base(),
+            self.username,
+            self.password
+        )
+    }
+
+    /// The panel's XMLTV guide for this account.
+    pub fn guide_url(&self) -> String {
+        format!(
+            "{}/xmltv.php?username={}&password={}",
+            self.base(),
+            self.username,
+            self.password
+        )
+    }
+
+    /// Value recorded as the playlist source. The password is left out.
+    pub fn label(&self) -> String {
+        format!("xtream:{}@{}", self.username, self.base())
+    }
+
+    /// Call `player_api.php` with `action`, or the login check without one.
+    async fn api(
+        &self,
+        client: &reqwest::Client,
+        action: Option<&str>,
+    ) -> Result<Value, XtreamError> {
+        let mut query = vec![
+            ("username", self.username.as_str()),
+            ("password", self.password.as_str()),
+        ];
+        if let Some(action) = action {
+            query.push(("action", action));
+        }
+        let body = client
+            .get(format!("{}/player_api.php", self.base()))
+            .query(&query)
+            .timeout(API_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Fetch the categories and streams of one kind (`live` or `vod`),
+    /// converted into channels.
+    async fn fetch_kind(
+        &self,
+        client: &reqwest::Client,
+        kind: StreamKind,
+        extension: &str,
+    ) -> Result<Vec<Channel>, XtreamError> {
+        let (categories_action, streams_action) = match kind {
+            StreamKind::Live => ("get_live_categories", "get_live_streams"),
+            StreamKind::Vod => ("get_vod_categories", "get_vod_streams"),
+        };
+        let categories = category_names(&self.api(client, Some(categories_action)).await?);
+        let streams = self.api(client, Some(streams_action)).await?;
+
+        Ok(streams
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|stream| self.to_channel(stream, kind, &categories, extension))
+            .collect())
+    }
+
+    /// Convert one entry of a stream list into a [`Channel`].
+    ///
+    /// Entries without a stream ID or name are skipped.
+    fn to_channel(
+        &self,
+        stream: &Value,
+        kind: StreamKind,
+        categories: &HashMap<String, String>,
+        live_extension: &str,
+    ) -> Option<Channel> {
+        let stream_id = field(stream, "stream_id")?;
+        let name = field(stream, "name")?;
+        let category = field(stream, "category_id")
+            .and_then(|id| categories.get(&id).cloned())
+            .unwrap_or_default();
+
+        let (stream_url, group, attributes) = match kind {
+            StreamKind::Live => {
+                let archive = field(stream, "tv_archive").is_some_and(|v| v == "1");
+                let attributes = M3uAttributes {
+                    tvg_chno: field(stream, "num"),
+                    catchup: archive.then(|| XTREAM_CATCHUP.to_string()),
+                    catchup_days: field(stream, "tv_archive_duration")
+                        .and_then(|days| days.parse().ok())
+                        .filter(|_| archive),
+                    ..Default::default()
+                };
+                let url = self.stream_url("live", &stream_id, live_extension);
+                (url, category, attributes)
+            }
+            StreamKind::Vod => {
+                let extension =
+                    field(stream, "container_extension").unwrap_or_else(|| "mp4".to_string());
+                let url = self.stream_url("movie", &stream_id, &extension);
+                let group = if category.is_empty() {
+                    "VOD".to_string()
+                } else {
+                    format!("VOD / {category}")
+                };
+                (url, group, M3uAttributes::default())
+            }
+        };
+
+        Some(Channel {
+            id: m3u_parser::hash_url(&stream_url),
+            name,
+            group,
+            logo_url: field(stream, "stream_icon"),
+            stream_url,
+            is_live: false,
+            tvg_id: field(stream, "epg_channel_id"),
+            variants: Vec::new(),
+            health_score: None,
+            stream_info: None,
+            health: Default::default(),
+            position: None,
+            attributes,
+        })
+    }
+}
+
+/// Which `player_api.php` stream list to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamKind {
+    Live,
+    Vod,
+}
+
+/// A non-empty field of a `player_api.php` object as a string.
+///
+/// Panels disagree on whether IDs and flags are numbers or strings, so
+/// both are accepted.
+fn field(value: &Value, key: &str) -> Option<String> {
+    let text = match value.get(key)? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Category ID → name map from a `get_*_categories` response.
+fn category_names(categories: &Value) -> HashMap<String, String> {
+    categories
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| Some((field(c, "category_id")?, field(c, "category_name")?)))
+        .collect()
+}
+
+/// Check the login and return the stream extension to use for live
+/// channels: `m3u8` when the account allows HLS output, `ts` otherwise.
+fn check_login(login: &Value) -> Result<&'static str, XtreamError> {
+    let user = login
+        .get("user_info")
+        .ok_or_else(|| XtreamError::Auth("no user_info in response".to_string()))?;
+    if field(user, "auth").as_deref() != Some("1") {
+        return Err(XtreamError::Auth(
+            "invalid username or password".to_string(),
+        ));
+    }
+    if let Some(status) = field(user, "status").filter(|s| s != "Active") {
+        return Err(XtreamError::Auth(format!("account status is {status}")));
+    }
+
+    let hls = user
+        .get("allowed_output_formats")
+        .and_then(Value::as_array)
+        .is_some_and(|formats| formats.iter().any(|f| f.as_str() == Some("m3u8")));
+    Ok(if hls { "m3u8" } else { "ts" })
+}
+
+/// Log in to an Xtream Codes panel and convert its live streams, and its
+/// VOD library when `include_vod` is set, into channels.
+///
+/// Live channels are grouped by category and keep their EPG ID, channel
+/// number and catch-up archive. VOD entries are grouped under
+/// `VOD / {category}`.
+pub async fn fetch_channels(
+    client: &reqwest::Client,
+    credentials: &XtreamCredentials,
+    include_vod: bool,
+) -> Result<Vec<Channel>, XtreamError> {
+    let extension = check_login(&credentials.api(client, None).await?)?;
+
+    let mut channels = credentials
+        .fetch_kind(client, StreamKind::Live, extension)
+        .await?;
+    if include_vod {
+        channels.extend(
+            credentials
+                .fetch_kind(client, StreamKind::Vod, extension)
+                .await?,
+        );
+    }
+
+    info!(
+        "Fetched {} streams from Xtream panel {}",
+        channels.len(),
+        credentials.base()
+    );
+    Ok(channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn credentials() -> XtreamCredentials {
+        XtreamCredentials {
+            server: "http://panel.example:8080/".to_string(),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn login_picks_output_format() {
+        let login = json!({"user_info": {
+            "auth": 1,
+            "status": "Active",
+            "allowed_output_formats": ["ts", "m3u8"]
+        }});
+        assert_eq!(check_login(&login).unwrap(), "m3u8");
+
+        let ts_only = json!({"user_info": {"auth": "1", "allowed_output_formats": ["ts"]}});
+        assert_eq!(check_login(&ts_only).unwrap(), "ts");
+
+        assert!(check_login(&json!({"user_info": {"auth": 0}})).is_err());
+        let expired = json!({"user_info": {"auth": 1, "status": "Expired"}});
+        assert!(check_login(&expired).is_err());
+    }
+
+    #[test]
+    fn converts_live_and_vod_streams() {
+        let creds = credentials();
+        let categories = category_names(&json!([
+            {"category_id": "3", "category_name": "News", "parent_id": 0},
+            {"category_id": 9, "category_name": "Movies"}
+        ]));
+
+        let live = json!({
+            "num": 12,
+            "name": "CNN",
+            "stream_id": 501,
+            "stream_icon": "",
+            "epg_channel_id": "CNN.us",
+            "category_id": "3",
+            "tv_archive": 1,
+            "tv_archive_duration": "3"
+        });
+        let ch = creds
+            .to_channel(&live, StreamKind::Live, &categories, "m3u8")
+            .unwrap();
+        assert_eq!(
+            ch.stream_url,
+            "http://panel.example:8080/live/alice/secret/501.m3u8"
+        );
+        assert_eq!(ch.group, "News");
+        assert_eq!(ch.tvg_id.as_deref(), Some("CNN.us"));
+        assert!(ch.logo_url.is_none());
+        assert_eq!(ch.attributes.tvg_chno.as_deref(), Some("12"));
+        assert_eq!(ch.attributes.catchup.as_deref(), Some("xc"));
+        assert_eq!(ch.attributes.catchup_days, Some(3));
+
+        let vod = json!({
+            "name": "Film",
+            "stream_id": "77",
+            "category_id": "9",
+            "container_extension": "mkv"
+        });
+        let ch = creds
+            .to_channel(&vod, StreamKind::Vod, &categories, "m3u8")
+            .unwrap();
+        assert_eq!(
+            ch.stream_url,
+            "http://panel.example:8080/movie/alice/secret/77.mkv"
+        );
+        assert_eq!(ch.group, "VOD / Movies");
+
+        let nameless = json!({"stream_id": 1});
+        assert!(creds
+            .to_channel(&nameless, StreamKind::Live, &categories, "ts")
+            .is_none());
+    }
+}
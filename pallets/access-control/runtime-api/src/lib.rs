@@ -0,0 +1,21 @@
+//! # Access Control Runtime API
+//!
+//! Lets off-chain services (the backend, wallets) ask the runtime whether an
+//! account may act on a resource, instead of re-implementing the pallet's rules.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_access_control::Action;
+
+sp_api::decl_runtime_apis! {
+    /// Authorization queries backed by `pallet_access_control::Pallet::can`.
+    pub trait AccessControlApi<AccountId>
+    where
+        AccountId: Codec,
+    {
+        /// Whether `account` may perform `action` on `resource_id` at this block.
+        fn can(account: AccountId, resource_id: u32, action: Action) -> bool;
+    }
+}
@@ -0,0 +1,356 @@
+//! Benchmarks for the access control pallet.
+//!
+//! Each call is measured in its worst case: the resource's member list is full
+//! (or one short of full for grants), so membership updates scan the whole list.
+//! Removing an admin also looks for another active admin; its benchmarks scan
+//! `m` members to find the only one, the others being suspended admins.
+
+use super::*;
+use crate::Pallet as AccessControl;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, Get, ReservableCurrency};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Saturating;
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+/// Resource every benchmark operates on.
+const RESOURCE: u32 = 1;
+
+/// Give `who` enough balance to cover many admin deposits.
+fn fund<T: Config>(who: &T::AccountId) {
+    let amount = T::AdminDeposit::get().saturating_mul(100u32.into());
+    T::Currency::make_free_balance_be(who, amount.saturating_add(T::Currency::minimum_balance()));
+}
+
+/// Make `admin` an `Admin` of [`RESOURCE`] and add viewers until the resource
+/// has `members` members.
+fn seed_resource<T: Config>(admin: &T::AccountId, members: u32) -> Result<(), BenchmarkError> {
+    fund::<T>(admin);
+    AccessControl::<T>::grant_role(
+        RawOrigin::Root.into(),
+        admin.clone(),
+        RESOURCE,
+        Role::Admin,
+        None,
+    )?;
+    for i in 1..members {
+        AccessControl::<T>::grant_role(
+            RawOrigin::Root.into(),
+            account("member", i, SEED),
+            RESOURCE,
+            Role::Viewer,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Grant `Admin` on [`RESOURCE`] to `count` fresh accounts and return them.
+fn seed_admins<T: Config>(count: u32) -> Result<Vec<T::AccountId>, BenchmarkError> {
+    let mut admins = Vec::new();
+    for i in 0..count {
+        let admin: T::AccountId = account("admin", i, SEED);
+        fund::<T>(&admin);
+        AccessControl::<T>::grant_role(
+            RawOrigin::Root.into(),
+            admin.clone(),
+            RESOURCE,
+            Role::Admin,
+            None,
+        )?;
+        admins.push(admin);
+    }
+    Ok(admins)
+}
+
+/// Grant `Admin` on [`RESOURCE`] to `count` fresh accounts, suspend them, and
+/// then make `caller` the only active admin. Returns the suspended admins.
+fn seed_suspended_admins<T: Config>(
+    caller: &T::AccountId,
+    count: u32,
+) -> Result<Vec<T::AccountId>, BenchmarkError> {
+    let admins = seed_admins::<T>(count)?;
+    let now = frame_system::Pallet::<T>::block_number();
+    for admin in &admins {
+        Suspended::<T>::insert(admin, RESOURCE, now);
+    }
+    fund::<T>(caller);
+    AccessControl::<T>::grant_role(
+        RawOrigin::Root.into(),
+        caller.clone(),
+        RESOURCE,
+        Role::Admin,
+        None,
+    )?;
+    Ok(admins)
+}
+
+fn assert_last_event<T: Config>(event: Event<T>) {
+    frame_system::Pallet::<T>::assert_last_event(<T as Config>::RuntimeEvent::from(event).into());
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn grant_role() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, T::MaxMembersPerResource::get().saturating_sub(1))?;
+        let grantee: T::AccountId = account("grantee", 0, SEED);
+        fund::<T>(&grantee);
+        let valid_until = frame_system::Pallet::<T>::block_number() + 100u32.into();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            grantee.clone(),
+            RESOURCE,
+            Role::Admin,
+            Some(valid_until),
+        );
+
+        assert_eq!(Roles::<T>::get(&grantee, RESOURCE), Some(Role::Admin));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn revoke_role(
+        m: Linear<2, { T::MaxMembersPerResource::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let target = seed_suspended_admins::<T>(&caller, m - 1)?.remove(0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), target.clone(), RESOURCE);
+
+        assert_last_event::<T>(Event::RoleRevoked {
+            account: target,
+            resource_id: RESOURCE,
+        });
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_revoke_role() -> Result<(), BenchmarkError> {
+        let admin: T::AccountId = account("admin", u32::MAX, SEED);
+        seed_resource::<T>(&admin, T::MaxMembersPerResource::get())?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, admin.clone(), RESOURCE);
+
+        assert_eq!(AccessControl::<T>::admin_count(RESOURCE), 0);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn grant_roles(n: Linear<1, { T::MaxBatchSize::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let members = T::MaxMembersPerResource::get().saturating_sub(n).max(1);
+        seed_resource::<T>(&caller, members)?;
+        let items: Vec<_> = (0..n)
+            .map(|i| {
+                let grantee: T::AccountId = account("grantee", i, SEED);
+                fund::<T>(&grantee);
+                (grantee, RESOURCE, Role::Admin)
+            })
+            .collect();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), items);
+
+        assert_eq!(AccessControl::<T>::admin_count(RESOURCE), n + 1);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn revoke_roles(
+        n: Linear<1, { T::MaxBatchSize::get() }>,
+        m: Linear<1, { T::MaxMembersPerResource::get().saturating_sub(T::MaxBatchSize::get()) }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        // Every other admin is suspended, so each removal scans all `m` others.
+        let items: Vec<_> = seed_suspended_admins::<T>(&caller, n + m - 1)?
+            .into_iter()
+            .take(n as usize)
+            .map(|admin| (admin, RESOURCE))
+            .collect();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), items);
+
+        assert_eq!(AccessControl::<T>::admin_count(RESOURCE), m);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn claim_resource() -> Result<(), BenchmarkError> {
+        if T::ReservedResources::contains(&RESOURCE) {
+            return Err(BenchmarkError::Skip);
+        }
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), RESOURCE);
+
+        assert_eq!(Owners::<T>::get(RESOURCE), Some(caller));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn transfer_ownership() {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        // Releasing a claim deposit is the expensive path.
+        Owners::<T>::insert(RESOURCE, &caller);
+        let deposit = T::AdminDeposit::get();
+        let _ = T::Currency::reserve(&caller, deposit);
+        OwnerDeposits::<T>::insert(RESOURCE, deposit);
+        let new_owner: T::AccountId = account("owner", 0, SEED);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), RESOURCE, new_owner.clone());
+
+        assert_eq!(Owners::<T>::get(RESOURCE), Some(new_owner));
+        assert!(!OwnerDeposits::<T>::contains_key(RESOURCE));
+    }
+
+    #[benchmark]
+    fn update_role(
+        m: Linear<2, { T::MaxMembersPerResource::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let admin = seed_suspended_admins::<T>(&caller, m - 1)?.remove(0);
+
+        // An admin stepping down without an owner reads the most storage.
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(admin.clone()),
+            admin.clone(),
+            RESOURCE,
+            Role::Viewer,
+        );
+
+        assert_eq!(Roles::<T>::get(&admin, RESOURCE), Some(Role::Viewer));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn delegate_granting() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, 1)?;
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            delegate.clone(),
+            RESOURCE,
+            Role::Editor,
+        );
+
+        assert_eq!(
+            Delegations::<T>::get(&delegate, RESOURCE),
+            Some(Role::Editor)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn revoke_delegation() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, 1)?;
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        Delegations::<T>::insert(&delegate, RESOURCE, Role::Editor);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), delegate.clone(), RESOURCE);
+
+        assert!(!Delegations::<T>::contains_key(&delegate, RESOURCE));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn suspend_role() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, 1)?;
+        let target = seed_admins::<T>(1)?.remove(0);
+        Owners::<T>::insert(RESOURCE, &caller);
+
+        // Suspending an admin also checks ownership.
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), target.clone(), RESOURCE);
+
+        assert!(Suspended::<T>::contains_key(&target, RESOURCE));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn unsuspend_role() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, 1)?;
+        let target = seed_admins::<T>(1)?.remove(0);
+        Suspended::<T>::insert(&target, RESOURCE, frame_system::Pallet::<T>::block_number());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), target.clone(), RESOURCE);
+
+        assert!(!Suspended::<T>::contains_key(&target, RESOURCE));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_permissions() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_resource::<T>(&caller, 1)?;
+        Owners::<T>::insert(RESOURCE, &caller);
+        let target = seed_admins::<T>(1)?.remove(0);
+
+        // Including MANAGE_ROLES also checks ownership.
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            target.clone(),
+            RESOURCE,
+            Permissions::ALL,
+        );
+
+        assert_eq!(
+            ExtraPermissions::<T>::get(&target, RESOURCE),
+            Some(Permissions::ALL)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn copy_roles(n: Linear<1, { T::MaxMembersPerResource::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let to = RESOURCE + 1;
+        // The caller and n - 1 admins on the source; only the caller on the target.
+        fund::<T>(&caller);
+        AccessControl::<T>::grant_role(
+            RawOrigin::Root.into(),
+            caller.clone(),
+            to,
+            Role::Admin,
+            None,
+        )?;
+        seed_resource::<T>(&caller, 1)?;
+        seed_admins::<T>(n - 1)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), RESOURCE, to);
+
+        assert_eq!(AccessControl::<T>::admin_count(to), n);
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(
+        AccessControl,
+        crate::tests::new_test_ext(),
+        crate::tests::Test
+    );
+}
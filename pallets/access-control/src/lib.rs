@@ -2,19 +2,166 @@
 //!
 //! A FRAME pallet for managing role-based access control on resources.
 //! Supports `Admin`, `Editor`, and `Viewer` roles, stored per-account per-resource.
+//! A role can be granted until a given block, after which it is treated as absent
+//! and cleaned up with the block's spare weight in `on_idle`.
+//!
+//! Each role implies a set of [`Permissions`]; admins can grant an account extra
+//! permissions on top of its role, such as `PUBLISH` for a `Viewer`.
+//!
+//! A role can also be suspended and later restored without losing its record.
+//!
+//! The latest role changes on each resource are kept in `RoleHistory`, so they can
+//! be queried on-chain long after their events are gone.
+//!
+//! Each `Admin` role is backed by a deposit, reserved from the account that grants
+//! it and released when the admin stops being one.
+//!
+//! Any account can claim an unowned resource for the same deposit, released when
+//! it transfers ownership. Its owner has implicit `Admin` rights
+//! and can appoint the first admins without root. Resources another pallet assigns
+//! to its users through [`ResourceAccess::set_owner`], such as playlists, are
+//! reserved in `ReservedResources` so nobody claims them first.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+use frame_support::traits::{Contains, EnsureOrigin, Get};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Call filter that allows every call of this pallet except destructive ones
+/// (currently `revoke_role`, `revoke_roles`, and `force_revoke_role`).
+///
+/// Runtimes can use it from their `BaseCallFilter` to disable revoking roles
+/// during an initial deployment phase:
+///
+/// ```ignore
+/// pub struct BaseFilter;
+/// impl Contains<RuntimeCall> for BaseFilter {
+///     fn contains(call: &RuntimeCall) -> bool {
+///         match call {
+///             RuntimeCall::AccessControl(c) => SafeCallFilter::<Runtime>::contains(c),
+///             _ => true,
+///         }
+///     }
+/// }
+/// ```
+pub struct SafeCallFilter<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Contains<Call<T>> for SafeCallFilter<T> {
+    fn contains(call: &Call<T>) -> bool {
+        !matches!(
+            call,
+            Call::revoke_role { .. } | Call::revoke_roles { .. } | Call::force_revoke_role { .. }
+        )
+    }
+}
+
+/// Role and ownership checks other pallets can depend on without depending on this
+/// pallet's `Config`.
+///
+/// A downstream pallet declares `type Access: ResourceAccess<Self::AccountId>` and the
+/// runtime wires it to `pallet_access_control::Pallet<Runtime>`.
+pub trait ResourceAccess<AccountId> {
+    /// Whether `who` currently holds `min_role` or a higher role on `resource_id`.
+    fn has_role(who: &AccountId, resource_id: u32, min_role: Role) -> bool;
+
+    /// Account that owns `resource_id`, if any. Defaults to none.
+    fn owner(_resource_id: u32) -> Option<AccountId> {
+        None
+    }
+
+    /// Make `owner` the owner of `resource_id`, replacing any previous owner.
+    /// Defaults to doing nothing.
+    fn set_owner(_resource_id: u32, _owner: &AccountId) {}
+}
+
+/// Grants nothing. Useful in mocks of pallets that do not exercise role checks.
+impl<AccountId> ResourceAccess<AccountId> for () {
+    fn has_role(_who: &AccountId, _resource_id: u32, _min_role: Role) -> bool {
+        false
+    }
+}
+
+impl<T: Config> ResourceAccess<T::AccountId> for Pallet<T> {
+    /// The resource owner counts as an `Admin`; expired roles count as none.
+    fn has_role(who: &T::AccountId, resource_id: u32, min_role: Role) -> bool {
+        if Self::owner(resource_id).as_ref() == Some(who) {
+            return true;
+        }
+        Self::active_role(who, resource_id).is_some_and(|role| role >= min_role)
+    }
+
+    fn owner(resource_id: u32) -> Option<T::AccountId> {
+        Owners::<T>::get(resource_id)
+    }
+
+    /// Emits `ResourceClaimed`, or `OwnershipTransferred` if the resource had
+    /// another owner. Roles on the resource are unchanged.
+    fn set_owner(resource_id: u32, owner: &T::AccountId) {
+        Self::assign_owner(resource_id, owner);
+    }
+}
+
+/// Origin check that passes for a signed account allowed to manage roles on the
+/// resource given by `ResourceId`: an unexpired `Admin` or the resource owner.
+///
+/// Succeeds with the signer's account. Root is not accepted; combine with
+/// `EnsureRoot` through `EitherOfDiverse` where it should be:
+///
+/// ```ignore
+/// parameter_types! { pub const CatalogueResource: u32 = 1; }
+/// type AdminOrigin = EnsureResourceAdmin<Runtime, CatalogueResource>;
+/// ```
+pub struct EnsureResourceAdmin<T, ResourceId>(core::marker::PhantomData<(T, ResourceId)>);
+
+impl<T: Config, ResourceId: Get<u32>> EnsureOrigin<T::RuntimeOrigin>
+    for EnsureResourceAdmin<T, ResourceId>
+{
+    type Success = T::AccountId;
+
+    fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+        o.into().and_then(|o| match o {
+            frame_system::RawOrigin::Signed(who)
+                if Pallet::<T>::can(&who, ResourceId::get(), Action::ManageRoles) =>
+            {
+                Ok(who)
+            }
+            r => Err(T::RuntimeOrigin::from(r)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+        use codec::Decode;
+        use sp_runtime::traits::TrailingZeroInput;
+
+        let who = T::AccountId::decode(&mut TrailingZeroInput::zeroes()).map_err(|_| ())?;
+        Owners::<T>::insert(ResourceId::get(), &who);
+        Ok(frame_system::RawOrigin::Signed(who).into())
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use codec::{Decode, Encode};
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Contains, ReservableCurrency},
+    };
     use frame_system::pallet_prelude::*;
     use scale_info::TypeInfo;
+    use sp_runtime::traits::Hash as HashT;
+    use sp_std::vec::Vec;
 
-    /// Role levels for resource access control.
+    use crate::WeightInfo;
+
+    /// Role levels for resource access control, ordered `Viewer` < `Editor` < `Admin`.
     #[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
     pub enum Role {
         /// Full control: can grant/revoke roles and modify the resource.
@@ -25,14 +172,193 @@ pub mod pallet {
         Viewer,
     }
 
+    /// Operations on a resource that off-chain services ask about with [`Pallet::can`].
+    #[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub enum Action {
+        /// Read the resource.
+        View,
+        /// Modify the resource.
+        Edit,
+        /// Grant and revoke roles on the resource.
+        ManageRoles,
+        /// Publish changes to the resource.
+        Publish,
+    }
+
+    /// A change to an account's role, as recorded in `RoleHistory`.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub enum RoleChange {
+        /// The role was granted.
+        Granted(Role),
+        /// The role was changed with `update_role`.
+        Updated { old: Role, new: Role },
+        /// The role was revoked.
+        Revoked(Role),
+        /// The role passed its `valid_until` block.
+        Expired(Role),
+        /// The role was suspended.
+        Suspended,
+        /// The role was restored.
+        Unsuspended,
+    }
+
+    /// One entry of `RoleHistory`.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub struct RoleChangeRecord<AccountId, BlockNumber> {
+        /// Account that made the change; `None` for root or automatic expiry.
+        pub by: Option<AccountId>,
+        /// Account whose role changed.
+        pub account: AccountId,
+        /// What changed.
+        pub change: RoleChange,
+        /// Block in which the change happened.
+        pub block: BlockNumber,
+    }
+
+    /// `RoleChangeRecord` for a runtime.
+    pub type RoleChangeRecordOf<T> =
+        RoleChangeRecord<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    /// An amount reserved from an account, as recorded in `AdminDeposits`.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub struct Deposit<AccountId, Balance> {
+        /// Account the amount is reserved from.
+        pub depositor: AccountId,
+        /// Amount reserved.
+        pub amount: Balance,
+    }
+
+    /// `Deposit` for a runtime.
+    pub type DepositOf<T> = Deposit<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+    /// Bitset of [`Action`]s an account may perform on a resource.
+    #[derive(
+        Clone, Copy, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug,
+    )]
+    pub struct Permissions(pub u8);
+
+    impl Permissions {
+        pub const NONE: Self = Self(0);
+        pub const VIEW: Self = Self(0b0001);
+        pub const EDIT: Self = Self(0b0010);
+        pub const MANAGE_ROLES: Self = Self(0b0100);
+        pub const PUBLISH: Self = Self(0b1000);
+        pub const ALL: Self = Self(0b1111);
+
+        /// Whether the set includes `action`.
+        pub fn allows(self, action: Action) -> bool {
+            let bit = Self::from(action).0;
+            self.0 & bit == bit
+        }
+
+        /// Whether the set only uses known bits.
+        pub fn is_valid(self) -> bool {
+            self.0 & !Self::ALL.0 == 0
+        }
+    }
+
+    impl From<Action> for Permissions {
+        fn from(action: Action) -> Self {
+            match action {
+                Action::View => Self::VIEW,
+                Action::Edit => Self::EDIT,
+                Action::ManageRoles => Self::MANAGE_ROLES,
+                Action::Publish => Self::PUBLISH,
+            }
+        }
+    }
+
+    impl core::ops::BitOr for Permissions {
+        type Output = Self;
+
+        fn bitor(self, rhs: Self) -> Self {
+            Self(self.0 | rhs.0)
+        }
+    }
+
+    impl Role {
+        /// Permissions this role implies.
+        pub fn permissions(self) -> Permissions {
+            match self {
+                Role::Admin => Permissions::ALL,
+                Role::Editor => Permissions::VIEW | Permissions::EDIT | Permissions::PUBLISH,
+                Role::Viewer => Permissions::VIEW,
+            }
+        }
+
+        /// Whether this role, by itself, allows `action`.
+        pub fn allows(self, action: Action) -> bool {
+            self.permissions().allows(action)
+        }
+
+        /// Position in the hierarchy, lowest first.
+        fn rank(self) -> u8 {
+            match self {
+                Role::Viewer => 0,
+                Role::Editor => 1,
+                Role::Admin => 2,
+            }
+        }
+    }
+
+    impl PartialOrd for Role {
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Role {
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+            self.rank().cmp(&other.rank())
+        }
+    }
+
+    /// Balance type of the pallet's currency.
+    pub type BalanceOf<T> = <<T as Config>::Currency as frame_support::traits::Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::Balance;
+
+    /// The in-code storage version; see [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
+    /// Tag hashed with a resource ID to form its event topic.
+    pub const RESOURCE_TOPIC: &[u8] = b"iptv/resource";
+
     /// Configuration trait for the access control pallet.
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// The overarching runtime event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Maximum number of accounts holding a role on a single resource.
+        #[pallet::constant]
+        type MaxMembersPerResource: Get<u32>;
+
+        /// Maximum number of items in a `grant_roles` or `revoke_roles` call.
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
+
+        /// Currency from which admin deposits are reserved.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Number of role changes kept per resource in `RoleHistory`.
+        #[pallet::constant]
+        type MaxHistory: Get<u32>;
+
+        /// Amount reserved for each `Admin` role, and for each claimed resource.
+        #[pallet::constant]
+        type AdminDeposit: Get<BalanceOf<Self>>;
+
+        /// Resources `claim_resource` refuses, because another pallet assigns their
+        /// owners through [`ResourceAccess::set_owner`](crate::ResourceAccess::set_owner).
+        type ReservedResources: Contains<u32>;
+
+        /// Weights for this pallet's calls.
+        type WeightInfo: WeightInfo;
     }
 
     /// Double map storing roles: (account, resource_id) -> Role.
@@ -41,21 +367,170 @@ pub mod pallet {
     pub type Roles<T: Config> =
         StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Role>;
 
+    /// Reverse index of `Roles`: resource_id -> accounts holding a role on it.
+    ///
+    /// Kept in sync by grants, revocations, and expiry, so members can be
+    /// enumerated without iterating `Roles`.
+    #[pallet::storage]
+    pub type ResourceMembers<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        BoundedVec<T::AccountId, T::MaxMembersPerResource>,
+        ValueQuery,
+    >;
+
+    /// Number of accounts holding `Admin` on each resource.
+    ///
+    /// Expired admins are counted until `on_idle` or a new grant removes them, and
+    /// suspended admins are counted too, so removing an admin checks the members
+    /// for an active one instead.
+    #[pallet::storage]
+    pub type AdminCount<T: Config> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
+
+    /// Deposit reserved for each admin: (account, resource_id) -> deposit.
+    ///
+    /// Reserved from the account that granted the role, or from the admin itself
+    /// when root granted it. Recorded so the same amount is released to the same
+    /// account even if `AdminDeposit` changes.
+    #[pallet::storage]
+    pub type AdminDeposits<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, DepositOf<T>>;
+
+    /// Deposit reserved from the account that claimed each resource: resource_id ->
+    /// amount.
+    ///
+    /// Released when the claimant hands ownership on.
+    #[pallet::storage]
+    pub type OwnerDeposits<T: Config> = StorageMap<_, Blake2_128Concat, u32, BalanceOf<T>>;
+
+    /// Last block at which a role in `Roles` is valid: (account, resource_id) -> block.
+    ///
+    /// Roles without an entry never expire.
+    #[pallet::storage]
+    pub type RoleExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        BlockNumberFor<T>,
+    >;
+
+    /// Owner of each claimed resource: resource_id -> account.
+    ///
+    /// The owner has implicit `Admin` rights without an entry in `Roles`.
+    #[pallet::storage]
+    pub type Owners<T: Config> = StorageMap<_, Blake2_128Concat, u32, T::AccountId>;
+
+    /// Granting rights delegated without `Admin`: (delegate, resource_id) -> highest
+    /// role the delegate may grant.
+    #[pallet::storage]
+    pub type Delegations<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Role>;
+
+    /// Permissions granted on top of a role: (account, resource_id) -> extra permissions.
+    ///
+    /// Only apply while the account holds an active role, and are cleared with it.
+    #[pallet::storage]
+    pub type ExtraPermissions<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u32, Permissions>;
+
+    /// Suspended roles: (account, resource_id) -> block the suspension started.
+    ///
+    /// A suspended role stays in `Roles` but allows nothing until unsuspended.
+    #[pallet::storage]
+    pub type Suspended<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        BlockNumberFor<T>,
+    >;
+
+    /// Latest role changes per resource, oldest first: resource_id -> records.
+    ///
+    /// Holds at most `MaxHistory` records; the oldest is dropped to make room.
+    #[pallet::storage]
+    pub type RoleHistory<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        BoundedVec<RoleChangeRecordOf<T>, T::MaxHistory>,
+        ValueQuery,
+    >;
+
+    /// Raw `RoleExpiry` key at which the next `on_idle` scan resumes.
+    #[pallet::storage]
+    pub type ExpiryCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>>;
+
     /// Events emitted by this pallet.
     #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// A role was granted to an account for a resource.
         RoleGranted {
             account: T::AccountId,
             resource_id: u32,
             role: Role,
+            valid_until: Option<BlockNumberFor<T>>,
+        },
+        /// An account's existing role on a resource was changed.
+        RoleUpdated {
+            account: T::AccountId,
+            resource_id: u32,
+            old: Role,
+            new: Role,
         },
         /// A role was revoked from an account for a resource.
         RoleRevoked {
             account: T::AccountId,
             resource_id: u32,
         },
+        /// A role passed its `valid_until` block and was removed.
+        RoleExpired {
+            account: T::AccountId,
+            resource_id: u32,
+            role: Role,
+        },
+        /// An unowned resource was claimed.
+        ResourceClaimed {
+            resource_id: u32,
+            owner: T::AccountId,
+        },
+        /// Ownership of a resource moved to another account.
+        OwnershipTransferred {
+            resource_id: u32,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// An account may now grant roles up to `max_role` on a resource.
+        GrantingDelegated {
+            delegate: T::AccountId,
+            resource_id: u32,
+            max_role: Role,
+        },
+        /// An account's delegated granting rights were removed.
+        DelegationRevoked {
+            delegate: T::AccountId,
+            resource_id: u32,
+        },
+        /// An account's role on a resource was suspended.
+        RoleSuspended {
+            account: T::AccountId,
+            resource_id: u32,
+        },
+        /// A suspended role was restored.
+        RoleUnsuspended {
+            account: T::AccountId,
+            resource_id: u32,
+        },
+        /// An account's extra permissions on a resource were replaced.
+        PermissionsSet {
+            account: T::AccountId,
+            resource_id: u32,
+            permissions: Permissions,
+        },
     }
 
     /// Errors that can occur in this pallet.
@@ -65,263 +540,2582 @@ pub mod pallet {
         NotAuthorized,
         /// No role was found for the specified account and resource.
         RoleNotFound,
+        /// The operation would leave an unowned resource without an active `Admin`.
+        /// Root can still remove the last admin with `force_revoke_role`.
+        CannotRemoveLastAdmin,
+        /// `valid_until` is before the current block.
+        ExpiryInPast,
+        /// The resource already has `MaxMembersPerResource` members.
+        TooManyMembers,
+        /// A batch call has more than `MaxBatchSize` items.
+        BatchTooLarge,
+        /// The resource already has an owner or admins.
+        AlreadyClaimed,
+        /// The resource is in `ReservedResources` and cannot be claimed.
+        ResourceReserved,
+        /// The caller is not the owner of the resource.
+        NotOwner,
+        /// The account already holds a different role; use `update_role` to change it.
+        RoleAlreadyAssigned,
+        /// Delegations are capped below `Admin`.
+        DelegationTooBroad,
+        /// The account has no delegation on the resource.
+        DelegationNotFound,
+        /// The role is already suspended.
+        AlreadySuspended,
+        /// The role is not suspended.
+        NotSuspended,
+        /// The permission set has unknown bits.
+        InvalidPermissions,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Remove expired roles using the block's leftover weight.
+        fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::expire_roles(now, remaining_weight)
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Grant a role to an account for a specific resource.
         ///
-        /// Only an existing `Admin` for the resource (or root origin) can grant roles.
+        /// An existing `Admin` for the resource, root, or a delegate whose `max_role` is
+        /// at least `role` can grant roles. A new `Admin` role reserves
+        /// `AdminDeposit` from the caller, or from `account` when root grants it.
+        /// Granting the role `account` already holds replaces its expiry; a different
+        /// role fails with `RoleAlreadyAssigned` and must be changed with `update_role`.
         ///
         /// - `account`: The account to receive the role.
         /// - `resource_id`: Numeric identifier of the resource.
         /// - `role`: The role to grant.
+        /// - `valid_until`: Last block at which the role is valid, or `None` for no expiry.
         ///
         /// Emits `RoleGranted` on success.
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::grant_role())]
         pub fn grant_role(
             origin: OriginFor<T>,
             account: T::AccountId,
             resource_id: u32,
             role: Role,
+            valid_until: Option<BlockNumberFor<T>>,
         ) -> DispatchResult {
-            // Allow root or a signed admin for this resource.
-            let maybe_who = Self::ensure_admin_or_root(origin, resource_id)?;
-
-            Roles::<T>::insert(&account, resource_id, role);
-
-            Self::deposit_event(Event::RoleGranted {
-                account,
-                resource_id,
-                role,
-            });
-
-            let _ = maybe_who;
-            Ok(())
+            Self::do_grant(origin, account, resource_id, role, valid_until)
         }
 
         /// Revoke a role from an account for a specific resource.
         ///
         /// An `Admin` for the resource, root, or the account itself can revoke.
+        /// Revoking the last active `Admin` of an unowned resource fails with
+        /// `CannotRemoveLastAdmin`; expired and suspended admins do not count. Use
+        /// `force_revoke_role` from root instead.
         ///
         /// - `account`: The account whose role should be revoked.
         /// - `resource_id`: Numeric identifier of the resource.
         ///
         /// Emits `RoleRevoked` on success.
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::revoke_role(T::MaxMembersPerResource::get()))]
         pub fn revoke_role(
             origin: OriginFor<T>,
             account: T::AccountId,
             resource_id: u32,
         ) -> DispatchResult {
-            Self::ensure_admin_root_or_self(origin, resource_id, &account)?;
+            Self::try_revoke(origin, account, resource_id)
+        }
 
-            // Ensure the role exists before removing.
-            Roles::<T>::get(&account, resource_id).ok_or(Error::<T>::RoleNotFound)?;
-            Roles::<T>::remove(&account, resource_id);
+        /// Revoke a role from an account, even if it is the last `Admin` of the resource.
+        ///
+        /// Root only. Used to recover or retire a resource. Also removes roles that
+        /// have expired but not yet been cleaned up.
+        ///
+        /// - `account`: The account whose role should be revoked.
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `RoleRevoked` on success.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::force_revoke_role())]
+        pub fn force_revoke_role(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
 
-            Self::deposit_event(Event::RoleRevoked {
-                account,
-                resource_id,
-            });
+            let role = Roles::<T>::get(&account, resource_id).ok_or(Error::<T>::RoleNotFound)?;
+            Self::do_revoke(None, account, resource_id, role);
+            Ok(())
+        }
 
+        /// Grant several roles in one call.
+        ///
+        /// Each `(account, resource_id, role)` item is checked and applied as by
+        /// `grant_role` without expiry, in order. The batch is atomic: if any item
+        /// fails, none are applied.
+        ///
+        /// Emits `RoleGranted` per item on success.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::grant_roles(items.len() as u32))]
+        pub fn grant_roles(
+            origin: OriginFor<T>,
+            items: Vec<(T::AccountId, u32, Role)>,
+        ) -> DispatchResult {
+            ensure!(
+                items.len() <= T::MaxBatchSize::get() as usize,
+                Error::<T>::BatchTooLarge
+            );
+            for (account, resource_id, role) in items {
+                Self::do_grant(origin.clone(), account, resource_id, role, None)?;
+            }
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Verify that the origin is either root or a signed account with `Admin` role
-        /// on the specified resource. Returns `Ok(Some(account))` for signed origins
-        /// and `Ok(None)` for root.
-        fn ensure_admin_or_root(
+        /// Revoke several roles in one call.
+        ///
+        /// Each `(account, resource_id)` item is checked and applied as by
+        /// `revoke_role`, in order. The batch is atomic: if any item fails, none
+        /// are applied.
+        ///
+        /// Emits `RoleRevoked` per item on success.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::revoke_roles(
+            items.len() as u32,
+            T::MaxMembersPerResource::get(),
+        ))]
+        pub fn revoke_roles(
             origin: OriginFor<T>,
-            resource_id: u32,
-        ) -> Result<Option<T::AccountId>, DispatchError> {
-            if ensure_root(origin.clone()).is_ok() {
-                return Ok(None);
+            items: Vec<(T::AccountId, u32)>,
+        ) -> DispatchResult {
+            ensure!(
+                items.len() <= T::MaxBatchSize::get() as usize,
+                Error::<T>::BatchTooLarge
+            );
+            for (account, resource_id) in items {
+                Self::try_revoke(origin.clone(), account, resource_id)?;
             }
+            Ok(())
+        }
+
+        /// Claim ownership of a resource that has no owner and no admins.
+        ///
+        /// The owner has implicit `Admin` rights, so they can appoint the first
+        /// admins themselves instead of asking root to seed them. Resources in
+        /// `ReservedResources` cannot be claimed. Reserves `AdminDeposit` from the
+        /// caller until they transfer ownership.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `ResourceClaimed` on success.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::claim_resource())]
+        pub fn claim_resource(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let caller_role =
-                Roles::<T>::get(&who, resource_id).ok_or(Error::<T>::NotAuthorized)?;
-            if caller_role != Role::Admin {
-                return Err(Error::<T>::NotAuthorized.into());
-            }
-            Ok(Some(who))
+            ensure!(
+                !T::ReservedResources::contains(&resource_id),
+                Error::<T>::ResourceReserved
+            );
+            ensure!(
+                !Owners::<T>::contains_key(resource_id) && Self::admin_count(resource_id) == 0,
+                Error::<T>::AlreadyClaimed
+            );
+
+            let deposit = T::AdminDeposit::get();
+            T::Currency::reserve(&who, deposit)?;
+            OwnerDeposits::<T>::insert(resource_id, deposit);
+            Owners::<T>::insert(resource_id, &who);
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::ResourceClaimed {
+                    resource_id,
+                    owner: who,
+                },
+            );
+            Ok(())
         }
 
-        /// Verify that the origin is root, an admin for the resource, or the target
-        /// account itself.
-        fn ensure_admin_root_or_self(
+        /// Hand ownership of a resource to another account.
+        ///
+        /// Only the current owner can transfer. Roles on the resource are unchanged.
+        /// The deposit of a claimed resource is released to the current owner; the
+        /// new owner pays none.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `new_owner`: The account that becomes owner.
+        ///
+        /// Emits `OwnershipTransferred` on success.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::transfer_ownership())]
+        pub fn transfer_ownership(
             origin: OriginFor<T>,
             resource_id: u32,
-            target: &T::AccountId,
-        ) -> Result<(), DispatchError> {
-            if ensure_root(origin.clone()).is_ok() {
-                return Ok(());
-            }
+            new_owner: T::AccountId,
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            // Allow self-revocation.
-            if &who == target {
+            ensure!(
+                Self::owner(resource_id).as_ref() == Some(&who),
+                Error::<T>::NotOwner
+            );
+
+            Self::release_owner_deposit(&who, resource_id);
+            Owners::<T>::insert(resource_id, &new_owner);
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::OwnershipTransferred {
+                    resource_id,
+                    from: who,
+                    to: new_owner,
+                },
+            );
+            Ok(())
+        }
+
+        /// Change the role an account already holds on a resource, keeping its expiry.
+        ///
+        /// An `Admin` for the resource (or root) can promote and change lower roles.
+        /// Demoting an `Admin` needs more: only root, the resource owner, or that admin
+        /// can do it, and never for the last active admin of an unowned resource.
+        ///
+        /// - `account`: The account whose role should change.
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `role`: The new role.
+        ///
+        /// Emits `RoleUpdated` if the role changed.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::update_role(T::MaxMembersPerResource::get()))]
+        pub fn update_role(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+            role: Role,
+        ) -> DispatchResult {
+            let caller = match ensure_root(origin.clone()) {
+                Ok(()) => None,
+                Err(_) => Some(ensure_signed(origin)?),
+            };
+            let old =
+                Self::unexpired_role(&account, resource_id).ok_or(Error::<T>::RoleNotFound)?;
+            if old == role {
                 return Ok(());
             }
-            let caller_role =
-                Roles::<T>::get(&who, resource_id).ok_or(Error::<T>::NotAuthorized)?;
-            if caller_role != Role::Admin {
-                return Err(Error::<T>::NotAuthorized.into());
+
+            if let Some(who) = &caller {
+                let allowed = if old == Role::Admin {
+                    who == &account || Self::owner(resource_id).as_ref() == Some(who)
+                } else {
+                    Self::can(who, resource_id, Action::ManageRoles)
+                };
+                ensure!(allowed, Error::<T>::NotAuthorized);
+            }
+            if old == Role::Admin {
+                ensure!(
+                    Self::can_lose_admin(&account, resource_id),
+                    Error::<T>::CannotRemoveLastAdmin
+                );
+                Self::remove_admin(&account, resource_id);
+            } else if role == Role::Admin {
+                Self::add_admin(caller.as_ref(), &account, resource_id)?;
             }
+
+            Roles::<T>::insert(&account, resource_id, role);
+            Self::record(
+                resource_id,
+                caller,
+                account.clone(),
+                RoleChange::Updated { old, new: role },
+            );
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleUpdated {
+                    account,
+                    resource_id,
+                    old,
+                    new: role,
+                },
+            );
             Ok(())
         }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use frame_support::{
-        assert_noop, assert_ok, derive_impl,
-        traits::{ConstU32, ConstU64},
-    };
-    use sp_core::H256;
-    use sp_io::TestExternalities;
-    use sp_runtime::{
-        traits::{BlakeTwo256, IdentityLookup},
-        BuildStorage,
-    };
-
-    type Block = frame_system::mocking::MockBlock<Test>;
 
-    frame_support::construct_runtime!(
-        pub enum Test {
-            System: frame_system,
-            AccessControl: pallet,
+        /// Let an account grant roles up to `max_role` on a resource without being an
+        /// `Admin`.
+        ///
+        /// Only an `Admin` for the resource (or root) can delegate. `max_role` must be
+        /// below `Admin`. Replaces any previous delegation of `delegate` on the resource.
+        ///
+        /// - `delegate`: The account receiving granting rights.
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `max_role`: The highest role the delegate may grant.
+        ///
+        /// Emits `GrantingDelegated` on success.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::delegate_granting())]
+        pub fn delegate_granting(
+            origin: OriginFor<T>,
+            delegate: T::AccountId,
+            resource_id: u32,
+            max_role: Role,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin, resource_id, None)?;
+            ensure!(max_role < Role::Admin, Error::<T>::DelegationTooBroad);
+
+            Delegations::<T>::insert(&delegate, resource_id, max_role);
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::GrantingDelegated {
+                    delegate,
+                    resource_id,
+                    max_role,
+                },
+            );
+            Ok(())
         }
-    );
 
-    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
-    impl frame_system::Config for Test {
-        type BaseCallFilter = frame_support::traits::Everything;
-        type BlockWeights = ();
-        type BlockLength = ();
-        type DbWeight = ();
-        type RuntimeOrigin = RuntimeOrigin;
-        type RuntimeCall = RuntimeCall;
-        type Nonce = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Block = Block;
-        type RuntimeEvent = RuntimeEvent;
-        type BlockHashCount = ConstU64<250>;
-        type Version = ();
-        type PalletInfo = PalletInfo;
-        type AccountData = ();
-        type OnNewAccount = ();
-        type OnKilledAccount = ();
-        type SystemWeightInfo = ();
-        type SS58Prefix = ();
-        type OnSetCode = ();
-        type MaxConsumers = ConstU32<16>;
+        /// Remove an account's delegated granting rights on a resource.
+        ///
+        /// An `Admin` for the resource, root, or the delegate itself can revoke.
+        /// Roles the delegate already granted are kept.
+        ///
+        /// - `delegate`: The account whose granting rights are removed.
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `DelegationRevoked` on success.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::revoke_delegation())]
+        pub fn revoke_delegation(
+            origin: OriginFor<T>,
+            delegate: T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            Self::ensure_admin_root_or_self(origin, resource_id, &delegate)?;
+            Delegations::<T>::take(&delegate, resource_id).ok_or(Error::<T>::DelegationNotFound)?;
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::DelegationRevoked {
+                    delegate,
+                    resource_id,
+                },
+            );
+            Ok(())
+        }
+
+        /// Temporarily disable an account's role on a resource, keeping the record.
+        ///
+        /// An `Admin` for the resource (or root) can suspend lower roles. Suspending
+        /// an `Admin` takes root or the resource owner, as demoting one does. The
+        /// role keeps its expiry and can still be revoked or updated.
+        ///
+        /// - `account`: The account whose role is suspended.
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `RoleSuspended` on success.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::suspend_role())]
+        pub fn suspend_role(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            let caller = Self::ensure_admin_or_root(origin, resource_id, None)?;
+            let role =
+                Self::unexpired_role(&account, resource_id).ok_or(Error::<T>::RoleNotFound)?;
+            if let (Some(who), Role::Admin) = (&caller, role) {
+                ensure!(
+                    Self::owner(resource_id).as_ref() == Some(who),
+                    Error::<T>::NotAuthorized
+                );
+            }
+            ensure!(
+                !Suspended::<T>::contains_key(&account, resource_id),
+                Error::<T>::AlreadySuspended
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+            Suspended::<T>::insert(&account, resource_id, now);
+            Self::record(resource_id, caller, account.clone(), RoleChange::Suspended);
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleSuspended {
+                    account,
+                    resource_id,
+                },
+            );
+            Ok(())
+        }
+
+        /// Restore a suspended role.
+        ///
+        /// Only an `Admin` for the resource (or root) can unsuspend.
+        ///
+        /// - `account`: The account whose role is restored.
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `RoleUnsuspended` on success.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::unsuspend_role())]
+        pub fn unsuspend_role(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            let caller = Self::ensure_admin_or_root(origin, resource_id, None)?;
+            Suspended::<T>::take(&account, resource_id).ok_or(Error::<T>::NotSuspended)?;
+            Self::record(
+                resource_id,
+                caller,
+                account.clone(),
+                RoleChange::Unsuspended,
+            );
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleUnsuspended {
+                    account,
+                    resource_id,
+                },
+            );
+            Ok(())
+        }
+
+        /// Replace the permissions an account has on top of its role on a resource.
+        ///
+        /// An `Admin` for the resource (or root) can set them, but only root or the
+        /// resource owner can include `MANAGE_ROLES`. The account must hold a role;
+        /// `Permissions::NONE` removes the extras.
+        ///
+        /// - `account`: The account receiving the permissions.
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `permissions`: The extra permissions.
+        ///
+        /// Emits `PermissionsSet` on success.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_permissions())]
+        pub fn set_permissions(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+            permissions: Permissions,
+        ) -> DispatchResult {
+            let caller = Self::ensure_admin_or_root(origin, resource_id, None)?;
+            ensure!(permissions.is_valid(), Error::<T>::InvalidPermissions);
+            if let Some(who) = &caller {
+                if permissions.allows(Action::ManageRoles) {
+                    ensure!(
+                        Self::owner(resource_id).as_ref() == Some(who),
+                        Error::<T>::NotAuthorized
+                    );
+                }
+            }
+            ensure!(
+                Self::unexpired_role(&account, resource_id).is_some(),
+                Error::<T>::RoleNotFound
+            );
+
+            if permissions == Permissions::NONE {
+                ExtraPermissions::<T>::remove(&account, resource_id);
+            } else {
+                ExtraPermissions::<T>::insert(&account, resource_id, permissions);
+            }
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::PermissionsSet {
+                    account,
+                    resource_id,
+                    permissions,
+                },
+            );
+            Ok(())
+        }
+
+        /// Copy every role on one resource to another, e.g. when a playlist is forked.
+        ///
+        /// The caller must be an `Admin` of both resources (or root). Each unexpired
+        /// role is granted on `to_resource` with the same expiry, except to accounts
+        /// that already hold a role there. Suspensions and extra permissions are not
+        /// copied. Fails without copying anything if `to_resource` would exceed
+        /// `MaxMembersPerResource` or the caller cannot cover the new admins'
+        /// deposits.
+        ///
+        /// - `from_resource`: Resource whose roles are copied.
+        /// - `to_resource`: Resource receiving the roles.
+        ///
+        /// Emits `RoleGranted` per copied role.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::copy_roles(T::MaxMembersPerResource::get()))]
+        pub fn copy_roles(
+            origin: OriginFor<T>,
+            from_resource: u32,
+            to_resource: u32,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin.clone(), from_resource, None)?;
+            let caller = Self::ensure_admin_or_root(origin, to_resource, None)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            for account in ResourceMembers::<T>::get(from_resource) {
+                if Self::is_expired(&account, from_resource, now)
+                    || Self::unexpired_role(&account, to_resource).is_some()
+                {
+                    continue;
+                }
+                let Some(role) = Roles::<T>::get(&account, from_resource) else {
+                    continue;
+                };
+                let valid_until = RoleExpiry::<T>::get(&account, from_resource);
+                Self::apply_grant(caller.clone(), account, to_resource, role, valid_until)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Topic under which every event about `resource_id` is indexed.
+        ///
+        /// Computed as `T::Hashing` of the SCALE-encoded `(b"iptv/resource", resource_id)`,
+        /// the same scheme the token-gate pallet uses, so a client can follow one
+        /// resource across pallets with `System::EventTopics`.
+        pub fn resource_topic(resource_id: u32) -> T::Hash {
+            <T::Hashing as HashT>::hash_of(&(RESOURCE_TOPIC, resource_id))
+        }
+
+        /// Deposit `event`, indexed under `topic`.
+        fn deposit_event(topic: T::Hash, event: Event<T>) {
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event_indexed(&[topic], event.into());
+        }
+
+        /// Number of accounts holding `Admin` on `resource_id`.
+        pub fn admin_count(resource_id: u32) -> u32 {
+            AdminCount::<T>::get(resource_id)
+        }
+
+        /// The role `account` holds on `resource_id`, ignoring expired and suspended roles.
+        pub fn active_role(account: &T::AccountId, resource_id: u32) -> Option<Role> {
+            if Suspended::<T>::contains_key(account, resource_id) {
+                return None;
+            }
+            Self::unexpired_role(account, resource_id)
+        }
+
+        /// The role `account` holds on `resource_id`, ignoring expired roles only.
+        fn unexpired_role(account: &T::AccountId, resource_id: u32) -> Option<Role> {
+            let now = frame_system::Pallet::<T>::block_number();
+            if Self::is_expired(account, resource_id, now) {
+                return None;
+            }
+            Roles::<T>::get(account, resource_id)
+        }
+
+        /// Whether `account` may perform `action` on `resource_id` right now.
+        ///
+        /// This is the authorization check the pallet's own calls rely on, and is
+        /// exposed through the runtime API so off-chain services get the same
+        /// answer. The owner may do anything; expired and suspended roles allow nothing.
+        pub fn can(account: &T::AccountId, resource_id: u32, action: Action) -> bool {
+            if Self::owner(resource_id).as_ref() == Some(account) {
+                return true;
+            }
+            Self::permissions(account, resource_id).allows(action)
+        }
+
+        /// Everything `account` may do on `resource_id` through its active role and
+        /// extra permissions. Ownership is not included.
+        pub fn permissions(account: &T::AccountId, resource_id: u32) -> Permissions {
+            match Self::active_role(account, resource_id) {
+                Some(role) => {
+                    role.permissions()
+                        | ExtraPermissions::<T>::get(account, resource_id).unwrap_or_default()
+                }
+                None => Permissions::NONE,
+            }
+        }
+
+        /// Owner of `resource_id`, if it has been claimed.
+        pub fn owner(resource_id: u32) -> Option<T::AccountId> {
+            Owners::<T>::get(resource_id)
+        }
+
+        /// Make `owner` the owner of `resource_id` and emit `ResourceClaimed`, or
+        /// `OwnershipTransferred` if it had another owner.
+        pub(crate) fn assign_owner(resource_id: u32, owner: &T::AccountId) {
+            let event = match Owners::<T>::get(resource_id) {
+                Some(from) if &from == owner => return,
+                Some(from) => {
+                    Self::release_owner_deposit(&from, resource_id);
+                    Event::OwnershipTransferred {
+                        resource_id,
+                        from,
+                        to: owner.clone(),
+                    }
+                }
+                None => Event::ResourceClaimed {
+                    resource_id,
+                    owner: owner.clone(),
+                },
+            };
+            Owners::<T>::insert(resource_id, owner);
+            Self::deposit_event(Self::resource_topic(resource_id), event);
+        }
+
+        /// Whether `account`, an `Admin` of `resource_id`, may be removed or
+        /// downgraded: the owner keeps the resource manageable, or another admin
+        /// remains that is neither expired nor suspended.
+        ///
+        /// Reads up to `MaxMembersPerResource` members.
+        fn can_lose_admin(account: &T::AccountId, resource_id: u32) -> bool {
+            if Owners::<T>::contains_key(resource_id) {
+                return true;
+            }
+            Self::admin_count(resource_id) > 1
+                && ResourceMembers::<T>::get(resource_id).iter().any(|member| {
+                    member != account && Self::active_role(member, resource_id) == Some(Role::Admin)
+                })
+        }
+
+        /// Whether `account`'s role on `resource_id` is past its `valid_until` at `now`.
+        fn is_expired(account: &T::AccountId, resource_id: u32, now: BlockNumberFor<T>) -> bool {
+            RoleExpiry::<T>::get(account, resource_id).is_some_and(|until| now > until)
+        }
+
+        /// Remove `account`'s expired role on `resource_id`, keep the admin count in
+        /// sync, and emit `RoleExpired`.
+        fn expire(account: T::AccountId, resource_id: u32) {
+            RoleExpiry::<T>::remove(&account, resource_id);
+            Suspended::<T>::remove(&account, resource_id);
+            ExtraPermissions::<T>::remove(&account, resource_id);
+            let Some(role) = Roles::<T>::take(&account, resource_id) else {
+                return;
+            };
+            Self::remove_member(&account, resource_id);
+            if role == Role::Admin {
+                Self::remove_admin(&account, resource_id);
+            }
+            Self::record(
+                resource_id,
+                None,
+                account.clone(),
+                RoleChange::Expired(role),
+            );
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleExpired {
+                    account,
+                    resource_id,
+                    role,
+                },
+            );
+        }
+
+        /// Scan `RoleExpiry` for roles expired at `now`, within `limit` weight.
+        ///
+        /// The scan resumes where the previous call stopped, so every expiring role
+        /// is eventually visited even when only a few fit in one block. Returns the
+        /// weight consumed.
+        fn expire_roles(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            // Taking and storing the cursor.
+            let mut used = db.reads_writes(1, 1);
+            // Visiting an entry, and removing its role, expiry, membership, admin count,
+            // and admin deposit, then recording it in the history.
+            let visit = db.reads(1);
+            let expire = db.reads_writes(6, 7);
+            let worst_case = visit.saturating_add(expire);
+            if used.any_gt(limit) {
+                return Weight::zero();
+            }
+
+            let mut iter = match ExpiryCursor::<T>::take() {
+                Some(cursor) => RoleExpiry::<T>::iter_from(cursor.into_inner()),
+                None => RoleExpiry::<T>::iter(),
+            };
+            let mut expired = Vec::new();
+            let mut exhausted = false;
+            while used.saturating_add(worst_case).all_lte(limit) {
+                let Some((account, resource_id, until)) = iter.next() else {
+                    exhausted = true;
+                    break;
+                };
+                used.saturating_accrue(visit);
+                if now > until {
+                    used.saturating_accrue(expire);
+                    expired.push((account, resource_id));
+                }
+            }
+            if !exhausted {
+                if let Ok(cursor) = BoundedVec::try_from(iter.last_raw_key().to_vec()) {
+                    ExpiryCursor::<T>::put(cursor);
+                }
+            }
+
+            for (account, resource_id) in expired {
+                Self::expire(account, resource_id);
+            }
+            used
+        }
+
+        /// Authorize and apply a grant for `grant_role` and `grant_roles`.
+        fn do_grant(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+            role: Role,
+            valid_until: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            // Allow root, a signed admin, or a delegate for this resource.
+            let caller = Self::ensure_admin_or_root(origin, resource_id, Some(role))?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            if let Some(until) = valid_until {
+                ensure!(until >= now, Error::<T>::ExpiryInPast);
+            }
+            Self::apply_grant(caller, account, resource_id, role, valid_until)
+        }
+
+        /// Give `account` `role` on `resource_id` until `valid_until` on behalf of
+        /// `by`, keep the membership and admin bookkeeping in sync, and emit
+        /// `RoleGranted`.
+        fn apply_grant(
+            by: Option<T::AccountId>,
+            account: T::AccountId,
+            resource_id: u32,
+            role: Role,
+            valid_until: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let now = frame_system::Pallet::<T>::block_number();
+            // Retire an expired role first so the admin count stays accurate.
+            if Self::is_expired(&account, resource_id, now) {
+                Self::expire(account.clone(), resource_id);
+            }
+
+            match Roles::<T>::get(&account, resource_id) {
+                Some(previous) => ensure!(previous == role, Error::<T>::RoleAlreadyAssigned),
+                None => {
+                    ResourceMembers::<T>::try_mutate(resource_id, |members| {
+                        members
+                            .try_push(account.clone())
+                            .map_err(|_| Error::<T>::TooManyMembers)
+                    })?;
+                    if role == Role::Admin {
+                        Self::add_admin(by.as_ref(), &account, resource_id)?;
+                    }
+                }
+            }
+
+            Roles::<T>::insert(&account, resource_id, role);
+            match valid_until {
+                Some(until) => RoleExpiry::<T>::insert(&account, resource_id, until),
+                None => RoleExpiry::<T>::remove(&account, resource_id),
+            }
+            Self::record(resource_id, by, account.clone(), RoleChange::Granted(role));
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleGranted {
+                    account,
+                    resource_id,
+                    role,
+                    valid_until,
+                },
+            );
+            Ok(())
+        }
+
+        /// Authorize and apply a revocation for `revoke_role` and `revoke_roles`.
+        fn try_revoke(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            let caller = Self::ensure_admin_root_or_self(origin, resource_id, &account)?;
+
+            // Ensure the role exists before removing.
+            let role =
+                Self::unexpired_role(&account, resource_id).ok_or(Error::<T>::RoleNotFound)?;
+            if role == Role::Admin {
+                ensure!(
+                    Self::can_lose_admin(&account, resource_id),
+                    Error::<T>::CannotRemoveLastAdmin
+                );
+            }
+
+            Self::do_revoke(caller, account, resource_id, role);
+            Ok(())
+        }
+
+        /// Reserve the admin deposit for `account` from `by` (`None` for root, in
+        /// which case from `account`) and count it as an admin of `resource_id`.
+        fn add_admin(
+            by: Option<&T::AccountId>,
+            account: &T::AccountId,
+            resource_id: u32,
+        ) -> DispatchResult {
+            let depositor = by.unwrap_or(account).clone();
+            let amount = T::AdminDeposit::get();
+            T::Currency::reserve(&depositor, amount)?;
+            AdminDeposits::<T>::insert(account, resource_id, Deposit { depositor, amount });
+            AdminCount::<T>::mutate(resource_id, |n| *n = n.saturating_add(1));
+            Ok(())
+        }
+
+        /// Release the deposit behind `account`'s admin role on `resource_id` and
+        /// stop counting it as an admin.
+        fn remove_admin(account: &T::AccountId, resource_id: u32) {
+            if let Some(deposit) = AdminDeposits::<T>::take(account, resource_id) {
+                T::Currency::unreserve(&deposit.depositor, deposit.amount);
+            }
+            AdminCount::<T>::mutate(resource_id, |n| *n = n.saturating_sub(1));
+        }
+
+        /// Release the deposit `owner` reserved to claim `resource_id`, if any.
+        fn release_owner_deposit(owner: &T::AccountId, resource_id: u32) {
+            if let Some(deposit) = OwnerDeposits::<T>::take(resource_id) {
+                T::Currency::unreserve(owner, deposit);
+            }
+        }
+
+        /// Append a change to the history of `resource_id`, dropping the oldest
+        /// record when it is full.
+        fn record(
+            resource_id: u32,
+            by: Option<T::AccountId>,
+            account: T::AccountId,
+            change: RoleChange,
+        ) {
+            let record = RoleChangeRecord {
+                by,
+                account,
+                change,
+                block: frame_system::Pallet::<T>::block_number(),
+            };
+            RoleHistory::<T>::mutate(resource_id, |history| {
+                if history.is_full() && !history.is_empty() {
+                    history.remove(0);
+                }
+                // Only fails when `MaxHistory` is zero, which disables the history.
+                let _ = history.try_push(record);
+            });
+        }
+
+        /// Drop `account` from the members of `resource_id`.
+        fn remove_member(account: &T::AccountId, resource_id: u32) {
+            ResourceMembers::<T>::mutate_exists(resource_id, |members| {
+                if let Some(list) = members {
+                    list.retain(|m| m != account);
+                    if list.is_empty() {
+                        *members = None;
+                    }
+                }
+            });
+        }
+
+        /// Remove `account`'s `role` on `resource_id` on behalf of `by`, keep the
+        /// admin count in sync, and emit `RoleRevoked`.
+        fn do_revoke(
+            by: Option<T::AccountId>,
+            account: T::AccountId,
+            resource_id: u32,
+            role: Role,
+        ) {
+            Roles::<T>::remove(&account, resource_id);
+            RoleExpiry::<T>::remove(&account, resource_id);
+            Suspended::<T>::remove(&account, resource_id);
+            ExtraPermissions::<T>::remove(&account, resource_id);
+            Self::remove_member(&account, resource_id);
+            if role == Role::Admin {
+                Self::remove_admin(&account, resource_id);
+            }
+            Self::record(resource_id, by, account.clone(), RoleChange::Revoked(role));
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::RoleRevoked {
+                    account,
+                    resource_id,
+                },
+            );
+        }
+
+        /// Verify that the origin is either root or a signed account with an unexpired
+        /// `Admin` role on the specified resource. When `granting` a role, a delegate
+        /// allowed to grant it passes too. Returns `Ok(Some(account))` for signed origins
+        /// and `Ok(None)` for root.
+        fn ensure_admin_or_root(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            granting: Option<Role>,
+        ) -> Result<Option<T::AccountId>, DispatchError> {
+            if ensure_root(origin.clone()).is_ok() {
+                return Ok(None);
+            }
+            let who = ensure_signed(origin)?;
+            let delegated = granting.is_some_and(|role| {
+                Delegations::<T>::get(&who, resource_id).is_some_and(|max_role| role <= max_role)
+            });
+            ensure!(
+                delegated || Self::can(&who, resource_id, Action::ManageRoles),
+                Error::<T>::NotAuthorized
+            );
+            Ok(Some(who))
+        }
+
+        /// Verify that the origin is root, an admin for the resource, or the target
+        /// account itself. Returns `Ok(Some(account))` for signed origins and
+        /// `Ok(None)` for root.
+        fn ensure_admin_root_or_self(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            target: &T::AccountId,
+        ) -> Result<Option<T::AccountId>, DispatchError> {
+            if ensure_root(origin.clone()).is_ok() {
+                return Ok(None);
+            }
+            let who = ensure_signed(origin)?;
+            // Allow self-revocation.
+            if &who == target {
+                return Ok(Some(who));
+            }
+            ensure!(
+                Self::can(&who, resource_id, Action::ManageRoles),
+                Error::<T>::NotAuthorized
+            );
+            Ok(Some(who))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+    use frame_support::{
+        assert_noop, assert_ok, derive_impl,
+        traits::{ConstU32, ConstU64, Contains, Hooks},
+        weights::Weight,
+    };
+    use sp_core::H256;
+    use sp_io::TestExternalities;
+    use sp_runtime::{
+        traits::{BlakeTwo256, Hash, IdentityLookup},
+        BuildStorage,
+    };
+
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    frame_support::construct_runtime!(
+        pub enum Test {
+            System: frame_system,
+            Balances: pallet_balances,
+            AccessControl: pallet,
+        }
+    );
+
+    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+    impl frame_system::Config for Test {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = ();
+        type BlockLength = ();
+        type DbWeight = ();
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Nonce = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Block = Block;
+        type RuntimeEvent = RuntimeEvent;
+        type BlockHashCount = ConstU64<250>;
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type AccountData = pallet_balances::AccountData<u64>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+        type SS58Prefix = ();
+        type OnSetCode = ();
+        type MaxConsumers = ConstU32<16>;
+    }
+
+    #[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+    impl pallet_balances::Config for Test {
+        type AccountStore = System;
+    }
+
+    /// Reserved per admin role in tests.
+    const ADMIN_DEPOSIT: u64 = 10;
+
+    /// Resources from 1000 up are assigned by another pallet in tests.
+    pub struct Reserved;
+
+    impl Contains<u32> for Reserved {
+        fn contains(resource_id: &u32) -> bool {
+            *resource_id >= 1000
+        }
+    }
+
+    impl pallet::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type MaxMembersPerResource = ConstU32<4>;
+        type MaxBatchSize = ConstU32<3>;
+        type MaxHistory = ConstU32<4>;
+        type Currency = Balances;
+        type AdminDeposit = ConstU64<ADMIN_DEPOSIT>;
+        type ReservedResources = Reserved;
+        type WeightInfo = ();
+    }
+
+    /// Build a test externalities instance with default genesis state.
+    /// Accounts 1 to 9 start with 100 units each; 10 and above start empty.
+    pub(crate) fn new_test_ext() -> TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis build should succeed in tests");
+        pallet_balances::GenesisConfig::<Test> {
+            balances: (1..10).map(|account| (account, 100)).collect(),
+        }
+        .assimilate_storage(&mut t)
+        .expect("balances genesis should succeed in tests");
+        let mut ext = TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    #[test]
+    fn grant_and_check_role() {
+        new_test_ext().execute_with(|| {
+            let account = 1u64;
+            let resource_id = 42u32;
+
+            // Root can grant any role.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                account,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+
+            // Verify storage.
+            assert_eq!(
+                pallet::Roles::<Test>::get(account, resource_id),
+                Some(pallet::Role::Admin)
+            );
+
+            // Verify event.
+            System::assert_last_event(
+                pallet::Event::<Test>::RoleGranted {
+                    account,
+                    resource_id,
+                    role: pallet::Role::Admin,
+                    valid_until: None,
+                }
+                .into(),
+            );
+
+            // Now the admin (account 1) can grant a role to account 2.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(account),
+                2u64,
+                resource_id,
+                pallet::Role::Editor,
+                None,
+            ));
+            assert_eq!(
+                pallet::Roles::<Test>::get(2u64, resource_id),
+                Some(pallet::Role::Editor)
+            );
+
+            // A non-admin (account 3) cannot grant roles.
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(3u64),
+                    4u64,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+        });
+    }
+
+    #[test]
+    fn revoke_role_works() {
+        new_test_ext().execute_with(|| {
+            let account = 1u64;
+            let resource_id = 10u32;
+
+            // Grant a role via root.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                account,
+                resource_id,
+                pallet::Role::Editor,
+                None,
+            ));
+            assert!(pallet::Roles::<Test>::get(account, resource_id).is_some());
+
+            // Self-revocation should work.
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(account),
+                account,
+                resource_id,
+            ));
+            assert!(pallet::Roles::<Test>::get(account, resource_id).is_none());
+
+            // Revoking a non-existent role should fail.
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(account), account, resource_id,),
+                pallet::Error::<Test>::RoleNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn call_indices_are_stable() {
+        // Downstream runtimes and signed transactions depend on these indices;
+        // changing one is a breaking change.
+        assert_eq!(
+            pallet::Call::<Test>::grant_role {
+                account: 1,
+                resource_id: 1,
+                role: pallet::Role::Viewer,
+                valid_until: None
+            }
+            .encode()[0],
+            0
+        );
+        assert_eq!(
+            pallet::Call::<Test>::revoke_role {
+                account: 1,
+                resource_id: 1
+            }
+            .encode()[0],
+            1
+        );
+        assert_eq!(
+            pallet::Call::<Test>::force_revoke_role {
+                account: 1,
+                resource_id: 1
+            }
+            .encode()[0],
+            2
+        );
+        assert_eq!(
+            pallet::Call::<Test>::grant_roles { items: vec![] }.encode()[0],
+            3
+        );
+        assert_eq!(
+            pallet::Call::<Test>::revoke_roles { items: vec![] }.encode()[0],
+            4
+        );
+        assert_eq!(
+            pallet::Call::<Test>::claim_resource { resource_id: 1 }.encode()[0],
+            5
+        );
+        assert_eq!(
+            pallet::Call::<Test>::transfer_ownership {
+                resource_id: 1,
+                new_owner: 2
+            }
+            .encode()[0],
+            6
+        );
+        assert_eq!(
+            pallet::Call::<Test>::update_role {
+                account: 1,
+                resource_id: 1,
+                role: pallet::Role::Viewer
+            }
+            .encode()[0],
+            7
+        );
+        assert_eq!(
+            pallet::Call::<Test>::delegate_granting {
+                delegate: 1,
+                resource_id: 1,
+                max_role: pallet::Role::Viewer
+            }
+            .encode()[0],
+            8
+        );
+        assert_eq!(
+            pallet::Call::<Test>::revoke_delegation {
+                delegate: 1,
+                resource_id: 1
+            }
+            .encode()[0],
+            9
+        );
+        assert_eq!(
+            pallet::Call::<Test>::suspend_role {
+                account: 1,
+                resource_id: 1
+            }
+            .encode()[0],
+            10
+        );
+        assert_eq!(
+            pallet::Call::<Test>::unsuspend_role {
+                account: 1,
+                resource_id: 1
+            }
+            .encode()[0],
+            11
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_permissions {
+                account: 1,
+                resource_id: 1,
+                permissions: pallet::Permissions::NONE
+            }
+            .encode()[0],
+            12
+        );
+        assert_eq!(
+            pallet::Call::<Test>::copy_roles {
+                from_resource: 1,
+                to_resource: 2
+            }
+            .encode()[0],
+            13
+        );
+    }
+
+    #[test]
+    fn safe_call_filter_blocks_destructive_calls() {
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::revoke_role {
+                account: 1,
+                resource_id: 1
+            }
+        ));
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::revoke_roles { items: vec![] }
+        ));
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::force_revoke_role {
+                account: 1,
+                resource_id: 1
+            }
+        ));
+        assert!(SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::grant_role {
+                account: 1,
+                resource_id: 1,
+                role: pallet::Role::Viewer,
+                valid_until: None,
+            }
+        ));
+    }
+
+    #[test]
+    fn last_admin_cannot_be_revoked() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 7u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+
+            // Neither self-revocation nor root can remove the only admin.
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(1u64), 1u64, resource_id),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::root(), 1u64, resource_id),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+
+            // With a second admin, the first can step down.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 2);
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(1u64),
+                1u64,
+                resource_id,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+        });
+    }
+
+    #[test]
+    fn last_admin_cannot_be_downgraded() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 8u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+
+            assert_noop!(
+                AccessControl::update_role(
+                    RuntimeOrigin::signed(1u64),
+                    1u64,
+                    resource_id,
+                    pallet::Role::Editor,
+                ),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+
+            // Re-granting Admin does not double count.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+
+            // Once another admin exists, the downgrade succeeds.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::signed(1u64),
+                1u64,
+                resource_id,
+                pallet::Role::Editor,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+            assert_eq!(
+                pallet::Roles::<Test>::get(1u64, resource_id),
+                Some(pallet::Role::Editor)
+            );
+        });
+    }
+
+    #[test]
+    fn expired_co_admins_do_not_count() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor};
+            let resource_id = 10u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Admin,
+                Some(5),
+            ));
+
+            // Before `on_idle` removes the expired admin, it is still counted, but
+            // the remaining admin can neither leave nor step down.
+            System::set_block_number(6);
+            assert_eq!(AccessControl::admin_count(resource_id), 2);
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(1u64), 1u64, resource_id),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+            assert_noop!(
+                AccessControl::update_role(RuntimeOrigin::signed(1u64), 1u64, resource_id, Editor,),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+        });
+    }
+
+    #[test]
+    fn suspended_co_admins_do_not_count() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor};
+            let resource_id = 11u32;
+            for account in [1u64, 2] {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    resource_id,
+                    Admin,
+                    None,
+                ));
+            }
+            assert_ok!(AccessControl::suspend_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+            ));
+
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(1u64), 1u64, resource_id),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+            assert_noop!(
+                AccessControl::update_role(RuntimeOrigin::signed(1u64), 1u64, resource_id, Editor,),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+
+            // The active admin can still remove the suspended one.
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+        });
+    }
+
+    #[test]
+    fn force_revoke_removes_last_admin() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 9u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+
+            // Only root may force.
+            assert_noop!(
+                AccessControl::force_revoke_role(RuntimeOrigin::signed(1u64), 1u64, resource_id),
+                sp_runtime::DispatchError::BadOrigin
+            );
+
+            assert_ok!(AccessControl::force_revoke_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 0);
+            assert!(pallet::Roles::<Test>::get(1u64, resource_id).is_none());
+            System::assert_last_event(
+                pallet::Event::<Test>::RoleRevoked {
+                    account: 1u64,
+                    resource_id,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn events_are_indexed_by_resource() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                42u32,
+                pallet::Role::Admin,
+                None,
+            ));
+
+            // The topic scheme is shared with the token-gate pallet; keep it stable.
+            let topic = BlakeTwo256::hash_of(&(b"iptv/resource".as_slice(), 42u32));
+            assert_eq!(AccessControl::resource_topic(42), topic);
+            assert_eq!(
+                System::events().last().expect("event deposited").topics,
+                vec![topic]
+            );
+            assert_eq!(
+                System::events()
+                    .iter()
+                    .filter(|r| r.topics.contains(&topic))
+                    .count(),
+                1
+            );
+        });
+    }
+
+    #[test]
+    fn expired_role_is_treated_as_absent() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 11u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+                Some(3),
+            ));
+
+            // Still valid at its last block.
+            System::set_block_number(3);
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(2u64),
+                3u64,
+                resource_id,
+                pallet::Role::Viewer,
+                None,
+            ));
+
+            System::set_block_number(4);
+            assert_eq!(AccessControl::active_role(&2u64, resource_id), None);
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(2u64),
+                    4u64,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(2u64), 2u64, resource_id),
+                pallet::Error::<Test>::RoleNotFound
+            );
+
+            // Re-granting retires the expired role first, so the admin count holds.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            System::assert_has_event(
+                pallet::Event::<Test>::RoleExpired {
+                    account: 2u64,
+                    resource_id,
+                    role: pallet::Role::Admin,
+                }
+                .into(),
+            );
+            assert_eq!(AccessControl::admin_count(resource_id), 2);
+            assert!(pallet::RoleExpiry::<Test>::get(2u64, resource_id).is_none());
+        });
+    }
+
+    #[test]
+    fn grant_rejects_expiry_in_the_past() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(5);
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    1u64,
+                    12u32,
+                    pallet::Role::Viewer,
+                    Some(4),
+                ),
+                pallet::Error::<Test>::ExpiryInPast
+            );
+        });
+    }
+
+    #[test]
+    fn on_idle_removes_expired_roles() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 13u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+                Some(3),
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                3u64,
+                resource_id,
+                pallet::Role::Viewer,
+                Some(10),
+            ));
+
+            System::set_block_number(4);
+            AccessControl::on_idle(4, Weight::MAX);
+
+            assert!(pallet::Roles::<Test>::get(2u64, resource_id).is_none());
+            assert!(pallet::RoleExpiry::<Test>::get(2u64, resource_id).is_none());
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+            System::assert_last_event(
+                pallet::Event::<Test>::RoleExpired {
+                    account: 2u64,
+                    resource_id,
+                    role: pallet::Role::Admin,
+                }
+                .into(),
+            );
+
+            // Unexpired roles are left alone.
+            assert_eq!(
+                pallet::Roles::<Test>::get(3u64, resource_id),
+                Some(pallet::Role::Viewer)
+            );
+            // A full scan leaves no cursor behind.
+            assert!(pallet::ExpiryCursor::<Test>::get().is_none());
+        });
+    }
+
+    #[test]
+    fn resource_members_track_grants_and_revocations() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 14u32;
+            let members = || pallet::ResourceMembers::<Test>::get(resource_id).into_inner();
+
+            for account in 1u64..=3 {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ));
+            }
+            assert_eq!(members(), vec![1, 2, 3]);
+
+            // Changing a member's role does not add them twice.
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+                pallet::Role::Admin,
+            ));
+            assert_eq!(members(), vec![1, 2, 3]);
+
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(1u64),
+                1u64,
+                resource_id,
+            ));
+            assert_eq!(members(), vec![2, 3]);
+
+            // Expired roles leave the index too.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                4u64,
+                resource_id,
+                pallet::Role::Viewer,
+                Some(2),
+            ));
+            System::set_block_number(3);
+            AccessControl::on_idle(3, Weight::MAX);
+            assert_eq!(members(), vec![2, 3]);
+        });
+    }
+
+    #[test]
+    fn resource_members_are_bounded() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 15u32;
+            for account in 1u64..=4 {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ));
+            }
+
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    5u64,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ),
+                pallet::Error::<Test>::TooManyMembers
+            );
+
+            // Existing members can still be updated.
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::root(),
+                4u64,
+                resource_id,
+                pallet::Role::Editor,
+            ));
+        });
+    }
+
+    #[test]
+    fn can_follows_role_and_expiry() {
+        new_test_ext().execute_with(|| {
+            use pallet::Action;
+            let resource_id = 16u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+                pallet::Role::Editor,
+                Some(5),
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                3u64,
+                resource_id,
+                pallet::Role::Viewer,
+                None,
+            ));
+
+            let all = [
+                Action::View,
+                Action::Edit,
+                Action::ManageRoles,
+                Action::Publish,
+            ];
+            assert!(all.iter().all(|&a| AccessControl::can(&1, resource_id, a)));
+            assert!(AccessControl::can(&2, resource_id, Action::Publish));
+            assert!(!AccessControl::can(&2, resource_id, Action::ManageRoles));
+            assert!(AccessControl::can(&3, resource_id, Action::View));
+            assert!(!AccessControl::can(&3, resource_id, Action::Edit));
+            assert!(!AccessControl::can(&4, resource_id, Action::View));
+
+            System::set_block_number(6);
+            assert!(!AccessControl::can(&2, resource_id, Action::View));
+        });
+    }
+
+    #[test]
+    fn resource_access_checks_minimum_role() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor, Viewer};
+            let resource_id = 17u32;
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(1u64),
+                resource_id
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Editor,
+                Some(5),
+            ));
+
+            let has = |who: u64, min_role| {
+                <AccessControl as ResourceAccess<u64>>::has_role(&who, resource_id, min_role)
+            };
+            assert!(has(1, Admin));
+            assert!(has(2, Viewer));
+            assert!(has(2, Editor));
+            assert!(!has(2, Admin));
+            assert!(!has(3, Viewer));
+
+            assert_eq!(
+                <AccessControl as ResourceAccess<u64>>::owner(resource_id),
+                Some(1)
+            );
+            assert_eq!(<AccessControl as ResourceAccess<u64>>::owner(99), None);
+
+            System::set_block_number(6);
+            assert!(!has(2, Viewer));
+        });
+    }
+
+    #[test]
+    fn ensure_resource_admin_origin() {
+        new_test_ext().execute_with(|| {
+            type AdminOrigin = EnsureResourceAdmin<Test, ConstU32<18>>;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                18u32,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                2u64,
+                18u32,
+                pallet::Role::Editor,
+                None,
+            ));
+
+            assert_eq!(
+                AdminOrigin::try_origin(RuntimeOrigin::signed(1)).ok(),
+                Some(1)
+            );
+            assert!(AdminOrigin::try_origin(RuntimeOrigin::signed(2)).is_err());
+            assert!(AdminOrigin::try_origin(RuntimeOrigin::root()).is_err());
+
+            // The owner passes without holding a role.
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(3u64),
+                19u32
+            ));
+            assert_eq!(
+                EnsureResourceAdmin::<Test, ConstU32<19>>::try_origin(RuntimeOrigin::signed(3))
+                    .ok(),
+                Some(3)
+            );
+        });
+    }
+
+    #[test]
+    fn update_role_guards_admin_demotion() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor, Viewer};
+            assert!(Admin > Editor && Editor > Viewer);
+            let resource_id = 23u32;
+            for (account, role, valid_until) in
+                [(1u64, Admin, None), (2, Admin, None), (3, Viewer, Some(10))]
+            {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    resource_id,
+                    role,
+                    valid_until,
+                ));
+            }
+
+            // grant_role no longer overwrites a different role.
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(1u64),
+                    3u64,
+                    resource_id,
+                    Editor,
+                    None,
+                ),
+                pallet::Error::<Test>::RoleAlreadyAssigned
+            );
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::signed(1u64),
+                3u64,
+                resource_id,
+                Editor,
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::RoleUpdated {
+                    account: 3,
+                    resource_id,
+                    old: Viewer,
+                    new: Editor,
+                }
+                .into(),
+            );
+            assert_eq!(pallet::RoleExpiry::<Test>::get(3u64, resource_id), Some(10));
+
+            // An admin can promote, but not demote another admin.
+            assert_noop!(
+                AccessControl::update_role(RuntimeOrigin::signed(1u64), 2u64, resource_id, Viewer),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+                Viewer,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 1);
+        });
+    }
+
+    #[test]
+    fn delegates_grant_up_to_their_cap() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor, Viewer};
+            let resource_id = 24u32;
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                Admin,
+                None,
+            ));
+            assert_noop!(
+                AccessControl::delegate_granting(
+                    RuntimeOrigin::signed(1u64),
+                    2u64,
+                    resource_id,
+                    Admin
+                ),
+                pallet::Error::<Test>::DelegationTooBroad
+            );
+            assert_ok!(AccessControl::delegate_granting(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Editor,
+            ));
+
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(2u64),
+                3u64,
+                resource_id,
+                Viewer,
+                None,
+            ));
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(2u64),
+                4u64,
+                resource_id,
+                Editor,
+                None,
+            ));
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(2u64),
+                    5u64,
+                    resource_id,
+                    Admin,
+                    None
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            // Delegates cannot manage roles otherwise.
+            assert_noop!(
+                AccessControl::revoke_role(RuntimeOrigin::signed(2u64), 3u64, resource_id),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_noop!(
+                AccessControl::delegate_granting(
+                    RuntimeOrigin::signed(2u64),
+                    3u64,
+                    resource_id,
+                    Viewer
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+
+            assert_ok!(AccessControl::revoke_delegation(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+            ));
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(2u64),
+                    5u64,
+                    resource_id,
+                    Viewer,
+                    None
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_eq!(pallet::Roles::<Test>::get(4u64, resource_id), Some(Editor));
+        });
+    }
+
+    #[test]
+    fn suspended_roles_allow_nothing_until_restored() {
+        new_test_ext().execute_with(|| {
+            use pallet::{
+                Action,
+                Role::{Admin, Editor},
+            };
+            let resource_id = 25u32;
+            for (account, role) in [(1u64, Admin), (2, Admin), (3, Editor)] {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    resource_id,
+                    role,
+                    None,
+                ));
+            }
+
+            assert_ok!(AccessControl::suspend_role(
+                RuntimeOrigin::signed(1u64),
+                3u64,
+                resource_id,
+            ));
+            assert!(!AccessControl::can(&3, resource_id, Action::View));
+            assert_eq!(pallet::Roles::<Test>::get(3u64, resource_id), Some(Editor));
+            assert_noop!(
+                AccessControl::suspend_role(RuntimeOrigin::signed(1u64), 3u64, resource_id),
+                pallet::Error::<Test>::AlreadySuspended
+            );
+
+            // Suspending an admin takes root or the owner.
+            assert_noop!(
+                AccessControl::suspend_role(RuntimeOrigin::signed(1u64), 2u64, resource_id),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_ok!(AccessControl::suspend_role(
+                RuntimeOrigin::root(),
+                2u64,
+                resource_id,
+            ));
+            assert_noop!(
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(2u64),
+                    4u64,
+                    resource_id,
+                    Editor,
+                    None
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+
+            assert_ok!(AccessControl::unsuspend_role(
+                RuntimeOrigin::signed(1u64),
+                3u64,
+                resource_id,
+            ));
+            assert!(AccessControl::can(&3, resource_id, Action::Edit));
+            System::assert_last_event(
+                pallet::Event::<Test>::RoleUnsuspended {
+                    account: 3,
+                    resource_id,
+                }
+                .into(),
+            );
+            assert_noop!(
+                AccessControl::unsuspend_role(RuntimeOrigin::signed(1u64), 3u64, resource_id),
+                pallet::Error::<Test>::NotSuspended
+            );
+
+            // Revoking a suspended role clears the suspension.
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+            ));
+            assert!(!pallet::Suspended::<Test>::contains_key(2u64, resource_id));
+        });
+    }
+
+    #[test]
+    fn extra_permissions_extend_a_role() {
+        new_test_ext().execute_with(|| {
+            use pallet::{
+                Action, Permissions,
+                Role::{Admin, Viewer},
+            };
+            let resource_id = 26u32;
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(1u64),
+                resource_id
+            ));
+            for (account, role) in [(2u64, Admin), (3, Viewer)] {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::signed(1u64),
+                    account,
+                    resource_id,
+                    role,
+                    None,
+                ));
+            }
+
+            assert_ok!(AccessControl::set_permissions(
+                RuntimeOrigin::signed(2u64),
+                3u64,
+                resource_id,
+                Permissions::PUBLISH,
+            ));
+            assert!(AccessControl::can(&3, resource_id, Action::Publish));
+            assert!(!AccessControl::can(&3, resource_id, Action::Edit));
+            assert_eq!(
+                AccessControl::permissions(&3, resource_id),
+                Permissions::VIEW | Permissions::PUBLISH
+            );
+
+            // Only the owner or root hands out role management.
+            assert_noop!(
+                AccessControl::set_permissions(
+                    RuntimeOrigin::signed(2u64),
+                    3u64,
+                    resource_id,
+                    Permissions::MANAGE_ROLES,
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert_noop!(
+                AccessControl::set_permissions(
+                    RuntimeOrigin::root(),
+                    3u64,
+                    resource_id,
+                    Permissions(1 << 7)
+                ),
+                pallet::Error::<Test>::InvalidPermissions
+            );
+            assert_noop!(
+                AccessControl::set_permissions(
+                    RuntimeOrigin::root(),
+                    4u64,
+                    resource_id,
+                    Permissions::EDIT
+                ),
+                pallet::Error::<Test>::RoleNotFound
+            );
+
+            // Extras lapse with the role.
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(2u64),
+                3u64,
+                resource_id,
+            ));
+            assert!(!pallet::ExtraPermissions::<Test>::contains_key(
+                3u64,
+                resource_id
+            ));
+        });
     }
 
-    impl pallet::Config for Test {
-        type RuntimeEvent = RuntimeEvent;
+    #[test]
+    fn admins_hold_a_deposit() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor};
+            let resource_id = 27u32;
+            // Root grants reserve from the new admin.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+                Admin,
+                None,
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), ADMIN_DEPOSIT);
+
+            // Other grants reserve from the granting admin.
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Admin,
+                None,
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), 2 * ADMIN_DEPOSIT);
+            assert_eq!(Balances::reserved_balance(2u64), 0);
+            assert_eq!(
+                pallet::AdminDeposits::<Test>::get(2u64, resource_id),
+                Some(pallet::Deposit {
+                    depositor: 1,
+                    amount: ADMIN_DEPOSIT
+                })
+            );
+
+            // Demotion and revocation both release it to whoever paid.
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::signed(2u64),
+                2u64,
+                resource_id,
+                Editor,
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), ADMIN_DEPOSIT);
+            assert_ok!(AccessControl::force_revoke_role(
+                RuntimeOrigin::root(),
+                1u64,
+                resource_id,
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), 0);
+
+            // Accounts that cannot cover the deposit cannot become admins through
+            // root, nor appoint admins.
+            assert!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                10u64,
+                resource_id,
+                Admin,
+                None,
+            )
+            .is_err());
+            assert!(pallet::Roles::<Test>::get(10u64, resource_id).is_none());
+            pallet::Owners::<Test>::insert(resource_id, 10u64);
+            assert!(AccessControl::grant_role(
+                RuntimeOrigin::signed(10u64),
+                3u64,
+                resource_id,
+                Admin,
+                None,
+            )
+            .is_err());
+            assert_eq!(Balances::reserved_balance(3u64), 0);
+        });
     }
 
-    /// Build a test externalities instance with default genesis state.
-    fn new_test_ext() -> TestExternalities {
-        let t = frame_system::GenesisConfig::<Test>::default()
-            .build_storage()
-            .expect("genesis build should succeed in tests");
-        let mut ext = TestExternalities::new(t);
-        ext.execute_with(|| System::set_block_number(1));
-        ext
+    #[test]
+    fn claims_hold_a_deposit_until_transferred() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 31u32;
+            assert!(
+                AccessControl::claim_resource(RuntimeOrigin::signed(10u64), resource_id).is_err()
+            );
+            assert!(pallet::Owners::<Test>::get(resource_id).is_none());
+
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(1u64),
+                resource_id
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), ADMIN_DEPOSIT);
+
+            // The new owner pays nothing, and the old one gets the deposit back.
+            assert_ok!(AccessControl::transfer_ownership(
+                RuntimeOrigin::signed(1u64),
+                resource_id,
+                2u64
+            ));
+            assert_eq!(Balances::reserved_balance(1u64), 0);
+            assert_eq!(Balances::reserved_balance(2u64), 0);
+            assert!(!pallet::OwnerDeposits::<Test>::contains_key(resource_id));
+        });
     }
 
     #[test]
-    fn grant_and_check_role() {
+    fn copy_roles_clones_assignments() {
         new_test_ext().execute_with(|| {
-            let account = 1u64;
-            let resource_id = 42u32;
+            use pallet::Role::{Admin, Editor, Viewer};
+            let (from, to) = (28u32, 29u32);
+            for (account, role, valid_until) in [
+                (1u64, Admin, None),
+                (2, Editor, Some(10)),
+                (3, Viewer, Some(1)),
+            ] {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    from,
+                    role,
+                    valid_until,
+                ));
+            }
+            for (account, role) in [(1u64, Admin), (4, Viewer)] {
+                assert_ok!(AccessControl::grant_role(
+                    RuntimeOrigin::root(),
+                    account,
+                    to,
+                    role,
+                    None,
+                ));
+            }
+            assert_noop!(
+                AccessControl::copy_roles(RuntimeOrigin::signed(4u64), from, to),
+                pallet::Error::<Test>::NotAuthorized
+            );
 
-            // Root can grant any role.
+            System::set_block_number(2);
+            assert_ok!(AccessControl::copy_roles(
+                RuntimeOrigin::signed(1u64),
+                from,
+                to
+            ));
+            assert_eq!(pallet::Roles::<Test>::get(2u64, to), Some(Editor));
+            assert_eq!(pallet::RoleExpiry::<Test>::get(2u64, to), Some(10));
+            // Account 3's role had expired.
+            assert!(pallet::Roles::<Test>::get(3u64, to).is_none());
+            assert_eq!(
+                pallet::ResourceMembers::<Test>::get(to).into_inner(),
+                vec![1, 4, 2]
+            );
+            assert_eq!(AccessControl::admin_count(to), 1);
+        });
+    }
+
+    #[test]
+    fn role_history_keeps_latest_changes() {
+        new_test_ext().execute_with(|| {
+            use pallet::{
+                Role::{Admin, Editor, Viewer},
+                RoleChange,
+            };
+            let resource_id = 33u32;
             assert_ok!(AccessControl::grant_role(
                 RuntimeOrigin::root(),
-                account,
+                1u64,
+                resource_id,
+                Admin,
+                None,
+            ));
+            System::set_block_number(2);
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Viewer,
+                Some(4),
+            ));
+            assert_ok!(AccessControl::update_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
+                resource_id,
+                Editor,
+            ));
+            assert_ok!(AccessControl::suspend_role(
+                RuntimeOrigin::signed(1u64),
+                2u64,
                 resource_id,
-                pallet::Role::Admin,
             ));
+            System::set_block_number(5);
+            AccessControl::on_idle(5, Weight::MAX);
 
-            // Verify storage.
+            let history = pallet::RoleHistory::<Test>::get(resource_id).into_inner();
+            let changes: Vec<_> = history.iter().map(|r| r.change.clone()).collect();
+            // Account 1's grant was dropped to stay within `MaxHistory`.
             assert_eq!(
-                pallet::Roles::<Test>::get(account, resource_id),
-                Some(pallet::Role::Admin)
+                changes,
+                vec![
+                    RoleChange::Granted(Viewer),
+                    RoleChange::Updated {
+                        old: Viewer,
+                        new: Editor
+                    },
+                    RoleChange::Suspended,
+                    RoleChange::Expired(Editor),
+                ]
             );
+            assert_eq!(history[0].by, Some(1));
+            assert_eq!(history[0].block, 2);
+            assert_eq!(history[3].by, None);
+            assert_eq!(history[3].account, 2);
+        });
+    }
 
-            // Verify event.
+    #[test]
+    fn batch_grant_and_revoke() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Editor, Viewer};
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                20u32,
+                Admin,
+                None,
+            ));
+
+            assert_ok!(AccessControl::grant_roles(
+                RuntimeOrigin::signed(1u64),
+                vec![
+                    (2u64, 20u32, Editor),
+                    (3u64, 20u32, Viewer),
+                    (4u64, 20u32, Admin)
+                ],
+            ));
+            assert_eq!(pallet::Roles::<Test>::get(3u64, 20u32), Some(Viewer));
+            assert_eq!(AccessControl::admin_count(20), 2);
             System::assert_last_event(
                 pallet::Event::<Test>::RoleGranted {
-                    account,
+                    account: 4u64,
+                    resource_id: 20,
+                    role: Admin,
+                    valid_until: None,
+                }
+                .into(),
+            );
+
+            assert_ok!(AccessControl::revoke_roles(
+                RuntimeOrigin::signed(1u64),
+                vec![(2u64, 20u32), (3u64, 20u32)],
+            ));
+            assert!(pallet::Roles::<Test>::get(2u64, 20u32).is_none());
+            assert!(pallet::Roles::<Test>::get(3u64, 20u32).is_none());
+        });
+    }
+
+    #[test]
+    fn batches_are_atomic_and_bounded() {
+        new_test_ext().execute_with(|| {
+            use pallet::Role::{Admin, Viewer};
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                21u32,
+                Admin,
+                None,
+            ));
+
+            // Account 1 is not an admin of resource 22, so the whole batch fails.
+            assert_noop!(
+                AccessControl::grant_roles(
+                    RuntimeOrigin::signed(1u64),
+                    vec![(2u64, 21u32, Viewer), (3u64, 22u32, Viewer)],
+                ),
+                pallet::Error::<Test>::NotAuthorized
+            );
+            assert!(pallet::Roles::<Test>::get(2u64, 21u32).is_none());
+
+            assert_noop!(
+                AccessControl::revoke_roles(
+                    RuntimeOrigin::root(),
+                    vec![(1u64, 21u32), (1u64, 21u32), (1u64, 21u32), (1u64, 21u32)],
+                ),
+                pallet::Error::<Test>::BatchTooLarge
+            );
+            assert_noop!(
+                AccessControl::revoke_roles(RuntimeOrigin::root(), vec![(1u64, 21u32)]),
+                pallet::Error::<Test>::CannotRemoveLastAdmin
+            );
+        });
+    }
+
+    #[test]
+    fn owner_bootstraps_admins() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 30u32;
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(1u64),
+                resource_id
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::ResourceClaimed {
                     resource_id,
-                    role: pallet::Role::Admin,
+                    owner: 1u64,
                 }
                 .into(),
             );
+            assert_noop!(
+                AccessControl::claim_resource(RuntimeOrigin::signed(2u64), resource_id),
+                pallet::Error::<Test>::AlreadyClaimed
+            );
 
-            // Now the admin (account 1) can grant a role to account 2.
+            // The owner needs no role to appoint the first admin.
+            assert!(AccessControl::can(
+                &1u64,
+                resource_id,
+                pallet::Action::ManageRoles
+            ));
             assert_ok!(AccessControl::grant_role(
-                RuntimeOrigin::signed(account),
+                RuntimeOrigin::signed(1u64),
                 2u64,
                 resource_id,
-                pallet::Role::Editor,
+                pallet::Role::Admin,
+                None,
             ));
-            assert_eq!(
-                pallet::Roles::<Test>::get(2u64, resource_id),
-                Some(pallet::Role::Editor)
+
+            // With an owner, the only admin can step down.
+            assert_ok!(AccessControl::revoke_role(
+                RuntimeOrigin::signed(2u64),
+                2u64,
+                resource_id,
+            ));
+            assert_eq!(AccessControl::admin_count(resource_id), 0);
+        });
+    }
+
+    #[test]
+    fn resources_with_admins_cannot_be_claimed() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(AccessControl::grant_role(
+                RuntimeOrigin::root(),
+                1u64,
+                31u32,
+                pallet::Role::Admin,
+                None,
+            ));
+            assert_noop!(
+                AccessControl::claim_resource(RuntimeOrigin::signed(2u64), 31u32),
+                pallet::Error::<Test>::AlreadyClaimed
             );
+        });
+    }
 
-            // A non-admin (account 3) cannot grant roles.
+    #[test]
+    fn reserved_resources_are_only_assigned_by_their_pallet() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 1000u32;
+            // Nobody can take a reserved resource before its pallet assigns it,
+            // and so grant themselves roles on it.
+            assert_noop!(
+                AccessControl::claim_resource(RuntimeOrigin::signed(3u64), resource_id),
+                pallet::Error::<Test>::ResourceReserved
+            );
+
+            <AccessControl as ResourceAccess<u64>>::set_owner(resource_id, &1u64);
+            System::assert_last_event(
+                pallet::Event::<Test>::ResourceClaimed {
+                    resource_id,
+                    owner: 1u64,
+                }
+                .into(),
+            );
             assert_noop!(
                 AccessControl::grant_role(
                     RuntimeOrigin::signed(3u64),
-                    4u64,
+                    3u64,
                     resource_id,
-                    pallet::Role::Viewer,
+                    pallet::Role::Editor,
+                    None,
                 ),
                 pallet::Error::<Test>::NotAuthorized
             );
+
+            <AccessControl as ResourceAccess<u64>>::set_owner(resource_id, &2u64);
+            System::assert_last_event(
+                pallet::Event::<Test>::OwnershipTransferred {
+                    resource_id,
+                    from: 1u64,
+                    to: 2u64,
+                }
+                .into(),
+            );
+            assert_eq!(
+                <AccessControl as ResourceAccess<u64>>::owner(resource_id),
+                Some(2u64)
+            );
         });
     }
 
     #[test]
-    fn revoke_role_works() {
+    fn transfer_ownership_moves_implicit_admin() {
         new_test_ext().execute_with(|| {
-            let account = 1u64;
-            let resource_id = 10u32;
+            let resource_id = 32u32;
+            assert_ok!(AccessControl::claim_resource(
+                RuntimeOrigin::signed(1u64),
+                resource_id
+            ));
+            assert_noop!(
+                AccessControl::transfer_ownership(RuntimeOrigin::signed(2u64), resource_id, 2u64),
+                pallet::Error::<Test>::NotOwner
+            );
 
-            // Grant a role via root.
-            assert_ok!(AccessControl::grant_role(
-                RuntimeOrigin::root(),
-                account,
+            assert_ok!(AccessControl::transfer_ownership(
+                RuntimeOrigin::signed(1u64),
                 resource_id,
-                pallet::Role::Editor,
+                2u64,
             ));
-            assert!(pallet::Roles::<Test>::get(account, resource_id).is_some());
-
-            // Self-revocation should work.
-            assert_ok!(AccessControl::revoke_role(
-                RuntimeOrigin::signed(account),
-                account,
+            assert_eq!(AccessControl::owner(resource_id), Some(2u64));
+            System::assert_last_event(
+                pallet::Event::<Test>::OwnershipTransferred {
+                    resource_id,
+                    from: 1u64,
+                    to: 2u64,
+                }
+                .into(),
+            );
+            assert!(!AccessControl::can(
+                &1u64,
                 resource_id,
+                pallet::Action::View
             ));
-            assert!(pallet::Roles::<Test>::get(account, resource_id).is_none());
-
-            // Revoking a non-existent role should fail.
             assert_noop!(
-                AccessControl::revoke_role(RuntimeOrigin::signed(account), account, resource_id,),
-                pallet::Error::<Test>::RoleNotFound
+                AccessControl::grant_role(
+                    RuntimeOrigin::signed(1u64),
+                    3u64,
+                    resource_id,
+                    pallet::Role::Viewer,
+                    None,
+                ),
+                pallet::Error::<Test>::NotAuthorized
             );
         });
     }
@@ -0,0 +1,162 @@
+//! Storage migrations for the access control pallet.
+//!
+//! Runtimes list them in their `Executive` migrations when upgrading from a
+//! release that stored the previous layout:
+//!
+//! ```ignore
+//! pub type Migrations = (pallet_access_control::migrations::v1::MigrateV0ToV1<Runtime>,);
+//! ```
+
+use super::*;
+use frame_support::{
+    migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use sp_runtime::Saturating;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// Version 1 indexes `Roles` in `ResourceMembers` and counts each resource's
+/// admins in `AdminCount`. Version 0 stored `Roles` alone, and grants made since
+/// the upgrade only index the roles they write.
+pub mod v1 {
+    use super::*;
+
+    /// Rebuild `ResourceMembers` and `AdminCount` from `Roles`.
+    ///
+    /// Roles on a resource beyond `MaxMembersPerResource` stay in `Roles` but are
+    /// left out of `ResourceMembers`. Admins from version 0 hold no deposit, so
+    /// removing them releases nothing.
+    pub struct InnerMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let cleared = ResourceMembers::<T>::clear(u32::MAX, None)
+                .unique
+                .saturating_add(AdminCount::<T>::clear(u32::MAX, None).unique);
+
+            let mut roles = 0u64;
+            for (account, resource_id, role) in Roles::<T>::iter() {
+                roles.saturating_inc();
+                let _ = ResourceMembers::<T>::try_mutate(resource_id, |members| {
+                    members.try_push(account)
+                });
+                if role == Role::Admin {
+                    AdminCount::<T>::mutate(resource_id, |n| n.saturating_inc());
+                }
+            }
+
+            // Per role: reading it, and its member list and admin count.
+            T::DbWeight::get().reads_writes(
+                roles.saturating_mul(3),
+                roles.saturating_mul(2).saturating_add(cleared.into()),
+            )
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((Roles::<T>::iter().count() as u32).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let roles = u32::decode(&mut &state[..])
+                .map_err(|_| "access-control: cannot decode the role count")?;
+            ensure!(
+                Roles::<T>::iter().count() as u32 == roles,
+                "access-control: roles changed"
+            );
+            for (resource_id, members) in ResourceMembers::<T>::iter() {
+                ensure!(
+                    members
+                        .iter()
+                        .all(|account| Roles::<T>::contains_key(account, resource_id)),
+                    "access-control: a member holds no role"
+                );
+            }
+            for (account, resource_id, role) in Roles::<T>::iter() {
+                let members = ResourceMembers::<T>::get(resource_id);
+                ensure!(
+                    members.contains(&account) || members.is_full(),
+                    "access-control: a role is missing from its members"
+                );
+                if role == Role::Admin {
+                    ensure!(
+                        AdminCount::<T>::get(resource_id) > 0,
+                        "access-control: an admin is not counted"
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// [`InnerMigrateV0ToV1`], run only while the on-chain storage version is 0,
+    /// which it then sets to 1.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::tests::{new_test_ext, AccessControl, RuntimeOrigin, Test};
+        use frame_support::{
+            assert_noop, assert_ok,
+            traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+        };
+
+        /// Store roles as version 0 did, without any index.
+        fn seed_v0() {
+            StorageVersion::new(0).put::<AccessControl>();
+            Roles::<Test>::insert(1u64, 7u32, Role::Admin);
+            Roles::<Test>::insert(2u64, 7u32, Role::Admin);
+            Roles::<Test>::insert(3u64, 7u32, Role::Viewer);
+            Roles::<Test>::insert(1u64, 8u32, Role::Editor);
+        }
+
+        #[test]
+        fn members_and_admins_are_rebuilt_from_roles() {
+            new_test_ext().execute_with(|| {
+                seed_v0();
+                // A stale count left by a grant made after the upgrade.
+                AdminCount::<Test>::insert(7u32, 5);
+
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+                let mut members = ResourceMembers::<Test>::get(7u32).into_inner();
+                members.sort();
+                assert_eq!(members, vec![1, 2, 3]);
+                assert_eq!(ResourceMembers::<Test>::get(8u32).into_inner(), vec![1]);
+                assert_eq!(AccessControl::admin_count(7), 2);
+                assert_eq!(AccessControl::admin_count(8), 0);
+                assert_eq!(AccessControl::on_chain_storage_version(), 1);
+
+                // With the index rebuilt, one admin can step down but not both.
+                assert_ok!(AccessControl::revoke_role(RuntimeOrigin::signed(1), 1, 7,));
+                assert_noop!(
+                    AccessControl::revoke_role(RuntimeOrigin::signed(2), 2, 7),
+                    Error::<Test>::CannotRemoveLastAdmin
+                );
+
+                // Running it again leaves the new values alone.
+                AdminCount::<Test>::insert(7u32, 5);
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+                assert_eq!(AccessControl::admin_count(7), 5);
+            });
+        }
+
+        #[cfg(feature = "try-runtime")]
+        #[test]
+        fn try_runtime_checks_pass() {
+            new_test_ext().execute_with(|| {
+                seed_v0();
+                assert_ok!(MigrateV0ToV1::<Test>::try_on_runtime_upgrade(true));
+                assert_eq!(AccessControl::on_chain_storage_version(), 1);
+            });
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! Weights for `pallet_access_control`.
+//!
+//! These defaults are estimated from the storage each call touches. Runtimes should
+//! replace them with weights measured from `benchmarking.rs` on their own hardware:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime <runtime.wasm> \
+//!     --pallet pallet_access_control \
+//!     --extrinsic "*" \
+//!     --output pallets/access-control/src/weights.rs
+//! ```
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use core::marker::PhantomData;
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for `pallet_access_control`.
+pub trait WeightInfo {
+    fn grant_role() -> Weight;
+    fn revoke_role(m: u32) -> Weight;
+    fn force_revoke_role() -> Weight;
+    fn grant_roles(n: u32) -> Weight;
+    fn revoke_roles(n: u32, m: u32) -> Weight;
+    fn claim_resource() -> Weight;
+    fn transfer_ownership() -> Weight;
+    fn update_role(m: u32) -> Weight;
+    fn delegate_granting() -> Weight;
+    fn revoke_delegation() -> Weight;
+    fn suspend_role() -> Weight;
+    fn unsuspend_role() -> Weight;
+    fn set_permissions() -> Weight;
+    fn copy_roles(n: u32) -> Weight;
+}
+
+/// Weights for `pallet_access_control` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:1 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    fn grant_role() -> Weight {
+        Weight::from_parts(31_450_000, 10_687)
+            .saturating_add(T::DbWeight::get().reads(9_u64))
+            .saturating_add(T::DbWeight::get().writes(7_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:1 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:100 w:1)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// The range of component `m` is `[2, MaxMembersPerResource]`.
+    fn revoke_role(m: u32) -> Weight {
+        Weight::from_parts(29_870_000, 10_687)
+            .saturating_add(Weight::from_parts(4_310_000, 0).saturating_mul(m.into()))
+            .saturating_add(T::DbWeight::get().reads(10_u64))
+            .saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(m.into())))
+            .saturating_add(T::DbWeight::get().writes(7_u64))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(m.into()))
+    }
+    /// Storage: `AccessControl::Roles` (r:1 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:1 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:0 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    fn force_revoke_role() -> Weight {
+        Weight::from_parts(24_120_000, 10_687)
+            .saturating_add(T::DbWeight::get().reads(6_u64))
+            .saturating_add(T::DbWeight::get().writes(7_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:1 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxBatchSize]`.
+    fn grant_roles(n: u32) -> Weight {
+        Weight::from_parts(4_210_000, 10_687)
+            .saturating_add(Weight::from_parts(27_640_000, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+            .saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(n.into()))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:1 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:100 w:1)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxBatchSize]`.
+    /// The range of component `m` is `[1, MaxMembersPerResource - MaxBatchSize]`.
+    fn revoke_roles(n: u32, m: u32) -> Weight {
+        Weight::from_parts(3_980_000, 10_687)
+            .saturating_add(Weight::from_parts(26_310_000, 0).saturating_mul(n.into()))
+            .saturating_add(
+                Weight::from_parts(4_310_000, 0)
+                    .saturating_mul(n.into())
+                    .saturating_mul(m.into()),
+            )
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(n.into())))
+            .saturating_add(
+                T::DbWeight::get().reads((3_u64).saturating_mul(n.into()).saturating_mul(m.into())),
+            )
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+            .saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(n.into()))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:1)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:0)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::OwnerDeposits` (r:0 w:1)
+    /// Proof: `AccessControl::OwnerDeposits` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+    fn claim_resource() -> Weight {
+        Weight::from_parts(21_640_000, 3593)
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:1)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::OwnerDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::OwnerDeposits` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    fn transfer_ownership() -> Weight {
+        Weight::from_parts(22_170_000, 3593)
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    /// Storage: `AccessControl::RoleExpiry` (r:1 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:1 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:1 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:100 w:1)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// The range of component `m` is `[2, MaxMembersPerResource]`.
+    fn update_role(m: u32) -> Weight {
+        Weight::from_parts(19_760_000, 10_687)
+            .saturating_add(Weight::from_parts(4_310_000, 0).saturating_mul(m.into()))
+            .saturating_add(T::DbWeight::get().reads(7_u64))
+            .saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(m.into())))
+            .saturating_add(T::DbWeight::get().writes(5_u64))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(m.into()))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:1 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:1 w:0)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Delegations` (r:0 w:1)
+    /// Proof: `AccessControl::Delegations` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    fn delegate_granting() -> Weight {
+        Weight::from_parts(17_230_000, 3537)
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:1 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:1 w:0)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Delegations` (r:1 w:1)
+    /// Proof: `AccessControl::Delegations` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    fn revoke_delegation() -> Weight {
+        Weight::from_parts(18_940_000, 3537)
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:2 w:1)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:0)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    fn suspend_role() -> Weight {
+        Weight::from_parts(21_360_000, 10_687)
+            .saturating_add(T::DbWeight::get().reads(8_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:2 w:1)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:1 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:1 w:0)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    fn unsuspend_role() -> Weight {
+        Weight::from_parts(18_510_000, 10_687)
+            .saturating_add(T::DbWeight::get().reads(6_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Suspended` (r:1 w:0)
+    /// Proof: `AccessControl::Suspended` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:2 w:0)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:0)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ExtraPermissions` (r:1 w:1)
+    /// Proof: `AccessControl::ExtraPermissions` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    fn set_permissions() -> Weight {
+        Weight::from_parts(22_840_000, 3537)
+            .saturating_add(T::DbWeight::get().reads(7_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:2 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::ResourceMembers` (r:2 w:1)
+    /// Proof: `AccessControl::ResourceMembers` (`max_values`: None, `max_size`: Some(3223), added: 5698, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleExpiry` (r:3 w:1)
+    /// Proof: `AccessControl::RoleExpiry` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Roles` (r:2 w:1)
+    /// Proof: `AccessControl::Roles` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminDeposits` (r:0 w:1)
+    /// Proof: `AccessControl::AdminDeposits` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::AdminCount` (r:1 w:1)
+    /// Proof: `AccessControl::AdminCount` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::RoleHistory` (r:1 w:1)
+    /// Proof: `AccessControl::RoleHistory` (`max_values`: None, `max_size`: Some(7222), added: 9697, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxMembersPerResource]`.
+    fn copy_roles(n: u32) -> Weight {
+        Weight::from_parts(12_050_000, 11_396)
+            .saturating_add(Weight::from_parts(29_700_000, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(8_u64))
+            .saturating_add(T::DbWeight::get().reads((8_u64).saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+            .saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2603).saturating_mul(n.into()))
+    }
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+    fn grant_role() -> Weight {
+        Weight::from_parts(31_450_000, 10_687)
+            .saturating_add(RocksDbWeight::get().reads(9_u64))
+            .saturating_add(RocksDbWeight::get().writes(7_u64))
+    }
+    fn revoke_role(m: u32) -> Weight {
+        Weight::from_parts(29_870_000, 10_687)
+            .saturating_add(Weight::from_parts(4_310_000, 0).saturating_mul(m.into()))
+            .saturating_add(RocksDbWeight::get().reads(10_u64))
+            .saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(m.into())))
+            .saturating_add(RocksDbWeight::get().writes(7_u64))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(m.into()))
+    }
+    fn force_revoke_role() -> Weight {
+        Weight::from_parts(24_120_000, 10_687)
+            .saturating_add(RocksDbWeight::get().reads(6_u64))
+            .saturating_add(RocksDbWeight::get().writes(7_u64))
+    }
+    fn grant_roles(n: u32) -> Weight {
+        Weight::from_parts(4_210_000, 10_687)
+            .saturating_add(Weight::from_parts(27_640_000, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+            .saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(n.into()))
+    }
+    fn revoke_roles(n: u32, m: u32) -> Weight {
+        Weight::from_parts(3_980_000, 10_687)
+            .saturating_add(Weight::from_parts(26_310_000, 0).saturating_mul(n.into()))
+            .saturating_add(
+                Weight::from_parts(4_310_000, 0)
+                    .saturating_mul(n.into())
+                    .saturating_mul(m.into()),
+            )
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().reads((7_u64).saturating_mul(n.into())))
+            .saturating_add(
+                RocksDbWeight::get()
+                    .reads((3_u64).saturating_mul(n.into()).saturating_mul(m.into())),
+            )
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+            .saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(n.into()))
+    }
+    fn claim_resource() -> Weight {
+        Weight::from_parts(21_640_000, 3593)
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn transfer_ownership() -> Weight {
+        Weight::from_parts(22_170_000, 3593)
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn update_role(m: u32) -> Weight {
+        Weight::from_parts(19_760_000, 10_687)
+            .saturating_add(Weight::from_parts(4_310_000, 0).saturating_mul(m.into()))
+            .saturating_add(RocksDbWeight::get().reads(7_u64))
+            .saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(m.into())))
+            .saturating_add(RocksDbWeight::get().writes(5_u64))
+            .saturating_add(Weight::from_parts(0, 2547).saturating_mul(m.into()))
+    }
+    fn delegate_granting() -> Weight {
+        Weight::from_parts(17_230_000, 3537)
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn revoke_delegation() -> Weight {
+        Weight::from_parts(18_940_000, 3537)
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn suspend_role() -> Weight {
+        Weight::from_parts(21_360_000, 10_687)
+            .saturating_add(RocksDbWeight::get().reads(8_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn unsuspend_role() -> Weight {
+        Weight::from_parts(18_510_000, 10_687)
+            .saturating_add(RocksDbWeight::get().reads(6_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn set_permissions() -> Weight {
+        Weight::from_parts(22_840_000, 3537)
+            .saturating_add(RocksDbWeight::get().reads(7_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn copy_roles(n: u32) -> Weight {
+        Weight::from_parts(12_050_000, 11_396)
+            .saturating_add(Weight::from_parts(29_700_000, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(8_u64))
+            .saturating_add(RocksDbWeight::get().reads((8_u64).saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+            .saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2603).saturating_mul(n.into()))
+    }
+}
@@ -0,0 +1,25 @@
+//! # Playlist Registry Runtime API
+//!
+//! Lets off-chain services (the backend, explorers) read structured playlist
+//! data over RPC instead of building storage keys and decoding bounded types.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_playlist_registry::{PlaylistId, PlaylistView, Visibility};
+
+sp_api::decl_runtime_apis! {
+    /// Playlist queries backed by `pallet_playlist_registry::Pallet`.
+    pub trait PlaylistRegistryApi<AccountId>
+    where
+        AccountId: Codec,
+    {
+        /// `account`'s playlists with their ids, oldest first.
+        fn playlists_of(account: AccountId) -> Vec<(PlaylistId, PlaylistView<AccountId>)>;
+
+        /// Playlist `playlist_id`, if it exists and is owned by `account`.
+        fn playlist(account: AccountId, playlist_id: PlaylistId) -> Option<PlaylistView<AccountId>>;
+    }
+}
@@ -0,0 +1,489 @@
+//! Benchmarks for the playlist registry pallet.
+//!
+//! Each call is measured in its worst case: the caller's playlist list, the
+//! channel list and the tags are as long as the components allow, so the
+//! lists are scanned and the tag index is rewritten in full. Calls open to
+//! curators are measured for the owner; role lookups through `T::Access` are
+//! not included. Gating a playlist needs a `T::Gate` that accepts gates, such
+//! as the token-gate pallet.
+
+use super::*;
+use crate::Pallet as PlaylistRegistry;
+use codec::Decode;
+use frame_benchmarking::v2::*;
+use frame_support::{
+    traits::{Currency, EnsureOrigin, Get},
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Saturating, TrailingZeroInput};
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+/// Give `who` enough balance to cover many playlist deposits.
+fn fund<T: Config>(who: &T::AccountId) {
+    let amount = T::PlaylistDeposit::get().saturating_mul(100u32.into());
+    T::Currency::make_free_balance_be(who, amount.saturating_add(T::Currency::minimum_balance()));
+}
+
+/// Register a playlist named `name` for `owner` and return its id.
+fn register<T: Config>(owner: &T::AccountId, name: Vec<u8>) -> Result<PlaylistId, BenchmarkError> {
+    let playlist_id = NextPlaylistId::<T>::get();
+    PlaylistRegistry::<T>::register_playlist(
+        RawOrigin::Signed(owner.clone()).into(),
+        name,
+        [0u8; 32],
+    )?;
+    Ok(playlist_id)
+}
+
+/// Fund `owner` and register `count` playlists for it, returning their ids.
+///
+/// Names are the little-endian bytes of the index, so they never clash with
+/// the ASCII names used by the benchmarks themselves.
+fn seed_playlists<T: Config>(
+    owner: &T::AccountId,
+    count: u32,
+) -> Result<Vec<PlaylistId>, BenchmarkError> {
+    fund::<T>(owner);
+    (0..count)
+        .map(|i| register::<T>(owner, i.to_le_bytes().to_vec()))
+        .collect()
+}
+
+/// `count` distinct tags, starting from `first`.
+fn tags(first: u32, count: u32) -> Vec<Vec<u8>> {
+    (first..first + count)
+        .map(|i| i.to_le_bytes().to_vec())
+        .collect()
+}
+
+/// A channel entry whose stream URL hash is derived from `i`.
+fn channel(i: u32) -> ChannelEntry {
+    let mut stream_url_hash = [0u8; 32];
+    stream_url_hash[..4].copy_from_slice(&i.to_le_bytes());
+    ChannelEntry {
+        name_hash: [1u8; 32],
+        stream_url_hash,
+    }
+}
+
+/// List `count` channels on-chain for `playlist_id`.
+fn seed_channels<T: Config>(
+    owner: &T::AccountId,
+    playlist_id: PlaylistId,
+    count: u32,
+) -> Result<(), BenchmarkError> {
+    PlaylistRegistry::<T>::set_channels(
+        RawOrigin::Signed(owner.clone()).into(),
+        playlist_id,
+        (0..count).map(channel).collect(),
+    )?;
+    Ok(())
+}
+
+/// Make `playlist_id` public and tag it with `count` tags.
+fn publish_with_tags<T: Config>(
+    owner: &T::AccountId,
+    playlist_id: PlaylistId,
+    count: u32,
+) -> Result<(), BenchmarkError> {
+    PlaylistRegistry::<T>::set_tags(
+        RawOrigin::Signed(owner.clone()).into(),
+        playlist_id,
+        tags(0, count),
+    )?;
+    PlaylistRegistry::<T>::set_visibility(
+        RawOrigin::Signed(owner.clone()).into(),
+        playlist_id,
+        Visibility::Public,
+    )?;
+    Ok(())
+}
+
+/// Fill the attestations of `playlist_id` with `count` accounts and return them.
+fn seed_attestations<T: Config>(playlist_id: PlaylistId, count: u32) -> Vec<T::AccountId> {
+    let attesters: Vec<T::AccountId> = (0..count).map(|i| account("attester", i, SEED)).collect();
+    let bounded: BoundedVec<T::AccountId, T::MaxAttestations> = attesters
+        .clone()
+        .try_into()
+        .expect("count is at most MaxAttestations");
+    Attestations::<T>::insert(playlist_id, bounded);
+    attesters
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_playlist(
+        n: Linear<1, { T::MaxPlaylistNameLength::get() }>,
+        p: Linear<0, { T::MaxPlaylistsPerAccount::get() - 1 }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_playlists::<T>(&caller, p)?;
+        let name = sp_std::vec![b'a'; n as usize];
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), name, [1u8; 32]);
+
+        assert_eq!(PlaylistMap::<T>::get(&caller).len() as u32, p + 1);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_playlist(
+        p: Linear<1, { T::MaxPlaylistsPerAccount::get() }>,
+        t: Linear<0, { T::MaxTags::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let ids = seed_playlists::<T>(&caller, p)?;
+        // The oldest playlist: removing it shifts every other id in the list.
+        let playlist_id = ids[0];
+        publish_with_tags::<T>(&caller, playlist_id, t)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), playlist_id);
+
+        assert!(!Playlists::<T>::contains_key(playlist_id));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn update_channel_count() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, 42);
+
+        assert_eq!(
+            Playlists::<T>::get(playlist_id).map(|p| p.channel_count),
+            Some(42)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_playlist_details(
+        d: Linear<0, { T::MaxDescriptionLength::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        let description = sp_std::vec![b'd'; d as usize];
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            playlist_id,
+            description,
+            Some([2u8; 32]),
+        );
+
+        assert_eq!(
+            Playlists::<T>::get(playlist_id).map(|p| p.description.len() as u32),
+            Some(d)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn update_playlist(
+        n: Linear<1, { T::MaxPlaylistNameLength::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        let name = sp_std::vec![b'a'; n as usize];
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            playlist_id,
+            Some(name.clone()),
+            Some([3u8; 32]),
+        );
+
+        assert_eq!(
+            PlaylistRegistry::<T>::playlist_by_name(&caller, &name),
+            Some(playlist_id)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn transfer_playlist() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, recipient.clone());
+
+        assert_eq!(PendingTransfers::<T>::get(playlist_id), Some(recipient));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn accept_playlist(
+        p: Linear<0, { T::MaxPlaylistsPerAccount::get() - 1 }>,
+    ) -> Result<(), BenchmarkError> {
+        // Two nearly full accounts may hold more than `MaxTotalPlaylists`.
+        GlobalCap::<T>::put(u32::MAX);
+        let owner: T::AccountId = account("owner", 0, SEED);
+        seed_playlists::<T>(&owner, p)?;
+        let playlist_id = register::<T>(&owner, b"offered".to_vec())?;
+        let caller: T::AccountId = whitelisted_caller();
+        seed_playlists::<T>(&caller, p)?;
+        PlaylistRegistry::<T>::transfer_playlist(
+            RawOrigin::Signed(owner).into(),
+            playlist_id,
+            caller.clone(),
+        )?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), playlist_id);
+
+        assert_eq!(
+            Playlists::<T>::get(playlist_id).map(|p| p.owner),
+            Some(caller)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn add_channel(
+        c: Linear<0, { T::MaxChannelsPerPlaylist::get() - 1 }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        seed_channels::<T>(&caller, playlist_id, c)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, channel(c));
+
+        assert_eq!(Channels::<T>::get(playlist_id).len() as u32, c + 1);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_channel(
+        c: Linear<1, { T::MaxChannelsPerPlaylist::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        seed_channels::<T>(&caller, playlist_id, c)?;
+
+        // The last channel is found after scanning the whole list.
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            playlist_id,
+            channel(c - 1).stream_url_hash,
+        );
+
+        assert_eq!(Channels::<T>::get(playlist_id).len() as u32, c - 1);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_channels(
+        c: Linear<0, { T::MaxChannelsPerPlaylist::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        seed_channels::<T>(&caller, playlist_id, T::MaxChannelsPerPlaylist::get())?;
+        let entries: Vec<_> = (0..c).map(|i| channel(u32::MAX - i)).collect();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, entries);
+
+        assert_eq!(Channels::<T>::get(playlist_id).len() as u32, c);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn publish_version() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        // With every slot taken, the oldest version is dropped.
+        for i in 0..T::MaxContentVersions::get() {
+            PlaylistRegistry::<T>::publish_version(
+                RawOrigin::Signed(caller.clone()).into(),
+                playlist_id,
+                [i as u8; 32],
+            )?;
+        }
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, [0xff; 32]);
+
+        assert_eq!(
+            PlaylistRegistry::<T>::latest_version(playlist_id).map(|v| v.content_hash),
+            Some([0xff; 32])
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_visibility(t: Linear<0, { T::MaxTags::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        PlaylistRegistry::<T>::set_tags(
+            RawOrigin::Signed(caller.clone()).into(),
+            playlist_id,
+            tags(0, t),
+        )?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, Visibility::Public);
+
+        assert!(PublicPlaylists::<T>::contains_key(playlist_id));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_tags(t: Linear<0, { T::MaxTags::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        // A public playlist is unindexed from its old tags and indexed under the new.
+        publish_with_tags::<T>(&caller, playlist_id, t)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, tags(t, t));
+
+        assert_eq!(
+            Playlists::<T>::get(playlist_id).map(|p| p.tags.len() as u32),
+            Some(t)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn attest_playlist() -> Result<(), BenchmarkError> {
+        let origin =
+            T::AttesterOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let curator = T::AttesterOrigin::ensure_origin(origin.clone())
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let playlist_id = seed_playlists::<T>(&owner, 1)?[0];
+        seed_attestations::<T>(playlist_id, T::MaxAttestations::get().saturating_sub(1));
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, owner, playlist_id);
+
+        assert!(Attestations::<T>::get(playlist_id).contains(&curator));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn revoke_attestation() -> Result<(), BenchmarkError> {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let playlist_id = seed_playlists::<T>(&owner, 1)?[0];
+        let mut attesters = seed_attestations::<T>(playlist_id, T::MaxAttestations::get());
+        // The last attester is found after scanning the whole list.
+        let caller = attesters.pop().ok_or(BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), playlist_id);
+
+        assert!(!Attestations::<T>::get(playlist_id).contains(&caller));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_global_cap() {
+        #[extrinsic_call]
+        _(RawOrigin::Root, Some(1_000));
+
+        assert_eq!(PlaylistRegistry::<T>::global_cap(), 1_000);
+    }
+
+    #[benchmark]
+    fn set_source_url(
+        u: Linear<1, { T::MaxSourceUrlLength::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let url = sp_std::vec![b'u'; u as usize];
+        let playlist_id = NextPlaylistId::<T>::get();
+        PlaylistRegistry::<T>::register_playlist(
+            RawOrigin::Signed(caller.clone()).into(),
+            b"source".to_vec(),
+            sp_io::hashing::sha2_256(&url),
+        )?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), playlist_id, url);
+
+        assert!(SourceUrls::<T>::contains_key(playlist_id));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn report_content() -> Result<(), BenchmarkError> {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let playlist_id = seed_playlists::<T>(&owner, 1)?[0];
+        PlaylistRegistry::<T>::publish_version(
+            RawOrigin::Signed(owner).into(),
+            playlist_id,
+            [1u8; 32],
+        )?;
+        let url: BoundedVec<u8, T::MaxSourceUrlLength> = sp_std::vec![b'u']
+            .try_into()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        SourceUrls::<T>::insert(playlist_id, url);
+        // Signatures are checked at validation, not dispatch.
+        let public = T::Public::decode(&mut TrailingZeroInput::zeroes())
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let signature = T::Signature::decode(&mut TrailingZeroInput::zeroes())
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let report = ContentReport {
+            playlist_id,
+            content_hash: [2u8; 32],
+            block_number: frame_system::Pallet::<T>::block_number(),
+            public,
+        };
+
+        // A mismatch also records the fetched hash and emits an event.
+        #[extrinsic_call]
+        _(RawOrigin::None, report, signature);
+
+        assert_eq!(ContentMismatches::<T>::get(playlist_id), Some([2u8; 32]));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_playlist_gate() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let playlist_id = seed_playlists::<T>(&caller, 1)?[0];
+        let requirement = pallet_token_gate::GateRequirement {
+            token_id: 1,
+            min_balance: 1,
+        };
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            playlist_id,
+            Some(requirement.clone()),
+        );
+
+        frame_system::Pallet::<T>::assert_last_event(
+            <T as Config>::RuntimeEvent::from(Event::PlaylistGateSet {
+                who: caller,
+                playlist_id,
+                requirement: Some(requirement),
+            })
+            .into(),
+        );
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(
+        PlaylistRegistry,
+        crate::tests::new_test_ext(),
+        crate::tests::Test
+    );
+}
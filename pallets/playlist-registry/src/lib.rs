@@ -2,23 +2,178 @@
 //!
 //! A FRAME pallet for registering and managing IPTV playlists on-chain.
 //! Each account can register up to `MaxPlaylistsPerAccount` playlists,
-//! each identified by a name and a hash of the source URL.
+//! each described by a name and a hash of the source URL.
+//!
+//! Every playlist gets a [`PlaylistId`] from a global counter when it is
+//! registered. Ids are never reused, so removing one playlist leaves the ids of
+//! the others unchanged. Names are unique per owner, so a playlist can also be
+//! looked up by owner and name.
+//!
+//! Owners can list a playlist's channels on-chain as hashes of their names and
+//! stream URLs; the playlist's `channel_count` then follows that list.
+//!
+//! Owners can also publish hashes of a playlist's M3U content as numbered
+//! versions, so consumers can check that what they fetched matches one of them.
+//!
+//! Playlists are private until their owner makes them public, which adds them to
+//! the `PublicPlaylists` index that dApps iterate to discover playlists. Public
+//! playlists are also listed in `TagIndex` under each of their tags (such as
+//! "sports", "news" or "fr").
+//!
+//! Curation can be shared: an account holding `Editor` or `Admin` in the
+//! access-control pallet on the resource of a playlist (see
+//! [`Pallet::resource_id`]) may change its channels like the owner. The registry
+//! makes the playlist's owner the owner of that resource, and runtimes reserve
+//! these resources with [`PlaylistResources`] so nobody else can claim them.
+//!
+//! Owners can also token-gate a playlist through the token-gate pallet, on the
+//! same resource id. Channel reads served through [`Pallet::readable_channels`]
+//! then require meeting the gate, except for the owner and curators.
+//!
+//! Well-known curators, admitted by `AttesterOrigin`, can attest playlists to vouch
+//! for their quality; dApps can show how many curators attested each playlist.
+//!
+//! Owners can also commit the plain source URL behind `source_url_hash`. An
+//! off-chain worker then periodically fetches it, hashes the content and reports
+//! whether it matches the latest published version; mismatches emit
+//! `ContentMismatch`. Reports travel as unsigned transactions carrying a
+//! payload signed with a [`crypto`] key of one of the `ContentReporters`, so
+//! nobody else can flag a playlist or use up its check.
+//!
+//! The registry as a whole holds at most `MaxTotalPlaylists` playlists, a cap
+//! governance can raise or lower with `set_global_cap`.
+//!
+//! Registering a playlist reserves `PlaylistDeposit` from its owner, released
+//! when the playlist is removed. A chain can also launch with default playlists
+//! listed in its genesis config; those carry no deposit.
+//!
+//! A playlist changes hands in two steps: its owner offers it with
+//! `transfer_playlist` and the recipient takes it with `accept_playlist`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+use frame_support::traits::Contains;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Call filter that allows every call of this pallet except destructive ones
+/// (currently `remove_playlist`).
+///
+/// Runtimes can use it from their `BaseCallFilter` to disable removing playlists
+/// during an initial deployment phase:
+///
+/// ```ignore
+/// pub struct BaseFilter;
+/// impl Contains<RuntimeCall> for BaseFilter {
+///     fn contains(call: &RuntimeCall) -> bool {
+///         match call {
+///             RuntimeCall::PlaylistRegistry(c) => SafeCallFilter::<Runtime>::contains(c),
+///             _ => true,
+///         }
+///     }
+/// }
+/// ```
+pub struct SafeCallFilter<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Contains<Call<T>> for SafeCallFilter<T> {
+    fn contains(call: &Call<T>) -> bool {
+        !matches!(call, Call::remove_playlist { .. })
+    }
+}
+
+/// Access-control resources of existing and future playlists.
+///
+/// Runtimes set it as the access-control pallet's `ReservedResources`, so only
+/// the registry assigns these resources, to the playlists' owners:
+///
+/// ```ignore
+/// type ReservedResources = pallet_playlist_registry::PlaylistResources<Runtime>;
+/// ```
+pub struct PlaylistResources<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Contains<u32> for PlaylistResources<T> {
+    fn contains(resource_id: &u32) -> bool {
+        // Resource ids are playlist ids, so every id from the next one up is
+        // still to be registered.
+        Playlists::<T>::contains_key(resource_id) || *resource_id >= NextPlaylistId::<T>::get()
+    }
+}
+
+/// Key type of the off-chain worker keys that sign content reports.
+pub const KEY_TYPE: sp_runtime::KeyTypeId = sp_runtime::KeyTypeId(*b"iptv");
+
+/// Keys the off-chain worker signs content reports with.
+///
+/// A node reports only with a key in its keystore under [`KEY_TYPE`] whose
+/// account is one of `ContentReporters`.
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// [`AppCrypto`](frame_system::offchain::AppCrypto) of the reporter keys
+    /// for runtimes signing with `MultiSignature`.
+    pub struct ReporterId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for ReporterId {
+        type RuntimeAppPublic = Public;
+        type GenericPublic = sr25519::Public;
+        type GenericSignature = sr25519::Signature;
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use codec::{Decode, Encode};
-    use frame_support::pallet_prelude::*;
-    use frame_system::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Contains, ReservableCurrency},
+    };
+    use frame_system::{
+        offchain::{
+            AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer,
+            SigningTypes,
+        },
+        pallet_prelude::*,
+    };
+    use pallet_access_control::{ResourceAccess, Role};
+    use pallet_token_gate::{GateRequirement, ResourceGate};
     use scale_info::TypeInfo;
+    use sp_runtime::{
+        offchain::{
+            http,
+            storage::{StorageRetrievalError, StorageValueRef},
+            Duration,
+        },
+        traits::{Hash as HashT, IdentifyAccount, Saturating},
+        transaction_validity::{
+            InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+            ValidTransaction,
+        },
+        SaturatedConversion,
+    };
+
+    use crate::WeightInfo;
+
+    /// Identifier of a registered playlist, unique across all accounts.
+    pub type PlaylistId = u32;
 
     /// Metadata for a registered playlist.
     #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
-    #[scale_info(skip_type_params(BoundedString))]
-    pub struct PlaylistMetadata<BoundedString> {
+    #[scale_info(skip_type_params(BoundedString, BoundedDescription, BoundedTags))]
+    pub struct PlaylistMetadata<AccountId, BoundedString, BoundedDescription, BoundedTags> {
+        /// Account that registered the playlist.
+        pub owner: AccountId,
         /// Human-readable name of the playlist.
         pub name: BoundedString,
         /// SHA-256 hash of the source URL.
@@ -27,14 +182,127 @@ pub mod pallet {
         pub channel_count: u32,
         /// Block number at which the playlist was created.
         pub created_at: u64,
+        /// Block number at which the playlist was last changed.
+        pub updated_at: u64,
+        /// Free-form description shown by discovery UIs (empty when unset).
+        pub description: BoundedDescription,
+        /// Hash of the playlist's artwork/logo, if any.
+        pub artwork_hash: Option<[u8; 32]>,
+        /// Whether the playlist is listed in `PublicPlaylists`.
+        pub visibility: Visibility,
+        /// Categories the playlist is listed under in `TagIndex` while public.
+        pub tags: BoundedTags,
+    }
+
+    /// Whether a playlist is listed for discovery.
+    #[derive(
+        Clone, Copy, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug,
+    )]
+    pub enum Visibility {
+        /// Listed in `PublicPlaylists`.
+        Public,
+        /// Only reachable through the owner's playlists.
+        #[default]
+        Private,
+    }
+
+    /// A channel listed on-chain for a playlist.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub struct ChannelEntry {
+        /// SHA-256 hash of the channel name.
+        pub name_hash: [u8; 32],
+        /// SHA-256 hash of the stream URL; unique within a playlist.
+        pub stream_url_hash: [u8; 32],
+    }
+
+    /// A published version of a playlist's content.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+    pub struct ContentVersion {
+        /// Version number, starting at 1 and increasing with each publication.
+        pub version: u32,
+        /// SHA-256 hash of the playlist's M3U content.
+        pub content_hash: [u8; 32],
+        /// Block number at which the version was published.
+        pub published_at: u64,
+    }
+
+    /// The off-chain worker's report of a playlist's fetched content.
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
+    pub struct ContentReport<Public, BlockNumber> {
+        /// Id of the checked playlist.
+        pub playlist_id: PlaylistId,
+        /// SHA-256 hash of the fetched content.
+        pub content_hash: [u8; 32],
+        /// Block at which the content was fetched.
+        pub block_number: BlockNumber,
+        /// Key the report is signed with.
+        pub public: Public,
     }
 
+    impl<T: SigningTypes> SignedPayload<T> for ContentReport<T::Public, BlockNumberFor<T>> {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    /// A playlist tag as stored for a runtime.
+    pub type TagOf<T> = BoundedVec<u8, <T as Config>::MaxTagLength>;
+
+    /// The tags of one playlist as stored for a runtime.
+    pub type TagsOf<T> = BoundedVec<TagOf<T>, <T as Config>::MaxTags>;
+
+    /// Playlist metadata as stored for a runtime.
+    pub type PlaylistMetadataOf<T> = PlaylistMetadata<
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<u8, <T as Config>::MaxPlaylistNameLength>,
+        BoundedVec<u8, <T as Config>::MaxDescriptionLength>,
+        TagsOf<T>,
+    >;
+
+    /// Playlist metadata with unbounded fields, as returned by the runtime API.
+    pub type PlaylistView<AccountId> = PlaylistMetadata<
+        AccountId,
+        sp_std::vec::Vec<u8>,
+        sp_std::vec::Vec<u8>,
+        sp_std::vec::Vec<sp_std::vec::Vec<u8>>,
+    >;
+
+    /// Balance type of the pallet's currency.
+    pub type BalanceOf<T> = <<T as Config>::Currency as frame_support::traits::Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::Balance;
+
+    /// The in-code storage version; see [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
+    /// Tag hashed with an owner account to form its event topic.
+    pub const ACCOUNT_TOPIC: &[u8] = b"iptv/account";
+
+    /// Tag hashed with a resource ID to form its event topic.
+    pub const RESOURCE_TOPIC: &[u8] = b"iptv/resource";
+
+    /// Tag hashed on its own to form the topic of registry-wide events.
+    pub const REGISTRY_TOPIC: &[u8] = b"iptv/registry";
+
+    /// Prefix of the off-chain storage keys recording when the worker last
+    /// fetched each playlist.
+    const OCW_LAST_FETCH: &[u8] = b"playlist-registry/last-fetch/";
+
+    /// Most source URLs the off-chain worker fetches per block.
+    const MAX_FETCHES_PER_BLOCK: u32 = 4;
+
+    /// How long the off-chain worker waits for one source URL, in milliseconds.
+    const FETCH_TIMEOUT_MS: u64 = 5_000;
+
     /// Configuration trait for the playlist registry pallet.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config:
+        frame_system::Config + SendTransactionTypes<Call<Self>> + SigningTypes
+    {
         /// The overarching runtime event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
@@ -42,41 +310,405 @@ pub mod pallet {
         #[pallet::constant]
         type MaxPlaylistsPerAccount: Get<u32>;
 
+        /// Maximum number of playlists registered across all accounts, unless
+        /// overridden with `set_global_cap`.
+        #[pallet::constant]
+        type MaxTotalPlaylists: Get<u32>;
+
         /// Maximum length (in bytes) for a playlist name.
         #[pallet::constant]
         type MaxPlaylistNameLength: Get<u32>;
+
+        /// Maximum length (in bytes) for a playlist description.
+        #[pallet::constant]
+        type MaxDescriptionLength: Get<u32>;
+
+        /// Maximum number of channels listed on-chain for one playlist.
+        #[pallet::constant]
+        type MaxChannelsPerPlaylist: Get<u32>;
+
+        /// Number of published content versions kept per playlist.
+        #[pallet::constant]
+        type MaxContentVersions: Get<u32>;
+
+        /// Maximum number of tags on one playlist.
+        #[pallet::constant]
+        type MaxTags: Get<u32>;
+
+        /// Maximum length (in bytes) of a tag.
+        #[pallet::constant]
+        type MaxTagLength: Get<u32>;
+
+        /// Currency from which playlist deposits are reserved.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Amount reserved from the owner of each registered playlist.
+        #[pallet::constant]
+        type PlaylistDeposit: Get<BalanceOf<Self>>;
+
+        /// Role checks for accounts curating playlists they do not own.
+        type Access: ResourceAccess<Self::AccountId>;
+
+        /// Token gates on playlists, keyed by [`Pallet::resource_id`].
+        type Gate: ResourceGate<Self::AccountId>;
+
+        /// Origin of the curators allowed to attest playlists.
+        type AttesterOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Maximum number of attestations on one playlist.
+        #[pallet::constant]
+        type MaxAttestations: Get<u32>;
+
+        /// Maximum length (in bytes) of a committed source URL.
+        #[pallet::constant]
+        type MaxSourceUrlLength: Get<u32>;
+
+        /// Blocks between two content checks of the same playlist.
+        #[pallet::constant]
+        type CheckInterval: Get<BlockNumberFor<Self>>;
+
+        /// Priority of the off-chain worker's content reports.
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// Keys content reports are signed with, such as [`crypto::ReporterId`].
+        ///
+        /// [`crypto::ReporterId`]: crate::crypto::ReporterId
+        type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+        /// Accounts whose keys may sign content reports.
+        type ContentReporters: Contains<Self::AccountId>;
+
+        /// Weights for this pallet's calls.
+        type WeightInfo: WeightInfo;
     }
 
-    /// Map from account to their registered playlists.
+    /// Registered playlists by id.
+    #[pallet::storage]
+    #[pallet::getter(fn playlist)]
+    pub type Playlists<T: Config> =
+        StorageMap<_, Blake2_128Concat, PlaylistId, PlaylistMetadataOf<T>, OptionQuery>;
+
+    /// Map from account to the ids of their registered playlists, oldest first.
     #[pallet::storage]
     #[pallet::getter(fn playlist_map)]
     pub type PlaylistMap<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
         T::AccountId,
-        BoundedVec<
-            PlaylistMetadata<BoundedVec<u8, T::MaxPlaylistNameLength>>,
-            T::MaxPlaylistsPerAccount,
-        >,
+        BoundedVec<PlaylistId, T::MaxPlaylistsPerAccount>,
+        ValueQuery,
+    >;
+
+    /// Playlists by owner and name: (owner, `T::Hashing` of the name) -> playlist_id.
+    #[pallet::storage]
+    pub type NameIndex<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Identity,
+        T::Hash,
+        PlaylistId,
+        OptionQuery,
+    >;
+
+    /// Id assigned to the next registered playlist.
+    #[pallet::storage]
+    pub type NextPlaylistId<T: Config> = StorageValue<_, PlaylistId, ValueQuery>;
+
+    /// Channels listed on-chain per playlist: playlist_id -> entries.
+    ///
+    /// Keyed by id alone, so the list follows the playlist through transfers.
+    #[pallet::storage]
+    pub type Channels<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        PlaylistId,
+        BoundedVec<ChannelEntry, T::MaxChannelsPerPlaylist>,
+        ValueQuery,
+    >;
+
+    /// Latest published content versions per playlist, oldest first.
+    ///
+    /// Holds at most `MaxContentVersions` versions; the oldest is dropped to make room.
+    #[pallet::storage]
+    pub type ContentVersions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        PlaylistId,
+        BoundedVec<ContentVersion, T::MaxContentVersions>,
+        ValueQuery,
+    >;
+
+    /// Deposit currently reserved from each playlist's owner: playlist_id -> amount.
+    ///
+    /// Recorded so the amount actually reserved is released, even if
+    /// `PlaylistDeposit` changes in the meantime.
+    #[pallet::storage]
+    pub type PlaylistDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, PlaylistId, BalanceOf<T>, OptionQuery>;
+
+    /// Index of public playlists, iterable by id.
+    #[pallet::storage]
+    pub type PublicPlaylists<T: Config> = StorageMap<_, Twox64Concat, PlaylistId, (), OptionQuery>;
+
+    /// Public playlists by tag: (tag, playlist_id) -> (), iterable per tag.
+    #[pallet::storage]
+    pub type TagIndex<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, TagOf<T>, Twox64Concat, PlaylistId, (), OptionQuery>;
+
+    /// Curators that attested each playlist: playlist_id -> attesters.
+    #[pallet::storage]
+    pub type Attestations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        PlaylistId,
+        BoundedVec<T::AccountId, T::MaxAttestations>,
         ValueQuery,
     >;
 
+    /// Pending ownership transfers: playlist_id -> account it was offered to.
+    #[pallet::storage]
+    pub type PendingTransfers<T: Config> =
+        StorageMap<_, Blake2_128Concat, PlaylistId, T::AccountId, OptionQuery>;
+
     /// Global counter of registered playlists.
     #[pallet::storage]
     #[pallet::getter(fn playlist_count)]
     pub type PlaylistCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+    /// Source URLs committed by owners for content checks: playlist_id -> URL.
+    ///
+    /// Each URL hashes to its playlist's `source_url_hash`.
+    #[pallet::storage]
+    pub type SourceUrls<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        PlaylistId,
+        BoundedVec<u8, T::MaxSourceUrlLength>,
+        OptionQuery,
+    >;
+
+    /// Block of the last accepted content report per playlist.
+    #[pallet::storage]
+    pub type LastChecked<T: Config> =
+        StorageMap<_, Blake2_128Concat, PlaylistId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Playlists whose fetched content differed from the latest published version
+    /// at their last check: playlist_id -> hash of the fetched content.
+    #[pallet::storage]
+    pub type ContentMismatches<T: Config> =
+        StorageMap<_, Blake2_128Concat, PlaylistId, [u8; 32], OptionQuery>;
+
+    /// Cap on `PlaylistCount` set by governance; `MaxTotalPlaylists` applies when unset.
+    #[pallet::storage]
+    pub type GlobalCap<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+    /// Playlists registered when the chain launches.
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Playlists to register, in id order, as `(owner, name, source_url_hash)`.
+        ///
+        /// They reserve no deposit from their owners.
+        pub playlists: sp_std::vec::Vec<(T::AccountId, sp_std::vec::Vec<u8>, [u8; 32])>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (owner, name, source_url_hash) in &self.playlists {
+                let name: BoundedVec<u8, T::MaxPlaylistNameLength> = name
+                    .clone()
+                    .try_into()
+                    .expect("genesis playlist name exceeds MaxPlaylistNameLength");
+                Pallet::<T>::insert_playlist(owner, name, *source_url_hash, None)
+                    .expect("genesis playlists must fit the registry limits");
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn integrity_test() {
+            // On-chain names must always fit the shared off-chain payload format.
+            assert!(
+                T::MaxPlaylistNameLength::get() as usize <= iptv_payload::MAX_NAME_LEN,
+                "MaxPlaylistNameLength exceeds iptv_payload::MAX_NAME_LEN",
+            );
+        }
+
+        /// Fetch the committed source URLs that are due for a check and report
+        /// those whose content no longer (or again) matches the latest version.
+        fn offchain_worker(now: BlockNumberFor<T>) {
+            // Only a node holding the key of an authorised reporter checks content.
+            let Some(reporter) = Signer::<T, T::AuthorityId>::keystore_accounts()
+                .find(|account| T::ContentReporters::contains(&account.id))
+            else {
+                return;
+            };
+            let signer = Signer::<T, T::AuthorityId>::any_account()
+                .with_filter(sp_std::vec![reporter.public]);
+
+            let mut fetched = 0;
+            for (playlist_id, url) in SourceUrls::<T>::iter() {
+                if fetched == MAX_FETCHES_PER_BLOCK {
+                    break;
+                }
+                let Some(expected) = Self::latest_version(playlist_id) else {
+                    continue;
+                };
+                if !Self::check_due(playlist_id, now) || !Self::claim_fetch(playlist_id, now) {
+                    continue;
+                }
+                fetched += 1;
+
+                let Ok(content_hash) = Self::fetch_content_hash(&url) else {
+                    continue;
+                };
+                let mismatch = content_hash != expected.content_hash;
+                if mismatch != ContentMismatches::<T>::contains_key(playlist_id) {
+                    // A rejected report is retried once the playlist is due again.
+                    let _ = signer.send_unsigned_transaction(
+                        |account| ContentReport {
+                            playlist_id,
+                            content_hash,
+                            block_number: now,
+                            public: account.public.clone(),
+                        },
+                        |report, signature| Call::report_content { report, signature },
+                    );
+                }
+            }
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Accept one content report per playlist and check interval, signed by
+        /// one of `ContentReporters` and made within the last `CheckInterval`
+        /// blocks.
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::report_content { report, signature } = call else {
+                return InvalidTransaction::Call.into();
+            };
+            if !SignedPayload::<T>::verify::<T::AuthorityId>(report, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            if !T::ContentReporters::contains(&report.public.clone().into_account()) {
+                return InvalidTransaction::BadSigner.into();
+            }
+            let now = frame_system::Pallet::<T>::block_number();
+            let playlist_id = &report.playlist_id;
+            if report.block_number > now
+                || now >= report.block_number.saturating_add(T::CheckInterval::get())
+                || Self::latest_version(*playlist_id).is_none()
+                || !Self::check_due(*playlist_id, now)
+            {
+                return InvalidTransaction::Stale.into();
+            }
+
+            ValidTransaction::with_tag_prefix("PlaylistRegistryContent")
+                .priority(T::UnsignedPriority::get())
+                .and_provides(playlist_id)
+                .longevity(5)
+                .propagate(true)
+                .build()
+        }
+    }
+
     /// Events emitted by this pallet.
     #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// A new playlist was registered.
         PlaylistRegistered {
             who: T::AccountId,
+            playlist_id: PlaylistId,
             name: BoundedVec<u8, T::MaxPlaylistNameLength>,
         },
         /// A playlist was removed.
-        PlaylistRemoved { who: T::AccountId, index: u32 },
+        PlaylistRemoved {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+        },
+        /// A playlist's name and/or source URL hash was updated.
+        PlaylistUpdated {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            name: BoundedVec<u8, T::MaxPlaylistNameLength>,
+            source_url_hash: [u8; 32],
+        },
+        /// An owner offered a playlist to another account.
+        TransferProposed {
+            playlist_id: PlaylistId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// An owner withdrew a pending transfer offer.
+        TransferCancelled { playlist_id: PlaylistId },
+        /// A playlist changed owner.
+        PlaylistTransferred {
+            playlist_id: PlaylistId,
+            from: T::AccountId,
+            to: T::AccountId,
+        },
+        /// The on-chain channel list of a playlist changed.
+        ChannelsChanged {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            channel_count: u32,
+        },
+        /// A new version of a playlist's content was published.
+        VersionPublished {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            version: u32,
+            content_hash: [u8; 32],
+        },
+        /// A playlist was made public or private.
+        VisibilityChanged {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            visibility: Visibility,
+        },
+        /// A curator attested a playlist.
+        PlaylistAttested {
+            curator: T::AccountId,
+            playlist_id: PlaylistId,
+        },
+        /// A curator withdrew their attestation of a playlist.
+        AttestationRevoked {
+            curator: T::AccountId,
+            playlist_id: PlaylistId,
+        },
+        /// A playlist's tags were replaced.
+        TagsSet {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+        },
+        /// A playlist's description and/or artwork hash was updated.
+        PlaylistDetailsUpdated {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            artwork_hash: Option<[u8; 32]>,
+        },
+        /// The content fetched from a playlist's source URL does not match its latest
+        /// published version.
+        ContentMismatch {
+            playlist_id: PlaylistId,
+            expected: [u8; 32],
+            found: [u8; 32],
+        },
+        /// Governance changed the global playlist cap (`None`: back to `MaxTotalPlaylists`).
+        GlobalCapSet { cap: Option<u32> },
+        /// A playlist's owner gated it on a token requirement (`None`: removed the gate).
+        PlaylistGateSet {
+            who: T::AccountId,
+            playlist_id: PlaylistId,
+            requirement: Option<GateRequirement>,
+        },
     }
 
     /// Errors that can occur in this pallet.
@@ -84,22 +716,71 @@ pub mod pallet {
     pub enum Error<T> {
         /// The account has reached the maximum number of playlists.
         TooManyPlaylists,
-        /// The specified playlist index does not exist.
+        /// The specified playlist does not exist.
         PlaylistNotFound,
+        /// The caller does not own the playlist (or, where allowed, curate it).
+        NotPlaylistOwner,
+        /// Every playlist id has been assigned.
+        NoAvailablePlaylistId,
+        /// The playlist has no pending transfer to the caller.
+        NoPendingTransfer,
+        /// The playlist already lists the maximum number of channels.
+        TooManyChannels,
+        /// The playlist already lists a channel with this stream URL hash.
+        DuplicateChannel,
+        /// The playlist does not list a channel with this stream URL hash.
+        ChannelNotFound,
+        /// The playlist lists its channels on-chain, so its count follows them.
+        ChannelsListedOnChain,
+        /// The runtime keeps no content versions (`MaxContentVersions` is zero).
+        VersioningDisabled,
         /// The playlist name exceeds the maximum allowed length.
         NameTooLong,
+        /// The account already has a playlist with this name.
+        DuplicateName,
+        /// More tags than `MaxTags` were given.
+        TooManyTags,
+        /// A tag is empty or exceeds `MaxTagLength`.
+        InvalidTag,
+        /// The same tag was given twice.
+        DuplicateTag,
+        /// The curator already attested the playlist.
+        AlreadyAttested,
+        /// The playlist already has `MaxAttestations` attestations.
+        TooManyAttestations,
+        /// The caller has not attested the playlist.
+        NotAttested,
+        /// The playlist description exceeds the maximum allowed length.
+        DescriptionTooLong,
+        /// The registry holds as many playlists as the global cap allows.
+        GlobalCapReached,
+        /// The source URL exceeds `MaxSourceUrlLength`.
+        SourceUrlTooLong,
+        /// The source URL does not hash to the playlist's `source_url_hash`.
+        SourceUrlMismatch,
+        /// The playlist has no committed source URL or no published version to
+        /// check against.
+        NothingToCheck,
+        /// The playlist was checked less than `CheckInterval` blocks ago.
+        CheckNotDue,
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Register a new playlist for the calling account.
+        /// Register a new playlist for the calling account under the next free id.
+        ///
+        /// The name must differ from the names of the caller's other playlists.
+        /// Reserves `PlaylistDeposit` from the caller until the playlist is removed.
         ///
         /// - `name`: Human-readable playlist name (must not exceed `MaxPlaylistNameLength`).
         /// - `source_url_hash`: A 32-byte hash of the playlist source URL.
         ///
         /// Emits `PlaylistRegistered` on success.
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::register_playlist(
+            name.len() as u32,
+            T::MaxPlaylistsPerAccount::get(),
+        ))]
         pub fn register_playlist(
             origin: OriginFor<T>,
             name: sp_std::vec::Vec<u8>,
@@ -109,125 +790,1235 @@ pub mod pallet {
 
             let bounded_name: BoundedVec<u8, T::MaxPlaylistNameLength> =
                 name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
-
-            let metadata = PlaylistMetadata {
-                name: bounded_name.clone(),
+            let playlist_id = Self::insert_playlist(
+                &who,
+                bounded_name.clone(),
                 source_url_hash,
-                channel_count: 0,
-                created_at: 0,
-            };
-
-            PlaylistMap::<T>::try_mutate(&who, |playlists| {
-                playlists
-                    .try_push(metadata)
-                    .map_err(|_| Error::<T>::TooManyPlaylists)
-            })?;
-
-            PlaylistCount::<T>::mutate(|count| {
-                *count = count.saturating_add(1);
-            });
+                Some(T::PlaylistDeposit::get()),
+            )?;
 
-            Self::deposit_event(Event::PlaylistRegistered {
-                who,
-                name: bounded_name,
-            });
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::PlaylistRegistered {
+                    who,
+                    playlist_id,
+                    name: bounded_name,
+                },
+            );
 
             Ok(())
         }
 
-        /// Remove a playlist by its index in the caller's playlist list.
+        /// Remove one of the caller's playlists and release its deposit.
         ///
-        /// - `index`: Zero-based index of the playlist to remove.
+        /// - `playlist_id`: Id of the playlist to remove.
         ///
         /// Emits `PlaylistRemoved` on success.
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
-        pub fn remove_playlist(origin: OriginFor<T>, index: u32) -> DispatchResult {
+        #[pallet::weight(T::WeightInfo::remove_playlist(
+            T::MaxPlaylistsPerAccount::get(),
+            T::MaxTags::get()
+        ))]
+        pub fn remove_playlist(origin: OriginFor<T>, playlist_id: PlaylistId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            PlaylistMap::<T>::try_mutate(&who, |playlists| -> DispatchResult {
-                let idx = index as usize;
-                if idx >= playlists.len() {
-                    return Err(Error::<T>::PlaylistNotFound.into());
-                }
-                playlists.remove(idx);
-                Ok(())
-            })?;
+            Self::ensure_owner(&who, playlist_id)?;
+            if let Some(playlist) = Playlists::<T>::take(playlist_id) {
+                NameIndex::<T>::remove(&who, Self::name_hash(&playlist.name));
+                Self::unindex_tags(playlist_id, &playlist.tags);
+            }
+            PublicPlaylists::<T>::remove(playlist_id);
+            PendingTransfers::<T>::remove(playlist_id);
+            Channels::<T>::remove(playlist_id);
+            ContentVersions::<T>::remove(playlist_id);
+            Attestations::<T>::remove(playlist_id);
+            Self::forget_source_url(playlist_id);
+            T::Gate::remove_gate(Self::resource_id(playlist_id), Some(who.clone()));
+            if let Some(deposit) = PlaylistDeposits::<T>::take(playlist_id) {
+                T::Currency::unreserve(&who, deposit);
+            }
+            PlaylistMap::<T>::mutate(&who, |playlists| playlists.retain(|id| *id != playlist_id));
 
             PlaylistCount::<T>::mutate(|count| {
                 *count = count.saturating_sub(1);
             });
 
-            Self::deposit_event(Event::PlaylistRemoved { who, index });
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::PlaylistRemoved { who, playlist_id },
+            );
 
             Ok(())
         }
 
-        /// Update the channel count for a playlist at the given index.
+        /// Update the channel count of a playlist the caller owns or curates.
         ///
-        /// - `index`: Zero-based index of the playlist to update.
+        /// Not allowed for playlists that list their channels on-chain; their count
+        /// follows the list.
+        ///
+        /// - `playlist_id`: Id of the playlist to update.
         /// - `count`: The new channel count value.
         #[pallet::call_index(2)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::update_channel_count())]
         pub fn update_channel_count(
             origin: OriginFor<T>,
-            index: u32,
+            playlist_id: PlaylistId,
             count: u32,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(
+                Channels::<T>::decode_len(playlist_id).unwrap_or(0) == 0,
+                Error::<T>::ChannelsListedOnChain
+            );
 
-            PlaylistMap::<T>::try_mutate(&who, |playlists| {
-                let idx = index as usize;
-                let playlist = playlists.get_mut(idx).ok_or(Error::<T>::PlaylistNotFound)?;
+            Self::mutate_curated(&who, playlist_id, |playlist| {
                 playlist.channel_count = count;
-                Ok(())
             })
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use frame_support::{
-        assert_noop, assert_ok, derive_impl,
-        traits::{ConstU32, ConstU64},
-    };
-    use sp_core::H256;
-    use sp_io::TestExternalities;
-    use sp_runtime::{
-        traits::{BlakeTwo256, IdentityLookup},
-        BuildStorage,
-    };
+        /// Set the description and artwork hash of one of the caller's playlists.
+        ///
+        /// Both fields are replaced; pass an empty `description` or `None` to clear them.
+        ///
+        /// - `playlist_id`: Id of the playlist to update.
+        /// - `description`: New description (must not exceed `MaxDescriptionLength`).
+        /// - `artwork_hash`: Optional 32-byte hash of the playlist artwork.
+        ///
+        /// Emits `PlaylistDetailsUpdated` on success.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::set_playlist_details(description.len() as u32))]
+        pub fn set_playlist_details(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            description: sp_std::vec::Vec<u8>,
+            artwork_hash: Option<[u8; 32]>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-    type Block = frame_system::mocking::MockBlock<Test>;
+            let bounded_description: BoundedVec<u8, T::MaxDescriptionLength> = description
+                .try_into()
+                .map_err(|_| Error::<T>::DescriptionTooLong)?;
 
-    frame_support::construct_runtime!(
-        pub enum Test {
-            System: frame_system,
-            PlaylistRegistry: pallet,
+            Self::mutate_owned(&who, playlist_id, |playlist| {
+                playlist.description = bounded_description;
+                playlist.artwork_hash = artwork_hash;
+            })?;
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::PlaylistDetailsUpdated {
+                    who,
+                    playlist_id,
+                    artwork_hash,
+                },
+            );
+
+            Ok(())
         }
-    );
 
-    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
-    impl frame_system::Config for Test {
-        type BaseCallFilter = frame_support::traits::Everything;
-        type BlockWeights = ();
-        type BlockLength = ();
-        type DbWeight = ();
-        type RuntimeOrigin = RuntimeOrigin;
-        type RuntimeCall = RuntimeCall;
-        type Nonce = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Block = Block;
-        type RuntimeEvent = RuntimeEvent;
+        /// Rename one of the caller's playlists and/or point it at a new source URL.
+        ///
+        /// Fields passed as `None` are left unchanged. A new name must differ from
+        /// the names of the caller's other playlists. A new source URL hash drops
+        /// the committed source URL, if any.
+        ///
+        /// - `playlist_id`: Id of the playlist to update.
+        /// - `new_name`: New name (must not exceed `MaxPlaylistNameLength`).
+        /// - `new_source_url_hash`: New 32-byte hash of the playlist source URL.
+        ///
+        /// Emits `PlaylistUpdated` on success.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::update_playlist(
+            new_name.as_ref().map_or(0, |name| name.len() as u32)
+        ))]
+        pub fn update_playlist(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            new_name: Option<sp_std::vec::Vec<u8>>,
+            new_source_url_hash: Option<[u8; 32]>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let new_name: Option<BoundedVec<u8, T::MaxPlaylistNameLength>> = new_name
+                .map(|name| name.try_into().map_err(|_| Error::<T>::NameTooLong))
+                .transpose()?;
+            let new_name_hash = new_name.as_ref().map(Self::name_hash);
+            if let Some(hash) = new_name_hash {
+                ensure!(
+                    NameIndex::<T>::get(&who, hash).is_none_or(|id| id == playlist_id),
+                    Error::<T>::DuplicateName
+                );
+            }
+
+            let (old_name_hash, source_changed, name, source_url_hash) =
+                Self::mutate_owned(&who, playlist_id, |playlist| {
+                    let old_name_hash = Self::name_hash(&playlist.name);
+                    let source_changed =
+                        new_source_url_hash.is_some_and(|hash| hash != playlist.source_url_hash);
+                    if let Some(name) = new_name {
+                        playlist.name = name;
+                    }
+                    if let Some(hash) = new_source_url_hash {
+                        playlist.source_url_hash = hash;
+                    }
+                    (
+                        old_name_hash,
+                        source_changed,
+                        playlist.name.clone(),
+                        playlist.source_url_hash,
+                    )
+                })?;
+            if let Some(hash) = new_name_hash {
+                NameIndex::<T>::remove(&who, old_name_hash);
+                NameIndex::<T>::insert(&who, hash, playlist_id);
+            }
+            if source_changed {
+                // The committed URL no longer matches the new hash.
+                Self::forget_source_url(playlist_id);
+            }
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::PlaylistUpdated {
+                    who,
+                    playlist_id,
+                    name,
+                    source_url_hash,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Offer one of the caller's playlists to `new_owner`.
+        ///
+        /// Ownership only moves once `new_owner` calls `accept_playlist`. A new offer
+        /// replaces any pending one; offering the playlist to its owner cancels it.
+        ///
+        /// - `playlist_id`: Id of the playlist to transfer.
+        /// - `new_owner`: Account the playlist is offered to.
+        ///
+        /// Emits `TransferProposed`, or `TransferCancelled` when `new_owner` is the
+        /// caller.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::transfer_playlist())]
+        pub fn transfer_playlist(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            new_owner: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_owner(&who, playlist_id)?;
+
+            if new_owner == who {
+                if PendingTransfers::<T>::take(playlist_id).is_some() {
+                    Self::deposit_event(
+                        Self::account_topic(&who),
+                        Event::TransferCancelled { playlist_id },
+                    );
+                }
+                return Ok(());
+            }
+
+            PendingTransfers::<T>::insert(playlist_id, &new_owner);
+            Self::deposit_event_for(
+                &[Self::account_topic(&who), Self::account_topic(&new_owner)],
+                Event::TransferProposed {
+                    playlist_id,
+                    from: who,
+                    to: new_owner,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Take ownership of a playlist offered to the caller.
+        ///
+        /// The playlist counts towards the caller's `MaxPlaylistsPerAccount`, and its
+        /// deposit is reserved from the caller and released to the previous owner.
+        /// Fails if the caller already has a playlist with the same name.
+        ///
+        /// - `playlist_id`: Id of the playlist offered with `transfer_playlist`.
+        ///
+        /// Emits `PlaylistTransferred` on success.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::accept_playlist(T::MaxPlaylistsPerAccount::get()))]
+        pub fn accept_playlist(origin: OriginFor<T>, playlist_id: PlaylistId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                PendingTransfers::<T>::get(playlist_id).as_ref() == Some(&who),
+                Error::<T>::NoPendingTransfer
+            );
+            let playlist = Self::playlist(playlist_id).ok_or(Error::<T>::PlaylistNotFound)?;
+            let from = playlist.owner;
+            let name_hash = Self::name_hash(&playlist.name);
+            ensure!(
+                !NameIndex::<T>::contains_key(&who, name_hash),
+                Error::<T>::DuplicateName
+            );
+
+            let deposit = T::PlaylistDeposit::get();
+            PlaylistMap::<T>::try_mutate(&who, |playlists| -> DispatchResult {
+                playlists
+                    .try_push(playlist_id)
+                    .map_err(|_| Error::<T>::TooManyPlaylists)?;
+                T::Currency::reserve(&who, deposit)
+            })?;
+            if let Some(previous) = PlaylistDeposits::<T>::take(playlist_id) {
+                T::Currency::unreserve(&from, previous);
+            }
+            PlaylistDeposits::<T>::insert(playlist_id, deposit);
+            PlaylistMap::<T>::mutate(&from, |playlists| playlists.retain(|id| *id != playlist_id));
+            NameIndex::<T>::remove(&from, name_hash);
+            NameIndex::<T>::insert(&who, name_hash, playlist_id);
+            Self::mutate_owned(&from, playlist_id, |playlist| {
+                playlist.owner = who.clone();
+            })?;
+            PendingTransfers::<T>::remove(playlist_id);
+            T::Access::set_owner(Self::resource_id(playlist_id), &who);
+
+            Self::deposit_event_for(
+                &[Self::account_topic(&from), Self::account_topic(&who)],
+                Event::PlaylistTransferred {
+                    playlist_id,
+                    from,
+                    to: who,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// List a channel on-chain for a playlist the caller owns or curates.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `entry`: Hashes of the channel name and stream URL.
+        ///
+        /// Emits `ChannelsChanged` on success.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::add_channel(T::MaxChannelsPerPlaylist::get()))]
+        pub fn add_channel(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            entry: ChannelEntry,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::mutate_channels(&who, playlist_id, |channels| {
+                ensure!(
+                    !channels
+                        .iter()
+                        .any(|c| c.stream_url_hash == entry.stream_url_hash),
+                    Error::<T>::DuplicateChannel
+                );
+                channels
+                    .try_push(entry)
+                    .map_err(|_| Error::<T>::TooManyChannels.into())
+            })
+        }
+
+        /// Remove a channel from the on-chain list of a playlist the caller owns or
+        /// curates.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `stream_url_hash`: Stream URL hash of the channel to remove.
+        ///
+        /// Emits `ChannelsChanged` on success.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::remove_channel(T::MaxChannelsPerPlaylist::get()))]
+        pub fn remove_channel(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            stream_url_hash: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::mutate_channels(&who, playlist_id, |channels| {
+                let position = channels
+                    .iter()
+                    .position(|c| c.stream_url_hash == stream_url_hash)
+                    .ok_or(Error::<T>::ChannelNotFound)?;
+                channels.remove(position);
+                Ok(())
+            })
+        }
+
+        /// Replace the whole on-chain channel list of a playlist the caller owns or
+        /// curates.
+        ///
+        /// Pass an empty list to stop listing channels on-chain.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `entries`: New channel list (at most `MaxChannelsPerPlaylist`, with
+        ///   unique stream URL hashes).
+        ///
+        /// Emits `ChannelsChanged` on success.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::set_channels(T::MaxChannelsPerPlaylist::get()))]
+        pub fn set_channels(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            entries: sp_std::vec::Vec<ChannelEntry>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let entries: BoundedVec<ChannelEntry, T::MaxChannelsPerPlaylist> =
+                entries
+                    .try_into()
+                    .map_err(|_| Error::<T>::TooManyChannels)?;
+            for (i, entry) in entries.iter().enumerate() {
+                ensure!(
+                    !entries[..i]
+                        .iter()
+                        .any(|c| c.stream_url_hash == entry.stream_url_hash),
+                    Error::<T>::DuplicateChannel
+                );
+            }
+
+            Self::mutate_channels(&who, playlist_id, |channels| {
+                *channels = entries;
+                Ok(())
+            })
+        }
+
+        /// Publish a new version of one of the caller's playlists.
+        ///
+        /// The version number is one more than the previous version's. Once
+        /// `MaxContentVersions` versions are kept, the oldest is dropped.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `content_hash`: SHA-256 hash of the M3U content being published.
+        ///
+        /// Emits `VersionPublished` on success.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::publish_version())]
+        pub fn publish_version(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            content_hash: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::MaxContentVersions::get() > 0,
+                Error::<T>::VersioningDisabled
+            );
+
+            let published_at = Self::current_block();
+            // Checks ownership and marks the playlist as updated.
+            Self::mutate_owned(&who, playlist_id, |_| ())?;
+            let version = ContentVersions::<T>::mutate(playlist_id, |versions| {
+                let version = versions.last().map_or(1, |v| v.version.saturating_add(1));
+                if versions.is_full() {
+                    versions.remove(0);
+                }
+                // Cannot fail: there is room after dropping the oldest version.
+                let _ = versions.try_push(ContentVersion {
+                    version,
+                    content_hash,
+                    published_at,
+                });
+                version
+            });
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::VersionPublished {
+                    who,
+                    playlist_id,
+                    version,
+                    content_hash,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Make one of the caller's playlists public or private.
+        ///
+        /// Public playlists are listed in `PublicPlaylists` and under their tags in
+        /// `TagIndex`.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `visibility`: New visibility.
+        ///
+        /// Emits `VisibilityChanged` when the visibility changes.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::set_visibility(T::MaxTags::get()))]
+        pub fn set_visibility(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            visibility: Visibility,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let previous = Self::playlist(playlist_id)
+                .ok_or(Error::<T>::PlaylistNotFound)?
+                .visibility;
+            if previous == visibility {
+                Self::ensure_owner(&who, playlist_id)?;
+                return Ok(());
+            }
+            let tags = Self::mutate_owned(&who, playlist_id, |playlist| {
+                playlist.visibility = visibility;
+                playlist.tags.clone()
+            })?;
+            match visibility {
+                Visibility::Public => {
+                    PublicPlaylists::<T>::insert(playlist_id, ());
+                    Self::index_tags(playlist_id, &tags);
+                }
+                Visibility::Private => {
+                    PublicPlaylists::<T>::remove(playlist_id);
+                    Self::unindex_tags(playlist_id, &tags);
+                }
+            }
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::VisibilityChanged {
+                    who,
+                    playlist_id,
+                    visibility,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Replace the tags of one of the caller's playlists.
+        ///
+        /// Pass an empty list to clear them.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `tags`: At most `MaxTags` distinct, non-empty tags of at most
+        ///   `MaxTagLength` bytes.
+        ///
+        /// Emits `TagsSet` on success.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_tags(T::MaxTags::get()))]
+        pub fn set_tags(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            tags: sp_std::vec::Vec<sp_std::vec::Vec<u8>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                tags.len() <= T::MaxTags::get() as usize,
+                Error::<T>::TooManyTags
+            );
+            let mut bounded = TagsOf::<T>::default();
+            for tag in tags {
+                ensure!(!tag.is_empty(), Error::<T>::InvalidTag);
+                let tag: TagOf<T> = tag.try_into().map_err(|_| Error::<T>::InvalidTag)?;
+                ensure!(!bounded.contains(&tag), Error::<T>::DuplicateTag);
+                bounded.try_push(tag).map_err(|_| Error::<T>::TooManyTags)?;
+            }
+
+            let (old_tags, visibility) = Self::mutate_owned(&who, playlist_id, |playlist| {
+                let old_tags = sp_std::mem::replace(&mut playlist.tags, bounded.clone());
+                (old_tags, playlist.visibility)
+            })?;
+            if visibility == Visibility::Public {
+                Self::unindex_tags(playlist_id, &old_tags);
+                Self::index_tags(playlist_id, &bounded);
+            }
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::TagsSet { who, playlist_id },
+            );
+
+            Ok(())
+        }
+
+        /// Vouch for the quality of a playlist as a curator.
+        ///
+        /// Attestations stay with the playlist when it changes hands; `owner` only
+        /// guards against attesting a playlist that was transferred in the meantime.
+        ///
+        /// - `owner`: The account the curator expects to own the playlist.
+        /// - `playlist_id`: Id of the playlist.
+        ///
+        /// Emits `PlaylistAttested` on success.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::attest_playlist())]
+        pub fn attest_playlist(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            playlist_id: PlaylistId,
+        ) -> DispatchResult {
+            let curator = T::AttesterOrigin::ensure_origin(origin)?;
+            let playlist = Self::playlist(playlist_id).ok_or(Error::<T>::PlaylistNotFound)?;
+            ensure!(playlist.owner == owner, Error::<T>::PlaylistNotFound);
+
+            Attestations::<T>::try_mutate(playlist_id, |attesters| -> DispatchResult {
+                ensure!(!attesters.contains(&curator), Error::<T>::AlreadyAttested);
+                attesters
+                    .try_push(curator.clone())
+                    .map_err(|_| Error::<T>::TooManyAttestations.into())
+            })?;
+
+            Self::deposit_event_for(
+                &[Self::account_topic(&owner), Self::account_topic(&curator)],
+                Event::PlaylistAttested {
+                    curator,
+                    playlist_id,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Withdraw the caller's attestation of a playlist.
+        ///
+        /// Open to any account that attested, including curators no longer admitted
+        /// by `AttesterOrigin`.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        ///
+        /// Emits `AttestationRevoked` on success.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::revoke_attestation())]
+        pub fn revoke_attestation(origin: OriginFor<T>, playlist_id: PlaylistId) -> DispatchResult {
+            let curator = ensure_signed(origin)?;
+
+            Attestations::<T>::try_mutate_exists(playlist_id, |maybe_attesters| {
+                let attesters = maybe_attesters.as_mut().ok_or(Error::<T>::NotAttested)?;
+                let position = attesters
+                    .iter()
+                    .position(|a| a == &curator)
+                    .ok_or(Error::<T>::NotAttested)?;
+                attesters.remove(position);
+                if attesters.is_empty() {
+                    *maybe_attesters = None;
+                }
+                Ok::<_, DispatchError>(())
+            })?;
+
+            let mut topics = sp_std::vec![Self::account_topic(&curator)];
+            if let Some(playlist) = Self::playlist(playlist_id) {
+                topics.insert(0, Self::account_topic(&playlist.owner));
+            }
+            Self::deposit_event_for(
+                &topics,
+                Event::AttestationRevoked {
+                    curator,
+                    playlist_id,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Override the global playlist cap, or pass `None` to fall back to
+        /// `MaxTotalPlaylists`.
+        ///
+        /// Lowering the cap below the current count removes no playlist; it only
+        /// stops new registrations until enough are removed.
+        ///
+        /// Emits `GlobalCapSet`.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::set_global_cap())]
+        pub fn set_global_cap(origin: OriginFor<T>, cap: Option<u32>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            GlobalCap::<T>::set(cap);
+            Self::deposit_event(Self::registry_topic(), Event::GlobalCapSet { cap });
+
+            Ok(())
+        }
+
+        /// Commit the source URL of one of the caller's playlists so the off-chain
+        /// worker can check its content.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `url`: The source URL; its SHA-256 hash must equal `source_url_hash`.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::set_source_url(url.len() as u32))]
+        pub fn set_source_url(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            url: sp_std::vec::Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let playlist = Self::playlist(playlist_id).ok_or(Error::<T>::PlaylistNotFound)?;
+            ensure!(playlist.owner == who, Error::<T>::NotPlaylistOwner);
+            ensure!(
+                sp_io::hashing::sha2_256(&url) == playlist.source_url_hash,
+                Error::<T>::SourceUrlMismatch
+            );
+            let url: BoundedVec<u8, T::MaxSourceUrlLength> =
+                url.try_into().map_err(|_| Error::<T>::SourceUrlTooLong)?;
+            SourceUrls::<T>::insert(playlist_id, url);
+
+            Ok(())
+        }
+
+        /// Record the hash of the content the off-chain worker fetched from a
+        /// playlist's source URL.
+        ///
+        /// Submitted unsigned by the off-chain worker, at most once per
+        /// `CheckInterval` per playlist. The report is signed with the key of one
+        /// of `ContentReporters`; the signature is checked when the transaction
+        /// is validated.
+        ///
+        /// - `report`: The checked playlist and the SHA-256 hash of its content.
+        /// - `signature`: The reporter's signature of `report`.
+        ///
+        /// Emits `ContentMismatch` when the hash differs from the latest version.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::report_content())]
+        pub fn report_content(
+            origin: OriginFor<T>,
+            report: ContentReport<T::Public, BlockNumberFor<T>>,
+            _signature: T::Signature,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            let ContentReport {
+                playlist_id,
+                content_hash,
+                ..
+            } = report;
+
+            let expected = Self::latest_version(playlist_id)
+                .ok_or(Error::<T>::NothingToCheck)?
+                .content_hash;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                SourceUrls::<T>::contains_key(playlist_id),
+                Error::<T>::NothingToCheck
+            );
+            ensure!(Self::check_due(playlist_id, now), Error::<T>::CheckNotDue);
+
+            LastChecked::<T>::insert(playlist_id, now);
+            if content_hash == expected {
+                ContentMismatches::<T>::remove(playlist_id);
+                return Ok(());
+            }
+            ContentMismatches::<T>::insert(playlist_id, content_hash);
+
+            let mut topics = sp_std::vec::Vec::new();
+            if let Some(playlist) = Self::playlist(playlist_id) {
+                topics.push(Self::account_topic(&playlist.owner));
+            }
+            Self::deposit_event_for(
+                &topics,
+                Event::ContentMismatch {
+                    playlist_id,
+                    expected,
+                    found: content_hash,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Gate one of the caller's playlists on a token requirement, or remove
+        /// its gate with `None`.
+        ///
+        /// The gate is set in the token-gate pallet on [`Pallet::resource_id`], and
+        /// is removed with the playlist.
+        ///
+        /// - `playlist_id`: Id of the playlist.
+        /// - `requirement`: Token and minimum balance viewers must hold.
+        ///
+        /// Emits `PlaylistGateSet` on success.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::set_playlist_gate())]
+        pub fn set_playlist_gate(
+            origin: OriginFor<T>,
+            playlist_id: PlaylistId,
+            requirement: Option<GateRequirement>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_owner(&who, playlist_id)?;
+            let resource_id = Self::resource_id(playlist_id);
+            match &requirement {
+                Some(requirement) => {
+                    T::Gate::set_gate(resource_id, requirement.clone(), Some(who.clone()))?
+                }
+                None => T::Gate::remove_gate(resource_id, Some(who.clone())),
+            }
+
+            Self::deposit_event(
+                Self::account_topic(&who),
+                Event::PlaylistGateSet {
+                    who,
+                    playlist_id,
+                    requirement,
+                },
+            );
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether `playlist_id` has a committed source URL and was not checked in
+        /// the last `CheckInterval` blocks.
+        pub fn check_due(playlist_id: PlaylistId, now: BlockNumberFor<T>) -> bool {
+            SourceUrls::<T>::contains_key(playlist_id)
+                && LastChecked::<T>::get(playlist_id)
+                    .is_none_or(|last| now >= last.saturating_add(T::CheckInterval::get()))
+        }
+
+        /// Drop the committed source URL of `playlist_id` and its check results.
+        fn forget_source_url(playlist_id: PlaylistId) {
+            SourceUrls::<T>::remove(playlist_id);
+            LastChecked::<T>::remove(playlist_id);
+            ContentMismatches::<T>::remove(playlist_id);
+        }
+
+        /// Record in off-chain storage that this node fetches `playlist_id` at
+        /// `now`, unless it already did in the last `CheckInterval` blocks.
+        ///
+        /// Keeps the worker from refetching a playlist every block while its
+        /// report waits for inclusion.
+        fn claim_fetch(playlist_id: PlaylistId, now: BlockNumberFor<T>) -> bool {
+            let key = (OCW_LAST_FETCH, playlist_id).encode();
+            StorageValueRef::persistent(&key)
+                .mutate(
+                    |last: Result<Option<BlockNumberFor<T>>, StorageRetrievalError>| match last {
+                        Ok(Some(last)) if now < last.saturating_add(T::CheckInterval::get()) => {
+                            Err(())
+                        }
+                        _ => Ok(now),
+                    },
+                )
+                .is_ok()
+        }
+
+        /// Fetch `url` over HTTP and return the SHA-256 hash of the response body.
+        fn fetch_content_hash(url: &[u8]) -> Result<[u8; 32], http::Error> {
+            let url = core::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+            let deadline =
+                sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+            let pending = http::Request::get(url)
+                .deadline(deadline)
+                .send()
+                .map_err(|_| http::Error::IoError)?;
+            let response = pending
+                .try_wait(deadline)
+                .map_err(|_| http::Error::DeadlineReached)??;
+            if response.code != 200 {
+                return Err(http::Error::Unknown);
+            }
+            let body: sp_std::vec::Vec<u8> = response.body().collect();
+            Ok(sp_io::hashing::sha2_256(&body))
+        }
+
+        /// Maximum number of playlists registered across all accounts.
+        pub fn global_cap() -> u32 {
+            GlobalCap::<T>::get().unwrap_or_else(T::MaxTotalPlaylists::get)
+        }
+
+        /// Store a new playlist for `who` under the next free id and return the id.
+        ///
+        /// Reserves `deposit` from `who` when given; genesis playlists carry none.
+        fn insert_playlist(
+            who: &T::AccountId,
+            name: BoundedVec<u8, T::MaxPlaylistNameLength>,
+            source_url_hash: [u8; 32],
+            deposit: Option<BalanceOf<T>>,
+        ) -> Result<PlaylistId, DispatchError> {
+            let name_hash = Self::name_hash(&name);
+            ensure!(
+                !NameIndex::<T>::contains_key(who, name_hash),
+                Error::<T>::DuplicateName
+            );
+            ensure!(
+                PlaylistCount::<T>::get() < Self::global_cap(),
+                Error::<T>::GlobalCapReached
+            );
+
+            let playlist_id = NextPlaylistId::<T>::get();
+            let next_id = playlist_id
+                .checked_add(1)
+                .ok_or(Error::<T>::NoAvailablePlaylistId)?;
+
+            let now = Self::current_block();
+            let metadata = PlaylistMetadata {
+                owner: who.clone(),
+                name,
+                source_url_hash,
+                channel_count: 0,
+                created_at: now,
+                updated_at: now,
+                description: BoundedVec::default(),
+                artwork_hash: None,
+                visibility: Visibility::Private,
+                tags: BoundedVec::default(),
+            };
+
+            PlaylistMap::<T>::try_mutate(who, |playlists| -> DispatchResult {
+                playlists
+                    .try_push(playlist_id)
+                    .map_err(|_| Error::<T>::TooManyPlaylists)?;
+                match deposit {
+                    Some(deposit) => T::Currency::reserve(who, deposit),
+                    None => Ok(()),
+                }
+            })?;
+            if let Some(deposit) = deposit {
+                PlaylistDeposits::<T>::insert(playlist_id, deposit);
+            }
+            NameIndex::<T>::insert(who, name_hash, playlist_id);
+            Playlists::<T>::insert(playlist_id, metadata);
+            NextPlaylistId::<T>::put(next_id);
+            T::Access::set_owner(Self::resource_id(playlist_id), who);
+
+            PlaylistCount::<T>::mutate(|count| {
+                *count = count.saturating_add(1);
+            });
+
+            Ok(playlist_id)
+        }
+
+        /// Topic under which every event about `owner`'s playlists is indexed.
+        ///
+        /// Computed as `T::Hashing` of the SCALE-encoded `(b"iptv/account", owner)`,
+        /// so a client can follow one owner with `System::EventTopics`.
+        pub fn account_topic(owner: &T::AccountId) -> T::Hash {
+            <T::Hashing as HashT>::hash_of(&(ACCOUNT_TOPIC, owner))
+        }
+
+        /// Topic under which every event about `resource_id` is indexed.
+        ///
+        /// Computed as `T::Hashing` of the SCALE-encoded `(b"iptv/resource", resource_id)`,
+        /// the same scheme the access-control and token-gate pallets use, so a client
+        /// can follow a playlist's [`Self::resource_id`] across pallets.
+        pub fn resource_topic(resource_id: u32) -> T::Hash {
+            <T::Hashing as HashT>::hash_of(&(RESOURCE_TOPIC, resource_id))
+        }
+
+        /// Topic of events about the registry as a whole, such as `GlobalCapSet`.
+        pub fn registry_topic() -> T::Hash {
+            <T::Hashing as HashT>::hash_of(&REGISTRY_TOPIC)
+        }
+
+        /// `owner`'s playlists with their ids, oldest first.
+        ///
+        /// Backs `PlaylistRegistryApi::playlists_of`.
+        pub fn playlists_of(
+            owner: &T::AccountId,
+        ) -> sp_std::vec::Vec<(PlaylistId, PlaylistView<T::AccountId>)> {
+            PlaylistMap::<T>::get(owner)
+                .into_iter()
+                .filter_map(|id| Playlists::<T>::get(id).map(|p| (id, Self::view(p))))
+                .collect()
+        }
+
+        /// `playlist_id`, if it exists and is owned by `owner`.
+        ///
+        /// Backs `PlaylistRegistryApi::playlist`.
+        pub fn owned_playlist(
+            owner: &T::AccountId,
+            playlist_id: PlaylistId,
+        ) -> Option<PlaylistView<T::AccountId>> {
+            Playlists::<T>::get(playlist_id)
+                .filter(|p| &p.owner == owner)
+                .map(Self::view)
+        }
+
+        /// Convert stored metadata into its runtime API form.
+        fn view(playlist: PlaylistMetadataOf<T>) -> PlaylistView<T::AccountId> {
+            PlaylistMetadata {
+                owner: playlist.owner,
+                name: playlist.name.into_inner(),
+                source_url_hash: playlist.source_url_hash,
+                channel_count: playlist.channel_count,
+                created_at: playlist.created_at,
+                updated_at: playlist.updated_at,
+                description: playlist.description.into_inner(),
+                artwork_hash: playlist.artwork_hash,
+                visibility: playlist.visibility,
+                tags: playlist
+                    .tags
+                    .into_iter()
+                    .map(BoundedVec::into_inner)
+                    .collect(),
+            }
+        }
+
+        /// Id of `owner`'s playlist named `name`, if any.
+        pub fn playlist_by_name(owner: &T::AccountId, name: &[u8]) -> Option<PlaylistId> {
+            NameIndex::<T>::get(owner, Self::name_hash(name))
+        }
+
+        /// Key of `name` in `NameIndex`.
+        pub(crate) fn name_hash(name: impl AsRef<[u8]>) -> T::Hash {
+            <T::Hashing as HashT>::hash(name.as_ref())
+        }
+
+        /// Number of curators that attested `playlist_id`.
+        pub fn attestation_count(playlist_id: PlaylistId) -> u32 {
+            Attestations::<T>::decode_len(playlist_id).unwrap_or(0) as u32
+        }
+
+        /// Ids of the public playlists tagged `tag`, in storage order.
+        pub fn playlists_tagged(tag: &[u8]) -> sp_std::vec::Vec<PlaylistId> {
+            match TagOf::<T>::try_from(tag.to_vec()) {
+                Ok(tag) => TagIndex::<T>::iter_key_prefix(tag).collect(),
+                Err(_) => sp_std::vec::Vec::new(),
+            }
+        }
+
+        /// List `playlist_id` under each of `tags`.
+        fn index_tags(playlist_id: PlaylistId, tags: &TagsOf<T>) {
+            for tag in tags {
+                TagIndex::<T>::insert(tag, playlist_id, ());
+            }
+        }
+
+        /// Drop `playlist_id` from under each of `tags`.
+        fn unindex_tags(playlist_id: PlaylistId, tags: &TagsOf<T>) {
+            for tag in tags {
+                TagIndex::<T>::remove(tag, playlist_id);
+            }
+        }
+
+        /// Ids of all public playlists, in storage order.
+        pub fn public_playlists() -> sp_std::vec::Vec<PlaylistId> {
+            PublicPlaylists::<T>::iter_keys().collect()
+        }
+
+        /// Access-control resource whose `Editor`s and `Admin`s curate `playlist_id`.
+        ///
+        /// Owned by the playlist's owner. Playlists share the resource id space with
+        /// anything else the runtime guards with the access-control pallet.
+        pub fn resource_id(playlist_id: PlaylistId) -> u32 {
+            playlist_id
+        }
+
+        /// Whether `who` may change the channels of `playlist_id` without owning it.
+        pub fn is_curator(who: &T::AccountId, playlist_id: PlaylistId) -> bool {
+            T::Access::has_role(who, Self::resource_id(playlist_id), Role::Editor)
+        }
+
+        /// Whether `who` may read the channels of `playlist_id`: its owner and
+        /// curators always can, anyone else must meet the playlist's token gate.
+        pub fn can_read_channels(who: &T::AccountId, playlist_id: PlaylistId) -> bool {
+            Playlists::<T>::get(playlist_id).is_some_and(|p| {
+                &p.owner == who
+                    || Self::is_curator(who, playlist_id)
+                    || T::Gate::check_access(who, Self::resource_id(playlist_id))
+            })
+        }
+
+        /// Channels of `playlist_id` as served to `who`, or `None` if the playlist
+        /// does not exist or `who` does not meet its token gate.
+        ///
+        /// Channel-read runtime APIs should go through this so gates apply.
+        pub fn readable_channels(
+            who: &T::AccountId,
+            playlist_id: PlaylistId,
+        ) -> Option<sp_std::vec::Vec<ChannelEntry>> {
+            Self::can_read_channels(who, playlist_id)
+                .then(|| Channels::<T>::get(playlist_id).into_inner())
+        }
+
+        /// Blocks at which `playlist_id` was created and last changed, as
+        /// `(created_at, updated_at)`.
+        pub fn playlist_timestamps(playlist_id: PlaylistId) -> Option<(u64, u64)> {
+            Playlists::<T>::get(playlist_id).map(|p| (p.created_at, p.updated_at))
+        }
+
+        /// Most recently published content version of `playlist_id`, if any.
+        pub fn latest_version(playlist_id: PlaylistId) -> Option<ContentVersion> {
+            ContentVersions::<T>::get(playlist_id).last().cloned()
+        }
+
+        /// Current block number as stored in playlist metadata.
+        fn current_block() -> u64 {
+            frame_system::Pallet::<T>::block_number().saturated_into()
+        }
+
+        /// Ensure `playlist_id` exists and is owned by `who`.
+        fn ensure_owner(who: &T::AccountId, playlist_id: PlaylistId) -> DispatchResult {
+            let playlist = Playlists::<T>::get(playlist_id).ok_or(Error::<T>::PlaylistNotFound)?;
+            ensure!(&playlist.owner == who, Error::<T>::NotPlaylistOwner);
+            Ok(())
+        }
+
+        /// Apply `f` to `playlist_id` if it exists and is owned by `who`, and mark it
+        /// as updated in the current block.
+        fn mutate_owned<R>(
+            who: &T::AccountId,
+            playlist_id: PlaylistId,
+            f: impl FnOnce(&mut PlaylistMetadataOf<T>) -> R,
+        ) -> Result<R, DispatchError> {
+            Self::mutate_playlist(playlist_id, |owner| owner == who, f)
+        }
+
+        /// Like `mutate_owned`, but also lets curators of the playlist through.
+        fn mutate_curated<R>(
+            who: &T::AccountId,
+            playlist_id: PlaylistId,
+            f: impl FnOnce(&mut PlaylistMetadataOf<T>) -> R,
+        ) -> Result<R, DispatchError> {
+            Self::mutate_playlist(
+                playlist_id,
+                |owner| owner == who || Self::is_curator(who, playlist_id),
+                f,
+            )
+        }
+
+        /// Apply `f` to `playlist_id` if it exists and `allowed` accepts its owner,
+        /// and mark it as updated in the current block.
+        fn mutate_playlist<R>(
+            playlist_id: PlaylistId,
+            allowed: impl FnOnce(&T::AccountId) -> bool,
+            f: impl FnOnce(&mut PlaylistMetadataOf<T>) -> R,
+        ) -> Result<R, DispatchError> {
+            Playlists::<T>::try_mutate(playlist_id, |maybe_playlist| {
+                let playlist = maybe_playlist
+                    .as_mut()
+                    .ok_or(Error::<T>::PlaylistNotFound)?;
+                ensure!(allowed(&playlist.owner), Error::<T>::NotPlaylistOwner);
+                playlist.updated_at = Self::current_block();
+                Ok(f(playlist))
+            })
+        }
+
+        /// Apply `f` to the channel list of `playlist_id` if `who` owns or curates it,
+        /// then sync the playlist's `channel_count` and emit `ChannelsChanged`.
+        ///
+        /// The event is indexed under the owner, and also under `who` when a curator
+        /// made the change.
+        fn mutate_channels(
+            who: &T::AccountId,
+            playlist_id: PlaylistId,
+            f: impl FnOnce(&mut BoundedVec<ChannelEntry, T::MaxChannelsPerPlaylist>) -> DispatchResult,
+        ) -> DispatchResult {
+            let owner = Self::playlist(playlist_id)
+                .ok_or(Error::<T>::PlaylistNotFound)?
+                .owner;
+            ensure!(
+                &owner == who || Self::is_curator(who, playlist_id),
+                Error::<T>::NotPlaylistOwner
+            );
+            let mut channels = Channels::<T>::get(playlist_id);
+            f(&mut channels)?;
+
+            let channel_count = channels.len() as u32;
+            if channels.is_empty() {
+                Channels::<T>::remove(playlist_id);
+            } else {
+                Channels::<T>::insert(playlist_id, channels);
+            }
+            Self::mutate_curated(who, playlist_id, |playlist| {
+                playlist.channel_count = channel_count;
+            })?;
+
+            let mut topics = sp_std::vec![Self::account_topic(&owner)];
+            if &owner != who {
+                topics.push(Self::account_topic(who));
+            }
+            Self::deposit_event_for(
+                &topics,
+                Event::ChannelsChanged {
+                    who: who.clone(),
+                    playlist_id,
+                    channel_count,
+                },
+            );
+            Ok(())
+        }
+
+        /// Deposit `event`, indexed under `topic`.
+        fn deposit_event(topic: T::Hash, event: Event<T>) {
+            Self::deposit_event_for(&[topic], event);
+        }
+
+        /// Deposit `event`, indexed under each of `topics` and, if it is about a
+        /// playlist, under that playlist's resource topic.
+        fn deposit_event_for(topics: &[T::Hash], event: Event<T>) {
+            let mut topics = topics.to_vec();
+            if let Some(playlist_id) = event.playlist_id() {
+                topics.push(Self::resource_topic(Self::resource_id(playlist_id)));
+            }
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event_indexed(&topics, event.into());
+        }
+    }
+
+    impl<T: Config> Event<T> {
+        /// The playlist this event is about, if any.
+        fn playlist_id(&self) -> Option<PlaylistId> {
+            match self {
+                Event::PlaylistRegistered { playlist_id, .. }
+                | Event::PlaylistRemoved { playlist_id, .. }
+                | Event::PlaylistUpdated { playlist_id, .. }
+                | Event::TransferProposed { playlist_id, .. }
+                | Event::TransferCancelled { playlist_id }
+                | Event::PlaylistTransferred { playlist_id, .. }
+                | Event::ChannelsChanged { playlist_id, .. }
+                | Event::VersionPublished { playlist_id, .. }
+                | Event::VisibilityChanged { playlist_id, .. }
+                | Event::PlaylistAttested { playlist_id, .. }
+                | Event::AttestationRevoked { playlist_id, .. }
+                | Event::TagsSet { playlist_id, .. }
+                | Event::PlaylistDetailsUpdated { playlist_id, .. }
+                | Event::ContentMismatch { playlist_id, .. }
+                | Event::PlaylistGateSet { playlist_id, .. } => Some(*playlist_id),
+                Event::GlobalCapSet { .. } => None,
+                Event::__Ignore(..) => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::{Decode, Encode};
+    use frame_support::{
+        assert_noop, assert_ok, derive_impl,
+        traits::{ConstU32, ConstU64, Contains, SortedMembers},
+    };
+    use frame_system::offchain::AppCrypto;
+    use pallet_token_gate::GateRequirement;
+    use sp_core::H256;
+    use sp_io::TestExternalities;
+    use sp_runtime::{
+        testing::{TestSignature, UintAuthorityId},
+        traits::{BlakeTwo256, Hash, IdentityLookup},
+        BuildStorage,
+    };
+
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    frame_support::construct_runtime!(
+        pub enum Test {
+            System: frame_system,
+            Balances: pallet_balances,
+            PlaylistRegistry: pallet,
+        }
+    );
+
+    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+    impl frame_system::Config for Test {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = ();
+        type BlockLength = ();
+        type DbWeight = ();
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Nonce = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Block = Block;
+        type RuntimeEvent = RuntimeEvent;
         type BlockHashCount = ConstU64<250>;
         type Version = ();
         type PalletInfo = PalletInfo;
-        type AccountData = ();
+        type AccountData = pallet_balances::AccountData<u64>;
         type OnNewAccount = ();
         type OnKilledAccount = ();
         type SystemWeightInfo = ();
@@ -236,81 +2027,1796 @@ mod tests {
         type MaxConsumers = ConstU32<16>;
     }
 
-    impl pallet::Config for Test {
-        type RuntimeEvent = RuntimeEvent;
-        type MaxPlaylistsPerAccount = ConstU32<5>;
-        type MaxPlaylistNameLength = ConstU32<64>;
-    }
+    #[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+    impl pallet_balances::Config for Test {
+        type AccountStore = System;
+    }
+
+    /// Reserved per registered playlist in tests.
+    const PLAYLIST_DEPOSIT: u64 = 5;
+
+    /// Account holding `Editor` on playlist 0's resource in tests.
+    const CURATOR: u64 = 8;
+
+    /// Grants [`CURATOR`] `Editor` on resource 0 and nothing else.
+    pub struct MockAccess;
+
+    std::thread_local! {
+        static RESOURCE_OWNERS: std::cell::RefCell<std::collections::BTreeMap<u32, u64>> =
+            Default::default();
+    }
+
+    impl pallet_access_control::ResourceAccess<u64> for MockAccess {
+        fn has_role(who: &u64, resource_id: u32, min_role: pallet_access_control::Role) -> bool {
+            *who == CURATOR && resource_id == 0 && min_role <= pallet_access_control::Role::Editor
+        }
+
+        fn owner(resource_id: u32) -> Option<u64> {
+            RESOURCE_OWNERS.with(|o| o.borrow().get(&resource_id).copied())
+        }
+
+        fn set_owner(resource_id: u32, owner: &u64) {
+            RESOURCE_OWNERS.with(|o| o.borrow_mut().insert(resource_id, *owner));
+        }
+    }
+
+    /// Account meeting every token gate in tests.
+    const TOKEN_HOLDER: u64 = 9;
+
+    std::thread_local! {
+        static GATES: std::cell::RefCell<std::collections::BTreeMap<u32, GateRequirement>> =
+            Default::default();
+    }
+
+    /// Keeps gates in memory; only [`TOKEN_HOLDER`] meets them.
+    pub struct MockGate;
+
+    impl pallet_token_gate::ResourceGate<u64> for MockGate {
+        fn check_access(who: &u64, resource_id: u32) -> bool {
+            *who == TOKEN_HOLDER || !GATES.with(|g| g.borrow().contains_key(&resource_id))
+        }
+
+        fn set_gate(
+            resource_id: u32,
+            requirement: GateRequirement,
+            _by: Option<u64>,
+        ) -> frame_support::dispatch::DispatchResult {
+            GATES.with(|g| g.borrow_mut().insert(resource_id, requirement));
+            Ok(())
+        }
+
+        fn remove_gate(resource_id: u32, _by: Option<u64>) {
+            GATES.with(|g| g.borrow_mut().remove(&resource_id));
+        }
+    }
+
+    /// Curators admitted to attest playlists in tests.
+    pub struct Attesters;
+
+    impl SortedMembers<u64> for Attesters {
+        fn sorted_members() -> Vec<u64> {
+            vec![4, 5, 6]
+        }
+    }
+
+    /// The only account whose key may sign content reports in tests.
+    const REPORTER: u64 = 11;
+
+    /// Admits [`REPORTER`] to sign content reports.
+    pub struct Reporters;
+
+    impl Contains<u64> for Reporters {
+        fn contains(who: &u64) -> bool {
+            *who == REPORTER
+        }
+    }
+
+    /// Reporter keys of the mock runtime, which signs with [`TestSignature`].
+    pub struct TestReporterId;
+
+    impl AppCrypto<UintAuthorityId, TestSignature> for TestReporterId {
+        type RuntimeAppPublic = UintAuthorityId;
+        type GenericPublic = UintAuthorityId;
+        type GenericSignature = TestSignature;
+    }
+
+    impl pallet::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type MaxPlaylistsPerAccount = ConstU32<5>;
+        type MaxTotalPlaylists = ConstU32<8>;
+        type MaxPlaylistNameLength = ConstU32<64>;
+        type MaxDescriptionLength = ConstU32<128>;
+        type MaxChannelsPerPlaylist = ConstU32<3>;
+        type MaxContentVersions = ConstU32<2>;
+        type Currency = Balances;
+        type PlaylistDeposit = ConstU64<PLAYLIST_DEPOSIT>;
+        type MaxTags = ConstU32<3>;
+        type MaxTagLength = ConstU32<8>;
+        type Access = MockAccess;
+        type Gate = MockGate;
+        type AttesterOrigin = frame_system::EnsureSignedBy<Attesters, u64>;
+        type MaxAttestations = ConstU32<2>;
+        type MaxSourceUrlLength = ConstU32<64>;
+        type CheckInterval = ConstU64<10>;
+        type UnsignedPriority = ConstU64<100>;
+        type AuthorityId = TestReporterId;
+        type ContentReporters = Reporters;
+        type WeightInfo = ();
+    }
+
+    type Extrinsic = sp_runtime::testing::TestXt<RuntimeCall, ()>;
+
+    impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+    where
+        RuntimeCall: From<C>,
+    {
+        type OverarchingCall = RuntimeCall;
+        type Extrinsic = Extrinsic;
+    }
+
+    impl frame_system::offchain::SigningTypes for Test {
+        type Public = UintAuthorityId;
+        type Signature = TestSignature;
+    }
+
+    /// A report of `content_hash` for playlist 0 made at block 1, signed by `key`.
+    fn content_report(
+        key: u64,
+        content_hash: [u8; 32],
+    ) -> (ContentReport<UintAuthorityId, u64>, TestSignature) {
+        let report = ContentReport {
+            playlist_id: 0,
+            content_hash,
+            block_number: 1,
+            public: UintAuthorityId(key),
+        };
+        let signature = TestSignature(key, report.encode());
+        (report, signature)
+    }
+
+    /// Build a test externalities instance with default genesis state.
+    /// Accounts 1 to 9 start with 100 units each; 10 and above start empty.
+    pub(crate) fn new_test_ext() -> TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis build should succeed in tests");
+        pallet_balances::GenesisConfig::<Test> {
+            balances: (1..10).map(|account| (account, 100)).collect(),
+        }
+        .assimilate_storage(&mut t)
+        .expect("balances genesis should succeed in tests");
+        let mut ext = TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    #[test]
+    fn register_playlist_works() {
+        new_test_ext().execute_with(|| {
+            let name = b"My Playlist".to_vec();
+            let hash = [1u8; 32];
+
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                name.clone(),
+                hash,
+            ));
+
+            // Verify storage
+            assert_eq!(pallet::PlaylistMap::<Test>::get(1).into_inner(), vec![0]);
+            let playlist = pallet::Playlists::<Test>::get(0).expect("registered");
+            assert_eq!(playlist.owner, 1);
+            assert_eq!(playlist.source_url_hash, hash);
+            assert_eq!(playlist.channel_count, 0);
+            assert!(playlist.description.is_empty());
+            assert_eq!(playlist.artwork_hash, None);
+            assert_eq!(pallet::PlaylistCount::<Test>::get(), 1);
+            assert_eq!(pallet::NextPlaylistId::<Test>::get(), 1);
+
+            // Verify event
+            let expected_name: frame_support::BoundedVec<u8, ConstU32<64>> =
+                name.try_into().expect("name fits in test");
+            System::assert_last_event(
+                pallet::Event::<Test>::PlaylistRegistered {
+                    who: 1,
+                    playlist_id: 0,
+                    name: expected_name,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn genesis_registers_playlists_without_deposit() {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis build should succeed in tests");
+        pallet::GenesisConfig::<Test> {
+            playlists: vec![
+                (20, b"News".to_vec(), [1u8; 32]),
+                (21, b"Sports".to_vec(), [2u8; 32]),
+            ],
+        }
+        .assimilate_storage(&mut t)
+        .expect("registry genesis should succeed in tests");
+
+        TestExternalities::new(t).execute_with(|| {
+            assert_eq!(PlaylistRegistry::playlist_count(), 2);
+            assert_eq!(PlaylistRegistry::playlist_by_name(&21, b"Sports"), Some(1));
+            assert_eq!(PlaylistRegistry::playlist(0).map(|p| p.owner), Some(20));
+            assert_eq!(NextPlaylistId::<Test>::get(), 2);
+
+            // Unfunded owners can still remove them: nothing was reserved.
+            assert!(PlaylistDeposits::<Test>::get(0).is_none());
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(20),
+                0
+            ));
+            assert_eq!(PlaylistRegistry::playlist_count(), 1);
+        });
+    }
+
+    #[test]
+    fn remove_playlist_works() {
+        new_test_ext().execute_with(|| {
+            let name = b"Remove Me".to_vec();
+            let hash = [2u8; 32];
+
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                name,
+                hash,
+            ));
+            assert_eq!(pallet::PlaylistMap::<Test>::get(1).len(), 1);
+            assert_eq!(pallet::PlaylistCount::<Test>::get(), 1);
+
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+            ));
+
+            assert_eq!(pallet::PlaylistMap::<Test>::get(1).len(), 0);
+            assert!(pallet::Playlists::<Test>::get(0).is_none());
+            assert_eq!(pallet::PlaylistCount::<Test>::get(), 0);
+
+            // Removing a non-existent playlist should fail
+            assert_noop!(
+                PlaylistRegistry::remove_playlist(RuntimeOrigin::signed(1), 0),
+                pallet::Error::<Test>::PlaylistNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn playlists_record_creation_and_update_blocks() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(5);
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Aged".to_vec(),
+                [1u8; 32],
+            ));
+            assert_eq!(PlaylistRegistry::playlist_timestamps(0), Some((5, 5)));
+
+            System::set_block_number(9);
+            assert_ok!(PlaylistRegistry::update_channel_count(
+                RuntimeOrigin::signed(1),
+                0,
+                3,
+            ));
+            assert_eq!(PlaylistRegistry::playlist_timestamps(0), Some((5, 9)));
+
+            // A rejected change leaves the playlist untouched.
+            System::set_block_number(12);
+            assert_noop!(
+                PlaylistRegistry::update_channel_count(RuntimeOrigin::signed(2), 0, 4),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+            assert_eq!(PlaylistRegistry::playlist_timestamps(0), Some((5, 9)));
+            assert_eq!(PlaylistRegistry::playlist_timestamps(1), None);
+        });
+    }
+
+    #[test]
+    fn names_are_unique_per_owner() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Sports".to_vec(),
+                [1u8; 32],
+            ));
+            assert_noop!(
+                PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(1),
+                    b"Sports".to_vec(),
+                    [2u8; 32],
+                ),
+                pallet::Error::<Test>::DuplicateName
+            );
+            // Another account may reuse the name.
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(2),
+                b"Sports".to_vec(),
+                [3u8; 32],
+            ));
+            assert_eq!(PlaylistRegistry::playlist_by_name(&1, b"Sports"), Some(0));
+            assert_eq!(PlaylistRegistry::playlist_by_name(&2, b"Sports"), Some(1));
+
+            // Renames keep the index in sync.
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"News".to_vec(),
+                [4u8; 32],
+            ));
+            assert_noop!(
+                PlaylistRegistry::update_playlist(
+                    RuntimeOrigin::signed(1),
+                    2,
+                    Some(b"Sports".to_vec()),
+                    None,
+                ),
+                pallet::Error::<Test>::DuplicateName
+            );
+            assert_ok!(PlaylistRegistry::update_playlist(
+                RuntimeOrigin::signed(1),
+                2,
+                Some(b"World News".to_vec()),
+                None,
+            ));
+            assert_eq!(PlaylistRegistry::playlist_by_name(&1, b"News"), None);
+            assert_eq!(
+                PlaylistRegistry::playlist_by_name(&1, b"World News"),
+                Some(2)
+            );
+
+            // A transfer is refused when the recipient already uses the name.
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                2
+            ));
+            assert_noop!(
+                PlaylistRegistry::accept_playlist(RuntimeOrigin::signed(2), 0),
+                pallet::Error::<Test>::DuplicateName
+            );
+
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert_eq!(PlaylistRegistry::playlist_by_name(&1, b"Sports"), None);
+        });
+    }
+
+    #[test]
+    fn playlist_ids_survive_removals() {
+        new_test_ext().execute_with(|| {
+            for name in [b"First".to_vec(), b"Second".to_vec(), b"Third".to_vec()] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(1),
+                    name,
+                    [5u8; 32],
+                ));
+            }
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+
+            // The remaining playlists keep their ids.
+            assert_eq!(pallet::PlaylistMap::<Test>::get(1).into_inner(), vec![1, 2]);
+            assert_ok!(PlaylistRegistry::update_channel_count(
+                RuntimeOrigin::signed(1),
+                2,
+                42,
+            ));
+            let third = pallet::Playlists::<Test>::get(2).expect("registered");
+            assert_eq!(third.name.to_vec(), b"Third".to_vec());
+            assert_eq!(third.channel_count, 42);
+
+            // Ids are never reused.
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(2),
+                b"Fourth".to_vec(),
+                [6u8; 32],
+            ));
+            assert_eq!(pallet::PlaylistMap::<Test>::get(2).into_inner(), vec![3]);
+            assert_noop!(
+                PlaylistRegistry::update_channel_count(RuntimeOrigin::signed(1), 0, 1),
+                pallet::Error::<Test>::PlaylistNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn transfer_playlist_needs_acceptance() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Curated".to_vec(),
+                [9u8; 32],
+            ));
+            assert_noop!(
+                PlaylistRegistry::transfer_playlist(RuntimeOrigin::signed(2), 0, 3),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                2
+            ));
+            // Nothing moves until the recipient accepts, and only they can.
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0).expect("registered").owner,
+                1
+            );
+            assert_noop!(
+                PlaylistRegistry::accept_playlist(RuntimeOrigin::signed(3), 0),
+                pallet::Error::<Test>::NoPendingTransfer
+            );
+
+            assert_ok!(PlaylistRegistry::accept_playlist(
+                RuntimeOrigin::signed(2),
+                0
+            ));
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0).expect("registered").owner,
+                2
+            );
+            assert!(pallet::PlaylistMap::<Test>::get(1).is_empty());
+            assert_eq!(pallet::PlaylistMap::<Test>::get(2).into_inner(), vec![0]);
+            assert!(pallet::PendingTransfers::<Test>::get(0).is_none());
+            System::assert_last_event(
+                pallet::Event::<Test>::PlaylistTransferred {
+                    playlist_id: 0,
+                    from: 1,
+                    to: 2,
+                }
+                .into(),
+            );
+
+            // The new owner can withdraw an offer by offering it to themselves.
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(2),
+                0,
+                1
+            ));
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(2),
+                0,
+                2
+            ));
+            assert_noop!(
+                PlaylistRegistry::accept_playlist(RuntimeOrigin::signed(1), 0),
+                pallet::Error::<Test>::NoPendingTransfer
+            );
+        });
+    }
+
+    #[test]
+    fn playlist_resources_cannot_be_hijacked() {
+        use pallet_access_control::ResourceAccess;
+
+        new_test_ext().execute_with(|| {
+            // Resources of playlists yet to be registered are reserved, so nobody
+            // can claim one in advance and curate the playlist or collect its
+            // pass payments.
+            assert!(PlaylistResources::<Test>::contains(&0));
+            assert!(PlaylistResources::<Test>::contains(&7));
+
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Curated".to_vec(),
+                [9u8; 32],
+            ));
+            let resource_id = PlaylistRegistry::resource_id(0);
+            assert_eq!(MockAccess::owner(resource_id), Some(1));
+            assert!(PlaylistResources::<Test>::contains(&resource_id));
+
+            // The resource follows the playlist to its new owner.
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                2
+            ));
+            assert_eq!(MockAccess::owner(resource_id), Some(1));
+            assert_ok!(PlaylistRegistry::accept_playlist(
+                RuntimeOrigin::signed(2),
+                0
+            ));
+            assert_eq!(MockAccess::owner(resource_id), Some(2));
+
+            // Removed playlists release the reservation; their resource stays
+            // with the last owner.
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(2),
+                0
+            ));
+            assert!(!PlaylistResources::<Test>::contains(&resource_id));
+            assert_eq!(MockAccess::owner(resource_id), Some(2));
+        });
+    }
+
+    #[test]
+    fn accepting_a_playlist_respects_the_per_account_cap() {
+        new_test_ext().execute_with(|| {
+            for i in 0..5u8 {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(2),
+                    vec![b'a' + i],
+                    [1u8; 32],
+                ));
+            }
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Extra".to_vec(),
+                [2u8; 32],
+            ));
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(1),
+                5,
+                2
+            ));
+            assert_noop!(
+                PlaylistRegistry::accept_playlist(RuntimeOrigin::signed(2), 5),
+                pallet::Error::<Test>::TooManyPlaylists
+            );
+        });
+    }
+
+    fn channel(n: u8) -> pallet::ChannelEntry {
+        pallet::ChannelEntry {
+            name_hash: [n; 32],
+            stream_url_hash: [n.wrapping_add(100); 32],
+        }
+    }
+
+    #[test]
+    fn channel_count_follows_on_chain_channels() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Listed".to_vec(),
+                [1u8; 32],
+            ));
+
+            assert_ok!(PlaylistRegistry::add_channel(
+                RuntimeOrigin::signed(1),
+                0,
+                channel(1)
+            ));
+            assert_ok!(PlaylistRegistry::add_channel(
+                RuntimeOrigin::signed(1),
+                0,
+                channel(2)
+            ));
+            assert_noop!(
+                PlaylistRegistry::add_channel(RuntimeOrigin::signed(1), 0, channel(1)),
+                pallet::Error::<Test>::DuplicateChannel
+            );
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0)
+                    .expect("registered")
+                    .channel_count,
+                2
+            );
+            System::assert_last_event(
+                pallet::Event::<Test>::ChannelsChanged {
+                    who: 1,
+                    playlist_id: 0,
+                    channel_count: 2,
+                }
+                .into(),
+            );
+
+            // The count can no longer be set by hand.
+            assert_noop!(
+                PlaylistRegistry::update_channel_count(RuntimeOrigin::signed(1), 0, 50),
+                pallet::Error::<Test>::ChannelsListedOnChain
+            );
+
+            assert_ok!(PlaylistRegistry::remove_channel(
+                RuntimeOrigin::signed(1),
+                0,
+                channel(1).stream_url_hash,
+            ));
+            assert_eq!(
+                pallet::Channels::<Test>::get(0).into_inner(),
+                vec![channel(2)]
+            );
+            assert_noop!(
+                PlaylistRegistry::remove_channel(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    channel(1).stream_url_hash
+                ),
+                pallet::Error::<Test>::ChannelNotFound
+            );
+            assert_noop!(
+                PlaylistRegistry::add_channel(RuntimeOrigin::signed(2), 0, channel(3)),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+        });
+    }
+
+    #[test]
+    fn curators_can_change_channels_of_others_playlists() {
+        new_test_ext().execute_with(|| {
+            for owner in [1, 2] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(owner),
+                    b"Shared".to_vec(),
+                    [1u8; 32],
+                ));
+            }
+            assert!(PlaylistRegistry::is_curator(&CURATOR, 0));
+            assert!(!PlaylistRegistry::is_curator(&CURATOR, 1));
+
+            assert_ok!(PlaylistRegistry::update_channel_count(
+                RuntimeOrigin::signed(CURATOR),
+                0,
+                4
+            ));
+            assert_ok!(PlaylistRegistry::add_channel(
+                RuntimeOrigin::signed(CURATOR),
+                0,
+                channel(1)
+            ));
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0)
+                    .expect("registered")
+                    .channel_count,
+                1
+            );
+
+            // The change shows up for both the owner and the curator.
+            let owner_topic = PlaylistRegistry::account_topic(&1);
+            let curator_topic = PlaylistRegistry::account_topic(&CURATOR);
+            let last = System::events().pop().expect("event deposited");
+            assert_eq!(
+                last.topics,
+                vec![
+                    owner_topic,
+                    curator_topic,
+                    PlaylistRegistry::resource_topic(0)
+                ]
+            );
+
+            // Curation does not extend to ownership or to other playlists.
+            assert_noop!(
+                PlaylistRegistry::update_playlist(
+                    RuntimeOrigin::signed(CURATOR),
+                    0,
+                    Some(b"Mine".to_vec()),
+                    None,
+                ),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+            assert_noop!(
+                PlaylistRegistry::remove_playlist(RuntimeOrigin::signed(CURATOR), 0),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+            assert_noop!(
+                PlaylistRegistry::add_channel(RuntimeOrigin::signed(CURATOR), 1, channel(1)),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+        });
+    }
+
+    #[test]
+    fn set_channels_replaces_the_list() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Listed".to_vec(),
+                [1u8; 32],
+            ));
+            assert_ok!(PlaylistRegistry::set_channels(
+                RuntimeOrigin::signed(1),
+                0,
+                vec![channel(1), channel(2), channel(3)],
+            ));
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0)
+                    .expect("registered")
+                    .channel_count,
+                3
+            );
+            assert_noop!(
+                PlaylistRegistry::add_channel(RuntimeOrigin::signed(1), 0, channel(4)),
+                pallet::Error::<Test>::TooManyChannels
+            );
+            assert_noop!(
+                PlaylistRegistry::set_channels(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    vec![channel(1), channel(2), channel(3), channel(4)],
+                ),
+                pallet::Error::<Test>::TooManyChannels
+            );
+            assert_noop!(
+                PlaylistRegistry::set_channels(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    vec![channel(1), channel(1)],
+                ),
+                pallet::Error::<Test>::DuplicateChannel
+            );
+
+            // An empty list hands the count back to `update_channel_count`.
+            assert_ok!(PlaylistRegistry::set_channels(
+                RuntimeOrigin::signed(1),
+                0,
+                Vec::new()
+            ));
+            assert!(!pallet::Channels::<Test>::contains_key(0));
+            assert_ok!(PlaylistRegistry::update_channel_count(
+                RuntimeOrigin::signed(1),
+                0,
+                7
+            ));
+
+            // Removing the playlist drops its channels.
+            assert_ok!(PlaylistRegistry::set_channels(
+                RuntimeOrigin::signed(1),
+                0,
+                vec![channel(1)]
+            ));
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert!(!pallet::Channels::<Test>::contains_key(0));
+        });
+    }
+
+    #[test]
+    fn published_versions_are_numbered_and_bounded() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Versioned".to_vec(),
+                [1u8; 32],
+            ));
+            assert_eq!(PlaylistRegistry::latest_version(0), None);
+
+            for (block, hash) in [(2, [0xa1; 32]), (3, [0xa2; 32]), (4, [0xa3; 32])] {
+                System::set_block_number(block);
+                assert_ok!(PlaylistRegistry::publish_version(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    hash,
+                ));
+            }
+            System::assert_last_event(
+                pallet::Event::<Test>::VersionPublished {
+                    who: 1,
+                    playlist_id: 0,
+                    version: 3,
+                    content_hash: [0xa3; 32],
+                }
+                .into(),
+            );
+
+            // Only the last `MaxContentVersions` are kept.
+            let versions = pallet::ContentVersions::<Test>::get(0);
+            let kept: Vec<_> = versions
+                .iter()
+                .map(|v| (v.version, v.published_at))
+                .collect();
+            assert_eq!(kept, vec![(2, 3), (3, 4)]);
+            assert_eq!(
+                PlaylistRegistry::latest_version(0).map(|v| v.content_hash),
+                Some([0xa3; 32])
+            );
+            assert_eq!(PlaylistRegistry::playlist_timestamps(0), Some((1, 4)));
+
+            assert_noop!(
+                PlaylistRegistry::publish_version(RuntimeOrigin::signed(2), 0, [0xb1; 32]),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+        });
+    }
+
+    #[test]
+    fn playlists_hold_a_deposit() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Paid".to_vec(),
+                [1u8; 32],
+            ));
+            assert_eq!(Balances::reserved_balance(1), PLAYLIST_DEPOSIT);
+
+            // The recipient of a transfer takes over the deposit.
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                2
+            ));
+            assert_ok!(PlaylistRegistry::accept_playlist(
+                RuntimeOrigin::signed(2),
+                0
+            ));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::reserved_balance(2), PLAYLIST_DEPOSIT);
+
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(2),
+                0
+            ));
+            assert_eq!(Balances::reserved_balance(2), 0);
+            assert!(pallet::PlaylistDeposits::<Test>::get(0).is_none());
+
+            // Accounts that cannot cover the deposit cannot register.
+            assert_noop!(
+                PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(10),
+                    b"Free".to_vec(),
+                    [2u8; 32],
+                ),
+                pallet_balances::Error::<Test>::InsufficientBalance
+            );
+        });
+    }
+
+    #[test]
+    fn public_playlists_are_discoverable() {
+        new_test_ext().execute_with(|| {
+            use pallet::Visibility;
+            for owner in [1, 2] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(owner),
+                    b"Discoverable".to_vec(),
+                    [1u8; 32],
+                ));
+            }
+            // New playlists are private.
+            assert!(PlaylistRegistry::public_playlists().is_empty());
+            assert_eq!(
+                pallet::Playlists::<Test>::get(0)
+                    .expect("registered")
+                    .visibility,
+                Visibility::Private
+            );
+
+            assert_ok!(PlaylistRegistry::set_visibility(
+                RuntimeOrigin::signed(2),
+                1,
+                Visibility::Public
+            ));
+            assert_eq!(PlaylistRegistry::public_playlists(), vec![1]);
+            System::assert_last_event(
+                pallet::Event::<Test>::VisibilityChanged {
+                    who: 2,
+                    playlist_id: 1,
+                    visibility: Visibility::Public,
+                }
+                .into(),
+            );
+            assert_noop!(
+                PlaylistRegistry::set_visibility(RuntimeOrigin::signed(1), 1, Visibility::Private),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+
+            assert_ok!(PlaylistRegistry::set_visibility(
+                RuntimeOrigin::signed(2),
+                1,
+                Visibility::Private
+            ));
+            assert!(PlaylistRegistry::public_playlists().is_empty());
+
+            // Removing a public playlist drops it from the index.
+            assert_ok!(PlaylistRegistry::set_visibility(
+                RuntimeOrigin::signed(1),
+                0,
+                Visibility::Public
+            ));
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert!(PlaylistRegistry::public_playlists().is_empty());
+        });
+    }
+
+    #[test]
+    fn public_playlists_are_listed_by_tag() {
+        new_test_ext().execute_with(|| {
+            use pallet::Visibility;
+            for owner in [1, 2] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(owner),
+                    b"Tagged".to_vec(),
+                    [1u8; 32],
+                ));
+            }
+            assert_ok!(PlaylistRegistry::set_tags(
+                RuntimeOrigin::signed(1),
+                0,
+                vec![b"sports".to_vec(), b"fr".to_vec()],
+            ));
+            assert_ok!(PlaylistRegistry::set_tags(
+                RuntimeOrigin::signed(2),
+                1,
+                vec![b"sports".to_vec()],
+            ));
+            // Private playlists keep their tags out of the index.
+            assert!(PlaylistRegistry::playlists_tagged(b"sports").is_empty());
+
+            for (owner, id) in [(1, 0), (2, 1)] {
+                assert_ok!(PlaylistRegistry::set_visibility(
+                    RuntimeOrigin::signed(owner),
+                    id,
+                    Visibility::Public
+                ));
+            }
+            let mut sports = PlaylistRegistry::playlists_tagged(b"sports");
+            sports.sort();
+            assert_eq!(sports, vec![0, 1]);
+            assert_eq!(PlaylistRegistry::playlists_tagged(b"fr"), vec![0]);
+
+            // Retagging a public playlist moves it between tags.
+            assert_ok!(PlaylistRegistry::set_tags(
+                RuntimeOrigin::signed(1),
+                0,
+                vec![b"news".to_vec()],
+            ));
+            assert_eq!(PlaylistRegistry::playlists_tagged(b"sports"), vec![1]);
+            assert!(PlaylistRegistry::playlists_tagged(b"fr").is_empty());
+            assert_eq!(PlaylistRegistry::playlists_tagged(b"news"), vec![0]);
+
+            assert_ok!(PlaylistRegistry::set_visibility(
+                RuntimeOrigin::signed(2),
+                1,
+                Visibility::Private
+            ));
+            assert!(PlaylistRegistry::playlists_tagged(b"sports").is_empty());
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert!(PlaylistRegistry::playlists_tagged(b"news").is_empty());
+        });
+    }
+
+    #[test]
+    fn set_tags_rejects_invalid_tags() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Tagged".to_vec(),
+                [1u8; 32],
+            ));
+            let set = |tags: &[&str]| {
+                PlaylistRegistry::set_tags(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    tags.iter().map(|t| t.as_bytes().to_vec()).collect(),
+                )
+            };
+            assert_noop!(
+                set(&["a", "b", "c", "d"]),
+                pallet::Error::<Test>::TooManyTags
+            );
+            assert_noop!(set(&[""]), pallet::Error::<Test>::InvalidTag);
+            assert_noop!(set(&["documentary"]), pallet::Error::<Test>::InvalidTag);
+            assert_noop!(set(&["news", "news"]), pallet::Error::<Test>::DuplicateTag);
+        });
+    }
+
+    #[test]
+    fn curators_attest_playlists() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Vetted".to_vec(),
+                [1u8; 32],
+            ));
+            assert_noop!(
+                PlaylistRegistry::attest_playlist(RuntimeOrigin::signed(2), 1, 0),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_noop!(
+                PlaylistRegistry::attest_playlist(RuntimeOrigin::signed(4), 2, 0),
+                pallet::Error::<Test>::PlaylistNotFound
+            );
 
-    /// Build a test externalities instance with default genesis state.
-    fn new_test_ext() -> TestExternalities {
-        let t = frame_system::GenesisConfig::<Test>::default()
-            .build_storage()
-            .expect("genesis build should succeed in tests");
-        let mut ext = TestExternalities::new(t);
-        ext.execute_with(|| System::set_block_number(1));
-        ext
+            assert_ok!(PlaylistRegistry::attest_playlist(
+                RuntimeOrigin::signed(4),
+                1,
+                0
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::PlaylistAttested {
+                    curator: 4,
+                    playlist_id: 0,
+                }
+                .into(),
+            );
+            assert_noop!(
+                PlaylistRegistry::attest_playlist(RuntimeOrigin::signed(4), 1, 0),
+                pallet::Error::<Test>::AlreadyAttested
+            );
+            assert_ok!(PlaylistRegistry::attest_playlist(
+                RuntimeOrigin::signed(5),
+                1,
+                0
+            ));
+            assert_noop!(
+                PlaylistRegistry::attest_playlist(RuntimeOrigin::signed(6), 1, 0),
+                pallet::Error::<Test>::TooManyAttestations
+            );
+            assert_eq!(PlaylistRegistry::attestation_count(0), 2);
+
+            assert_ok!(PlaylistRegistry::revoke_attestation(
+                RuntimeOrigin::signed(4),
+                0
+            ));
+            assert_noop!(
+                PlaylistRegistry::revoke_attestation(RuntimeOrigin::signed(4), 0),
+                pallet::Error::<Test>::NotAttested
+            );
+            assert_eq!(pallet::Attestations::<Test>::get(0).into_inner(), vec![5]);
+
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert_eq!(PlaylistRegistry::attestation_count(0), 0);
+        });
     }
 
     #[test]
-    fn register_playlist_works() {
+    fn set_playlist_details_works() {
         new_test_ext().execute_with(|| {
-            let name = b"My Playlist".to_vec();
-            let hash = [1u8; 32];
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Detailed".to_vec(),
+                [3u8; 32],
+            ));
+
+            let artwork = Some([9u8; 32]);
+            assert_ok!(PlaylistRegistry::set_playlist_details(
+                RuntimeOrigin::signed(1),
+                0,
+                b"News and sport".to_vec(),
+                artwork,
+            ));
+
+            let playlist = pallet::Playlists::<Test>::get(0).expect("registered");
+            assert_eq!(playlist.description.to_vec(), b"News and sport".to_vec());
+            assert_eq!(playlist.artwork_hash, artwork);
+            System::assert_last_event(
+                pallet::Event::<Test>::PlaylistDetailsUpdated {
+                    who: 1,
+                    playlist_id: 0,
+                    artwork_hash: artwork,
+                }
+                .into(),
+            );
+
+            // Clearing both fields.
+            assert_ok!(PlaylistRegistry::set_playlist_details(
+                RuntimeOrigin::signed(1),
+                0,
+                Vec::new(),
+                None,
+            ));
+            let playlist = pallet::Playlists::<Test>::get(0).expect("registered");
+            assert!(playlist.description.is_empty());
+            assert_eq!(playlist.artwork_hash, None);
+        });
+    }
 
+    #[test]
+    fn set_playlist_details_rejects_invalid_input() {
+        new_test_ext().execute_with(|| {
             assert_ok!(PlaylistRegistry::register_playlist(
                 RuntimeOrigin::signed(1),
-                name.clone(),
-                hash,
+                b"Detailed".to_vec(),
+                [3u8; 32],
             ));
 
-            // Verify storage
-            let playlists = pallet::PlaylistMap::<Test>::get(1);
-            assert_eq!(playlists.len(), 1);
-            assert_eq!(playlists[0].source_url_hash, hash);
-            assert_eq!(playlists[0].channel_count, 0);
-            assert_eq!(pallet::PlaylistCount::<Test>::get(), 1);
+            assert_noop!(
+                PlaylistRegistry::set_playlist_details(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    vec![b'x'; 129],
+                    None,
+                ),
+                pallet::Error::<Test>::DescriptionTooLong
+            );
+            // Another account cannot edit playlist 0.
+            assert_noop!(
+                PlaylistRegistry::set_playlist_details(
+                    RuntimeOrigin::signed(2),
+                    0,
+                    b"Hijack".to_vec(),
+                    None,
+                ),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+            assert_noop!(
+                PlaylistRegistry::set_playlist_details(
+                    RuntimeOrigin::signed(1),
+                    1,
+                    b"Missing".to_vec(),
+                    None,
+                ),
+                pallet::Error::<Test>::PlaylistNotFound
+            );
+        });
+    }
 
-            // Verify event
-            let expected_name: frame_support::BoundedVec<u8, ConstU32<64>> =
-                name.try_into().expect("name fits in test");
+    #[test]
+    fn update_playlist_changes_only_given_fields() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Nwes".to_vec(),
+                [7u8; 32],
+            ));
+            assert_ok!(PlaylistRegistry::update_channel_count(
+                RuntimeOrigin::signed(1),
+                0,
+                12,
+            ));
+
+            assert_ok!(PlaylistRegistry::update_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                Some(b"News".to_vec()),
+                None,
+            ));
+            let playlist = pallet::Playlists::<Test>::get(0).expect("registered");
+            assert_eq!(playlist.name.to_vec(), b"News".to_vec());
+            assert_eq!(playlist.source_url_hash, [7u8; 32]);
+            assert_eq!(playlist.channel_count, 12);
             System::assert_last_event(
-                pallet::Event::<Test>::PlaylistRegistered {
+                pallet::Event::<Test>::PlaylistUpdated {
                     who: 1,
-                    name: expected_name,
+                    playlist_id: 0,
+                    name: playlist.name.clone(),
+                    source_url_hash: [7u8; 32],
                 }
                 .into(),
             );
+
+            assert_ok!(PlaylistRegistry::update_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                None,
+                Some([8u8; 32]),
+            ));
+            let playlist = pallet::Playlists::<Test>::get(0).expect("registered");
+            assert_eq!(playlist.name.to_vec(), b"News".to_vec());
+            assert_eq!(playlist.source_url_hash, [8u8; 32]);
+
+            assert_noop!(
+                PlaylistRegistry::update_playlist(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    Some(vec![b'x'; 65]),
+                    None,
+                ),
+                pallet::Error::<Test>::NameTooLong
+            );
+            assert_noop!(
+                PlaylistRegistry::update_playlist(
+                    RuntimeOrigin::signed(2),
+                    0,
+                    Some(b"Mine".to_vec()),
+                    None,
+                ),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
         });
     }
 
     #[test]
-    fn remove_playlist_works() {
+    fn name_limit_fits_payload_format() {
         new_test_ext().execute_with(|| {
-            let name = b"Remove Me".to_vec();
-            let hash = [2u8; 32];
+            <PlaylistRegistry as frame_support::traits::IntegrityTest>::integrity_test();
+        });
+    }
+
+    #[test]
+    fn call_indices_are_stable() {
+        // Downstream runtimes and signed transactions depend on these indices;
+        // changing one is a breaking change.
+        assert_eq!(
+            pallet::Call::<Test>::register_playlist {
+                name: b"x".to_vec(),
+                source_url_hash: [0u8; 32]
+            }
+            .encode()[0],
+            0
+        );
+        assert_eq!(
+            pallet::Call::<Test>::remove_playlist { playlist_id: 0 }.encode()[0],
+            1
+        );
+        assert_eq!(
+            pallet::Call::<Test>::update_channel_count {
+                playlist_id: 0,
+                count: 1
+            }
+            .encode()[0],
+            2
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_playlist_details {
+                playlist_id: 0,
+                description: Vec::new(),
+                artwork_hash: None
+            }
+            .encode()[0],
+            3
+        );
+        assert_eq!(
+            pallet::Call::<Test>::update_playlist {
+                playlist_id: 0,
+                new_name: None,
+                new_source_url_hash: None
+            }
+            .encode()[0],
+            4
+        );
+        assert_eq!(
+            pallet::Call::<Test>::transfer_playlist {
+                playlist_id: 0,
+                new_owner: 2
+            }
+            .encode()[0],
+            5
+        );
+        assert_eq!(
+            pallet::Call::<Test>::accept_playlist { playlist_id: 0 }.encode()[0],
+            6
+        );
+        assert_eq!(
+            pallet::Call::<Test>::add_channel {
+                playlist_id: 0,
+                entry: channel(0)
+            }
+            .encode()[0],
+            7
+        );
+        assert_eq!(
+            pallet::Call::<Test>::remove_channel {
+                playlist_id: 0,
+                stream_url_hash: [0u8; 32]
+            }
+            .encode()[0],
+            8
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_channels {
+                playlist_id: 0,
+                entries: Vec::new()
+            }
+            .encode()[0],
+            9
+        );
+        assert_eq!(
+            pallet::Call::<Test>::publish_version {
+                playlist_id: 0,
+                content_hash: [0u8; 32]
+            }
+            .encode()[0],
+            10
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_visibility {
+                playlist_id: 0,
+                visibility: pallet::Visibility::Public
+            }
+            .encode()[0],
+            11
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_tags {
+                playlist_id: 0,
+                tags: Vec::new()
+            }
+            .encode()[0],
+            12
+        );
+        assert_eq!(
+            pallet::Call::<Test>::attest_playlist {
+                owner: 1,
+                playlist_id: 0
+            }
+            .encode()[0],
+            13
+        );
+        assert_eq!(
+            pallet::Call::<Test>::revoke_attestation { playlist_id: 0 }.encode()[0],
+            14
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_global_cap { cap: None }.encode()[0],
+            15
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_source_url {
+                playlist_id: 0,
+                url: Vec::new()
+            }
+            .encode()[0],
+            16
+        );
+        assert_eq!(
+            pallet::Call::<Test>::report_content {
+                report: content_report(REPORTER, [0u8; 32]).0,
+                signature: content_report(REPORTER, [0u8; 32]).1,
+            }
+            .encode()[0],
+            17
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_playlist_gate {
+                playlist_id: 0,
+                requirement: None
+            }
+            .encode()[0],
+            18
+        );
+    }
 
+    #[test]
+    fn gated_playlists_serve_channels_to_holders_only() {
+        new_test_ext().execute_with(|| {
             assert_ok!(PlaylistRegistry::register_playlist(
                 RuntimeOrigin::signed(1),
-                name,
-                hash,
+                b"Premium".to_vec(),
+                [1u8; 32],
             ));
-            assert_eq!(pallet::PlaylistMap::<Test>::get(1).len(), 1);
-            assert_eq!(pallet::PlaylistCount::<Test>::get(), 1);
+            assert_ok!(PlaylistRegistry::add_channel(
+                RuntimeOrigin::signed(1),
+                0,
+                channel(1)
+            ));
+            assert_eq!(
+                PlaylistRegistry::readable_channels(&3, 0).map(|c| c.len()),
+                Some(1)
+            );
+            assert_eq!(PlaylistRegistry::readable_channels(&3, 1), None);
+
+            let requirement = GateRequirement {
+                token_id: 7,
+                min_balance: 100,
+            };
+            assert_noop!(
+                PlaylistRegistry::set_playlist_gate(
+                    RuntimeOrigin::signed(2),
+                    0,
+                    Some(requirement.clone())
+                ),
+                pallet::Error::<Test>::NotPlaylistOwner
+            );
+            assert_ok!(PlaylistRegistry::set_playlist_gate(
+                RuntimeOrigin::signed(1),
+                0,
+                Some(requirement.clone())
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::PlaylistGateSet {
+                    who: 1,
+                    playlist_id: 0,
+                    requirement: Some(requirement),
+                }
+                .into(),
+            );
+
+            assert!(PlaylistRegistry::readable_channels(&3, 0).is_none());
+            assert!(PlaylistRegistry::readable_channels(&TOKEN_HOLDER, 0).is_some());
+            assert!(PlaylistRegistry::readable_channels(&1, 0).is_some());
+            assert!(PlaylistRegistry::readable_channels(&CURATOR, 0).is_some());
+
+            // Removing the playlist removes its gate.
+            assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(1),
+                0
+            ));
+            assert!(GATES.with(|g| g.borrow().is_empty()));
+        });
+    }
+
+    #[test]
+    fn safe_call_filter_blocks_destructive_calls() {
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::remove_playlist { playlist_id: 0 }
+        ));
+        assert!(SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::update_channel_count {
+                playlist_id: 0,
+                count: 1
+            }
+        ));
+    }
 
+    #[test]
+    fn events_are_indexed_by_owner() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(7),
+                b"Indexed".to_vec(),
+                [4u8; 32],
+            ));
             assert_ok!(PlaylistRegistry::remove_playlist(
+                RuntimeOrigin::signed(7),
+                0
+            ));
+
+            let topic = BlakeTwo256::hash_of(&(b"iptv/account".as_slice(), 7u64));
+            assert_eq!(PlaylistRegistry::account_topic(&7), topic);
+            // Deposit reserves add untopiced `Balances` events; only the
+            // registry's own events are indexed.
+            assert!(System::events()
+                .iter()
+                .filter(|r| matches!(r.event, RuntimeEvent::PlaylistRegistry(_)))
+                .all(|r| r.topics == vec![topic, PlaylistRegistry::resource_topic(0)]));
+            assert_eq!(
+                System::events()
+                    .iter()
+                    .filter(|r| r.topics.contains(&topic))
+                    .count(),
+                2
+            );
+        });
+    }
+
+    #[test]
+    fn events_are_indexed_by_resource() {
+        new_test_ext().execute_with(|| {
+            for (account, name) in [(7u64, b"Kept".to_vec()), (8, b"Given".to_vec())] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(account),
+                    name,
+                    [4u8; 32],
+                ));
+            }
+            assert_ok!(PlaylistRegistry::transfer_playlist(
+                RuntimeOrigin::signed(8),
+                1,
+                7
+            ));
+            assert_ok!(PlaylistRegistry::accept_playlist(
+                RuntimeOrigin::signed(7),
+                1
+            ));
+
+            // The same topic access-control and token-gate use for the resource.
+            let topic = BlakeTwo256::hash_of(&(b"iptv/resource".as_slice(), 1u32));
+            assert_eq!(
+                PlaylistRegistry::resource_topic(PlaylistRegistry::resource_id(1)),
+                topic
+            );
+            let indexed: Vec<_> = System::events()
+                .into_iter()
+                .filter(|r| r.topics.contains(&topic))
+                .map(|r| r.event)
+                .collect();
+            assert_eq!(indexed.len(), 3);
+            assert!(indexed.iter().all(|event| matches!(
+                event,
+                RuntimeEvent::PlaylistRegistry(
+                    Event::PlaylistRegistered { playlist_id: 1, .. }
+                        | Event::TransferProposed { playlist_id: 1, .. }
+                        | Event::PlaylistTransferred { playlist_id: 1, .. }
+                )
+            )));
+
+            // Registry-wide events have a topic of their own.
+            assert_ok!(PlaylistRegistry::set_global_cap(
+                RuntimeOrigin::root(),
+                Some(3)
+            ));
+            let last = System::events().pop().expect("event deposited");
+            assert_eq!(
+                last.topics,
+                vec![BlakeTwo256::hash_of(&b"iptv/registry".as_slice())]
+            );
+            assert_eq!(last.topics, vec![PlaylistRegistry::registry_topic()]);
+        });
+    }
+
+    #[test]
+    fn playlists_of_returns_owned_playlists_in_order() {
+        new_test_ext().execute_with(|| {
+            for name in [b"First".to_vec(), b"Second".to_vec()] {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(1),
+                    name,
+                    [1u8; 32],
+                ));
+            }
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(2),
+                b"Other".to_vec(),
+                [2u8; 32],
+            ));
+            assert_ok!(PlaylistRegistry::set_tags(
+                RuntimeOrigin::signed(1),
+                1,
+                vec![b"news".to_vec()],
+            ));
+
+            let listed = PlaylistRegistry::playlists_of(&1);
+            let names: Vec<_> = listed.iter().map(|(id, p)| (*id, p.name.clone())).collect();
+            assert_eq!(names, vec![(0, b"First".to_vec()), (1, b"Second".to_vec())]);
+            assert_eq!(listed[1].1.tags, vec![b"news".to_vec()]);
+
+            assert_eq!(
+                PlaylistRegistry::owned_playlist(&2, 2).map(|p| p.name),
+                Some(b"Other".to_vec())
+            );
+            assert!(PlaylistRegistry::owned_playlist(&1, 2).is_none());
+            assert!(PlaylistRegistry::playlists_of(&3).is_empty());
+        });
+    }
+
+    #[test]
+    fn global_cap_limits_registrations_and_can_be_overridden() {
+        new_test_ext().execute_with(|| {
+            // Accounts 1 to 8 fill the default cap of eight playlists.
+            for account in 1..=8u64 {
+                assert_ok!(PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(account),
+                    b"Mine".to_vec(),
+                    [0u8; 32],
+                ));
+            }
+            assert_noop!(
+                PlaylistRegistry::register_playlist(
+                    RuntimeOrigin::signed(9),
+                    b"Mine".to_vec(),
+                    [0u8; 32]
+                ),
+                Error::<Test>::GlobalCapReached
+            );
+
+            assert_noop!(
+                PlaylistRegistry::set_global_cap(RuntimeOrigin::signed(1), Some(9)),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_ok!(PlaylistRegistry::set_global_cap(
+                RuntimeOrigin::root(),
+                Some(9)
+            ));
+            System::assert_last_event(Event::GlobalCapSet { cap: Some(9) }.into());
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(9),
+                b"Mine".to_vec(),
+                [0u8; 32],
+            ));
+
+            // Back to the constant: nine playlists exceed it, but none is removed.
+            assert_ok!(PlaylistRegistry::set_global_cap(
+                RuntimeOrigin::root(),
+                None
+            ));
+            assert_eq!(PlaylistRegistry::global_cap(), 8);
+            assert_eq!(PlaylistRegistry::playlist_count(), 9);
+        });
+    }
+
+    const SOURCE_URL: &[u8] = b"https://example.com/list.m3u";
+
+    /// Register playlist 0 for account 1 with [`SOURCE_URL`], commit the URL and
+    /// publish a version with `content_hash`.
+    fn setup_checked_playlist(content_hash: [u8; 32]) {
+        assert_ok!(PlaylistRegistry::register_playlist(
+            RuntimeOrigin::signed(1),
+            b"Checked".to_vec(),
+            sp_io::hashing::sha2_256(SOURCE_URL),
+        ));
+        assert_ok!(PlaylistRegistry::set_source_url(
+            RuntimeOrigin::signed(1),
+            0,
+            SOURCE_URL.to_vec()
+        ));
+        assert_ok!(PlaylistRegistry::publish_version(
+            RuntimeOrigin::signed(1),
+            0,
+            content_hash
+        ));
+    }
+
+    #[test]
+    fn source_url_must_match_its_hash() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(PlaylistRegistry::register_playlist(
+                RuntimeOrigin::signed(1),
+                b"Checked".to_vec(),
+                sp_io::hashing::sha2_256(SOURCE_URL),
+            ));
+            assert_noop!(
+                PlaylistRegistry::set_source_url(
+                    RuntimeOrigin::signed(1),
+                    0,
+                    b"https://example.com/other.m3u".to_vec()
+                ),
+                Error::<Test>::SourceUrlMismatch
+            );
+            assert_noop!(
+                PlaylistRegistry::set_source_url(RuntimeOrigin::signed(2), 0, SOURCE_URL.to_vec()),
+                Error::<Test>::NotPlaylistOwner
+            );
+            assert_ok!(PlaylistRegistry::set_source_url(
                 RuntimeOrigin::signed(1),
                 0,
+                SOURCE_URL.to_vec()
             ));
 
-            assert_eq!(pallet::PlaylistMap::<Test>::get(1).len(), 0);
-            assert_eq!(pallet::PlaylistCount::<Test>::get(), 0);
+            // Pointing the playlist elsewhere drops the committed URL.
+            assert_ok!(PlaylistRegistry::update_playlist(
+                RuntimeOrigin::signed(1),
+                0,
+                None,
+                Some([9u8; 32])
+            ));
+            assert!(pallet::SourceUrls::<Test>::get(0).is_none());
+        });
+    }
 
-            // Removing a non-existent playlist should fail
+    #[test]
+    fn content_reports_flag_mismatches_once_per_interval() {
+        use sp_runtime::traits::ValidateUnsigned;
+
+        new_test_ext().execute_with(|| {
+            setup_checked_playlist([1u8; 32]);
+
+            let (report, signature) = content_report(REPORTER, [2u8; 32]);
             assert_noop!(
-                PlaylistRegistry::remove_playlist(RuntimeOrigin::signed(1), 0),
-                pallet::Error::<Test>::PlaylistNotFound
+                PlaylistRegistry::report_content(
+                    RuntimeOrigin::signed(1),
+                    report.clone(),
+                    signature.clone()
+                ),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_ok!(PlaylistRegistry::report_content(
+                RuntimeOrigin::none(),
+                report,
+                signature
+            ));
+            System::assert_last_event(
+                Event::ContentMismatch {
+                    playlist_id: 0,
+                    expected: [1u8; 32],
+                    found: [2u8; 32],
+                }
+                .into(),
             );
+            assert_eq!(pallet::ContentMismatches::<Test>::get(0), Some([2u8; 32]));
+
+            let (report, signature) = content_report(REPORTER, [1u8; 32]);
+            let call = pallet::Call::<Test>::report_content {
+                report: report.clone(),
+                signature: signature.clone(),
+            };
+            assert_noop!(
+                PlaylistRegistry::report_content(
+                    RuntimeOrigin::none(),
+                    report.clone(),
+                    signature.clone()
+                ),
+                Error::<Test>::CheckNotDue
+            );
+            assert!(<PlaylistRegistry as ValidateUnsigned>::validate_unsigned(
+                sp_runtime::transaction_validity::TransactionSource::External,
+                &call
+            )
+            .is_err());
+
+            // After the interval a matching report clears the flag.
+            System::set_block_number(11);
+            assert_ok!(PlaylistRegistry::report_content(
+                RuntimeOrigin::none(),
+                report,
+                signature
+            ));
+            assert!(pallet::ContentMismatches::<Test>::get(0).is_none());
+        });
+    }
+
+    #[test]
+    fn content_reports_need_an_authorised_signature() {
+        use sp_runtime::{
+            traits::ValidateUnsigned,
+            transaction_validity::{InvalidTransaction, TransactionSource},
+        };
+
+        new_test_ext().execute_with(|| {
+            setup_checked_playlist([1u8; 32]);
+            let validate = |report: ContentReport<UintAuthorityId, u64>, signature| {
+                <PlaylistRegistry as ValidateUnsigned>::validate_unsigned(
+                    TransactionSource::External,
+                    &pallet::Call::<Test>::report_content { report, signature },
+                )
+            };
+
+            // A key outside `ContentReporters` cannot flag the playlist.
+            let (report, signature) = content_report(1, [2u8; 32]);
+            assert_eq!(
+                validate(report, signature),
+                Err(InvalidTransaction::BadSigner.into())
+            );
+
+            // Nor can anyone claim to be the reporter without its signature.
+            let (report, _) = content_report(REPORTER, [2u8; 32]);
+            let (_, forged) = content_report(1, [2u8; 32]);
+            assert_eq!(
+                validate(report, forged),
+                Err(InvalidTransaction::BadProof.into())
+            );
+
+            // A report from the past interval cannot be replayed.
+            let (report, signature) = content_report(REPORTER, [2u8; 32]);
+            System::set_block_number(11);
+            assert_eq!(
+                validate(report, signature),
+                Err(InvalidTransaction::Stale.into())
+            );
+
+            System::set_block_number(1);
+            let (report, signature) = content_report(REPORTER, [2u8; 32]);
+            assert!(validate(report, signature).is_ok());
+        });
+    }
+
+    #[test]
+    fn offchain_worker_reports_changed_content() {
+        use frame_support::traits::Hooks;
+        use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+
+        let (offchain, offchain_state) = testing::TestOffchainExt::new();
+        let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+        let mut ext = new_test_ext();
+        ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+        ext.register_extension(OffchainDbExt::new(offchain));
+        ext.register_extension(TransactionPoolExt::new(pool));
+
+        let body = b"#EXTM3U\n#EXTINF:-1,News\nhttp://example.com/news.m3u8\n".to_vec();
+        offchain_state
+            .write()
+            .expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: core::str::from_utf8(SOURCE_URL).unwrap().into(),
+                response: Some(body.clone()),
+                sent: true,
+                ..Default::default()
+            });
+
+        ext.execute_with(|| {
+            setup_checked_playlist([1u8; 32]);
+
+            // Without a reporter key the node does not check content.
+            PlaylistRegistry::offchain_worker(1);
+            assert!(pool_state.read().transactions.is_empty());
+
+            UintAuthorityId::set_all_keys(vec![1, REPORTER]);
+            PlaylistRegistry::offchain_worker(1);
+            let tx = pool_state
+                .write()
+                .transactions
+                .pop()
+                .expect("a report is submitted");
+            let tx = Extrinsic::decode(&mut &*tx).unwrap();
+            let (report, signature) = content_report(REPORTER, sp_io::hashing::sha2_256(&body));
+            assert_eq!(
+                tx.call,
+                RuntimeCall::PlaylistRegistry(pallet::Call::report_content { report, signature })
+            );
+
+            // The node does not fetch the same playlist again within the interval.
+            PlaylistRegistry::offchain_worker(2);
+            assert!(pool_state.read().transactions.is_empty());
         });
     }
 }
@@ -0,0 +1,198 @@
+//! Storage migrations for the playlist registry pallet.
+//!
+//! Runtimes list them in their `Executive` migrations when upgrading from a
+//! release that stored the previous layout:
+//!
+//! ```ignore
+//! pub type Migrations = (pallet_playlist_registry::migrations::v1::MigrateV0ToV1<Runtime>,);
+//! ```
+
+use super::*;
+use frame_support::{
+    migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use pallet_access_control::ResourceAccess;
+use sp_runtime::Saturating;
+
+/// Version 1 keeps each playlist's metadata in `Playlists` under a global
+/// [`PlaylistId`], with `PlaylistMap` listing the ids of each account's
+/// playlists. Version 0 stored the metadata itself in `PlaylistMap`, without
+/// an owner or any of the fields added since.
+pub mod v1 {
+    use super::*;
+
+    /// The version 0 layout.
+    mod v0 {
+        use super::*;
+
+        /// Playlist metadata as stored by version 0.
+        #[derive(Encode, Decode)]
+        pub struct PlaylistMetadata<BoundedString> {
+            pub name: BoundedString,
+            pub source_url_hash: [u8; 32],
+            pub channel_count: u32,
+            pub created_at: u64,
+        }
+
+        #[frame_support::storage_alias]
+        pub type PlaylistMap<T: Config> = StorageMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as frame_system::Config>::AccountId,
+            BoundedVec<
+                PlaylistMetadata<BoundedVec<u8, <T as Config>::MaxPlaylistNameLength>>,
+                <T as Config>::MaxPlaylistsPerAccount,
+            >,
+            ValueQuery,
+        >;
+    }
+
+    /// Give every stored playlist an id, in account then list order, and move
+    /// its metadata to `Playlists`.
+    ///
+    /// Migrated playlists are private, carry no deposit, as genesis playlists,
+    /// and their access-control resources are assigned to their owners. When an
+    /// account has several playlists with the same name, `NameIndex` points to
+    /// the first.
+    pub struct InnerMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut next_id = NextPlaylistId::<T>::get();
+            let (mut accounts, mut playlists) = (0u64, 0u64);
+            PlaylistMap::<T>::translate::<
+                BoundedVec<
+                    v0::PlaylistMetadata<BoundedVec<u8, T::MaxPlaylistNameLength>>,
+                    T::MaxPlaylistsPerAccount,
+                >,
+                _,
+            >(|owner, old| {
+                accounts.saturating_inc();
+                let mut ids = BoundedVec::new();
+                for metadata in old {
+                    playlists.saturating_inc();
+                    let playlist_id = next_id;
+                    next_id.saturating_inc();
+                    let name_hash = Pallet::<T>::name_hash(&metadata.name);
+                    if !NameIndex::<T>::contains_key(&owner, name_hash) {
+                        NameIndex::<T>::insert(&owner, name_hash, playlist_id);
+                    }
+                    Playlists::<T>::insert(
+                        playlist_id,
+                        PlaylistMetadata {
+                            owner: owner.clone(),
+                            name: metadata.name,
+                            source_url_hash: metadata.source_url_hash,
+                            channel_count: metadata.channel_count,
+                            created_at: metadata.created_at,
+                            updated_at: metadata.created_at,
+                            description: BoundedVec::default(),
+                            artwork_hash: None,
+                            visibility: Visibility::Private,
+                            tags: BoundedVec::default(),
+                        },
+                    );
+                    T::Access::set_owner(Pallet::<T>::resource_id(playlist_id), &owner);
+                    // Both lists are bounded by `MaxPlaylistsPerAccount`.
+                    let _ = ids.try_push(playlist_id);
+                }
+                Some(ids)
+            });
+            NextPlaylistId::<T>::put(next_id);
+
+            // Per playlist: the name index and resource owner checks, and the
+            // metadata, name index and resource owner writes.
+            T::DbWeight::get().reads_writes(
+                accounts
+                    .saturating_add(playlists.saturating_mul(2))
+                    .saturating_add(1),
+                accounts
+                    .saturating_add(playlists.saturating_mul(3))
+                    .saturating_add(1),
+            )
+        }
+    }
+
+    /// [`InnerMigrateV0ToV1`], run only while the on-chain storage version is 0,
+    /// which it then sets to 1.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::tests::{new_test_ext, MockAccess, PlaylistRegistry, Test};
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+        fn old(
+            name: &[u8],
+            channel_count: u32,
+        ) -> v0::PlaylistMetadata<BoundedVec<u8, ConstU32<64>>> {
+            v0::PlaylistMetadata {
+                name: name.to_vec().try_into().unwrap(),
+                source_url_hash: [channel_count as u8; 32],
+                channel_count,
+                created_at: 0,
+            }
+        }
+
+        #[test]
+        fn playlists_move_to_their_own_map() {
+            new_test_ext().execute_with(|| {
+                StorageVersion::new(0).put::<PlaylistRegistry>();
+                v0::PlaylistMap::<Test>::insert(
+                    1,
+                    BoundedVec::truncate_from(vec![old(b"News", 4), old(b"News", 2)]),
+                );
+                v0::PlaylistMap::<Test>::insert(
+                    2,
+                    BoundedVec::truncate_from(vec![old(b"Sport", 1)]),
+                );
+
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+                assert_eq!(PlaylistRegistry::on_chain_storage_version(), 1);
+                assert_eq!(NextPlaylistId::<Test>::get(), 3);
+                let mut ids: Vec<_> = [1u64, 2]
+                    .into_iter()
+                    .flat_map(|who| PlaylistMap::<Test>::get(who).into_inner())
+                    .collect();
+                ids.sort();
+                assert_eq!(ids, vec![0, 1, 2]);
+
+                let [first, second] = PlaylistMap::<Test>::get(1).into_inner()[..] else {
+                    panic!("account 1 keeps both playlists");
+                };
+                let playlist = Playlists::<Test>::get(first).expect("migrated");
+                assert_eq!(playlist.owner, 1);
+                assert_eq!(playlist.name.into_inner(), b"News".to_vec());
+                assert_eq!(playlist.channel_count, 4);
+                assert_eq!(playlist.visibility, Visibility::Private);
+                assert_eq!(
+                    Playlists::<Test>::get(second)
+                        .expect("migrated")
+                        .channel_count,
+                    2
+                );
+                assert_eq!(
+                    NameIndex::<Test>::get(1, PlaylistRegistry::name_hash(b"News")),
+                    Some(first)
+                );
+                assert_eq!(
+                    MockAccess::owner(PlaylistRegistry::resource_id(first)),
+                    Some(1)
+                );
+                assert!(PlaylistDeposits::<Test>::get(first).is_none());
+
+                // Running it again leaves the new layout alone.
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+                assert_eq!(NextPlaylistId::<Test>::get(), 3);
+            });
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! Weights for `pallet_playlist_registry`.
+//!
+//! These defaults are estimated from the storage each call touches. Runtimes should
+//! replace them with weights measured from `benchmarking.rs` on their own hardware:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime <runtime.wasm> \
+//!     --pallet pallet_playlist_registry \
+//!     --extrinsic "*" \
+//!     --output pallets/playlist-registry/src/weights.rs
+//! ```
+//!
+//! Components: `n` is the name length, `p` the number of playlists the account
+//! already owns, `d` the description length, `c` the number of channels listed
+//! on-chain, `t` the number of tags and `u` the source URL length.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use core::marker::PhantomData;
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for `pallet_playlist_registry`.
+pub trait WeightInfo {
+    fn register_playlist(n: u32, p: u32) -> Weight;
+    fn remove_playlist(p: u32, t: u32) -> Weight;
+    fn update_channel_count() -> Weight;
+    fn set_playlist_details(d: u32) -> Weight;
+    fn update_playlist(n: u32) -> Weight;
+    fn transfer_playlist() -> Weight;
+    fn accept_playlist(p: u32) -> Weight;
+    fn add_channel(c: u32) -> Weight;
+    fn remove_channel(c: u32) -> Weight;
+    fn set_channels(c: u32) -> Weight;
+    fn publish_version() -> Weight;
+    fn set_visibility(t: u32) -> Weight;
+    fn set_tags(t: u32) -> Weight;
+    fn attest_playlist() -> Weight;
+    fn revoke_attestation() -> Weight;
+    fn set_global_cap() -> Weight;
+    fn set_source_url(u: u32) -> Weight;
+    fn report_content() -> Weight;
+    fn set_playlist_gate() -> Weight;
+}
+
+/// Weights for `pallet_playlist_registry` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    /// Storage: `PlaylistRegistry::NameIndex` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::NameIndex` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::NextPlaylistId` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::NextPlaylistId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistMap` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistMap` (`max_values`: None, `max_size`: Some(450), added: 2925, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistCount` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistCount` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::GlobalCap` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::GlobalCap` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistDeposits` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistDeposits` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxPlaylistNameLength]`.
+    /// The range of component `p` is `[0, MaxPlaylistsPerAccount - 1]`.
+    fn register_playlist(n: u32, p: u32) -> Weight {
+        Weight::from_parts(38_620_000, 3915)
+            .saturating_add(Weight::from_parts(1_240, 0).saturating_mul(n.into()))
+            .saturating_add(Weight::from_parts(46_310, 0).saturating_mul(p.into()))
+            .saturating_add(T::DbWeight::get().reads(6_u64))
+            .saturating_add(T::DbWeight::get().writes(7_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::NameIndex` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::NameIndex` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::TagIndex` (r:0 w:8)
+    /// Proof: `PlaylistRegistry::TagIndex` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PublicPlaylists` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::PublicPlaylists` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PendingTransfers` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::PendingTransfers` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Channels` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::Channels` (`max_values`: None, `max_size`: Some(64022), added: 66497, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::ContentVersions` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::ContentVersions` (`max_values`: None, `max_size`: Some(461), added: 2936, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Attestations` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::Attestations` (`max_values`: None, `max_size`: Some(533), added: 3008, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::SourceUrls` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::SourceUrls` (`max_values`: None, `max_size`: Some(534), added: 3009, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::LastChecked` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::LastChecked` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::ContentMismatches` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::ContentMismatches` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Gates` (r:1 w:1)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(102), added: 2577, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:0 w:1)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(106), added: 2581, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateExpiry` (r:0 w:1)
+    /// Proof: `TokenGate::GateExpiry` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateSetBy` (r:0 w:1)
+    /// Proof: `TokenGate::GateSetBy` (`max_values`: None, `max_size`: Some(57), added: 2532, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistDeposits` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistDeposits` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistMap` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistMap` (`max_values`: None, `max_size`: Some(450), added: 2925, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistCount` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistCount` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+    /// The range of component `p` is `[1, MaxPlaylistsPerAccount]`.
+    /// The range of component `t` is `[0, MaxTags]`.
+    fn remove_playlist(p: u32, t: u32) -> Weight {
+        Weight::from_parts(52_170_000, 4512)
+            .saturating_add(Weight::from_parts(41_870, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(2_935_000, 0).saturating_mul(t.into()))
+            .saturating_add(T::DbWeight::get().reads(6_u64))
+            .saturating_add(T::DbWeight::get().writes(18_u64))
+            .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(t.into())))
+    }
+    /// Storage: `PlaylistRegistry::Channels` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Channels` (`max_values`: None, `max_size`: Some(64022), added: 66497, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    fn update_channel_count() -> Weight {
+        Weight::from_parts(17_450_000, 67_487)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// The range of component `d` is `[0, MaxDescriptionLength]`.
+    fn set_playlist_details(d: u32) -> Weight {
+        Weight::from_parts(15_830_000, 4512)
+            .saturating_add(Weight::from_parts(1_120, 0).saturating_mul(d.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::NameIndex` (r:1 w:2)
+    /// Proof: `PlaylistRegistry::NameIndex` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::SourceUrls` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::SourceUrls` (`max_values`: None, `max_size`: Some(534), added: 3009, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::LastChecked` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::LastChecked` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::ContentMismatches` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::ContentMismatches` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxPlaylistNameLength]`.
+    fn update_playlist(n: u32) -> Weight {
+        Weight::from_parts(24_310_000, 4512)
+            .saturating_add(Weight::from_parts(1_310, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(6_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PendingTransfers` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::PendingTransfers` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    fn transfer_playlist() -> Weight {
+        Weight::from_parts(14_960_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::PendingTransfers` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PendingTransfers` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::NameIndex` (r:1 w:2)
+    /// Proof: `PlaylistRegistry::NameIndex` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistMap` (r:2 w:2)
+    /// Proof: `PlaylistRegistry::PlaylistMap` (`max_values`: None, `max_size`: Some(450), added: 2925, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:2 w:2)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PlaylistDeposits` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::PlaylistDeposits` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+    /// The range of component `p` is `[0, MaxPlaylistsPerAccount - 1]`.
+    fn accept_playlist(p: u32) -> Weight {
+        Weight::from_parts(61_540_000, 4512)
+            .saturating_add(Weight::from_parts(88_420, 0).saturating_mul(p.into()))
+            .saturating_add(T::DbWeight::get().reads(8_u64))
+            .saturating_add(T::DbWeight::get().writes(9_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Channels` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Channels` (`max_values`: None, `max_size`: Some(64022), added: 66497, mode: `MaxEncodedLen`)
+    /// The range of component `c` is `[0, MaxChannelsPerPlaylist - 1]`.
+    fn add_channel(c: u32) -> Weight {
+        Weight::from_parts(21_080_000, 67_487)
+            .saturating_add(Weight::from_parts(61_730, 0).saturating_mul(c.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Channels` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Channels` (`max_values`: None, `max_size`: Some(64022), added: 66497, mode: `MaxEncodedLen`)
+    /// The range of component `c` is `[1, MaxChannelsPerPlaylist]`.
+    fn remove_channel(c: u32) -> Weight {
+        Weight::from_parts(20_640_000, 67_487)
+            .saturating_add(Weight::from_parts(63_150, 0).saturating_mul(c.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Channels` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Channels` (`max_values`: None, `max_size`: Some(64022), added: 66497, mode: `MaxEncodedLen`)
+    /// The range of component `c` is `[0, MaxChannelsPerPlaylist]`.
+    fn set_channels(c: u32) -> Weight {
+        Weight::from_parts(19_870_000, 67_487)
+            .saturating_add(Weight::from_parts(124_900, 0).saturating_mul(c.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::ContentVersions` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::ContentVersions` (`max_values`: None, `max_size`: Some(461), added: 2936, mode: `MaxEncodedLen`)
+    fn publish_version() -> Weight {
+        Weight::from_parts(22_530_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::PublicPlaylists` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::PublicPlaylists` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::TagIndex` (r:0 w:8)
+    /// Proof: `PlaylistRegistry::TagIndex` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+    /// The range of component `t` is `[0, MaxTags]`.
+    fn set_visibility(t: u32) -> Weight {
+        Weight::from_parts(19_720_000, 4512)
+            .saturating_add(Weight::from_parts(2_874_000, 0).saturating_mul(t.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+            .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(t.into())))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::TagIndex` (r:0 w:16)
+    /// Proof: `PlaylistRegistry::TagIndex` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `MaxEncodedLen`)
+    /// The range of component `t` is `[0, MaxTags]`.
+    fn set_tags(t: u32) -> Weight {
+        Weight::from_parts(18_960_000, 4512)
+            .saturating_add(Weight::from_parts(5_612_000, 0).saturating_mul(t.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+            .saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(t.into())))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Attestations` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Attestations` (`max_values`: None, `max_size`: Some(533), added: 3008, mode: `MaxEncodedLen`)
+    fn attest_playlist() -> Weight {
+        Weight::from_parts(20_270_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::Attestations` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::Attestations` (`max_values`: None, `max_size`: Some(533), added: 3008, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    fn revoke_attestation() -> Weight {
+        Weight::from_parts(19_340_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::GlobalCap` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::GlobalCap` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+    fn set_global_cap() -> Weight {
+        Weight::from_parts(7_840_000, 0).saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::SourceUrls` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::SourceUrls` (`max_values`: None, `max_size`: Some(534), added: 3009, mode: `MaxEncodedLen`)
+    /// The range of component `u` is `[1, MaxSourceUrlLength]`.
+    fn set_source_url(u: u32) -> Weight {
+        Weight::from_parts(17_260_000, 4512)
+            .saturating_add(Weight::from_parts(1_870, 0).saturating_mul(u.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `PlaylistRegistry::ContentVersions` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::ContentVersions` (`max_values`: None, `max_size`: Some(461), added: 2936, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::SourceUrls` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::SourceUrls` (`max_values`: None, `max_size`: Some(534), added: 3009, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::LastChecked` (r:1 w:1)
+    /// Proof: `PlaylistRegistry::LastChecked` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::ContentMismatches` (r:0 w:1)
+    /// Proof: `PlaylistRegistry::ContentMismatches` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    fn report_content() -> Weight {
+        Weight::from_parts(21_790_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    /// Storage: `PlaylistRegistry::Playlists` (r:1 w:0)
+    /// Proof: `PlaylistRegistry::Playlists` (`max_values`: None, `max_size`: Some(1047), added: 3522, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Gates` (r:1 w:1)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(102), added: 2577, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:0 w:1)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(106), added: 2581, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateSetBy` (r:0 w:1)
+    /// Proof: `TokenGate::GateSetBy` (`max_values`: None, `max_size`: Some(57), added: 2532, mode: `MaxEncodedLen`)
+    fn set_playlist_gate() -> Weight {
+        Weight::from_parts(24_130_000, 4512)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+    fn register_playlist(n: u32, p: u32) -> Weight {
+        Weight::from_parts(38_620_000, 3915)
+            .saturating_add(Weight::from_parts(1_240, 0).saturating_mul(n.into()))
+            .saturating_add(Weight::from_parts(46_310, 0).saturating_mul(p.into()))
+            .saturating_add(RocksDbWeight::get().reads(6_u64))
+            .saturating_add(RocksDbWeight::get().writes(7_u64))
+    }
+    fn remove_playlist(p: u32, t: u32) -> Weight {
+        Weight::from_parts(52_170_000, 4512)
+            .saturating_add(Weight::from_parts(41_870, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(2_935_000, 0).saturating_mul(t.into()))
+            .saturating_add(RocksDbWeight::get().reads(6_u64))
+            .saturating_add(RocksDbWeight::get().writes(18_u64))
+            .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(t.into())))
+    }
+    fn update_channel_count() -> Weight {
+        Weight::from_parts(17_450_000, 67_487)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn set_playlist_details(d: u32) -> Weight {
+        Weight::from_parts(15_830_000, 4512)
+            .saturating_add(Weight::from_parts(1_120, 0).saturating_mul(d.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn update_playlist(n: u32) -> Weight {
+        Weight::from_parts(24_310_000, 4512)
+            .saturating_add(Weight::from_parts(1_310, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(6_u64))
+    }
+    fn transfer_playlist() -> Weight {
+        Weight::from_parts(14_960_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn accept_playlist(p: u32) -> Weight {
+        Weight::from_parts(61_540_000, 4512)
+            .saturating_add(Weight::from_parts(88_420, 0).saturating_mul(p.into()))
+            .saturating_add(RocksDbWeight::get().reads(8_u64))
+            .saturating_add(RocksDbWeight::get().writes(9_u64))
+    }
+    fn add_channel(c: u32) -> Weight {
+        Weight::from_parts(21_080_000, 67_487)
+            .saturating_add(Weight::from_parts(61_730, 0).saturating_mul(c.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn remove_channel(c: u32) -> Weight {
+        Weight::from_parts(20_640_000, 67_487)
+            .saturating_add(Weight::from_parts(63_150, 0).saturating_mul(c.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn set_channels(c: u32) -> Weight {
+        Weight::from_parts(19_870_000, 67_487)
+            .saturating_add(Weight::from_parts(124_900, 0).saturating_mul(c.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn publish_version() -> Weight {
+        Weight::from_parts(22_530_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn set_visibility(t: u32) -> Weight {
+        Weight::from_parts(19_720_000, 4512)
+            .saturating_add(Weight::from_parts(2_874_000, 0).saturating_mul(t.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+            .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(t.into())))
+    }
+    fn set_tags(t: u32) -> Weight {
+        Weight::from_parts(18_960_000, 4512)
+            .saturating_add(Weight::from_parts(5_612_000, 0).saturating_mul(t.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+            .saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(t.into())))
+    }
+    fn attest_playlist() -> Weight {
+        Weight::from_parts(20_270_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn revoke_attestation() -> Weight {
+        Weight::from_parts(19_340_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn set_global_cap() -> Weight {
+        Weight::from_parts(7_840_000, 0).saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn set_source_url(u: u32) -> Weight {
+        Weight::from_parts(17_260_000, 4512)
+            .saturating_add(Weight::from_parts(1_870, 0).saturating_mul(u.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn report_content() -> Weight {
+        Weight::from_parts(21_790_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn set_playlist_gate() -> Weight {
+        Weight::from_parts(24_130_000, 4512)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+}
@@ -0,0 +1,301 @@
+//! Benchmarks for the token gate pallet.
+//!
+//! Each call is measured in its worst case: replaced gates hold
+//! `MaxRequirements` requirements and are tightened, so a grace period starts;
+//! access checks read a balance for every requirement of both the current and
+//! the previous gate; and subscribing queues its renewal in an almost full
+//! block. Resources get their owner through `T::Access::set_owner`, and tokens
+//! are created with `T::BenchmarkHelper`.
+
+use super::*;
+use crate::Pallet as TokenGate;
+use frame_benchmarking::v2::*;
+use frame_support::{
+    traits::{fungibles, Get},
+    BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use pallet_access_control::ResourceAccess;
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    SaturatedConversion,
+};
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+/// Resource every benchmark operates on.
+const RESOURCE: u32 = 1;
+
+/// Token passes and subscriptions are paid in.
+const PAYMENT_TOKEN: u32 = 0;
+
+/// Price of a pass or a subscription period, and the balance each gate
+/// requirement asks for.
+const PRICE: u128 = 1000;
+
+/// Blocks a pass or a subscription period lasts.
+const DURATION: u32 = 100;
+
+/// Make `T::Access` report an owner for [`RESOURCE`] and return it.
+fn owner<T: Config>() -> Result<T::AccountId, BenchmarkError> {
+    T::Access::set_owner(RESOURCE, &account("owner", 0, SEED));
+    T::Access::owner(RESOURCE).ok_or(BenchmarkError::Stop("`Access` reports no owner"))
+}
+
+/// Create `token_id` and mint `amount` of it to `who`.
+fn mint<T: Config>(token_id: u32, who: &T::AccountId, amount: u128) -> Result<(), BenchmarkError> {
+    T::BenchmarkHelper::create_token(token_id);
+    <T::Assets as fungibles::Mutate<T::AccountId>>::mint_into(
+        token_id,
+        who,
+        amount.saturated_into(),
+    )?;
+    Ok(())
+}
+
+/// `count` requirements of [`PRICE`] each, on tokens from `first` on.
+fn requirements<T: Config>(
+    first: u32,
+    count: u32,
+) -> BoundedVec<GateRequirement, T::MaxRequirements> {
+    let requirements: Vec<_> = (first..first + count)
+        .map(|token_id| {
+            T::BenchmarkHelper::create_token(token_id);
+            GateRequirement {
+                token_id,
+                min_balance: PRICE,
+            }
+        })
+        .collect();
+    BoundedVec::truncate_from(requirements)
+}
+
+/// Gate [`RESOURCE`] on `MaxRequirements` tokens on behalf of `owner`.
+fn seed_gate<T: Config>(owner: &T::AccountId) {
+    let gate = Gate {
+        requirements: requirements::<T>(0, T::MaxRequirements::get()),
+        combinator: Combinator::All,
+    };
+    TokenGate::<T>::do_set_gate(RESOURCE, gate, Some(owner.clone()));
+}
+
+/// Terms of a pass or subscription costing [`PRICE`] for [`DURATION`] blocks.
+fn terms<T: Config>() -> PassTerms<BlockNumberFor<T>> {
+    PassTerms {
+        token_id: PAYMENT_TOKEN,
+        price: PRICE,
+        duration: DURATION.into(),
+    }
+}
+
+/// Give [`RESOURCE`] an owner to pay, and fund `who` to pay it.
+fn seed_buyer<T: Config>(who: &T::AccountId) -> Result<(), BenchmarkError> {
+    owner::<T>()?;
+    mint::<T>(PAYMENT_TOKEN, who, PRICE.saturating_mul(10))
+}
+
+fn assert_last_event<T: Config>(event: Event<T>) {
+    frame_system::Pallet::<T>::assert_last_event(<T as Config>::RuntimeEvent::from(event).into());
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn set_gate() -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+        seed_gate::<T>(&owner);
+        // A requirement on another token tightens the gate.
+        let requirement = requirements::<T>(T::MaxRequirements::get(), 1).remove(0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), RESOURCE, requirement.clone());
+
+        assert_eq!(Gates::<T>::get(RESOURCE), Some(Gate::single(requirement)));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_gate() -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+        seed_gate::<T>(&owner);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), RESOURCE);
+
+        assert!(!Gates::<T>::contains_key(RESOURCE));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn verify_access(n: Linear<1, { T::MaxRequirements::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner = owner::<T>()?;
+        // The caller meets the previous gate but not the last requirement of the
+        // current one, so both gates are checked in full.
+        let previous = requirements::<T>(0, n);
+        let mut current = previous.clone();
+        if let Some(last) = current.last_mut() {
+            last.min_balance = PRICE + 1;
+        }
+        for requirements in [previous, current] {
+            let gate = Gate {
+                requirements,
+                combinator: Combinator::All,
+            };
+            TokenGate::<T>::do_set_gate(RESOURCE, gate, Some(owner.clone()));
+        }
+        for token_id in 0..n {
+            mint::<T>(token_id, &caller, PRICE)?;
+        }
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), RESOURCE);
+
+        let event = if T::GracePeriod::get().is_zero() {
+            Event::AccessDenied {
+                who: caller,
+                resource_id: RESOURCE,
+            }
+        } else {
+            Event::AccessVerified {
+                who: caller,
+                resource_id: RESOURCE,
+            }
+        };
+        assert_last_event::<T>(event);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_composite_gate(
+        n: Linear<1, { T::MaxRequirements::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+        seed_gate::<T>(&owner);
+        // Requirements on other tokens tighten the gate.
+        let requirements = requirements::<T>(T::MaxRequirements::get(), n);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(owner),
+            RESOURCE,
+            requirements.clone(),
+            Combinator::Any,
+        );
+
+        assert_eq!(
+            Gates::<T>::get(RESOURCE).map(|gate| gate.requirements),
+            Some(requirements)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_gate_expiry() -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+        seed_gate::<T>(&owner);
+        let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(DURATION.into());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), RESOURCE, Some(expires_at));
+
+        assert_eq!(GateExpiry::<T>::get(RESOURCE), Some(expires_at));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_pass_terms() -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), RESOURCE, Some(terms::<T>()));
+
+        assert_eq!(PassTermsOf::<T>::get(RESOURCE), Some(terms::<T>()));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn purchase_access() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_buyer::<T>(&caller)?;
+        PassTermsOf::<T>::insert(RESOURCE, terms::<T>());
+        // Buying while a pass is still valid extends it.
+        let current = frame_system::Pallet::<T>::block_number().saturating_add(1u32.into());
+        AccessPasses::<T>::insert(&caller, RESOURCE, current);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), RESOURCE);
+
+        assert_eq!(
+            AccessPasses::<T>::get(&caller, RESOURCE),
+            Some(current.saturating_add(DURATION.into()))
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_subscription_terms() -> Result<(), BenchmarkError> {
+        let owner = owner::<T>()?;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), RESOURCE, Some(terms::<T>()));
+
+        assert_eq!(SubscriptionTermsOf::<T>::get(RESOURCE), Some(terms::<T>()));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn subscribe() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        seed_buyer::<T>(&caller)?;
+        SubscriptionTermsOf::<T>::insert(RESOURCE, terms::<T>());
+        // The renewal goes into a queue with room for just one more entry.
+        let paid_until = frame_system::Pallet::<T>::block_number().saturating_add(DURATION.into());
+        let queued: Vec<_> = (1..T::MaxRenewalsPerBlock::get())
+            .map(|i| (account("subscriber", i, SEED), RESOURCE))
+            .collect();
+        RenewalQueue::<T>::insert(paid_until, BoundedVec::truncate_from(queued));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), RESOURCE);
+
+        assert_eq!(
+            Subscriptions::<T>::get(&caller, RESOURCE),
+            Some(Subscription {
+                paid_until,
+                auto_renew: true,
+            })
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn unsubscribe() {
+        let caller: T::AccountId = whitelisted_caller();
+        let paid_until = frame_system::Pallet::<T>::block_number().saturating_add(DURATION.into());
+        Subscriptions::<T>::insert(
+            &caller,
+            RESOURCE,
+            Subscription {
+                paid_until,
+                auto_renew: true,
+            },
+        );
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), RESOURCE);
+
+        assert_eq!(
+            Subscriptions::<T>::get(&caller, RESOURCE),
+            Some(Subscription {
+                paid_until,
+                auto_renew: false,
+            })
+        );
+    }
+
+    impl_benchmark_test_suite!(TokenGate, crate::tests::new_test_ext(), crate::tests::Test);
+}
@@ -1,19 +1,161 @@
 //! # Token Gate Pallet
 //!
 //! A FRAME pallet for token-gating access to resources.
-//! Stores a `GateRequirement` per resource, specifying which token and
-//! minimum balance is required for access.
+//! Stores a `Gate` per resource: a bounded list of `GateRequirement`s (token
+//! and minimum balance) combined with `All` or `Any` semantics, e.g. "hold 100
+//! TOKEN *or* one badge from collection 7".
+//!
+//! Balances are read from the runtime's `Assets` (any `fungibles::Inspect`
+//! implementation, such as `pallet-assets`), so [`Pallet::check_access`] can
+//! enforce gates on-chain and `verify_access` records the outcome as an event.
+//!
+//! When a gate is tightened, the previous requirement keeps granting access
+//! for `GracePeriod` blocks so existing viewers are not cut off instantly.
+//! Gates can also be given an expiry, after which the resource is open again
+//! and `on_idle` removes the gate, e.g. to end a promotion without a follow-up
+//! extrinsic.
+//!
+//! A resource owner (as reported by `Access`) can also sell time-limited
+//! access passes: `purchase_access` pays the owner the configured amount of a
+//! token and grants access for a fixed number of blocks, regardless of gates.
+//! Subscriptions work the same way but renew themselves: `on_initialize`
+//! charges the next period when the paid one ends, and lapses subscriptions
+//! that were cancelled or could not pay.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+use frame_support::{dispatch::DispatchResult, traits::Contains};
+use sp_runtime::DispatchError;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+/// Call filter that allows every call of this pallet except destructive ones
+/// (`remove_gate`, and `set_gate_expiry` scheduling a removal).
+///
+/// Runtimes can use it from their `BaseCallFilter` to disable removing gates
+/// during an initial deployment phase:
+///
+/// ```ignore
+/// pub struct BaseFilter;
+/// impl Contains<RuntimeCall> for BaseFilter {
+///     fn contains(call: &RuntimeCall) -> bool {
+///         match call {
+///             RuntimeCall::TokenGate(c) => SafeCallFilter::<Runtime>::contains(c),
+///             _ => true,
+///         }
+///     }
+/// }
+/// ```
+pub struct SafeCallFilter<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Contains<Call<T>> for SafeCallFilter<T> {
+    fn contains(call: &Call<T>) -> bool {
+        !matches!(
+            call,
+            Call::remove_gate { .. }
+                | Call::set_gate_expiry {
+                    expires_at: Some(_),
+                    ..
+                }
+        )
+    }
+}
+
+/// Gate checks and updates other pallets can depend on without depending on this
+/// pallet's `Config`.
+///
+/// A downstream pallet declares `type Gate: ResourceGate<Self::AccountId>` and the
+/// runtime wires it to `pallet_token_gate::Pallet<Runtime>`.
+pub trait ResourceGate<AccountId> {
+    /// Whether `who` may access `resource_id`; ungated resources are open.
+    fn check_access(who: &AccountId, resource_id: u32) -> bool;
+
+    /// Gate `resource_id` on `requirement` on behalf of `by` (`None` for root).
+    /// Fails if `by` may not replace the resource's current gate.
+    fn set_gate(
+        resource_id: u32,
+        requirement: GateRequirement,
+        by: Option<AccountId>,
+    ) -> DispatchResult;
+
+    /// Remove the gate of `resource_id` on behalf of `by`, if it has one and `by`
+    /// may remove it.
+    fn remove_gate(resource_id: u32, by: Option<AccountId>);
+}
+
+/// Gates nothing and refuses to set gates. Useful in mocks of pallets that do not
+/// exercise token gates.
+impl<AccountId> ResourceGate<AccountId> for () {
+    fn check_access(_who: &AccountId, _resource_id: u32) -> bool {
+        true
+    }
+
+    fn set_gate(
+        _resource_id: u32,
+        _requirement: GateRequirement,
+        _by: Option<AccountId>,
+    ) -> DispatchResult {
+        Err(DispatchError::Other("token gates are not available"))
+    }
+
+    fn remove_gate(_resource_id: u32, _by: Option<AccountId>) {}
+}
+
+/// Creates the tokens the benchmarks gate resources on and sell passes in.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+    /// Create `token_id` in `Assets` if it does not exist, with a minimum balance
+    /// of at most 1000 so the benchmarks can mint and transfer it.
+    fn create_token(token_id: u32);
+}
+
+impl<T: Config> ResourceGate<T::AccountId> for Pallet<T> {
+    fn check_access(who: &T::AccountId, resource_id: u32) -> bool {
+        Self::check_access(who, resource_id)
+    }
+
+    fn set_gate(
+        resource_id: u32,
+        requirement: GateRequirement,
+        by: Option<T::AccountId>,
+    ) -> DispatchResult {
+        Self::ensure_gate_manager(by.as_ref(), resource_id)?;
+        Self::do_set_gate(resource_id, Gate::single(requirement), by);
+        Ok(())
+    }
+
+    fn remove_gate(resource_id: u32, by: Option<T::AccountId>) {
+        if Gates::<T>::contains_key(resource_id)
+            && Self::ensure_gate_manager(by.as_ref(), resource_id).is_ok()
+        {
+            Self::do_remove_gate(resource_id, by);
+        }
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use codec::{Decode, Encode};
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        storage::with_storage_layer,
+        traits::{fungibles, tokens::Preservation},
+    };
     use frame_system::pallet_prelude::*;
+    use pallet_access_control::ResourceAccess;
     use scale_info::TypeInfo;
+    use sp_runtime::{
+        traits::{Hash as HashT, Saturating, Zero},
+        SaturatedConversion,
+    };
+
+    use crate::WeightInfo;
 
     /// Defines the token requirement for gating a resource.
     #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
@@ -24,32 +166,302 @@ pub mod pallet {
         pub min_balance: u128,
     }
 
+    impl GateRequirement {
+        /// Whether holding `balance` of `token_id` satisfies this requirement.
+        pub fn is_met_by(&self, token_id: u32, balance: u128) -> bool {
+            self.token_id == token_id && balance >= self.min_balance
+        }
+
+        /// Whether replacing `self` with `new` can lock out a current holder.
+        pub fn is_tightened_by(&self, new: &GateRequirement) -> bool {
+            self.token_id != new.token_id || new.min_balance > self.min_balance
+        }
+    }
+
+    /// How the requirements of a gate are combined.
+    #[derive(
+        Clone, Copy, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq, Default,
+    )]
+    pub enum Combinator {
+        /// Every requirement must be met.
+        #[default]
+        All,
+        /// At least one requirement must be met.
+        Any,
+    }
+
+    /// The requirements guarding a resource.
+    #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
+    #[scale_info(skip_type_params(Requirements))]
+    pub struct Gate<Requirements> {
+        /// Requirements checked against the account's balances; never empty.
+        pub requirements: Requirements,
+        /// Whether all or any of `requirements` must be met.
+        pub combinator: Combinator,
+    }
+
+    impl<S: Get<u32>> Gate<BoundedVec<GateRequirement, S>> {
+        /// A gate with a single requirement.
+        pub fn single(requirement: GateRequirement) -> Self {
+            Gate {
+                requirements: BoundedVec::truncate_from(sp_std::vec![requirement]),
+                combinator: Combinator::All,
+            }
+        }
+
+        /// Whether an account holding `balance_of(token_id)` of each token meets the gate.
+        pub fn is_met_with(&self, balance_of: impl Fn(u32) -> u128) -> bool {
+            let met = |r: &GateRequirement| r.is_met_by(r.token_id, balance_of(r.token_id));
+            match self.combinator {
+                Combinator::All => self.requirements.iter().all(met),
+                Combinator::Any => self.requirements.iter().any(met),
+            }
+        }
+
+        /// Whether every account meeting all of `held` also meets this gate.
+        fn is_implied_by(&self, held: &[GateRequirement]) -> bool {
+            let implied = |new: &GateRequirement| held.iter().any(|old| !old.is_tightened_by(new));
+            match self.combinator {
+                Combinator::All => self.requirements.iter().all(implied),
+                Combinator::Any => self.requirements.iter().any(implied),
+            }
+        }
+
+        /// Whether replacing `self` with `new` can lock out a current holder.
+        ///
+        /// Conservative: requirements are compared pairwise, so a gate is only
+        /// considered relaxed when each way of meeting `self` visibly meets `new`.
+        pub fn is_tightened_by(&self, new: &Self) -> bool {
+            match self.combinator {
+                Combinator::All => !new.is_implied_by(&self.requirements),
+                Combinator::Any => !self
+                    .requirements
+                    .iter()
+                    .all(|r| new.is_implied_by(core::slice::from_ref(r))),
+            }
+        }
+    }
+
+    /// Price and length of an access pass to a resource.
+    #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
+    pub struct PassTerms<BlockNumber> {
+        /// Numeric identifier of the token the pass is paid in.
+        pub token_id: u32,
+        /// Amount of `token_id` paid to the resource owner per pass.
+        pub price: u128,
+        /// Number of blocks a pass grants access for; never zero.
+        pub duration: BlockNumber,
+    }
+
+    /// A recurring subscription to a resource.
+    #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
+    pub struct Subscription<BlockNumber> {
+        /// First block not covered by the periods paid so far; the next renewal
+        /// is charged in this block.
+        pub paid_until: BlockNumber,
+        /// Whether to charge another period at `paid_until`; cleared by `unsubscribe`.
+        pub auto_renew: bool,
+    }
+
+    /// Who last set a gate, and when.
+    #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
+    pub struct GateSetter<AccountId, BlockNumber> {
+        /// Account that set the gate; `None` for root.
+        pub by: Option<AccountId>,
+        /// Block in which the gate was set.
+        pub block: BlockNumber,
+    }
+
+    /// A superseded gate that still grants access until `expires_at`.
+    #[derive(Clone, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq)]
+    pub struct GracePeriodInfo<Gate, BlockNumber> {
+        /// The gate in force before it was tightened.
+        pub previous: Gate,
+        /// First block at which the previous requirement no longer applies.
+        pub expires_at: BlockNumber,
+    }
+
+    /// The in-code storage version; see [`crate::migrations`].
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
+    /// Tag hashed with a resource ID to form its event topic.
+    pub const RESOURCE_TOPIC: &[u8] = b"iptv/resource";
+
+    /// A gate as stored for this runtime.
+    pub type GateOf<T> = Gate<BoundedVec<GateRequirement, <T as Config>::MaxRequirements>>;
+
     /// Configuration trait for the token gate pallet.
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// The overarching runtime event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Number of blocks the previous requirement stays valid after a gate is
+        /// tightened. Zero disables the grace period.
+        #[pallet::constant]
+        type GracePeriod: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of requirements in one gate.
+        #[pallet::constant]
+        type MaxRequirements: Get<u32>;
+
+        /// Tokens whose balances gates check and passes are paid in, keyed by
+        /// `GateRequirement::token_id`.
+        type Assets: fungibles::Mutate<Self::AccountId, AssetId = u32>;
+
+        /// Resource ownership, to know who is paid for access passes and subscriptions.
+        type Access: ResourceAccess<Self::AccountId>;
+
+        /// Maximum number of subscriptions renewing in the same block.
+        #[pallet::constant]
+        type MaxRenewalsPerBlock: Get<u32>;
+
+        /// Weights for this pallet's calls.
+        type WeightInfo: WeightInfo;
+
+        /// Creates tokens in `Assets` for the benchmarks.
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: crate::BenchmarkHelper;
     }
 
-    /// Map from resource ID to its gate requirement.
+    /// Map from resource ID to its gate.
     #[pallet::storage]
     #[pallet::getter(fn gates)]
-    pub type Gates<T: Config> = StorageMap<_, Blake2_128Concat, u32, GateRequirement>;
+    pub type Gates<T: Config> = StorageMap<_, Blake2_128Concat, u32, GateOf<T>>;
+
+    /// Map from resource ID to the gate it replaced, while its grace period runs.
+    #[pallet::storage]
+    pub type GracePeriods<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, GracePeriodInfo<GateOf<T>, BlockNumberFor<T>>>;
+
+    /// Map from resource ID to who last set its gate, kept while the gate exists.
+    #[pallet::storage]
+    pub type GateSetBy<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, GateSetter<T::AccountId, BlockNumberFor<T>>>;
+
+    /// Map from resource ID to the first block at which its gate no longer applies.
+    #[pallet::storage]
+    pub type GateExpiry<T: Config> = StorageMap<_, Blake2_128Concat, u32, BlockNumberFor<T>>;
+
+    /// Raw `GateExpiry` key at which the next `on_idle` scan resumes.
+    #[pallet::storage]
+    pub type ExpiryCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<256>>>;
+
+    /// Map from resource ID to the terms its access passes are sold on.
+    #[pallet::storage]
+    pub type PassTermsOf<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, PassTerms<BlockNumberFor<T>>>;
+
+    /// Access passes: `(account, resource ID)` to the first block the pass no
+    /// longer covers.
+    #[pallet::storage]
+    pub type AccessPasses<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        BlockNumberFor<T>,
+    >;
+
+    /// Map from resource ID to the price and period of its subscriptions.
+    #[pallet::storage]
+    pub type SubscriptionTermsOf<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, PassTerms<BlockNumberFor<T>>>;
+
+    /// Subscriptions by `(account, resource ID)`.
+    #[pallet::storage]
+    pub type Subscriptions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        Subscription<BlockNumberFor<T>>,
+    >;
+
+    /// Subscriptions to renew or lapse in each block, as `(account, resource ID)`.
+    #[pallet::storage]
+    pub type RenewalQueue<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<(T::AccountId, u32), T::MaxRenewalsPerBlock>,
+        ValueQuery,
+    >;
 
     /// Events emitted by this pallet.
     #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// A gate requirement was set for a resource.
+        /// A gate was set for a resource by `by` (`None` for root).
         GateSet {
             resource_id: u32,
-            requirement: GateRequirement,
+            gate: GateOf<T>,
+            by: Option<T::AccountId>,
+        },
+        /// A gate was removed from a resource by `by` (`None` for root).
+        GateRemoved {
+            resource_id: u32,
+            by: Option<T::AccountId>,
+        },
+        /// A gate was tightened; `previous` keeps granting access until `expires_at`.
+        GraceStarted {
+            resource_id: u32,
+            previous: GateOf<T>,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// A gate's expiry was set or cleared by `by` (`None` for root).
+        GateExpirySet {
+            resource_id: u32,
+            expires_at: Option<BlockNumberFor<T>>,
+            by: Option<T::AccountId>,
+        },
+        /// A gate reached its expiry and was removed.
+        GateExpired { resource_id: u32 },
+        /// `who` holds enough tokens to access a resource.
+        AccessVerified { who: T::AccountId, resource_id: u32 },
+        /// `who` does not hold enough tokens to access a resource.
+        AccessDenied { who: T::AccountId, resource_id: u32 },
+        /// Access passes to a resource went on sale, changed terms or were withdrawn.
+        PassTermsSet {
+            resource_id: u32,
+            terms: Option<PassTerms<BlockNumberFor<T>>>,
+        },
+        /// `who` bought an access pass that is valid until `expires_at`.
+        AccessPurchased {
+            who: T::AccountId,
+            resource_id: u32,
+            expires_at: BlockNumberFor<T>,
+        },
+        /// Subscriptions to a resource went on sale, changed terms or were withdrawn.
+        SubscriptionTermsSet {
+            resource_id: u32,
+            terms: Option<PassTerms<BlockNumberFor<T>>>,
+        },
+        /// `who` subscribed to a resource, paid until `paid_until`.
+        Subscribed {
+            who: T::AccountId,
+            resource_id: u32,
+            paid_until: BlockNumberFor<T>,
+        },
+        /// `who` cancelled a subscription; it lapses at `paid_until`.
+        SubscriptionCancelled {
+            who: T::AccountId,
+            resource_id: u32,
+            paid_until: BlockNumberFor<T>,
+        },
+        /// A subscription was charged for another period, up to `paid_until`.
+        SubscriptionRenewed {
+            who: T::AccountId,
+            resource_id: u32,
+            paid_until: BlockNumberFor<T>,
         },
-        /// A gate requirement was removed from a resource.
-        GateRemoved { resource_id: u32 },
+        /// A subscription ended: cancelled, no longer on sale, or not paid.
+        SubscriptionLapsed { who: T::AccountId, resource_id: u32 },
     }
 
     /// Errors that can occur in this pallet.
@@ -57,164 +469,902 @@ pub mod pallet {
     pub enum Error<T> {
         /// No gate requirement exists for the specified resource.
         GateNotFound,
+        /// A gate must contain at least one requirement.
+        NoRequirements,
+        /// The caller does not own the resource.
+        NotResourceOwner,
+        /// The resource has no owner to pay for a pass.
+        NoResourceOwner,
+        /// Access passes to the resource are not on sale.
+        PassNotForSale,
+        /// A pass must last at least one block.
+        ZeroPassDuration,
+        /// The expiry block has already been reached.
+        ExpiryInPast,
+        /// Subscriptions to the resource are not on sale.
+        SubscriptionNotForSale,
+        /// The account already has a renewing subscription to the resource.
+        AlreadySubscribed,
+        /// The account has no renewing subscription to the resource.
+        NotSubscribed,
+        /// Too many subscriptions already renew in the block this one would.
+        TooManyRenewals,
+        /// The caller owns neither the resource nor, on a resource without an
+        /// owner, its gate.
+        NotGateManager,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Renew or lapse the subscriptions whose paid period ends in this block.
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            Self::process_renewals(now)
+        }
+
+        /// Remove expired gates using the block's leftover weight.
+        fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::expire_gates(now, remaining_weight)
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::MaxRequirements::get() > 0,
+                "MaxRequirements must allow at least one requirement"
+            );
+            assert!(
+                T::MaxRenewalsPerBlock::get() > 0,
+                "MaxRenewalsPerBlock must allow at least one renewal"
+            );
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Set a gate requirement for a resource.
+        /// Gate a resource on a single requirement.
         ///
-        /// Can be called by any signed origin or root.
+        /// Can be called by root or the resource owner. A resource without an
+        /// owner may be gated by anyone, and its gate then replaced only by root
+        /// or the account that set it.
+        ///
+        /// If the new gate is stricter than the existing one, the old gate keeps
+        /// granting access for `GracePeriod` blocks. Relaxing a gate ends any
+        /// running grace period.
         ///
         /// - `resource_id`: Numeric identifier of the resource to gate.
         /// - `requirement`: The token requirement (token ID and minimum balance).
         ///
-        /// Emits `GateSet` on success.
+        /// Emits `GateSet` on success, preceded by `GraceStarted` when a grace
+        /// period begins.
         #[pallet::call_index(0)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::set_gate())]
         pub fn set_gate(
             origin: OriginFor<T>,
             resource_id: u32,
             requirement: GateRequirement,
         ) -> DispatchResult {
-            Self::ensure_signed_or_root(origin)?;
-
-            Gates::<T>::insert(resource_id, requirement.clone());
-
-            Self::deposit_event(Event::GateSet {
-                resource_id,
-                requirement,
-            });
-
+            let by = Self::ensure_signed_or_root(origin)?;
+            Self::ensure_gate_manager(by.as_ref(), resource_id)?;
+            Self::do_set_gate(resource_id, Gate::single(requirement), by);
             Ok(())
         }
 
         /// Remove a gate requirement from a resource.
         ///
-        /// Can be called by any signed origin or root.
+        /// Can be called by root or the resource owner, or by the account that
+        /// set the gate if the resource has no owner.
         ///
         /// - `resource_id`: Numeric identifier of the resource to ungate.
         ///
         /// Emits `GateRemoved` on success.
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_parts(10_000, 0))]
+        #[pallet::weight(T::WeightInfo::remove_gate())]
         pub fn remove_gate(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
-            Self::ensure_signed_or_root(origin)?;
+            let by = Self::ensure_signed_or_root(origin)?;
 
             Gates::<T>::get(resource_id).ok_or(Error::<T>::GateNotFound)?;
-            Gates::<T>::remove(resource_id);
+            Self::ensure_gate_manager(by.as_ref(), resource_id)?;
+            Self::do_remove_gate(resource_id, by);
+
+            Ok(())
+        }
+
+        /// Check on-chain whether the caller may access a resource.
+        ///
+        /// Succeeds either way; the outcome is recorded as an event so dApps and
+        /// indexers can rely on a verified answer.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `AccessVerified` or `AccessDenied`.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::verify_access(T::MaxRequirements::get()))]
+        pub fn verify_access(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            Self::deposit_event(Event::GateRemoved { resource_id });
+            let event = if Self::check_access(&who, resource_id) {
+                Event::AccessVerified { who, resource_id }
+            } else {
+                Event::AccessDenied { who, resource_id }
+            };
+            Self::deposit_event(Self::resource_topic(resource_id), event);
 
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Ensure the origin is either signed or root.
-        fn ensure_signed_or_root(origin: OriginFor<T>) -> Result<(), DispatchError> {
-            if ensure_root(origin.clone()).is_ok() {
-                return Ok(());
-            }
-            ensure_signed(origin)?;
+        /// Gate a resource on several requirements, all or any of which must be met.
+        ///
+        /// Can be called by the same origins as `set_gate`. Grace periods apply as for `set_gate`.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource to gate.
+        /// - `requirements`: The token requirements; at least one.
+        /// - `combinator`: Whether all or any of `requirements` must be met.
+        ///
+        /// Emits `GateSet` on success, preceded by `GraceStarted` when a grace
+        /// period begins.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::set_composite_gate(requirements.len() as u32))]
+        pub fn set_composite_gate(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            requirements: BoundedVec<GateRequirement, T::MaxRequirements>,
+            combinator: Combinator,
+        ) -> DispatchResult {
+            let by = Self::ensure_signed_or_root(origin)?;
+            ensure!(!requirements.is_empty(), Error::<T>::NoRequirements);
+            Self::ensure_gate_manager(by.as_ref(), resource_id)?;
+            Self::do_set_gate(
+                resource_id,
+                Gate {
+                    requirements,
+                    combinator,
+                },
+                by,
+            );
             Ok(())
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use frame_support::{
-        assert_noop, assert_ok, derive_impl,
-        traits::{ConstU32, ConstU64},
-    };
-    use sp_core::H256;
-    use sp_io::TestExternalities;
-    use sp_runtime::{
-        traits::{BlakeTwo256, IdentityLookup},
-        BuildStorage,
-    };
+        /// Schedule a gate to lift at `expires_at`, or keep it indefinitely with `None`.
+        ///
+        /// Can be called by the same origins as `remove_gate`. From `expires_at` on the resource is open; the gate is removed by
+        /// `on_idle` soon after. Replacing the gate with `set_gate` keeps the expiry.
+        ///
+        /// - `resource_id`: Numeric identifier of the gated resource.
+        /// - `expires_at`: First block at which the gate no longer applies.
+        ///
+        /// Emits `GateExpirySet` on success.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_gate_expiry())]
+        pub fn set_gate_expiry(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            expires_at: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let by = Self::ensure_signed_or_root(origin)?;
+            ensure!(
+                Gates::<T>::contains_key(resource_id),
+                Error::<T>::GateNotFound
+            );
+            Self::ensure_gate_manager(by.as_ref(), resource_id)?;
+            if let Some(at) = expires_at {
+                let now = frame_system::Pallet::<T>::block_number();
+                ensure!(at > now, Error::<T>::ExpiryInPast);
+            }
 
-    type Block = frame_system::mocking::MockBlock<Test>;
+            GateExpiry::<T>::set(resource_id, expires_at);
 
-    frame_support::construct_runtime!(
-        pub enum Test {
-            System: frame_system,
-            TokenGate: pallet,
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::GateExpirySet {
+                    resource_id,
+                    expires_at,
+                    by,
+                },
+            );
+
+            Ok(())
         }
-    );
 
-    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
-    impl frame_system::Config for Test {
-        type BaseCallFilter = frame_support::traits::Everything;
-        type BlockWeights = ();
-        type BlockLength = ();
-        type DbWeight = ();
-        type RuntimeOrigin = RuntimeOrigin;
-        type RuntimeCall = RuntimeCall;
-        type Nonce = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Block = Block;
-        type RuntimeEvent = RuntimeEvent;
-        type BlockHashCount = ConstU64<250>;
-        type Version = ();
-        type PalletInfo = PalletInfo;
-        type AccountData = ();
-        type OnNewAccount = ();
-        type OnKilledAccount = ();
-        type SystemWeightInfo = ();
-        type SS58Prefix = ();
-        type OnSetCode = ();
-        type MaxConsumers = ConstU32<16>;
-    }
+        /// Put access passes to a resource on sale, change their terms, or stop
+        /// selling them with `None`. Passes already bought stay valid.
+        ///
+        /// Can be called by the resource owner or root.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `terms`: Token, price and duration of a pass.
+        ///
+        /// Emits `PassTermsSet` on success.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_pass_terms())]
+        pub fn set_pass_terms(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            terms: Option<PassTerms<BlockNumberFor<T>>>,
+        ) -> DispatchResult {
+            Self::ensure_owner_or_root(origin, resource_id)?;
+            ensure!(
+                !matches!(&terms, Some(t) if t.duration.is_zero()),
+                Error::<T>::ZeroPassDuration
+            );
 
-    impl pallet::Config for Test {
-        type RuntimeEvent = RuntimeEvent;
-    }
+            PassTermsOf::<T>::set(resource_id, terms.clone());
 
-    /// Build a test externalities instance with default genesis state.
-    fn new_test_ext() -> TestExternalities {
-        let t = frame_system::GenesisConfig::<Test>::default()
-            .build_storage()
-            .expect("genesis build should succeed in tests");
-        let mut ext = TestExternalities::new(t);
-        ext.execute_with(|| System::set_block_number(1));
-        ext
-    }
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::PassTermsSet { resource_id, terms },
+            );
 
-    #[test]
-    fn set_gate_works() {
-        new_test_ext().execute_with(|| {
-            let resource_id = 1u32;
-            let requirement = pallet::GateRequirement {
-                token_id: 100,
-                min_balance: 500,
-            };
+            Ok(())
+        }
 
-            // Signed origin can set a gate.
-            assert_ok!(TokenGate::set_gate(
-                RuntimeOrigin::signed(1),
-                resource_id,
-                requirement.clone(),
-            ));
+        /// Buy an access pass to a resource, paying its owner the pass price.
+        ///
+        /// Buying while a pass is still valid extends it by another `duration`.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `AccessPurchased` on success.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::purchase_access())]
+        pub fn purchase_access(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-            // Verify storage.
-            assert_eq!(
-                pallet::Gates::<Test>::get(resource_id),
-                Some(requirement.clone())
-            );
+            let terms = PassTermsOf::<T>::get(resource_id).ok_or(Error::<T>::PassNotForSale)?;
+            Self::pay_owner(&who, resource_id, &terms)?;
 
-            // Verify event.
-            System::assert_last_event(
-                pallet::Event::<Test>::GateSet {
+            let now = frame_system::Pallet::<T>::block_number();
+            let expires_at = AccessPasses::<T>::get(&who, resource_id)
+                .map_or(now, |current| current.max(now))
+                .saturating_add(terms.duration);
+            AccessPasses::<T>::insert(&who, resource_id, expires_at);
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::AccessPurchased {
+                    who,
                     resource_id,
-                    requirement,
-                }
+                    expires_at,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Offer subscriptions to a resource, change their terms, or stop offering
+        /// them with `None`.
+        ///
+        /// Can be called by the resource owner or root. Running subscriptions renew
+        /// on the terms in force at renewal, and lapse if there are none.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        /// - `terms`: Token, price per period and period length in blocks.
+        ///
+        /// Emits `SubscriptionTermsSet` on success.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_subscription_terms())]
+        pub fn set_subscription_terms(
+            origin: OriginFor<T>,
+            resource_id: u32,
+            terms: Option<PassTerms<BlockNumberFor<T>>>,
+        ) -> DispatchResult {
+            Self::ensure_owner_or_root(origin, resource_id)?;
+            ensure!(
+                !matches!(&terms, Some(t) if t.duration.is_zero()),
+                Error::<T>::ZeroPassDuration
+            );
+
+            SubscriptionTermsOf::<T>::set(resource_id, terms.clone());
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::SubscriptionTermsSet { resource_id, terms },
+            );
+
+            Ok(())
+        }
+
+        /// Subscribe to a resource, paying its owner for the first period.
+        ///
+        /// Each following period is charged in `on_initialize` when the paid one
+        /// ends. Subscribing again after `unsubscribe`, while the period is still
+        /// paid, resumes renewals without charging.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `Subscribed` on success.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::subscribe())]
+        pub fn subscribe(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let paid_until = match Subscriptions::<T>::get(&who, resource_id) {
+                Some(sub) if sub.auto_renew => return Err(Error::<T>::AlreadySubscribed.into()),
+                Some(sub) => sub.paid_until,
+                None => {
+                    let terms = SubscriptionTermsOf::<T>::get(resource_id)
+                        .ok_or(Error::<T>::SubscriptionNotForSale)?;
+                    let paid_until =
+                        frame_system::Pallet::<T>::block_number().saturating_add(terms.duration);
+                    RenewalQueue::<T>::try_mutate(paid_until, |queue| {
+                        queue.try_push((who.clone(), resource_id))
+                    })
+                    .map_err(|_| Error::<T>::TooManyRenewals)?;
+                    Self::pay_owner(&who, resource_id, &terms)?;
+                    paid_until
+                }
+            };
+            Subscriptions::<T>::insert(
+                &who,
+                resource_id,
+                Subscription {
+                    paid_until,
+                    auto_renew: true,
+                },
+            );
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::Subscribed {
+                    who,
+                    resource_id,
+                    paid_until,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Stop renewing a subscription. Access continues until the paid period ends.
+        ///
+        /// - `resource_id`: Numeric identifier of the resource.
+        ///
+        /// Emits `SubscriptionCancelled` on success.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::unsubscribe())]
+        pub fn unsubscribe(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let paid_until = Subscriptions::<T>::try_mutate(&who, resource_id, |sub| match sub {
+                Some(sub) if sub.auto_renew => {
+                    sub.auto_renew = false;
+                    Ok(sub.paid_until)
+                }
+                _ => Err(Error::<T>::NotSubscribed),
+            })?;
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::SubscriptionCancelled {
+                    who,
+                    resource_id,
+                    paid_until,
+                },
+            );
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Topic under which every event about `resource_id` is indexed.
+        ///
+        /// Computed as `T::Hashing` of the SCALE-encoded `(b"iptv/resource", resource_id)`,
+        /// the same scheme the access-control pallet uses, so a client can follow one
+        /// resource across pallets with `System::EventTopics`.
+        pub fn resource_topic(resource_id: u32) -> T::Hash {
+            <T::Hashing as HashT>::hash_of(&(RESOURCE_TOPIC, resource_id))
+        }
+
+        /// Deposit `event`, indexed under `topic`.
+        fn deposit_event(topic: T::Hash, event: Event<T>) {
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event_indexed(&[topic], event.into());
+        }
+
+        /// Replace the gate of `resource_id` on behalf of `by`, starting or ending
+        /// its grace period.
+        pub(crate) fn do_set_gate(resource_id: u32, gate: GateOf<T>, by: Option<T::AccountId>) {
+            let now = frame_system::Pallet::<T>::block_number();
+            let grace = T::GracePeriod::get();
+            match Gates::<T>::get(resource_id) {
+                // Re-setting the same gate leaves any grace period running.
+                Some(previous) if previous == gate => {}
+                Some(previous) if !grace.is_zero() && previous.is_tightened_by(&gate) => {
+                    let expires_at = now.saturating_add(grace);
+                    GracePeriods::<T>::insert(
+                        resource_id,
+                        GracePeriodInfo {
+                            previous: previous.clone(),
+                            expires_at,
+                        },
+                    );
+                    Self::deposit_event(
+                        Self::resource_topic(resource_id),
+                        Event::GraceStarted {
+                            resource_id,
+                            previous,
+                            expires_at,
+                        },
+                    );
+                }
+                _ => GracePeriods::<T>::remove(resource_id),
+            }
+
+            Gates::<T>::insert(resource_id, gate.clone());
+            GateSetBy::<T>::insert(
+                resource_id,
+                GateSetter {
+                    by: by.clone(),
+                    block: now,
+                },
+            );
+
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::GateSet {
+                    resource_id,
+                    gate,
+                    by,
+                },
+            );
+        }
+
+        /// Whether `who` may access `resource_id`, through a valid access pass, a
+        /// paid subscription, or the tokens it holds in `Assets`.
+        ///
+        /// Ungated resources are open to everyone. While a grace period runs,
+        /// meeting the previous gate also grants access.
+        pub fn check_access(who: &T::AccountId, resource_id: u32) -> bool {
+            Self::has_valid_pass(who, resource_id)
+                || Self::is_subscribed(who, resource_id)
+                || Self::grants_access(resource_id, |token_id| Self::balance_of(who, token_id))
+        }
+
+        /// Whether `who` has a subscription to `resource_id` covering the current block.
+        pub fn is_subscribed(who: &T::AccountId, resource_id: u32) -> bool {
+            Subscriptions::<T>::get(who, resource_id)
+                .is_some_and(|sub| frame_system::Pallet::<T>::block_number() < sub.paid_until)
+        }
+
+        /// Pay the owner of `resource_id` the price in `terms` from `who`.
+        fn pay_owner(
+            who: &T::AccountId,
+            resource_id: u32,
+            terms: &PassTerms<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let owner = T::Access::owner(resource_id).ok_or(Error::<T>::NoResourceOwner)?;
+            <T::Assets as fungibles::Mutate<T::AccountId>>::transfer(
+                terms.token_id,
+                who,
+                &owner,
+                terms.price.saturated_into(),
+                Preservation::Expendable,
+            )?;
+            Ok(())
+        }
+
+        /// Renew or lapse every subscription queued for `now`. Returns the weight
+        /// consumed.
+        fn process_renewals(now: BlockNumberFor<T>) -> Weight {
+            let db = T::DbWeight::get();
+            let queue = RenewalQueue::<T>::take(now);
+            // Taking the queue, then per entry: the subscription, terms and owner,
+            // both asset accounts, and queueing the next renewal.
+            let per_entry = db.reads_writes(5, 4);
+            let weight = db
+                .reads_writes(1, 1)
+                .saturating_add(per_entry.saturating_mul(queue.len() as u64));
+
+            for (who, resource_id) in queue {
+                let Some(sub) = Subscriptions::<T>::get(&who, resource_id) else {
+                    continue;
+                };
+                // Only the entry queued for the subscription's current period counts.
+                if sub.paid_until != now {
+                    continue;
+                }
+                match Self::try_renew(&who, resource_id, now, sub.auto_renew) {
+                    Some(paid_until) => Self::deposit_event(
+                        Self::resource_topic(resource_id),
+                        Event::SubscriptionRenewed {
+                            who,
+                            resource_id,
+                            paid_until,
+                        },
+                    ),
+                    None => {
+                        Subscriptions::<T>::remove(&who, resource_id);
+                        Self::deposit_event(
+                            Self::resource_topic(resource_id),
+                            Event::SubscriptionLapsed { who, resource_id },
+                        );
+                    }
+                }
+            }
+            weight
+        }
+
+        /// Charge `who` another period of its subscription to `resource_id`, ending
+        /// at `now`. Returns the new `paid_until`, or `None` if it lapses.
+        fn try_renew(
+            who: &T::AccountId,
+            resource_id: u32,
+            now: BlockNumberFor<T>,
+            auto_renew: bool,
+        ) -> Option<BlockNumberFor<T>> {
+            if !auto_renew {
+                return None;
+            }
+            let terms = SubscriptionTermsOf::<T>::get(resource_id)?;
+            let paid_until = now.saturating_add(terms.duration);
+            with_storage_layer(|| {
+                RenewalQueue::<T>::try_mutate(paid_until, |queue| {
+                    queue.try_push((who.clone(), resource_id))
+                })
+                .map_err(|_| Error::<T>::TooManyRenewals)?;
+                Self::pay_owner(who, resource_id, &terms)
+            })
+            .ok()?;
+            Subscriptions::<T>::insert(
+                who,
+                resource_id,
+                Subscription {
+                    paid_until,
+                    auto_renew,
+                },
+            );
+            Some(paid_until)
+        }
+
+        /// Whether `who` holds an access pass to `resource_id` that has not expired.
+        pub fn has_valid_pass(who: &T::AccountId, resource_id: u32) -> bool {
+            AccessPasses::<T>::get(who, resource_id)
+                .is_some_and(|expires_at| frame_system::Pallet::<T>::block_number() < expires_at)
+        }
+
+        /// Balance of `token_id` held by `who` in `Assets`.
+        pub fn balance_of(who: &T::AccountId, token_id: u32) -> u128 {
+            <T::Assets as fungibles::Inspect<T::AccountId>>::balance(token_id, who).saturated_into()
+        }
+
+        /// Whether holding `balance` of `token_id`, and no other token, grants
+        /// access to `resource_id`.
+        ///
+        /// Ungated resources are open to everyone. While a grace period runs,
+        /// either the current or the previous gate is accepted.
+        pub fn balance_grants_access(resource_id: u32, token_id: u32, balance: u128) -> bool {
+            Self::grants_access(resource_id, |id| if id == token_id { balance } else { 0 })
+        }
+
+        /// Whether an account holding `balance_of(token_id)` of each token may
+        /// access `resource_id`.
+        fn grants_access(resource_id: u32, balance_of: impl Fn(u32) -> u128) -> bool {
+            let Some(gate) = Gates::<T>::get(resource_id) else {
+                return true;
+            };
+            let now = frame_system::Pallet::<T>::block_number();
+            if Self::is_expired(resource_id, now) || gate.is_met_with(&balance_of) {
+                return true;
+            }
+            GracePeriods::<T>::get(resource_id).is_some_and(|grace| {
+                now < grace.expires_at && grace.previous.is_met_with(&balance_of)
+            })
+        }
+
+        /// Whether the gate of `resource_id` has reached its expiry at `now`.
+        fn is_expired(resource_id: u32, now: BlockNumberFor<T>) -> bool {
+            GateExpiry::<T>::get(resource_id).is_some_and(|at| now >= at)
+        }
+
+        /// Remove the existing gate of `resource_id` on behalf of `by`.
+        pub(crate) fn do_remove_gate(resource_id: u32, by: Option<T::AccountId>) {
+            Self::clear_gate(resource_id);
+            Self::deposit_event(
+                Self::resource_topic(resource_id),
+                Event::GateRemoved { resource_id, by },
+            );
+        }
+
+        /// Remove the gate of `resource_id` with its grace period, expiry and setter.
+        fn clear_gate(resource_id: u32) {
+            Gates::<T>::remove(resource_id);
+            GracePeriods::<T>::remove(resource_id);
+            GateExpiry::<T>::remove(resource_id);
+            GateSetBy::<T>::remove(resource_id);
+        }
+
+        /// Scan `GateExpiry` for gates expired at `now`, within `limit` weight.
+        ///
+        /// The scan resumes where the previous call stopped, so every expiring gate
+        /// is eventually visited even when only a few fit in one block. Returns the
+        /// weight consumed.
+        fn expire_gates(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            // Taking and storing the cursor.
+            let mut used = db.reads_writes(1, 1);
+            // Visiting an entry, and removing its gate, grace period, expiry and setter.
+            let visit = db.reads(1);
+            let expire = db.writes(4);
+            let worst_case = visit.saturating_add(expire);
+            if used.any_gt(limit) {
+                return Weight::zero();
+            }
+
+            let mut iter = match ExpiryCursor::<T>::take() {
+                Some(cursor) => GateExpiry::<T>::iter_from(cursor.into_inner()),
+                None => GateExpiry::<T>::iter(),
+            };
+            let mut expired = sp_std::vec::Vec::new();
+            let mut exhausted = false;
+            while used.saturating_add(worst_case).all_lte(limit) {
+                let Some((resource_id, at)) = iter.next() else {
+                    exhausted = true;
+                    break;
+                };
+                used.saturating_accrue(visit);
+                if now >= at {
+                    used.saturating_accrue(expire);
+                    expired.push(resource_id);
+                }
+            }
+            if !exhausted {
+                if let Ok(cursor) = BoundedVec::try_from(iter.last_raw_key().to_vec()) {
+                    ExpiryCursor::<T>::put(cursor);
+                }
+            }
+
+            for resource_id in expired {
+                Self::clear_gate(resource_id);
+                Self::deposit_event(
+                    Self::resource_topic(resource_id),
+                    Event::GateExpired { resource_id },
+                );
+            }
+            used
+        }
+
+        /// Ensure the origin is either signed or root. Returns `Ok(Some(account))`
+        /// for signed origins and `Ok(None)` for root.
+        fn ensure_signed_or_root(
+            origin: OriginFor<T>,
+        ) -> Result<Option<T::AccountId>, DispatchError> {
+            if ensure_root(origin.clone()).is_ok() {
+                return Ok(None);
+            }
+            Ok(Some(ensure_signed(origin)?))
+        }
+
+        /// Ensure `by` (`None` for root) may manage the gate of `resource_id`.
+        ///
+        /// Only root and the owner manage the gate of an owned resource. A
+        /// resource without an owner may be gated by anyone, and its gate is then
+        /// managed by root and the account that set it.
+        pub(crate) fn ensure_gate_manager(
+            by: Option<&T::AccountId>,
+            resource_id: u32,
+        ) -> DispatchResult {
+            let Some(who) = by else {
+                return Ok(());
+            };
+            let allowed = match T::Access::owner(resource_id) {
+                Some(owner) => &owner == who,
+                None => GateSetBy::<T>::get(resource_id)
+                    .map_or(!Gates::<T>::contains_key(resource_id), |setter| {
+                        setter.by.as_ref() == Some(who)
+                    }),
+            };
+            ensure!(allowed, Error::<T>::NotGateManager);
+            Ok(())
+        }
+
+        /// Ensure the origin is root or signed by the owner of `resource_id`.
+        fn ensure_owner_or_root(origin: OriginFor<T>, resource_id: u32) -> DispatchResult {
+            if ensure_root(origin.clone()).is_ok() {
+                return Ok(());
+            }
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::Access::owner(resource_id).as_ref() == Some(&who),
+                Error::<T>::NotResourceOwner
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+    use frame_support::{
+        assert_noop, assert_ok, derive_impl,
+        traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, Contains, Hooks},
+        weights::Weight,
+    };
+    use frame_system::{EnsureRoot, EnsureSigned};
+    use sp_core::H256;
+    use sp_io::TestExternalities;
+    use sp_runtime::{
+        traits::{BlakeTwo256, Hash, IdentityLookup},
+        BoundedVec, BuildStorage,
+    };
+
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    frame_support::construct_runtime!(
+        pub enum Test {
+            System: frame_system,
+            Balances: pallet_balances,
+            Assets: pallet_assets,
+            TokenGate: pallet,
+        }
+    );
+
+    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+    impl frame_system::Config for Test {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = ();
+        type BlockLength = ();
+        type DbWeight = ();
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Nonce = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Block = Block;
+        type RuntimeEvent = RuntimeEvent;
+        type BlockHashCount = ConstU64<250>;
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type AccountData = pallet_balances::AccountData<u64>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+        type SS58Prefix = ();
+        type OnSetCode = ();
+        type MaxConsumers = ConstU32<16>;
+    }
+
+    #[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+    impl pallet_balances::Config for Test {
+        type AccountStore = System;
+    }
+
+    #[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+    impl pallet_assets::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type Currency = Balances;
+        type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<u64>>;
+        type ForceOrigin = EnsureRoot<u64>;
+        type Freezer = ();
+    }
+
+    impl pallet::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type GracePeriod = ConstU64<10>;
+        type MaxRequirements = ConstU32<4>;
+        type Assets = Assets;
+        type Access = MockAccess;
+        type MaxRenewalsPerBlock = ConstU32<2>;
+        type WeightInfo = ();
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper = MockAssets;
+    }
+
+    /// Creates tokens as sufficient assets with a minimum balance of one.
+    #[cfg(feature = "runtime-benchmarks")]
+    pub struct MockAssets;
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl crate::BenchmarkHelper for MockAssets {
+        fn create_token(token_id: u32) {
+            if !<Assets as frame_support::traits::fungibles::Inspect<u64>>::asset_exists(token_id) {
+                Assets::force_create(RuntimeOrigin::root(), token_id, OWNER, true, 1)
+                    .expect("root can create assets");
+            }
+        }
+    }
+
+    /// Account that owns every resource in tests.
+    const OWNER: u64 = 9;
+
+    /// Reports [`OWNER`] as the owner of resources below 100 and grants no roles.
+    pub struct MockAccess;
+
+    impl pallet_access_control::ResourceAccess<u64> for MockAccess {
+        fn has_role(_who: &u64, _resource_id: u32, _min_role: pallet_access_control::Role) -> bool {
+            false
+        }
+
+        fn owner(resource_id: u32) -> Option<u64> {
+            (resource_id < 100).then_some(OWNER)
+        }
+    }
+
+    /// Token that tests gate resources on.
+    const TOKEN: u32 = 7;
+
+    /// Collection whose members hold one badge each.
+    const BADGE: u32 = 8;
+
+    /// Build a test externalities instance with default genesis state.
+    ///
+    /// Account 1 holds 500 [`TOKEN`], account 2 holds 100 [`TOKEN`] and a
+    /// [`BADGE`], and account 3 holds only a [`BADGE`].
+    pub(crate) fn new_test_ext() -> TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis build should succeed in tests");
+        pallet_assets::GenesisConfig::<Test> {
+            assets: vec![(TOKEN, 1, true, 1), (BADGE, 1, true, 1)],
+            metadata: vec![],
+            accounts: vec![
+                (TOKEN, 1, 500),
+                (TOKEN, 2, 100),
+                (BADGE, 2, 1),
+                (BADGE, 3, 1),
+            ],
+            next_asset_id: None,
+        }
+        .assimilate_storage(&mut t)
+        .expect("assets genesis should succeed in tests");
+        let mut ext = TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    #[test]
+    fn set_gate_works() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 1u32;
+            let requirement = pallet::GateRequirement {
+                token_id: 100,
+                min_balance: 500,
+            };
+
+            // The owner can set a gate.
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                requirement.clone(),
+            ));
+
+            // Verify storage.
+            let gate = pallet::Gate::single(requirement);
+            assert_eq!(pallet::Gates::<Test>::get(resource_id), Some(gate.clone()));
+
+            // Verify event.
+            System::assert_last_event(
+                pallet::Event::<Test>::GateSet {
+                    resource_id,
+                    gate,
+                    by: Some(OWNER),
+                }
                 .into(),
             );
+            assert_eq!(
+                pallet::GateSetBy::<Test>::get(resource_id),
+                Some(pallet::GateSetter {
+                    by: Some(OWNER),
+                    block: 1
+                })
+            );
+
+            // Root is recorded as `None`.
+            System::set_block_number(2);
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::root(),
+                resource_id,
+                pallet::GateRequirement {
+                    token_id: 100,
+                    min_balance: 100,
+                },
+            ));
+            assert_eq!(
+                pallet::GateSetBy::<Test>::get(resource_id),
+                Some(pallet::GateSetter { by: None, block: 2 })
+            );
         });
     }
 
@@ -229,7 +1379,7 @@ mod tests {
 
             // Set a gate first.
             assert_ok!(TokenGate::set_gate(
-                RuntimeOrigin::signed(1),
+                RuntimeOrigin::signed(OWNER),
                 resource_id,
                 requirement,
             ));
@@ -237,16 +1387,761 @@ mod tests {
 
             // Remove it.
             assert_ok!(TokenGate::remove_gate(
-                RuntimeOrigin::signed(1),
+                RuntimeOrigin::signed(OWNER),
                 resource_id,
             ));
             assert!(pallet::Gates::<Test>::get(resource_id).is_none());
+            assert!(pallet::GateSetBy::<Test>::get(resource_id).is_none());
+            System::assert_last_event(
+                pallet::Event::<Test>::GateRemoved {
+                    resource_id,
+                    by: Some(OWNER),
+                }
+                .into(),
+            );
 
             // Removing a non-existent gate should fail.
             assert_noop!(
-                TokenGate::remove_gate(RuntimeOrigin::signed(1), resource_id),
+                TokenGate::remove_gate(RuntimeOrigin::signed(OWNER), resource_id),
                 pallet::Error::<Test>::GateNotFound
             );
         });
     }
+
+    #[test]
+    fn gates_are_changed_only_by_their_setter_or_owner() {
+        new_test_ext().execute_with(|| {
+            let requirement = pallet::GateRequirement {
+                token_id: TOKEN,
+                min_balance: 1_000,
+            };
+            let looser = pallet::GateRequirement {
+                token_id: TOKEN,
+                min_balance: 1,
+            };
+
+            // A resource without an owner: only the setter and root manage its gate.
+            let resource_id = 200u32;
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(1),
+                resource_id,
+                requirement.clone(),
+            ));
+            assert_noop!(
+                TokenGate::set_gate(RuntimeOrigin::signed(2), resource_id, looser.clone()),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_noop!(
+                TokenGate::set_composite_gate(
+                    RuntimeOrigin::signed(2),
+                    resource_id,
+                    vec![looser.clone()].try_into().unwrap(),
+                    pallet::Combinator::Any,
+                ),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_noop!(
+                TokenGate::remove_gate(RuntimeOrigin::signed(2), resource_id),
+                pallet::Error::<Test>::NotGateManager
+            );
+            <TokenGate as ResourceGate<u64>>::remove_gate(resource_id, Some(2));
+            assert!(pallet::Gates::<Test>::get(resource_id).is_some());
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(1),
+                resource_id,
+                looser.clone(),
+            ));
+
+            // The owner can take over a gate someone else set on their resource.
+            let resource_id = 4u32;
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::root(),
+                resource_id,
+                requirement,
+            ));
+            assert_noop!(
+                <TokenGate as ResourceGate<u64>>::set_gate(resource_id, looser.clone(), Some(2)),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_ok!(<TokenGate as ResourceGate<u64>>::set_gate(
+                resource_id,
+                looser,
+                Some(OWNER)
+            ));
+            assert_ok!(TokenGate::remove_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id
+            ));
+        });
+    }
+
+    #[test]
+    fn third_parties_cannot_gate_owned_resources() {
+        new_test_ext().execute_with(|| {
+            // Resource 12 is owned, like a registered playlist, and has no gate.
+            let resource_id = 12u32;
+            let requirement = pallet::GateRequirement {
+                token_id: TOKEN,
+                min_balance: 1_000,
+            };
+            assert_noop!(
+                TokenGate::set_gate(RuntimeOrigin::signed(1), resource_id, requirement.clone()),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_noop!(
+                TokenGate::set_composite_gate(
+                    RuntimeOrigin::signed(1),
+                    resource_id,
+                    vec![requirement.clone()].try_into().unwrap(),
+                    pallet::Combinator::All,
+                ),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_noop!(
+                <TokenGate as ResourceGate<u64>>::set_gate(
+                    resource_id,
+                    requirement.clone(),
+                    Some(1)
+                ),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert!(pallet::Gates::<Test>::get(resource_id).is_none());
+
+            // Root and the owner still can.
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::root(),
+                resource_id,
+                requirement.clone(),
+            ));
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                requirement,
+            ));
+        });
+    }
+
+    #[test]
+    fn raising_requirement_starts_grace_period() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 3u32;
+            let old = pallet::GateRequirement {
+                token_id: 7,
+                min_balance: 100,
+            };
+            let new = pallet::GateRequirement {
+                token_id: 7,
+                min_balance: 500,
+            };
+
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                old.clone(),
+            ));
+            assert!(pallet::GracePeriods::<Test>::get(resource_id).is_none());
+
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                new.clone(),
+            ));
+            System::assert_has_event(
+                pallet::Event::<Test>::GraceStarted {
+                    resource_id,
+                    previous: pallet::Gate::single(old),
+                    expires_at: 11,
+                }
+                .into(),
+            );
+
+            // Holders of the old requirement keep access during the grace period.
+            assert!(TokenGate::balance_grants_access(resource_id, 7, 100));
+            assert!(TokenGate::balance_grants_access(resource_id, 7, 500));
+            assert!(!TokenGate::balance_grants_access(resource_id, 7, 99));
+
+            System::set_block_number(11);
+            assert!(!TokenGate::balance_grants_access(resource_id, 7, 100));
+            assert!(TokenGate::balance_grants_access(resource_id, 7, 500));
+        });
+    }
+
+    #[test]
+    fn relaxing_requirement_ends_grace_period() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 4u32;
+            let requirement = |min_balance| pallet::GateRequirement {
+                token_id: 7,
+                min_balance,
+            };
+
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                requirement(100),
+            ));
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                requirement(500),
+            ));
+            assert!(pallet::GracePeriods::<Test>::get(resource_id).is_some());
+
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                requirement(200),
+            ));
+            assert!(pallet::GracePeriods::<Test>::get(resource_id).is_none());
+            assert!(!TokenGate::balance_grants_access(resource_id, 7, 100));
+            assert!(TokenGate::balance_grants_access(resource_id, 7, 200));
+
+            // Ungated resources are open.
+            assert!(TokenGate::balance_grants_access(99, 7, 0));
+        });
+    }
+
+    #[test]
+    fn call_indices_are_stable() {
+        // Downstream runtimes and signed transactions depend on these indices;
+        // changing one is a breaking change.
+        assert_eq!(
+            pallet::Call::<Test>::set_gate {
+                resource_id: 1,
+                requirement: pallet::GateRequirement {
+                    token_id: 1,
+                    min_balance: 1
+                }
+            }
+            .encode()[0],
+            0
+        );
+        assert_eq!(
+            pallet::Call::<Test>::remove_gate { resource_id: 1 }.encode()[0],
+            1
+        );
+        assert_eq!(
+            pallet::Call::<Test>::verify_access { resource_id: 1 }.encode()[0],
+            2
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_composite_gate {
+                resource_id: 1,
+                requirements: Default::default(),
+                combinator: pallet::Combinator::Any,
+            }
+            .encode()[0],
+            3
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_pass_terms {
+                resource_id: 1,
+                terms: None
+            }
+            .encode()[0],
+            4
+        );
+        assert_eq!(
+            pallet::Call::<Test>::purchase_access { resource_id: 1 }.encode()[0],
+            5
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_gate_expiry {
+                resource_id: 1,
+                expires_at: None
+            }
+            .encode()[0],
+            6
+        );
+        assert_eq!(
+            pallet::Call::<Test>::set_subscription_terms {
+                resource_id: 1,
+                terms: None
+            }
+            .encode()[0],
+            7
+        );
+        assert_eq!(
+            pallet::Call::<Test>::subscribe { resource_id: 1 }.encode()[0],
+            8
+        );
+        assert_eq!(
+            pallet::Call::<Test>::unsubscribe { resource_id: 1 }.encode()[0],
+            9
+        );
+    }
+
+    /// Gate `resource_id` beyond every account's balance and offer subscriptions
+    /// at 30 [`TOKEN`] per 5 blocks.
+    fn setup_subscriptions(resource_id: u32) {
+        assert_ok!(TokenGate::set_gate(
+            RuntimeOrigin::signed(OWNER),
+            resource_id,
+            pallet::GateRequirement {
+                token_id: TOKEN,
+                min_balance: 1_000,
+            },
+        ));
+        assert_ok!(TokenGate::set_subscription_terms(
+            RuntimeOrigin::signed(OWNER),
+            resource_id,
+            Some(pallet::PassTerms {
+                token_id: TOKEN,
+                price: 30,
+                duration: 5,
+            }),
+        ));
+    }
+
+    /// Advance to block `n` and run `on_initialize` for it.
+    fn run_to_block(n: u64) {
+        System::set_block_number(n);
+        TokenGate::on_initialize(n);
+    }
+
+    #[test]
+    fn subscriptions_renew_until_they_cannot_pay() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 9u32;
+            assert_noop!(
+                TokenGate::subscribe(RuntimeOrigin::signed(2), resource_id),
+                pallet::Error::<Test>::SubscriptionNotForSale
+            );
+            setup_subscriptions(resource_id);
+
+            assert_ok!(TokenGate::subscribe(RuntimeOrigin::signed(2), resource_id));
+            assert_noop!(
+                TokenGate::subscribe(RuntimeOrigin::signed(2), resource_id),
+                pallet::Error::<Test>::AlreadySubscribed
+            );
+            assert_eq!(TokenGate::balance_of(&2, TOKEN), 70);
+            assert!(TokenGate::check_access(&2, resource_id));
+
+            run_to_block(6);
+            System::assert_last_event(
+                pallet::Event::<Test>::SubscriptionRenewed {
+                    who: 2,
+                    resource_id,
+                    paid_until: 11,
+                }
+                .into(),
+            );
+            run_to_block(11);
+            assert_eq!(TokenGate::balance_of(&2, TOKEN), 10);
+            assert_eq!(TokenGate::balance_of(&OWNER, TOKEN), 90);
+            assert!(TokenGate::check_access(&2, resource_id));
+
+            // 10 TOKEN cannot pay for another period.
+            run_to_block(16);
+            System::assert_last_event(
+                pallet::Event::<Test>::SubscriptionLapsed {
+                    who: 2,
+                    resource_id,
+                }
+                .into(),
+            );
+            assert_eq!(TokenGate::balance_of(&2, TOKEN), 10);
+            assert!(pallet::Subscriptions::<Test>::get(2, resource_id).is_none());
+            assert!(!TokenGate::check_access(&2, resource_id));
+        });
+    }
+
+    #[test]
+    fn cancelled_subscriptions_lapse_at_the_end_of_the_paid_period() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 10u32;
+            setup_subscriptions(resource_id);
+            assert_noop!(
+                TokenGate::unsubscribe(RuntimeOrigin::signed(1), resource_id),
+                pallet::Error::<Test>::NotSubscribed
+            );
+
+            assert_ok!(TokenGate::subscribe(RuntimeOrigin::signed(1), resource_id));
+            assert_ok!(TokenGate::unsubscribe(
+                RuntimeOrigin::signed(1),
+                resource_id
+            ));
+            assert!(TokenGate::check_access(&1, resource_id));
+
+            // Resubscribing within the paid period does not charge again.
+            assert_ok!(TokenGate::subscribe(RuntimeOrigin::signed(1), resource_id));
+            assert_ok!(TokenGate::unsubscribe(
+                RuntimeOrigin::signed(1),
+                resource_id
+            ));
+            assert_eq!(TokenGate::balance_of(&1, TOKEN), 470);
+
+            run_to_block(6);
+            System::assert_last_event(
+                pallet::Event::<Test>::SubscriptionLapsed {
+                    who: 1,
+                    resource_id,
+                }
+                .into(),
+            );
+            assert_eq!(TokenGate::balance_of(&1, TOKEN), 470);
+            assert!(!TokenGate::check_access(&1, resource_id));
+
+            // The renewal queue is bounded per block.
+            assert_ok!(TokenGate::subscribe(RuntimeOrigin::signed(1), resource_id));
+            assert_ok!(TokenGate::subscribe(RuntimeOrigin::signed(2), resource_id));
+            assert_noop!(
+                TokenGate::subscribe(RuntimeOrigin::signed(OWNER), resource_id),
+                pallet::Error::<Test>::TooManyRenewals
+            );
+        });
+    }
+
+    #[test]
+    fn expired_gates_open_the_resource_and_are_removed() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 8u32;
+            assert_noop!(
+                TokenGate::set_gate_expiry(RuntimeOrigin::signed(OWNER), resource_id, Some(5)),
+                pallet::Error::<Test>::GateNotFound
+            );
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                pallet::GateRequirement {
+                    token_id: TOKEN,
+                    min_balance: 1_000,
+                },
+            ));
+            assert_noop!(
+                TokenGate::set_gate_expiry(RuntimeOrigin::signed(OWNER), resource_id, Some(1)),
+                pallet::Error::<Test>::ExpiryInPast
+            );
+            // Nobody else can schedule someone's gate to lift.
+            assert_noop!(
+                TokenGate::set_gate_expiry(RuntimeOrigin::signed(2), resource_id, Some(5)),
+                pallet::Error::<Test>::NotGateManager
+            );
+            assert_ok!(TokenGate::set_gate_expiry(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                None
+            ));
+            assert_ok!(TokenGate::set_gate_expiry(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                Some(5)
+            ));
+
+            System::set_block_number(4);
+            TokenGate::on_idle(4, Weight::MAX);
+            assert!(!TokenGate::check_access(&2, resource_id));
+            assert!(pallet::Gates::<Test>::get(resource_id).is_some());
+
+            // The resource opens at the expiry block, before the gate is removed.
+            System::set_block_number(5);
+            assert!(TokenGate::check_access(&2, resource_id));
+            TokenGate::on_idle(5, Weight::MAX);
+            System::assert_last_event(pallet::Event::<Test>::GateExpired { resource_id }.into());
+            assert!(pallet::Gates::<Test>::get(resource_id).is_none());
+            assert!(pallet::GateExpiry::<Test>::get(resource_id).is_none());
+            assert!(pallet::ExpiryCursor::<Test>::get().is_none());
+        });
+    }
+
+    #[test]
+    fn access_passes_are_sold_by_the_owner() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 7u32;
+            let terms = pallet::PassTerms {
+                token_id: TOKEN,
+                price: 40,
+                duration: 5,
+            };
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                pallet::GateRequirement {
+                    token_id: TOKEN,
+                    min_balance: 1_000,
+                },
+            ));
+            assert_noop!(
+                TokenGate::purchase_access(RuntimeOrigin::signed(2), resource_id),
+                pallet::Error::<Test>::PassNotForSale
+            );
+
+            assert_noop!(
+                TokenGate::set_pass_terms(
+                    RuntimeOrigin::signed(1),
+                    resource_id,
+                    Some(terms.clone())
+                ),
+                pallet::Error::<Test>::NotResourceOwner
+            );
+            assert_noop!(
+                TokenGate::set_pass_terms(
+                    RuntimeOrigin::signed(OWNER),
+                    resource_id,
+                    Some(pallet::PassTerms {
+                        duration: 0,
+                        ..terms.clone()
+                    })
+                ),
+                pallet::Error::<Test>::ZeroPassDuration
+            );
+            assert_ok!(TokenGate::set_pass_terms(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                Some(terms.clone())
+            ));
+
+            assert!(!TokenGate::check_access(&2, resource_id));
+            assert_ok!(TokenGate::purchase_access(
+                RuntimeOrigin::signed(2),
+                resource_id
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::AccessPurchased {
+                    who: 2,
+                    resource_id,
+                    expires_at: 6,
+                }
+                .into(),
+            );
+            assert_eq!(TokenGate::balance_of(&2, TOKEN), 60);
+            assert_eq!(TokenGate::balance_of(&OWNER, TOKEN), 40);
+            assert!(TokenGate::has_valid_pass(&2, resource_id));
+            assert!(TokenGate::check_access(&2, resource_id));
+
+            // Buying again extends the running pass.
+            System::set_block_number(3);
+            assert_ok!(TokenGate::purchase_access(
+                RuntimeOrigin::signed(2),
+                resource_id
+            ));
+            assert_eq!(pallet::AccessPasses::<Test>::get(2, resource_id), Some(11));
+
+            System::set_block_number(11);
+            assert!(!TokenGate::has_valid_pass(&2, resource_id));
+            assert!(!TokenGate::check_access(&2, resource_id));
+
+            // Unowned resources cannot sell passes.
+            assert_ok!(TokenGate::set_pass_terms(
+                RuntimeOrigin::root(),
+                100,
+                Some(terms)
+            ));
+            assert_noop!(
+                TokenGate::purchase_access(RuntimeOrigin::signed(2), 100),
+                pallet::Error::<Test>::NoResourceOwner
+            );
+        });
+    }
+
+    /// Requirements on [`TOKEN`] and [`BADGE`], for composite gates.
+    fn token_and_badge(min_tokens: u128) -> BoundedVec<pallet::GateRequirement, ConstU32<4>> {
+        BoundedVec::truncate_from(vec![
+            pallet::GateRequirement {
+                token_id: TOKEN,
+                min_balance: min_tokens,
+            },
+            pallet::GateRequirement {
+                token_id: BADGE,
+                min_balance: 1,
+            },
+        ])
+    }
+
+    #[test]
+    fn composite_gates_combine_requirements() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 6u32;
+            assert_noop!(
+                TokenGate::set_composite_gate(
+                    RuntimeOrigin::signed(OWNER),
+                    resource_id,
+                    Default::default(),
+                    pallet::Combinator::Any,
+                ),
+                pallet::Error::<Test>::NoRequirements
+            );
+
+            assert_ok!(TokenGate::set_composite_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                token_and_badge(200),
+                pallet::Combinator::Any,
+            ));
+            assert!(TokenGate::check_access(&1, resource_id));
+            assert!(TokenGate::check_access(&2, resource_id));
+            assert!(TokenGate::check_access(&3, resource_id));
+            assert!(!TokenGate::check_access(&4, resource_id));
+
+            // Requiring both is stricter, so account 3 keeps access during the grace period.
+            assert_ok!(TokenGate::set_composite_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                token_and_badge(100),
+                pallet::Combinator::All,
+            ));
+            assert!(pallet::GracePeriods::<Test>::get(resource_id).is_some());
+            assert!(TokenGate::check_access(&3, resource_id));
+
+            System::set_block_number(11);
+            assert!(!TokenGate::check_access(&1, resource_id));
+            assert!(TokenGate::check_access(&2, resource_id));
+            assert!(!TokenGate::check_access(&3, resource_id));
+
+            // Going back to either requirement is a relaxation.
+            assert_ok!(TokenGate::set_composite_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                token_and_badge(100),
+                pallet::Combinator::Any,
+            ));
+            assert!(pallet::GracePeriods::<Test>::get(resource_id).is_none());
+        });
+    }
+
+    #[test]
+    fn access_follows_token_balances() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 5u32;
+            assert!(TokenGate::check_access(&3, resource_id));
+
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                resource_id,
+                pallet::GateRequirement {
+                    token_id: TOKEN,
+                    min_balance: 200,
+                },
+            ));
+            assert!(TokenGate::check_access(&1, resource_id));
+            assert!(!TokenGate::check_access(&2, resource_id));
+
+            assert_ok!(TokenGate::verify_access(
+                RuntimeOrigin::signed(2),
+                resource_id
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::AccessDenied {
+                    who: 2,
+                    resource_id,
+                }
+                .into(),
+            );
+
+            assert_ok!(Assets::transfer(RuntimeOrigin::signed(1), TOKEN, 2, 100));
+            assert_ok!(TokenGate::verify_access(
+                RuntimeOrigin::signed(2),
+                resource_id
+            ));
+            System::assert_last_event(
+                pallet::Event::<Test>::AccessVerified {
+                    who: 2,
+                    resource_id,
+                }
+                .into(),
+            );
+        });
+    }
+
+    #[test]
+    fn resource_gate_sets_and_removes_gates() {
+        new_test_ext().execute_with(|| {
+            let resource_id = 11u32;
+            assert_ok!(<TokenGate as ResourceGate<u64>>::set_gate(
+                resource_id,
+                pallet::GateRequirement {
+                    token_id: TOKEN,
+                    min_balance: 200,
+                },
+                Some(OWNER),
+            ));
+            assert!(<TokenGate as ResourceGate<u64>>::check_access(
+                &1,
+                resource_id
+            ));
+            assert!(!<TokenGate as ResourceGate<u64>>::check_access(
+                &2,
+                resource_id
+            ));
+            assert_eq!(
+                pallet::GateSetBy::<Test>::get(resource_id).map(|s| s.by),
+                Some(Some(OWNER))
+            );
+
+            <TokenGate as ResourceGate<u64>>::remove_gate(resource_id, Some(OWNER));
+            System::assert_last_event(
+                pallet::Event::<Test>::GateRemoved {
+                    resource_id,
+                    by: Some(OWNER),
+                }
+                .into(),
+            );
+            assert!(<TokenGate as ResourceGate<u64>>::check_access(
+                &2,
+                resource_id
+            ));
+
+            // Removing a missing gate is a no-op.
+            let events = System::events().len();
+            <TokenGate as ResourceGate<u64>>::remove_gate(resource_id, None);
+            assert_eq!(System::events().len(), events);
+        });
+    }
+
+    #[test]
+    fn safe_call_filter_blocks_destructive_calls() {
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::remove_gate { resource_id: 1 }
+        ));
+        assert!(!SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::set_gate_expiry {
+                resource_id: 1,
+                expires_at: Some(5),
+            }
+        ));
+        assert!(SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::set_gate_expiry {
+                resource_id: 1,
+                expires_at: None,
+            }
+        ));
+        assert!(SafeCallFilter::<Test>::contains(
+            &pallet::Call::<Test>::set_gate {
+                resource_id: 1,
+                requirement: pallet::GateRequirement {
+                    token_id: 1,
+                    min_balance: 1,
+                },
+            }
+        ));
+    }
+
+    #[test]
+    fn events_are_indexed_by_resource() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(TokenGate::set_gate(
+                RuntimeOrigin::signed(OWNER),
+                42u32,
+                pallet::GateRequirement {
+                    token_id: 1,
+                    min_balance: 1,
+                },
+            ));
+
+            // The topic scheme is shared with the access-control pallet; keep it stable.
+            let topic = BlakeTwo256::hash_of(&(b"iptv/resource".as_slice(), 42u32));
+            assert_eq!(TokenGate::resource_topic(42), topic);
+            assert_eq!(
+                System::events().last().expect("event deposited").topics,
+                vec![topic]
+            );
+            assert_eq!(
+                System::events()
+                    .iter()
+                    .filter(|r| r.topics.contains(&topic))
+                    .count(),
+                1
+            );
+        });
+    }
 }
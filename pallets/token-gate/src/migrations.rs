@@ -0,0 +1,119 @@
+//! Storage migrations for the token gate pallet.
+//!
+//! Runtimes list them in their `Executive` migrations when upgrading from a
+//! release that stored the previous layout:
+//!
+//! ```ignore
+//! pub type Migrations = (pallet_token_gate::migrations::v1::MigrateV0ToV1<Runtime>,);
+//! ```
+
+use super::*;
+use frame_support::{
+    migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::Saturating;
+
+/// Version 1 stores a composite [`Gate`] per resource, where version 0 stored a
+/// single [`GateRequirement`].
+pub mod v1 {
+    use super::*;
+
+    /// The version 0 layout.
+    mod v0 {
+        use super::*;
+
+        /// A grace period as stored by version 0.
+        #[derive(Encode, Decode)]
+        pub struct GracePeriodInfo<BlockNumber> {
+            pub previous: GateRequirement,
+            pub expires_at: BlockNumber,
+        }
+
+        #[frame_support::storage_alias]
+        pub type Gates<T: Config> = StorageMap<Pallet<T>, Blake2_128Concat, u32, GateRequirement>;
+
+        #[frame_support::storage_alias]
+        pub type GracePeriods<T: Config> =
+            StorageMap<Pallet<T>, Blake2_128Concat, u32, GracePeriodInfo<BlockNumberFor<T>>>;
+    }
+
+    /// Turn every stored requirement, including those of running grace periods,
+    /// into a gate with that single requirement.
+    pub struct InnerMigrateV0ToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            Gates::<T>::translate::<GateRequirement, _>(|_, requirement| {
+                translated.saturating_inc();
+                Some(Gate::single(requirement))
+            });
+            GracePeriods::<T>::translate::<v0::GracePeriodInfo<BlockNumberFor<T>>, _>(
+                |_, grace| {
+                    translated.saturating_inc();
+                    Some(GracePeriodInfo {
+                        previous: Gate::single(grace.previous),
+                        expires_at: grace.expires_at,
+                    })
+                },
+            );
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+    }
+
+    /// [`InnerMigrateV0ToV1`], run only while the on-chain storage version is 0,
+    /// which it then sets to 1.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::tests::{new_test_ext, Test, TokenGate};
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+        #[test]
+        fn gates_become_single_requirement_gates() {
+            new_test_ext().execute_with(|| {
+                StorageVersion::new(0).put::<TokenGate>();
+                let requirement = GateRequirement {
+                    token_id: 7,
+                    min_balance: 1_000,
+                };
+                v0::Gates::<Test>::insert(1, requirement.clone());
+                v0::GracePeriods::<Test>::insert(
+                    1,
+                    v0::GracePeriodInfo {
+                        previous: requirement.clone(),
+                        expires_at: 5u64,
+                    },
+                );
+
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+                assert_eq!(
+                    Gates::<Test>::get(1),
+                    Some(Gate::single(requirement.clone()))
+                );
+                assert_eq!(
+                    GracePeriods::<Test>::get(1),
+                    Some(GracePeriodInfo {
+                        previous: Gate::single(requirement.clone()),
+                        expires_at: 5,
+                    })
+                );
+                assert_eq!(TokenGate::on_chain_storage_version(), 1);
+
+                // Running it again leaves the new values alone.
+                MigrateV0ToV1::<Test>::on_runtime_upgrade();
+                assert_eq!(Gates::<Test>::get(1), Some(Gate::single(requirement)));
+            });
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Weights for `pallet_token_gate`.
+//!
+//! These defaults are estimated from the storage each call touches. Runtimes should
+//! replace them with weights measured from `benchmarking.rs` on their own hardware:
+//!
+//! ```text
+//! frame-omni-bencher v1 benchmark pallet \
+//!     --runtime <runtime.wasm> \
+//!     --pallet pallet_token_gate \
+//!     --extrinsic "*" \
+//!     --output pallets/token-gate/src/weights.rs
+//! ```
+//!
+//! Components: `n` is the number of requirements in a gate.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use core::marker::PhantomData;
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for `pallet_token_gate`.
+pub trait WeightInfo {
+    fn set_gate() -> Weight;
+    fn remove_gate() -> Weight;
+    fn verify_access(n: u32) -> Weight;
+    fn set_composite_gate(n: u32) -> Weight;
+    fn set_gate_expiry() -> Weight;
+    fn set_pass_terms() -> Weight;
+    fn purchase_access() -> Weight;
+    fn set_subscription_terms() -> Weight;
+    fn subscribe() -> Weight;
+    fn unsubscribe() -> Weight;
+}
+
+/// Weights for `pallet_token_gate` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Gates` (r:1 w:1)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(182), added: 2657, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:0 w:1)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(186), added: 2661, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateSetBy` (r:0 w:1)
+    /// Proof: `TokenGate::GateSetBy` (`max_values`: None, `max_size`: Some(57), added: 2532, mode: `MaxEncodedLen`)
+    fn set_gate() -> Weight {
+        Weight::from_parts(24_310_000, 3651)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    /// Storage: `TokenGate::Gates` (r:1 w:1)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(182), added: 2657, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:0 w:1)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(186), added: 2661, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateExpiry` (r:0 w:1)
+    /// Proof: `TokenGate::GateExpiry` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateSetBy` (r:0 w:1)
+    /// Proof: `TokenGate::GateSetBy` (`max_values`: None, `max_size`: Some(57), added: 2532, mode: `MaxEncodedLen`)
+    fn remove_gate() -> Weight {
+        Weight::from_parts(21_870_000, 3651)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(4_u64))
+    }
+    /// Storage: `TokenGate::AccessPasses` (r:1 w:0)
+    /// Proof: `TokenGate::AccessPasses` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Subscriptions` (r:1 w:0)
+    /// Proof: `TokenGate::Subscriptions` (`max_values`: None, `max_size`: Some(73), added: 2548, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Gates` (r:1 w:0)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(182), added: 2657, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateExpiry` (r:1 w:0)
+    /// Proof: `TokenGate::GateExpiry` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:1 w:0)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(186), added: 2661, mode: `MaxEncodedLen`)
+    /// Storage: `Assets::Account` (r:8 w:0)
+    /// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxRequirements]`.
+    fn verify_access(n: u32) -> Weight {
+        Weight::from_parts(19_540_000, 3651)
+            .saturating_add(Weight::from_parts(3_270_000, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(5_u64))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2609).saturating_mul(n.into()))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::Gates` (r:1 w:1)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(182), added: 2657, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GracePeriods` (r:0 w:1)
+    /// Proof: `TokenGate::GracePeriods` (`max_values`: None, `max_size`: Some(186), added: 2661, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateSetBy` (r:0 w:1)
+    /// Proof: `TokenGate::GateSetBy` (`max_values`: None, `max_size`: Some(57), added: 2532, mode: `MaxEncodedLen`)
+    /// The range of component `n` is `[1, MaxRequirements]`.
+    fn set_composite_gate(n: u32) -> Weight {
+        Weight::from_parts(22_960_000, 3651)
+            .saturating_add(Weight::from_parts(410_000, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    /// Storage: `TokenGate::Gates` (r:1 w:0)
+    /// Proof: `TokenGate::Gates` (`max_values`: None, `max_size`: Some(182), added: 2657, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::GateExpiry` (r:0 w:1)
+    /// Proof: `TokenGate::GateExpiry` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+    fn set_gate_expiry() -> Weight {
+        Weight::from_parts(17_250_000, 3647)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::PassTermsOf` (r:0 w:1)
+    /// Proof: `TokenGate::PassTermsOf` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+    fn set_pass_terms() -> Weight {
+        Weight::from_parts(14_380_000, 3517)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `TokenGate::PassTermsOf` (r:1 w:0)
+    /// Proof: `TokenGate::PassTermsOf` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `Assets::Asset` (r:1 w:1)
+    /// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+    /// Storage: `Assets::Account` (r:2 w:2)
+    /// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::AccessPasses` (r:1 w:1)
+    /// Proof: `TokenGate::AccessPasses` (`max_values`: None, `max_size`: Some(72), added: 2547, mode: `MaxEncodedLen`)
+    fn purchase_access() -> Weight {
+        Weight::from_parts(52_640_000, 3675)
+            .saturating_add(T::DbWeight::get().reads(7_u64))
+            .saturating_add(T::DbWeight::get().writes(5_u64))
+    }
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::SubscriptionTermsOf` (r:0 w:1)
+    /// Proof: `TokenGate::SubscriptionTermsOf` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+    fn set_subscription_terms() -> Weight {
+        Weight::from_parts(14_510_000, 3517)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    /// Storage: `TokenGate::Subscriptions` (r:1 w:1)
+    /// Proof: `TokenGate::Subscriptions` (`max_values`: None, `max_size`: Some(73), added: 2548, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::SubscriptionTermsOf` (r:1 w:0)
+    /// Proof: `TokenGate::SubscriptionTermsOf` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+    /// Storage: `TokenGate::RenewalQueue` (r:1 w:1)
+    /// Proof: `TokenGate::RenewalQueue` (`max_values`: None, `max_size`: Some(1813), added: 4288, mode: `MaxEncodedLen`)
+    /// Storage: `AccessControl::Owners` (r:1 w:0)
+    /// Proof: `AccessControl::Owners` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+    /// Storage: `Assets::Asset` (r:1 w:1)
+    /// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+    /// Storage: `Assets::Account` (r:2 w:2)
+    /// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+    fn subscribe() -> Weight {
+        Weight::from_parts(61_930_000, 5278)
+            .saturating_add(T::DbWeight::get().reads(8_u64))
+            .saturating_add(T::DbWeight::get().writes(6_u64))
+    }
+    /// Storage: `TokenGate::Subscriptions` (r:1 w:1)
+    /// Proof: `TokenGate::Subscriptions` (`max_values`: None, `max_size`: Some(73), added: 2548, mode: `MaxEncodedLen`)
+    fn unsubscribe() -> Weight {
+        Weight::from_parts(15_720_000, 3538)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+    fn set_gate() -> Weight {
+        Weight::from_parts(24_310_000, 3651)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn remove_gate() -> Weight {
+        Weight::from_parts(21_870_000, 3651)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(4_u64))
+    }
+    fn verify_access(n: u32) -> Weight {
+        Weight::from_parts(19_540_000, 3651)
+            .saturating_add(Weight::from_parts(3_270_000, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(5_u64))
+            .saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 2609).saturating_mul(n.into()))
+    }
+    fn set_composite_gate(n: u32) -> Weight {
+        Weight::from_parts(22_960_000, 3651)
+            .saturating_add(Weight::from_parts(410_000, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn set_gate_expiry() -> Weight {
+        Weight::from_parts(17_250_000, 3647)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn set_pass_terms() -> Weight {
+        Weight::from_parts(14_380_000, 3517)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn purchase_access() -> Weight {
+        Weight::from_parts(52_640_000, 3675)
+            .saturating_add(RocksDbWeight::get().reads(7_u64))
+            .saturating_add(RocksDbWeight::get().writes(5_u64))
+    }
+    fn set_subscription_terms() -> Weight {
+        Weight::from_parts(14_510_000, 3517)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn subscribe() -> Weight {
+        Weight::from_parts(61_930_000, 5278)
+            .saturating_add(RocksDbWeight::get().reads(8_u64))
+            .saturating_add(RocksDbWeight::get().writes(6_u64))
+    }
+    fn unsubscribe() -> Weight {
+        Weight::from_parts(15_720_000, 3538)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+}
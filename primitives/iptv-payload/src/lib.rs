@@ -0,0 +1,450 @@
+//! # IPTV Payload
+//!
+//! Canonical definition of the compact on-chain playlist payload published via
+//! `system.remark_with_event` as `IPTV:{...}`, shared by the backend and the
+//! pallets so both sides agree on field names, limits, and encoding.
+//!
+//! The JSON form uses short keys, sorted alphabetically, with `None` fields
+//! omitted:
+//!
+//! ```text
+//! IPTV:{"c":[{"g":"News","l":"https://logo.png","n":"CNN","s":"https://cnn.m3u8"}],"n":"My Playlist","v":1}
+//! ```
+//!
+//! The same structure is also available as SCALE for storage in pallets.
+//!
+//! Playlists too large for a remark can be stored on IPFS instead, with only
+//! their CID published on-chain as `IPTV:ipfs://<cid>`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Prefix identifying an IPTV playlist remark.
+pub const REMARK_PREFIX: &str = "IPTV:";
+
+/// Scheme of a remark body naming a playlist stored on IPFS instead of
+/// carrying it.
+pub const IPFS_SCHEME: &str = "ipfs://";
+
+/// Current payload format version, written as the `v` field.
+pub const PAYLOAD_VERSION: u8 = 1;
+
+/// Maximum length (in bytes) of a playlist or channel name.
+pub const MAX_NAME_LEN: usize = 256;
+
+/// Maximum length (in bytes) of a channel group.
+pub const MAX_GROUP_LEN: usize = 128;
+
+/// Maximum length (in bytes) of a stream or logo URL.
+pub const MAX_URL_LEN: usize = 2048;
+
+/// Maximum number of channels in a single payload.
+pub const MAX_CHANNELS: usize = 10_000;
+
+/// Errors produced while validating or decoding a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadError {
+    /// The remark does not start with [`REMARK_PREFIX`].
+    MissingPrefix,
+    /// The payload declares a version newer than this crate understands.
+    UnsupportedVersion(u64),
+    /// The payload contains more than [`MAX_CHANNELS`] channels.
+    TooManyChannels,
+    /// A field exceeds its maximum length.
+    FieldTooLong(&'static str),
+    /// The JSON body is malformed or has the wrong shape.
+    InvalidJson,
+    /// The SCALE bytes could not be decoded.
+    InvalidScale,
+}
+
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => write!(f, "remark is missing the {REMARK_PREFIX} prefix"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported payload version {v}"),
+            Self::TooManyChannels => write!(f, "payload exceeds {MAX_CHANNELS} channels"),
+            Self::FieldTooLong(field) => write!(f, "field `{field}` exceeds its maximum length"),
+            Self::InvalidJson => write!(f, "payload is not valid JSON"),
+            Self::InvalidScale => write!(f, "payload is not valid SCALE"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PayloadError {}
+
+/// A single channel in the compact payload.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PayloadChannel {
+    /// Display name (`n`).
+    pub name: String,
+    /// Stream URL (`s`).
+    pub stream_url: String,
+    /// Optional group / category (`g`).
+    pub group: Option<String>,
+    /// Optional logo URL (`l`).
+    pub logo_url: Option<String>,
+}
+
+/// A playlist in the compact on-chain format.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PlaylistPayload {
+    /// Format version (`v`).
+    pub version: u8,
+    /// Playlist name (`n`).
+    pub name: String,
+    /// Channels (`c`).
+    pub channels: Vec<PayloadChannel>,
+}
+
+impl PlaylistPayload {
+    /// Create a payload at the current [`PAYLOAD_VERSION`].
+    pub fn new(name: String, channels: Vec<PayloadChannel>) -> Self {
+        Self {
+            version: PAYLOAD_VERSION,
+            name,
+            channels,
+        }
+    }
+
+    /// Check the payload against the format limits.
+    pub fn validate(&self) -> Result<(), PayloadError> {
+        if u64::from(self.version) > u64::from(PAYLOAD_VERSION) {
+            return Err(PayloadError::UnsupportedVersion(u64::from(self.version)));
+        }
+        if self.name.len() > MAX_NAME_LEN {
+            return Err(PayloadError::FieldTooLong("n"));
+        }
+        if self.channels.len() > MAX_CHANNELS {
+            return Err(PayloadError::TooManyChannels);
+        }
+        for ch in &self.channels {
+            if ch.name.len() > MAX_NAME_LEN {
+                return Err(PayloadError::FieldTooLong("c.n"));
+            }
+            if ch.stream_url.len() > MAX_URL_LEN {
+                return Err(PayloadError::FieldTooLong("c.s"));
+            }
+            if ch.group.as_ref().is_some_and(|g| g.len() > MAX_GROUP_LEN) {
+                return Err(PayloadError::FieldTooLong("c.g"));
+            }
+            if ch.logo_url.as_ref().is_some_and(|l| l.len() > MAX_URL_LEN) {
+                return Err(PayloadError::FieldTooLong("c.l"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialise to canonical JSON: sorted keys, no whitespace, `None` omitted.
+    ///
+    /// Two equal payloads always produce byte-identical output.
+    pub fn to_canonical_json(&self) -> String {
+        let mut out = String::with_capacity(32 + self.channels.len() * 96);
+        out.push_str("{\"c\":[");
+        for (i, ch) in self.channels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('{');
+            if let Some(ref g) = ch.group {
+                out.push_str("\"g\":");
+                push_json_string(&mut out, g);
+                out.push(',');
+            }
+            if let Some(ref l) = ch.logo_url {
+                out.push_str("\"l\":");
+                push_json_string(&mut out, l);
+                out.push(',');
+            }
+            out.push_str("\"n\":");
+            push_json_string(&mut out, &ch.name);
+            out.push_str(",\"s\":");
+            push_json_string(&mut out, &ch.stream_url);
+            out.push('}');
+        }
+        out.push_str("],\"n\":");
+        push_json_string(&mut out, &self.name);
+        out.push_str(",\"v\":");
+        push_u8(&mut out, self.version);
+        out.push('}');
+        out
+    }
+
+    /// Serialise to the `IPTV:{...}` remark text.
+    pub fn to_remark(&self) -> String {
+        let mut out = String::from(REMARK_PREFIX);
+        out.push_str(&self.to_canonical_json());
+        out
+    }
+
+    /// Serialise to SCALE bytes.
+    pub fn to_scale(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Decode and validate SCALE bytes.
+    pub fn from_scale(mut bytes: &[u8]) -> Result<Self, PayloadError> {
+        let payload = Self::decode(&mut bytes).map_err(|_| PayloadError::InvalidScale)?;
+        if !bytes.is_empty() {
+            return Err(PayloadError::InvalidScale);
+        }
+        payload.validate()?;
+        Ok(payload)
+    }
+
+    /// Parse a JSON payload (without the remark prefix).
+    ///
+    /// Parsing is tolerant of legacy payloads: a missing `v` is treated as
+    /// version 1, keys may appear in any order, `null` optional fields are
+    /// accepted, and channels missing a name or stream URL are skipped.
+    /// Size limits are always enforced.
+    #[cfg(feature = "std")]
+    pub fn from_json(json: &str) -> Result<Self, PayloadError> {
+        use serde_json::Value;
+
+        let value: Value = serde_json::from_str(json).map_err(|_| PayloadError::InvalidJson)?;
+        let obj = value.as_object().ok_or(PayloadError::InvalidJson)?;
+
+        let version = match obj.get("v") {
+            None | Some(Value::Null) => u64::from(PAYLOAD_VERSION),
+            Some(v) => v.as_u64().ok_or(PayloadError::InvalidJson)?,
+        };
+        let version = u8::try_from(version)
+            .ok()
+            .filter(|v| *v <= PAYLOAD_VERSION)
+            .ok_or(PayloadError::UnsupportedVersion(version))?;
+
+        let name = obj
+            .get("n")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let raw_channels = match obj.get("c") {
+            None | Some(Value::Null) => &[][..],
+            Some(c) => c.as_array().ok_or(PayloadError::InvalidJson)?.as_slice(),
+        };
+        if raw_channels.len() > MAX_CHANNELS {
+            return Err(PayloadError::TooManyChannels);
+        }
+
+        let optional =
+            |ch: &Value, key: &str| ch.get(key).and_then(Value::as_str).map(String::from);
+        let channels = raw_channels
+            .iter()
+            .filter_map(|ch| {
+                Some(PayloadChannel {
+                    name: ch.get("n").and_then(Value::as_str)?.to_string(),
+                    stream_url: ch.get("s").and_then(Value::as_str)?.to_string(),
+                    group: optional(ch, "g"),
+                    logo_url: optional(ch, "l"),
+                })
+            })
+            .collect();
+
+        let payload = Self {
+            version,
+            name,
+            channels,
+        };
+        payload.validate()?;
+        Ok(payload)
+    }
+
+    /// Parse an `IPTV:{...}` remark.
+    #[cfg(feature = "std")]
+    pub fn from_remark(remark: &str) -> Result<Self, PayloadError> {
+        let json = remark
+            .strip_prefix(REMARK_PREFIX)
+            .ok_or(PayloadError::MissingPrefix)?;
+        Self::from_json(json)
+    }
+}
+
+/// The CID named by an `IPTV:ipfs://<cid>` remark.
+pub fn remark_cid(remark: &str) -> Option<&str> {
+    let cid = remark
+        .strip_prefix(REMARK_PREFIX)?
+        .strip_prefix(IPFS_SCHEME)?
+        .trim();
+    (!cid.is_empty()).then_some(cid)
+}
+
+/// Append `s` as a JSON string literal, escaping per RFC 8259.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                const HEX: &[u8; 16] = b"0123456789abcdef";
+                let b = c as u8;
+                out.push_str("\\u00");
+                out.push(HEX[usize::from(b >> 4)] as char);
+                out.push(HEX[usize::from(b & 0xf)] as char);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Append the decimal representation of `n` without pulling in `format!`.
+fn push_u8(out: &mut String, n: u8) {
+    if n >= 100 {
+        out.push((b'0' + n / 100) as char);
+    }
+    if n >= 10 {
+        out.push((b'0' + (n / 10) % 10) as char);
+    }
+    out.push((b'0' + n % 10) as char);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn sample() -> PlaylistPayload {
+        PlaylistPayload::new(
+            "My Playlist".to_string(),
+            vec![
+                PayloadChannel {
+                    name: "Channel \"One\"".to_string(),
+                    stream_url: "http://example.com/1.m3u8".to_string(),
+                    group: Some("News".to_string()),
+                    logo_url: None,
+                },
+                PayloadChannel {
+                    name: "Two".to_string(),
+                    stream_url: "http://example.com/2.m3u8".to_string(),
+                    group: None,
+                    logo_url: Some("http://example.com/2.png".to_string()),
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn canonical_json_has_sorted_keys() {
+        assert_eq!(
+            sample().to_canonical_json(),
+            r#"{"c":[{"g":"News","n":"Channel \"One\"","s":"http://example.com/1.m3u8"},{"l":"http://example.com/2.png","n":"Two","s":"http://example.com/2.m3u8"}],"n":"My Playlist","v":1}"#
+        );
+    }
+
+    #[test]
+    fn remark_round_trip() {
+        let payload = sample();
+        let remark = payload.to_remark();
+        assert!(remark.starts_with(REMARK_PREFIX));
+        assert_eq!(PlaylistPayload::from_remark(&remark), Ok(payload));
+    }
+
+    #[test]
+    fn reads_ipfs_remarks() {
+        let cid = "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+        assert_eq!(remark_cid(&format!("IPTV:ipfs://{cid}")), Some(cid));
+        assert_eq!(remark_cid("IPTV:ipfs://"), None);
+        assert_eq!(remark_cid(&sample().to_remark()), None);
+        assert_eq!(remark_cid("ipfs://bafy"), None);
+        assert!(PlaylistPayload::from_remark("IPTV:ipfs://bafy").is_err());
+    }
+
+    #[test]
+    fn scale_round_trip() {
+        let payload = sample();
+        assert_eq!(
+            PlaylistPayload::from_scale(&payload.to_scale()),
+            Ok(payload)
+        );
+    }
+
+    #[test]
+    fn legacy_json_without_version_is_accepted() {
+        let payload =
+            PlaylistPayload::from_json(r#"{"n":"Old","c":[{"s":"http://x","n":"X","l":null}]}"#)
+                .unwrap();
+        assert_eq!(payload.version, 1);
+        assert_eq!(payload.channels.len(), 1);
+        assert!(payload.channels[0].logo_url.is_none());
+    }
+
+    #[test]
+    fn rejects_future_version_and_missing_prefix() {
+        assert_eq!(
+            PlaylistPayload::from_json(r#"{"v":2,"n":"x","c":[]}"#),
+            Err(PayloadError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            PlaylistPayload::from_remark(r#"{"v":1}"#),
+            Err(PayloadError::MissingPrefix)
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_fields() {
+        let mut payload = sample();
+        payload.channels[0].stream_url = "x".repeat(MAX_URL_LEN + 1);
+        assert_eq!(payload.validate(), Err(PayloadError::FieldTooLong("c.s")));
+        assert_eq!(
+            PlaylistPayload::from_json(&payload.to_canonical_json()),
+            Err(PayloadError::FieldTooLong("c.s"))
+        );
+    }
+
+    fn arb_channel() -> impl Strategy<Value = PayloadChannel> {
+        (
+            ".{0,40}",
+            ".{0,80}",
+            proptest::option::of(".{0,20}"),
+            proptest::option::of(".{0,80}"),
+        )
+            .prop_map(|(name, stream_url, group, logo_url)| PayloadChannel {
+                name,
+                stream_url,
+                group,
+                logo_url,
+            })
+    }
+
+    fn arb_payload() -> impl Strategy<Value = PlaylistPayload> {
+        (".{0,40}", proptest::collection::vec(arb_channel(), 0..8))
+            .prop_map(|(name, channels)| PlaylistPayload::new(name, channels))
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_json_round_trip(payload in arb_payload()) {
+            let json = payload.to_canonical_json();
+            let decoded = PlaylistPayload::from_json(&json).unwrap();
+            prop_assert_eq!(&decoded, &payload);
+            prop_assert_eq!(decoded.to_canonical_json(), json);
+        }
+
+        #[test]
+        fn fuzz_scale_round_trip(payload in arb_payload()) {
+            prop_assert_eq!(PlaylistPayload::from_scale(&payload.to_scale()), Ok(payload));
+        }
+
+        #[test]
+        fn fuzz_arbitrary_input_never_panics(input in ".{0,200}", bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            let _ = PlaylistPayload::from_remark(&input);
+            let _ = PlaylistPayload::from_json(&input);
+            let _ = PlaylistPayload::from_scale(&bytes);
+        }
+    }
+}
@@ -1,9 +1,15 @@
-import type { Playlist } from "./types";
+import type { ImportJob, Playlist } from "./types";
 
 const API_URL = process.env.NEXT_PUBLIC_API_URL ?? "http://localhost:3001";
+const API_KEY = process.env.NEXT_PUBLIC_API_KEY;
+
+/** Headers carrying the API key, when one is configured, for requests that change state or read gated groups. */
+function authHeaders(): Record<string, string> {
+  return API_KEY ? { "X-API-Key": API_KEY } : {};
+}
 
 export async function fetchPlaylist(): Promise<Playlist> {
-  const res = await fetch(`${API_URL}/api/playlist`);
+  const res = await fetch(`${API_URL}/api/playlist`, { headers: authHeaders() });
   if (!res.ok) {
     throw new Error(`Failed to fetch playlist: ${res.status}`);
   }
@@ -13,7 +19,7 @@ export async function fetchPlaylist(): Promise<Playlist> {
 export async function updatePlaylist(playlist: Playlist): Promise<void> {
   const res = await fetch(`${API_URL}/api/playlist`, {
     method: "PUT",
-    headers: { "Content-Type": "application/json" },
+    headers: { "Content-Type": "application/json", ...authHeaders() },
     body: JSON.stringify(playlist),
   });
   if (!res.ok) {
@@ -21,16 +27,43 @@ export async function updatePlaylist(playlist: Playlist): Promise<void> {
   }
 }
 
-export async function uploadPlaylist(file: File, mode?: "append" | "replace"): Promise<{ status: string; channels_loaded: number }> {
+const JOB_POLL_INTERVAL_MS = 1000;
+
+export async function fetchJob(id: string): Promise<ImportJob> {
+  const res = await fetch(`${API_URL}/api/jobs/${encodeURIComponent(id)}`);
+  if (!res.ok) {
+    throw new Error(`Failed to fetch job: ${res.status}`);
+  }
+  return res.json() as Promise<ImportJob>;
+}
+
+/**
+ * Uploads an M3U file and waits until its channels are in the playlist.
+ * The import job keeps probing the new channels in the background.
+ */
+export async function uploadPlaylist(file: File, mode?: "append" | "replace"): Promise<ImportJob> {
   const form = new FormData();
   form.append("file", file);
   const modeParam = mode ? `?mode=${mode}` : "";
   const res = await fetch(`${API_URL}/api/playlist/upload${modeParam}`, {
     method: "POST",
+    headers: authHeaders(),
     body: form,
   });
   if (!res.ok) {
     throw new Error(`Failed to upload playlist: ${res.status}`);
   }
-  return res.json() as Promise<{ status: string; channels_loaded: number }>;
+  const { job_id } = (await res.json()) as { status: string; job_id: string };
+
+  for (;;) {
+    const job = await fetchJob(job_id);
+    if (job.status === "failed") {
+      throw new Error(job.error ?? "Playlist import failed");
+    }
+    const applied = job.stages.some((s) => s.stage === "dedup" && s.status === "done");
+    if (job.status === "completed" || applied) {
+      return job;
+    }
+    await new Promise((resolve) => setTimeout(resolve, JOB_POLL_INTERVAL_MS));
+  }
 }
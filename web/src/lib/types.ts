@@ -6,6 +6,16 @@ export type Channel = {
   stream_url: string;
   is_live: boolean;
   tvg_id?: string;
+  health_score?: number;
+  position?: number;
+  tvg_chno?: string;
+  tvg_shift?: string;
+  catchup?: string;
+  catchup_days?: number;
+  catchup_source?: string;
+  radio?: boolean;
+  user_agent?: string;
+  referrer?: string;
 };
 
 export type EpgProgram = {
@@ -35,6 +45,42 @@ export type Playlist = {
   channels: Channel[];
   last_checked: string | null;
   source: string;
+  epg_urls?: string[];
+};
+
+export type ImportStage = {
+  stage: "parse" | "dedup" | "probe";
+  status: "pending" | "running" | "done";
+  processed: number;
+  total: number;
+};
+
+export type ParseIssue = {
+  line: number;
+  reason: "missing_header" | "missing_url" | "url_without_extinf" | "missing_name";
+  text: string;
+};
+
+export type ImportJob = {
+  id: string;
+  source: string;
+  mode: "append" | "replace";
+  strict: boolean;
+  status: "queued" | "running" | "completed" | "failed";
+  stages: ImportStage[];
+  summary?: {
+    channels_loaded: number;
+    duplicates_skipped: number;
+    live_channels: number;
+    total_channels: number;
+  };
+  parse_issues?: {
+    total: number;
+    issues: ParseIssue[];
+  };
+  error?: string;
+  created_at: string;
+  finished_at?: string;
 };
 
 export type UserSettings = {